@@ -877,6 +877,7 @@ impl<P: Pairing, C: CircomWitnessExtensionProtocol<P::ScalarField>> WitnessExten
             .get(&self.main)
             .ok_or(eyre!("cannot find main template: {}", self.main))?;
         let mut main_component = Component::init(main_templ, 1);
+        self.driver.set_phase("witness extension");
         main_component.run(&mut self.driver, &mut self.ctx, &self.config)?;
         Ok(())
     }
@@ -1,18 +1,23 @@
 use std::{
-    io::{Cursor, Read},
+    io::{Cursor, Read, Write},
     marker::PhantomData,
+    ops::Range,
 };
 
 use ark_ec::pairing::Pairing;
-use ark_serialize::SerializationError;
-use byteorder::{LittleEndian, ReadBytesExt};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use thiserror::Error;
 
 use crate::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
 
 pub(crate) type ZKeyParserResult<T> = std::result::Result<T, ZKeyParserError>;
 
-/// Error type describing errors during parsing zkey files
+/// The largest section id we accept. circom's bin files use far fewer sections (ptau files have the most with 15), so larger ids only occur in corrupted files, for which we would allocate a huge section table.
+const MAX_SECTION_ID: usize = 64;
+
+/// Error type describing errors during parsing and writing zkey and ptau files
 #[derive(Debug, Error)]
 pub enum ZKeyParserError {
     /// Error during serialization
@@ -36,7 +41,6 @@ where
     P::BaseField: CircomArkworksPrimeFieldBridge,
     P::ScalarField: CircomArkworksPrimeFieldBridge,
 {
-    #[allow(dead_code)]
     ftype: String,
     #[allow(dead_code)]
     version: u32,
@@ -66,6 +70,7 @@ where
             .try_into()
             .expect("u32 fits into usize");
         tracing::debug!("we got {} sections in binfile", num_sections);
+        check_num_sections(num_sections)?;
         let mut sections = vec![vec![]; num_sections];
 
         for _ in 0..num_sections {
@@ -78,6 +83,11 @@ where
                 .try_into()
                 .expect("u64 fits into usize");
 
+            check_section_id(section_id)?;
+            // section ids are not necessarily consecutive (e.g., ptau files have the sections 1-7 and 12-15)
+            if sections.len() < section_id {
+                sections.resize(section_id, vec![]);
+            }
             let section = &mut sections[section_id - 1];
             if !section.is_empty() {
                 return Err(ZKeyParserError::CorruptedBinFile(
@@ -97,10 +107,178 @@ where
     }
 
     pub(crate) fn take_section(&mut self, id: usize) -> Cursor<Vec<u8>> {
-        Cursor::new(std::mem::take(&mut self.sections[id - 1]))
+        Cursor::new(self.take_section_raw(id))
     }
 
     pub(crate) fn take_section_raw(&mut self, id: usize) -> Vec<u8> {
-        std::mem::take(&mut self.sections[id - 1])
+        self.sections
+            .get_mut(id - 1)
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn has_section(&self, id: usize) -> bool {
+        self.sections
+            .get(id - 1)
+            .is_some_and(|section| !section.is_empty())
+    }
+
+    pub(crate) fn check_file_type(&self, ftype: &str) -> ZKeyParserResult<()> {
+        check_file_type(&self.ftype, ftype)
+    }
+}
+
+/// Checks that the file type read from the magic number of a bin file is the expected one.
+pub(crate) fn check_file_type(actual: &str, expected: &str) -> ZKeyParserResult<()> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(ZKeyParserError::CorruptedBinFile(format!(
+            "expected file type \"{expected}\" but got \"{actual}\""
+        )))
+    }
+}
+
+fn check_num_sections(num_sections: usize) -> ZKeyParserResult<()> {
+    if num_sections > MAX_SECTION_ID {
+        return Err(ZKeyParserError::CorruptedBinFile(format!(
+            "too many sections ({num_sections}), expected at most {MAX_SECTION_ID}"
+        )));
+    }
+    Ok(())
+}
+
+fn check_section_id(section_id: usize) -> ZKeyParserResult<()> {
+    if section_id == 0 {
+        return Err(ZKeyParserError::CorruptedBinFile(
+            "section ids start at 1".to_owned(),
+        ));
+    }
+    if section_id > MAX_SECTION_ID {
+        return Err(ZKeyParserError::CorruptedBinFile(format!(
+            "section id {section_id} exceeds the maximum of {MAX_SECTION_ID}"
+        )));
+    }
+    Ok(())
+}
+
+/// Locates the sections of a bin file without copying them, e.g., to parse parts of a memory-mapped file on demand. Returns the file type and the byte ranges of the sections in `bytes`, where the section with id i is at index i - 1.
+pub(crate) fn section_ranges(
+    bytes: &[u8],
+) -> ZKeyParserResult<(String, Vec<Option<Range<usize>>>)> {
+    let mut reader = Cursor::new(bytes);
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    let ftype = std::str::from_utf8(&magic[..])
+        .map_err(|_| ZKeyParserError::CorruptedBinFile("cannot parse magic number".to_owned()))?
+        .to_string();
+    let _version = reader.read_u32::<LittleEndian>()?;
+    let num_sections: usize = reader
+        .read_u32::<LittleEndian>()?
+        .try_into()
+        .expect("u32 fits into usize");
+
+    check_num_sections(num_sections)?;
+    let mut sections = vec![None; num_sections];
+    for _ in 0..num_sections {
+        let section_id: usize = reader
+            .read_u32::<LittleEndian>()?
+            .try_into()
+            .expect("u32 fits into usize");
+        let section_length: usize = reader
+            .read_u64::<LittleEndian>()?
+            .try_into()
+            .expect("u64 fits into usize");
+
+        check_section_id(section_id)?;
+        if sections.len() < section_id {
+            sections.resize(section_id, None);
+        }
+        if sections[section_id - 1].is_some() {
+            return Err(ZKeyParserError::CorruptedBinFile(format!(
+                "section {section_id} occurs twice"
+            )));
+        }
+        let start = usize::try_from(reader.position()).expect("u64 fits into usize");
+        let end = start
+            .checked_add(section_length)
+            .filter(|end| *end <= bytes.len())
+            .ok_or_else(|| {
+                ZKeyParserError::CorruptedBinFile(format!("section {section_id} exceeds the file"))
+            })?;
+        sections[section_id - 1] = Some(start..end);
+        reader.set_position(u64::try_from(end).expect("usize fits into u64"));
+    }
+    Ok((ftype, sections))
+}
+
+/// Writes a bin file (the container format of circom's binary files) with the provided
+/// sections. The sections are written in the order of the slice, which is not necessarily
+/// sorted by section id (e.g., snarkjs writes the header of PLONK zkeys last).
+pub(crate) fn write_bin_file<W: Write>(
+    mut writer: W,
+    ftype: &str,
+    version: u32,
+    sections: &[(u32, Vec<u8>)],
+) -> std::io::Result<()> {
+    debug_assert_eq!(ftype.len(), 4);
+    writer.write_all(ftype.as_bytes())?;
+    writer.write_u32::<LittleEndian>(version)?;
+    writer.write_u32::<LittleEndian>(
+        u32::try_from(sections.len()).expect("number of sections fits into u32"),
+    )?;
+    for (id, section) in sections {
+        writer.write_u32::<LittleEndian>(*id)?;
+        writer.write_u64::<LittleEndian>(
+            u64::try_from(section.len()).expect("usize fits into u64"),
+        )?;
+        writer.write_all(section)?;
+    }
+    Ok(())
+}
+
+/// Writes the sizes and moduli of the base and scalar field, which is the common start of the
+/// header section of Groth16 and PLONK zkeys.
+pub(crate) fn write_zkey_field_header<P: Pairing + CircomArkworksPairingBridge, W: Write>(
+    mut writer: W,
+) -> ZKeyParserResult<()>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    writer.write_u32::<LittleEndian>(P::BaseField::MODULUS_BIT_SIZE.div_ceil(8))?;
+    P::BaseField::MODULUS.serialize_uncompressed(&mut writer)?;
+    writer.write_u32::<LittleEndian>(P::ScalarField::MODULUS_BIT_SIZE.div_ceil(8))?;
+    P::ScalarField::MODULUS.serialize_uncompressed(&mut writer)?;
+    Ok(())
+}
+
+/// Reads and checks the sizes and moduli of the base and scalar field at the start of the header
+/// section of a zkey. Returns the byte size of the scalar field.
+pub(crate) fn read_zkey_field_header<P: Pairing, R: Read>(
+    mut reader: R,
+) -> ZKeyParserResult<usize> {
+    let n8q: u32 = u32::deserialize_uncompressed(&mut reader)?;
+    //modulus of BaseField
+    let q = <P::BaseField as PrimeField>::BigInt::deserialize_uncompressed(&mut reader)?;
+    tracing::debug!("base field byte size: {n8q}");
+    let expected_n8q = P::BaseField::MODULUS_BIT_SIZE.div_ceil(8);
+    if n8q != expected_n8q {
+        return Err(ZKeyParserError::UnexpectedByteSize(expected_n8q, n8q));
+    }
+    if q != <P::BaseField as PrimeField>::MODULUS {
+        return Err(ZKeyParserError::InvalidPrimeInHeader);
+    }
+    let n8r: u32 = u32::deserialize_uncompressed(&mut reader)?;
+    //modulus of ScalarField
+    let r = <P::ScalarField as PrimeField>::BigInt::deserialize_uncompressed(&mut reader)?;
+    tracing::debug!("scalar field byte size: {n8r}");
+    let expected_n8r = P::ScalarField::MODULUS_BIT_SIZE.div_ceil(8);
+    if n8r != expected_n8r {
+        return Err(ZKeyParserError::UnexpectedByteSize(expected_n8r, n8r));
+    }
+    if r != <P::ScalarField as PrimeField>::MODULUS {
+        return Err(ZKeyParserError::InvalidPrimeInHeader);
     }
+    Ok(usize::try_from(n8r).expect("u32 fits into usize"))
 }
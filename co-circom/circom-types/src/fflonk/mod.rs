@@ -0,0 +1,13 @@
+//! This module defines types related to FFLONK used in circom and utilities to read these types from files.
+
+mod proof;
+mod setup;
+mod verification_key;
+mod zkey;
+
+pub use proof::FflonkEvaluations;
+pub use proof::FflonkPolynomials;
+pub use proof::FflonkProof;
+pub use verification_key::JsonVerificationKey;
+pub use zkey::VerifyingKey;
+pub use zkey::ZKey;
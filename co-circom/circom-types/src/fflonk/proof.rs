@@ -0,0 +1,125 @@
+//! This module defines the [`FflonkProof`] in the JSON format of snarkjs. It implements de/serialization using [`serde`].
+
+use crate::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
+use ark_ec::pairing::Pairing;
+use serde::{Deserialize, Serialize};
+
+/// Represents a FFLONK proof in the format of snarkjs. Supports de/serialization using [`serde`].
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct FflonkProof<P: Pairing + CircomArkworksPairingBridge>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// The commitments of the proof
+    pub polynomials: FflonkPolynomials<P>,
+    /// The evaluations of the proof
+    pub evaluations: FflonkEvaluations<P>,
+    /// The protocol used to generate the proof
+    pub protocol: String,
+    /// The curve used to generate the proof
+    pub curve: String,
+}
+
+/// The commitments of a [`FflonkProof`]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FflonkPolynomials<P: Pairing + CircomArkworksPairingBridge>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Commitment to C1, which combines the wire polynomials and T0
+    #[serde(rename = "C1")]
+    #[serde(serialize_with = "P::serialize_g1::<_>")]
+    #[serde(deserialize_with = "P::deserialize_g1_element::<_>")]
+    pub c1: P::G1Affine,
+    /// Commitment to C2, which combines Z, T1 and T2
+    #[serde(rename = "C2")]
+    #[serde(serialize_with = "P::serialize_g1::<_>")]
+    #[serde(deserialize_with = "P::deserialize_g1_element::<_>")]
+    pub c2: P::G1Affine,
+    /// The first opening proof
+    #[serde(rename = "W1")]
+    #[serde(serialize_with = "P::serialize_g1::<_>")]
+    #[serde(deserialize_with = "P::deserialize_g1_element::<_>")]
+    pub w1: P::G1Affine,
+    /// The second opening proof
+    #[serde(rename = "W2")]
+    #[serde(serialize_with = "P::serialize_g1::<_>")]
+    #[serde(deserialize_with = "P::deserialize_g1_element::<_>")]
+    pub w2: P::G1Affine,
+}
+
+/// The evaluations of a [`FflonkProof`]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FflonkEvaluations<P: Pairing + CircomArkworksPairingBridge>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Evaluation of Ql at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub ql: P::ScalarField,
+    /// Evaluation of Qr at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub qr: P::ScalarField,
+    /// Evaluation of Qm at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub qm: P::ScalarField,
+    /// Evaluation of Qo at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub qo: P::ScalarField,
+    /// Evaluation of Qc at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub qc: P::ScalarField,
+    /// Evaluation of σ1 at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub s1: P::ScalarField,
+    /// Evaluation of σ2 at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub s2: P::ScalarField,
+    /// Evaluation of σ3 at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub s3: P::ScalarField,
+    /// Evaluation of A at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub a: P::ScalarField,
+    /// Evaluation of B at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub b: P::ScalarField,
+    /// Evaluation of C at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub c: P::ScalarField,
+    /// Evaluation of Z at xi
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub z: P::ScalarField,
+    /// Evaluation of Z at xi * w
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub zw: P::ScalarField,
+    /// Evaluation of T1 at xi * w
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub t1w: P::ScalarField,
+    /// Evaluation of T2 at xi * w
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub t2w: P::ScalarField,
+    /// The inverse of the product of the denominators the verifier needs
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub inv: P::ScalarField,
+}
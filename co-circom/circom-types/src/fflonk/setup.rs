@@ -0,0 +1,305 @@
+//! This module implements the FFLONK setup of snarkjs. [`ZKey::from_r1cs_and_ptau`] corresponds to
+//! `snarkjs fflonk setup`. The circuit is translated into the same gates as for PLONK.
+use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
+use ark_ff::{One, PrimeField, Zero};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Radix2EvaluationDomain};
+use rayon::prelude::*;
+
+use crate::{
+    plonk::{
+        setup::{
+            domain_power, k1_k2, n_public, polynomial, selector, sigma, usize_to_u32, GateBuilder,
+        },
+        Additions,
+    },
+    ptau::{evaluation_domain, PowersOfTau, SetupError},
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+    R1CS,
+};
+
+use super::{VerifyingKey, ZKey};
+
+// C2 has degree 9n + 17, so the ptau needs at least 16n powers of tau
+const ADDITIONAL_POWER: usize = 3;
+
+impl<P: Pairing + CircomArkworksPairingBridge> ZKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Creates the FFLONK zkey for the given [`R1CS`] from a [`PowersOfTau`]. Like PLONK, FFLONK has no
+    /// circuit specific secrets and the ptau does not need to be prepared for phase 2.
+    pub fn from_r1cs_and_ptau(r1cs: &R1CS<P>, ptau: &PowersOfTau<P>) -> Result<Self, SetupError> {
+        let n_public = n_public(r1cs);
+        let GateBuilder {
+            n_vars,
+            gates,
+            additions,
+        } = GateBuilder::from_r1cs(r1cs);
+        let n_constraints = gates.len();
+
+        let power = domain_power(n_constraints);
+        let domain_size = 1 << power;
+        let n_taus = Self::n_taus(domain_size);
+        if power + ADDITIONAL_POWER > ptau.power || ptau.tau_g1.len() < n_taus {
+            return Err(SetupError::PtauTooSmall(
+                power + ADDITIONAL_POWER,
+                ptau.power,
+            ));
+        }
+        if ptau.tau_g2.len() < 2 {
+            return Err(SetupError::PtauTooSmall(
+                power + ADDITIONAL_POWER,
+                ptau.power,
+            ));
+        }
+        let domain =
+            evaluation_domain::<P::ScalarField>(domain_size).ok_or(SetupError::CircuitTooBig)?;
+        let extended_domain = evaluation_domain::<P::ScalarField>(domain_size * 4)
+            .ok_or(SetupError::CircuitTooBig)?;
+        tracing::debug!(
+            "fflonk setup with {n_constraints} gates, {} additions and domain size {domain_size}",
+            additions.len()
+        );
+
+        let (k1, k2) = k1_k2(&domain);
+        // the order in which the polynomials are interleaved in C0
+        let mut evaluations = vec![
+            selector(&gates, domain_size, |g| g.ql),
+            selector(&gates, domain_size, |g| g.qr),
+            selector(&gates, domain_size, |g| g.qo),
+            selector(&gates, domain_size, |g| g.qm),
+            selector(&gates, domain_size, |g| g.qc),
+        ];
+        evaluations.extend(sigma(&gates, n_vars, &domain, k1, k2));
+
+        tracing::debug!("computing polynomials and C0...");
+        let polys = evaluations
+            .par_iter()
+            .map(|evals| polynomial(evals, &domain, &extended_domain))
+            .collect::<Vec<_>>();
+        // C0(X) = QL(X^8) + X * QR(X^8) + X^2 * QO(X^8) + X^3 * QM(X^8) + X^4 * QC(X^8)
+        //       + X^5 * S1(X^8) + X^6 * S2(X^8) + X^7 * S3(X^8)
+        let mut c0 = vec![P::ScalarField::zero(); domain_size * 8];
+        for (k, poly) in polys.iter().enumerate() {
+            for (i, coeff) in poly.coeffs.coeffs.iter().enumerate() {
+                c0[i * 8 + k] = *coeff;
+            }
+        }
+        let c0_bigints = c0.iter().map(|x| x.into_bigint()).collect::<Vec<_>>();
+        let c0_commitment =
+            P::G1::msm_bigint(&ptau.tau_g1[..domain_size * 8], &c0_bigints).into_affine();
+        let lagrange_polys = (0..n_public.max(1))
+            .into_par_iter()
+            .map(|i| {
+                let mut evals = vec![P::ScalarField::zero(); domain_size];
+                evals[i] = P::ScalarField::one();
+                polynomial(&evals, &domain, &extended_domain)
+            })
+            .collect::<Vec<_>>();
+
+        let root = |size| {
+            evaluation_domain::<P::ScalarField>(size)
+                .expect("small domains exist")
+                .group_gen
+        };
+        let mut polys = polys.into_iter();
+        let mut next_poly = || polys.next().expect("we have 8 polynomials");
+        let zkey = ZKey {
+            n_vars,
+            n_public,
+            domain_size,
+            power,
+            n_additions: additions.len(),
+            n_constraints,
+            verifying_key: VerifyingKey {
+                k1,
+                k2,
+                w3: cube_root_of_unity(),
+                w4: root(4),
+                w8: root(8),
+                wr: cube_root_of_domain_root(&domain),
+                x_2: ptau.tau_g2[1],
+                c0: c0_commitment,
+            },
+            additions: additions
+                .into_iter()
+                .map(|(sl, sr, factor1, factor2)| Additions {
+                    signal_id1: usize_to_u32(sl),
+                    signal_id2: usize_to_u32(sr),
+                    factor1,
+                    factor2,
+                })
+                .collect(),
+            map_a: gates.iter().map(|g| g.a).collect(),
+            map_b: gates.iter().map(|g| g.b).collect(),
+            map_c: gates.iter().map(|g| g.c).collect(),
+            ql_poly: next_poly(),
+            qr_poly: next_poly(),
+            qo_poly: next_poly(),
+            qm_poly: next_poly(),
+            qc_poly: next_poly(),
+            s1_poly: next_poly(),
+            s2_poly: next_poly(),
+            s3_poly: next_poly(),
+            lagrange: lagrange_polys,
+            p_tau: ptau.tau_g1[..n_taus].to_vec(),
+            c0_poly: DensePolynomial { coeffs: c0 },
+        };
+        tracing::debug!("fflonk setup done!");
+        Ok(zkey)
+    }
+}
+
+// The primitive third roots of unity are (-1 ± sqrt(-3)) / 2. snarkjs uses the larger one.
+fn cube_root_of_unity<F: PrimeField>() -> F {
+    let sqrt = (-F::from(3u64))
+        .sqrt()
+        .expect("the scalar field has third roots of unity");
+    let two_inv = F::from(2u64).inverse().expect("2 is not zero");
+    let w = (sqrt - F::one()) * two_inv;
+    let w_squared = w.square();
+    if w.into_bigint() > w_squared.into_bigint() {
+        w
+    } else {
+        w_squared
+    }
+}
+
+// The element wr with wr^3 = w, where w is the root of unity of the domain. As the domain size n
+// is a power of two, 3 is invertible modulo n and wr = w^(3^-1 mod n) is the power of w that snarkjs uses.
+fn cube_root_of_domain_root<F: PrimeField>(domain: &Radix2EvaluationDomain<F>) -> F {
+    let n = domain.size();
+    let three_inv = if (n + 1) % 3 == 0 {
+        (n + 1) / 3
+    } else {
+        (2 * n + 1) / 3
+    };
+    domain
+        .group_gen
+        .pow([u64::try_from(three_inv).expect("usize fits into u64")])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use ark_ec::AffineRepr;
+    use ark_ff::Field;
+    use ark_poly::Polynomial;
+    use ark_std::UniformRand;
+    use std::{fs::File, str::FromStr};
+
+    #[test]
+    fn roots_match_snarkjs_bn254() {
+        // the constants that snarkjs uses for BN254
+        assert_eq!(
+            cube_root_of_unity::<ark_bn254::Fr>(),
+            ark_bn254::Fr::from_str(
+                "21888242871839275217838484774961031246154997185409878258781734729429964517155"
+            )
+            .unwrap()
+        );
+        let domain = evaluation_domain::<ark_bn254::Fr>(1 << 28).unwrap();
+        assert_eq!(
+            cube_root_of_domain_root(&domain),
+            ark_bn254::Fr::from_str(
+                "467799165886069610036046866799264026481344299079011762026774533774345988080"
+            )
+            .unwrap()
+        );
+    }
+
+    fn setup_roundtrip<P: Pairing + CircomArkworksPairingBridge>(r1cs: &str)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let mut rng = ark_std::test_rng();
+        let tau = P::ScalarField::rand(&mut rng);
+        let r1cs = R1CS::<P>::from_reader(File::open(r1cs).unwrap()).unwrap();
+        let ptau = PowersOfTau::<P>::from_secrets(
+            6,
+            tau,
+            P::ScalarField::rand(&mut rng),
+            P::ScalarField::rand(&mut rng),
+        );
+        let zkey = ZKey::<P>::from_r1cs_and_ptau(&r1cs, &ptau).unwrap();
+        let vk = &zkey.verifying_key;
+        let domain = evaluation_domain::<P::ScalarField>(zkey.domain_size).unwrap();
+        assert_eq!(vk.w3.pow([3]), P::ScalarField::one());
+        assert_ne!(vk.w3, P::ScalarField::one());
+        assert_eq!(vk.w4.pow([2]), -P::ScalarField::one());
+        assert_eq!(vk.w8.pow([4]), -P::ScalarField::one());
+        assert_eq!(vk.wr.pow([3]), domain.group_gen);
+        assert_eq!(
+            (P::G1Affine::generator() * zkey.c0_poly.evaluate(&tau)).into_affine(),
+            vk.c0
+        );
+        // C0 interleaves the preprocessed polynomials
+        let x = P::ScalarField::rand(&mut rng);
+        let x8 = x.pow([8]);
+        let expected = [
+            &zkey.ql_poly,
+            &zkey.qr_poly,
+            &zkey.qo_poly,
+            &zkey.qm_poly,
+            &zkey.qc_poly,
+            &zkey.s1_poly,
+            &zkey.s2_poly,
+            &zkey.s3_poly,
+        ]
+        .iter()
+        .rev()
+        .fold(P::ScalarField::zero(), |acc, poly| {
+            acc * x + poly.evaluate(&x8)
+        });
+        assert_eq!(zkey.c0_poly.evaluate(&x), expected);
+
+        let mut ser = Vec::new();
+        zkey.to_writer(&mut ser).unwrap();
+        let der = ZKey::<P>::from_reader(ser.as_slice()).unwrap();
+        let mut ser2 = Vec::new();
+        der.to_writer(&mut ser2).unwrap();
+        assert_eq!(ser, ser2);
+        assert_eq!(der.p_tau, zkey.p_tau);
+        assert_eq!(der.c0_poly, zkey.c0_poly);
+    }
+
+    #[test]
+    fn setup_roundtrip_bn254() {
+        setup_roundtrip::<Bn254>("../../test_vectors/Plonk/bn254/multiplier2/circuit.r1cs");
+    }
+
+    #[test]
+    fn setup_roundtrip_bls12_381() {
+        setup_roundtrip::<Bls12_381>("../../test_vectors/Plonk/bls12_381/multiplier2/circuit.r1cs");
+    }
+
+    #[test]
+    fn setup_fails_for_small_ptau() {
+        let r1cs = R1CS::<Bn254>::from_reader(
+            File::open("../../test_vectors/Plonk/bn254/multiplier2/circuit.r1cs").unwrap(),
+        )
+        .unwrap();
+        let mut rng = ark_std::test_rng();
+        let ptau = PowersOfTau::<Bn254>::from_secrets(
+            5,
+            UniformRand::rand(&mut rng),
+            UniformRand::rand(&mut rng),
+            UniformRand::rand(&mut rng),
+        );
+        assert!(matches!(
+            ZKey::from_r1cs_and_ptau(&r1cs, &ptau),
+            Err(SetupError::PtauTooSmall(6, 5))
+        ));
+    }
+
+    #[test]
+    fn rejects_plonk_zkey() {
+        let bytes =
+            std::fs::read("../../test_vectors/Plonk/bn254/multiplier2/circuit.zkey").unwrap();
+        assert!(ZKey::<Bn254>::from_reader(bytes.as_slice()).is_err());
+    }
+}
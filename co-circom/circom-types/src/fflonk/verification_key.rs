@@ -0,0 +1,96 @@
+//! This module defines the [`JsonVerificationKey`] struct that implements de/serialization using [`serde`].
+
+use ark_ec::pairing::Pairing;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ptau::evaluation_domain,
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+
+use super::ZKey;
+
+/// Represents a FFLONK verification key in the JSON format of snarkjs. Supports de/serialization using [`serde`].
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonVerificationKey<P: Pairing + CircomArkworksPairingBridge>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// The protocol (fflonk in this case)
+    pub protocol: String,
+    /// The curve
+    pub curve: String,
+    /// The number of public inputs
+    #[serde(rename = "nPublic")]
+    pub n_public: usize,
+    /// The size of the fft domain
+    pub power: usize,
+    /// Proof element k1
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub k1: P::ScalarField,
+    /// Proof element k2
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub k2: P::ScalarField,
+    /// The root of unity of the domain
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub w: P::ScalarField,
+    /// A primitive third root of unity
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub w3: P::ScalarField,
+    /// A primitive fourth root of unity
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub w4: P::ScalarField,
+    /// A primitive eighth root of unity
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub w8: P::ScalarField,
+    /// A third root of w
+    #[serde(serialize_with = "P::serialize_fr::<_>")]
+    #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
+    pub wr: P::ScalarField,
+    /// Proof element x2
+    #[serde(rename = "X_2")]
+    #[serde(serialize_with = "P::serialize_g2::<_>")]
+    #[serde(deserialize_with = "P::deserialize_g2_element::<_>")]
+    pub x2: P::G2Affine,
+    /// The commitment to the preprocessed polynomial C0
+    #[serde(rename = "C0")]
+    #[serde(serialize_with = "P::serialize_g1::<_>")]
+    #[serde(deserialize_with = "P::deserialize_g1_element::<_>")]
+    pub c0: P::G1Affine,
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> From<&ZKey<P>> for JsonVerificationKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Extracts the verification key from a zkey, like `snarkjs zkey export verificationkey`.
+    fn from(zkey: &ZKey<P>) -> Self {
+        let vk = &zkey.verifying_key;
+        Self {
+            protocol: "fflonk".to_owned(),
+            curve: P::get_circom_name(),
+            n_public: zkey.n_public,
+            power: zkey.power,
+            k1: vk.k1,
+            k2: vk.k2,
+            w: evaluation_domain::<P::ScalarField>(zkey.domain_size)
+                .expect("the zkey has a valid domain size")
+                .group_gen,
+            w3: vk.w3,
+            w4: vk.w4,
+            w8: vk.w8,
+            wr: vk.wr,
+            x2: vk.x_2,
+            c0: vk.c0,
+        }
+    }
+}
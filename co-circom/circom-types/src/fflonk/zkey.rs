@@ -0,0 +1,379 @@
+//! This module defines the [`ZKey`] struct that implements deserialization of circom FFLONK zkey files via
+//! [`ZKey::from_reader`] and serialization via [`ZKey::to_writer`].
+use ark_ec::pairing::Pairing;
+use ark_poly::univariate::DensePolynomial;
+use ark_serialize::CanonicalDeserialize;
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::io::{Read, Write};
+
+use crate::{
+    binfile::{self, BinFile, ZKeyParserError, ZKeyParserResult},
+    plonk::{Additions, CircomPolynomial, ZKey as PlonkZKey},
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+
+macro_rules! u32_to_usize {
+    ($x: expr) => {
+        usize::try_from($x).expect("u32 fits into usize")
+    };
+}
+
+macro_rules! usize_to_u32 {
+    ($x: expr) => {
+        u32::try_from($x).expect("circom zkeys use u32 for sizes and indices")
+    };
+}
+
+const ZKEY_FTYPE: &str = "zkey";
+const ZKEY_VERSION: u32 = 1;
+pub(crate) const FFLONK_PROTOCOL_ID: u32 = 10;
+// snarkjs writes the header sections of FFLONK zkeys after all other sections
+const SECTION_ORDER: [u32; 17] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 1, 2];
+
+/// Represents a FFLONK zkey in the format defined by snarkjs. Implements [`ZKey::from_reader`] to deserialize a zkey from a reader.
+#[derive(Clone)]
+pub struct ZKey<P: Pairing> {
+    /// The amount of vars in the circuit
+    pub n_vars: usize,
+    /// The amount of public values in the circuit
+    pub n_public: usize,
+    /// The domain size (power of two)
+    pub domain_size: usize,
+    /// ld(domain size)
+    pub power: usize,
+    /// The amounts of additions
+    pub n_additions: usize,
+    /// The amounts of constraints
+    pub n_constraints: usize,
+    /// The verifying key
+    pub verifying_key: VerifyingKey<P>,
+    /// The indices of the additions of the witness and their respective blinding factors
+    pub additions: Vec<Additions<P>>,
+    /// The witness indices of the signals of wire mapping a
+    pub map_a: Vec<usize>,
+    /// The witness indices of the signals of wire mapping b
+    pub map_b: Vec<usize>,
+    /// The witness indices of the signals of wire mapping c
+    pub map_c: Vec<usize>,
+    /// Ql polynomial
+    pub ql_poly: CircomPolynomial<P::ScalarField>,
+    /// Qr polynomial
+    pub qr_poly: CircomPolynomial<P::ScalarField>,
+    /// Qm polynomial
+    pub qm_poly: CircomPolynomial<P::ScalarField>,
+    /// Qo polynomial
+    pub qo_poly: CircomPolynomial<P::ScalarField>,
+    /// Qc polynomial
+    pub qc_poly: CircomPolynomial<P::ScalarField>,
+    /// σ1 polynomial
+    pub s1_poly: CircomPolynomial<P::ScalarField>,
+    /// σ2 polynomial
+    pub s2_poly: CircomPolynomial<P::ScalarField>,
+    /// σ3 polynomial
+    pub s3_poly: CircomPolynomial<P::ScalarField>,
+    /// Lagrange polynomials. One polynomial for each public input, but at least one.
+    pub lagrange: Vec<CircomPolynomial<P::ScalarField>>,
+    /// The powers of 𝜏
+    pub p_tau: Vec<P::G1Affine>,
+    /// The preprocessed polynomial C0, which combines the selectors and permutation polynomials
+    pub c0_poly: DensePolynomial<P::ScalarField>,
+}
+
+/// The verifying key for a FFLONK proof.
+#[derive(Default, Clone, Debug)]
+pub struct VerifyingKey<P: Pairing> {
+    /// k1
+    pub k1: P::ScalarField,
+    /// k2
+    pub k2: P::ScalarField,
+    /// A primitive third root of unity
+    pub w3: P::ScalarField,
+    /// A primitive fourth root of unity
+    pub w4: P::ScalarField,
+    /// A primitive eighth root of unity
+    pub w8: P::ScalarField,
+    /// A third root of the root of unity of the domain
+    pub wr: P::ScalarField,
+    /// x_2
+    pub x_2: P::G2Affine,
+    /// The commitment to [`ZKey::c0_poly`]
+    pub c0: P::G1Affine,
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> ZKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Deserializes a [`ZKey`] from a reader.
+    pub fn from_reader<R: Read>(mut reader: R) -> ZKeyParserResult<Self> {
+        BinFile::<P>::new(&mut reader)?.try_into()
+    }
+
+    /// The amount of powers of 𝜏 in the zkey, which are needed to commit to the polynomial C2
+    pub(crate) fn n_taus(domain_size: usize) -> usize {
+        domain_size * 9 + 18
+    }
+
+    fn c0<R: Read>(domain_size: usize, mut reader: R) -> ZKeyParserResult<Vec<P::ScalarField>> {
+        let mut coeffs = Vec::with_capacity(domain_size * 8);
+        for _ in 0..domain_size * 8 {
+            coeffs.push(P::ScalarField::montgomery_bigint_from_reader(&mut reader)?);
+        }
+        Ok(coeffs)
+    }
+
+    /// Serializes the [`ZKey`] into a writer. The output is byte-exact with the zkeys written by snarkjs.
+    pub fn to_writer<W: Write>(&self, writer: W) -> ZKeyParserResult<()> {
+        let mut sections = Vec::with_capacity(SECTION_ORDER.len());
+        for id in SECTION_ORDER {
+            let mut section = Vec::new();
+            match id {
+                1 => section.write_u32::<LittleEndian>(FFLONK_PROTOCOL_ID)?,
+                2 => self.write_header(&mut section)?,
+                3 => {
+                    for addition in self.additions.iter() {
+                        section.write_u32::<LittleEndian>(addition.signal_id1)?;
+                        section.write_u32::<LittleEndian>(addition.signal_id2)?;
+                        addition.factor1.montgomery_bigint_to_writer(&mut section)?;
+                        addition.factor2.montgomery_bigint_to_writer(&mut section)?;
+                    }
+                }
+                4 => PlonkZKey::<P>::write_id_map(&self.map_a, &mut section)?,
+                5 => PlonkZKey::<P>::write_id_map(&self.map_b, &mut section)?,
+                6 => PlonkZKey::<P>::write_id_map(&self.map_c, &mut section)?,
+                7 => self.ql_poly.write(&mut section)?,
+                8 => self.qr_poly.write(&mut section)?,
+                9 => self.qm_poly.write(&mut section)?,
+                10 => self.qo_poly.write(&mut section)?,
+                11 => self.qc_poly.write(&mut section)?,
+                12 => self.s1_poly.write(&mut section)?,
+                13 => self.s2_poly.write(&mut section)?,
+                14 => self.s3_poly.write(&mut section)?,
+                15 => {
+                    for lagrange in self.lagrange.iter() {
+                        lagrange.write(&mut section)?;
+                    }
+                }
+                16 => P::g1_vec_to_writer(&self.p_tau, &mut section)?,
+                17 => {
+                    for coeff in self.c0_poly.coeffs.iter() {
+                        coeff.montgomery_bigint_to_writer(&mut section)?;
+                    }
+                }
+                _ => unreachable!("FFLONK zkeys have 17 sections"),
+            }
+            sections.push((id, section));
+        }
+        binfile::write_bin_file(writer, ZKEY_FTYPE, ZKEY_VERSION, &sections)?;
+        Ok(())
+    }
+
+    fn write_header<W: Write>(&self, mut writer: W) -> ZKeyParserResult<()> {
+        binfile::write_zkey_field_header::<P, _>(&mut writer)?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.n_vars))?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.n_public))?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.domain_size))?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.n_additions))?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.n_constraints))?;
+        self.verifying_key.write(writer)
+    }
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> TryFrom<BinFile<P>> for ZKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    type Error = ZKeyParserError;
+    fn try_from(mut binfile: BinFile<P>) -> Result<Self, Self::Error> {
+        tracing::debug!("start transforming bin file into fflonk zkey...");
+        let protocol_id = u32::deserialize_uncompressed(&mut binfile.take_section(1))?;
+        if protocol_id != FFLONK_PROTOCOL_ID {
+            return Err(ZKeyParserError::CorruptedBinFile(format!(
+                "expected FFLONK zkey (protocol id {FFLONK_PROTOCOL_ID}) but got protocol id {protocol_id}"
+            )));
+        }
+        let header = FflonkHeader::<P>::read(&mut binfile.take_section(2))?;
+        let n_additions = header.n_additions;
+        let n_constraints = header.n_constraints;
+        let n_public = header.n_public;
+        let domain_size = header.domain_size;
+
+        let add_section = binfile.take_section(3);
+        let a_section = binfile.take_section(4);
+        let b_section = binfile.take_section(5);
+        let c_section = binfile.take_section(6);
+        let ql_section = binfile.take_section(7);
+        let qr_section = binfile.take_section(8);
+        let qm_section = binfile.take_section(9);
+        let qo_section = binfile.take_section(10);
+        let qc_section = binfile.take_section(11);
+        let sigma1_section = binfile.take_section(12);
+        let sigma2_section = binfile.take_section(13);
+        let sigma3_section = binfile.take_section(14);
+        let l_section = binfile.take_section(15);
+        let t_section = binfile.take_section(16);
+        let c0_section = binfile.take_section(17);
+
+        let mut additions = None;
+        let mut map_a = None;
+        let mut map_b = None;
+        let mut map_c = None;
+        let mut ql = None;
+        let mut qr = None;
+        let mut qm = None;
+        let mut qo = None;
+        let mut qc = None;
+        let mut sigma1 = None;
+        let mut sigma2 = None;
+        let mut sigma3 = None;
+        let mut lagrange = None;
+        let mut p_tau = None;
+        let mut c0 = None;
+        tracing::debug!("parsing zkey sections with rayon...");
+        rayon::scope(|s| {
+            s.spawn(|_| {
+                additions = Some(PlonkZKey::<P>::additions_indices(n_additions, add_section))
+            });
+            s.spawn(|_| map_a = Some(PlonkZKey::<P>::id_map(n_constraints, a_section)));
+            s.spawn(|_| map_b = Some(PlonkZKey::<P>::id_map(n_constraints, b_section)));
+            s.spawn(|_| map_c = Some(PlonkZKey::<P>::id_map(n_constraints, c_section)));
+            s.spawn(|_| ql = Some(PlonkZKey::<P>::evaluations(domain_size, ql_section)));
+            s.spawn(|_| qr = Some(PlonkZKey::<P>::evaluations(domain_size, qr_section)));
+            s.spawn(|_| qm = Some(PlonkZKey::<P>::evaluations(domain_size, qm_section)));
+            s.spawn(|_| qo = Some(PlonkZKey::<P>::evaluations(domain_size, qo_section)));
+            s.spawn(|_| qc = Some(PlonkZKey::<P>::evaluations(domain_size, qc_section)));
+            s.spawn(|_| sigma1 = Some(PlonkZKey::<P>::evaluations(domain_size, sigma1_section)));
+            s.spawn(|_| sigma2 = Some(PlonkZKey::<P>::evaluations(domain_size, sigma2_section)));
+            s.spawn(|_| sigma3 = Some(PlonkZKey::<P>::evaluations(domain_size, sigma3_section)));
+            s.spawn(|_| {
+                lagrange = Some(PlonkZKey::<P>::lagrange(
+                    n_public.max(1),
+                    domain_size,
+                    l_section,
+                ))
+            });
+            s.spawn(|_| {
+                p_tau = Some(
+                    P::g1_vec_from_reader(t_section, Self::n_taus(domain_size))
+                        .map_err(ZKeyParserError::from),
+                )
+            });
+            s.spawn(|_| c0 = Some(Self::c0(domain_size, c0_section)));
+        });
+        tracing::debug!("we are done with parsing sections!");
+        Ok(Self {
+            n_vars: header.n_vars,
+            n_public,
+            domain_size,
+            power: header.power,
+            n_additions,
+            n_constraints,
+            verifying_key: header.verifying_key,
+            //we unwrap all elements here, as we know they have to be Some.
+            //this thread automatically joins on the rayon scope, therefore we can
+            //only be here if the scope finished.
+            //Even on the error case, we then have a Some value
+            additions: additions.unwrap()?,
+            map_a: map_a.unwrap()?,
+            map_b: map_b.unwrap()?,
+            map_c: map_c.unwrap()?,
+            ql_poly: ql.unwrap()?,
+            qr_poly: qr.unwrap()?,
+            qm_poly: qm.unwrap()?,
+            qo_poly: qo.unwrap()?,
+            qc_poly: qc.unwrap()?,
+            s1_poly: sigma1.unwrap()?,
+            s2_poly: sigma2.unwrap()?,
+            s3_poly: sigma3.unwrap()?,
+            lagrange: lagrange.unwrap()?,
+            p_tau: p_tau.unwrap()?,
+            c0_poly: DensePolynomial {
+                coeffs: c0.unwrap()?,
+            },
+        })
+    }
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> VerifyingKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn new<R: Read>(mut reader: R) -> ZKeyParserResult<Self> {
+        let k1 = P::ScalarField::montgomery_bigint_from_reader(&mut reader)?;
+        let k2 = P::ScalarField::montgomery_bigint_from_reader(&mut reader)?;
+        let w3 = P::ScalarField::montgomery_bigint_from_reader(&mut reader)?;
+        let w4 = P::ScalarField::montgomery_bigint_from_reader(&mut reader)?;
+        let w8 = P::ScalarField::montgomery_bigint_from_reader(&mut reader)?;
+        let wr = P::ScalarField::montgomery_bigint_from_reader(&mut reader)?;
+        let x_2 = P::g2_from_reader(&mut reader)?;
+        let c0 = P::g1_from_reader(&mut reader)?;
+        Ok(Self {
+            k1,
+            k2,
+            w3,
+            w4,
+            w8,
+            wr,
+            x_2,
+            c0,
+        })
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> ZKeyParserResult<()> {
+        self.k1.montgomery_bigint_to_writer(&mut writer)?;
+        self.k2.montgomery_bigint_to_writer(&mut writer)?;
+        self.w3.montgomery_bigint_to_writer(&mut writer)?;
+        self.w4.montgomery_bigint_to_writer(&mut writer)?;
+        self.w8.montgomery_bigint_to_writer(&mut writer)?;
+        self.wr.montgomery_bigint_to_writer(&mut writer)?;
+        P::g2_to_writer(&self.x_2, &mut writer)?;
+        P::g1_to_writer(&self.c0, &mut writer)?;
+        Ok(())
+    }
+}
+
+struct FflonkHeader<P: Pairing> {
+    n_vars: usize,
+    n_public: usize,
+    domain_size: usize,
+    power: usize,
+    n_additions: usize,
+    n_constraints: usize,
+    verifying_key: VerifyingKey<P>,
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> FflonkHeader<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn read<R: Read>(mut reader: &mut R) -> ZKeyParserResult<Self> {
+        binfile::read_zkey_field_header::<P, _>(&mut reader)?;
+        let n_vars = u32::deserialize_uncompressed(&mut reader)?;
+        let n_public = u32::deserialize_uncompressed(&mut reader)?;
+        let domain_size = u32::deserialize_uncompressed(&mut reader)?;
+        let n_additions = u32::deserialize_uncompressed(&mut reader)?;
+        let n_constraints = u32::deserialize_uncompressed(&mut reader)?;
+        tracing::debug!("n_vars: {n_vars}; n_public: {n_public}, domain_size: {domain_size}");
+        let verifying_key = VerifyingKey::new(&mut reader)?;
+        if domain_size.is_power_of_two() {
+            tracing::debug!("read header done!");
+            Ok(Self {
+                n_vars: u32_to_usize!(n_vars),
+                n_public: u32_to_usize!(n_public),
+                domain_size: u32_to_usize!(domain_size),
+                power: u32_to_usize!(domain_size.ilog2()),
+                n_additions: u32_to_usize!(n_additions),
+                n_constraints: u32_to_usize!(n_constraints),
+                verifying_key,
+            })
+        } else {
+            Err(ZKeyParserError::CorruptedBinFile(format!(
+                "Invalid domain size {domain_size}. Must be power of 2"
+            )))
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! This module defines the [`LazyZKey`] struct, which memory-maps a circom Groth16 zkey and only parses the queries on demand.
+//! In contrast to the [`ZKey`](super::ZKey), the memory required by a [`LazyZKey`] does not grow with the size of the queries, which makes it suitable for very large circuits.
+use std::{fs::File, ops::Range, sync::Arc};
+
+use ark_ec::pairing::Pairing;
+use ark_relations::r1cs::ConstraintMatrices;
+use ark_serialize::SerializationError;
+use memmap2::Mmap;
+
+use crate::{
+    binfile::{self, ZKeyParserError, ZKeyParserResult},
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+
+use super::zkey::{HeaderGroth, VerifyingKey, ZKey, ZKEY_FTYPE};
+
+/// A Groth16 zkey of which only the header, the verifying key and the constraint matrices are held in memory.
+/// The queries are parsed from the memory-mapped zkey file whenever they are accessed via [`LazyPoints::get`].
+pub struct LazyZKey<P: Pairing> {
+    /// amount of public inputs
+    pub n_public: usize,
+    /// domain size
+    pub pow: usize,
+    /// The underlying verification key.
+    pub vk: VerifyingKey<P>,
+    /// beta
+    pub beta_g1: P::G1Affine,
+    /// delta
+    pub delta_g1: P::G1Affine,
+    /// a_query
+    pub a_query: LazyPoints<P::G1Affine>,
+    /// b_query in G1
+    pub b_g1_query: LazyPoints<P::G1Affine>,
+    /// b_query in G2
+    pub b_g2_query: LazyPoints<P::G2Affine>,
+    /// h_query
+    pub h_query: LazyPoints<P::G1Affine>,
+    /// l_query
+    pub l_query: LazyPoints<P::G1Affine>,
+    /// The constraint matrices A, B, and C
+    pub matrices: ConstraintMatrices<P::ScalarField>,
+}
+
+/// A vector of curve points stored in a memory-mapped file, which are only parsed when they are accessed.
+#[derive(Clone)]
+pub struct LazyPoints<A> {
+    mmap: Arc<Mmap>,
+    offset: usize,
+    len: usize,
+    point_size: usize,
+    parse: fn(&[u8], usize) -> Result<Vec<A>, SerializationError>,
+}
+
+impl<A> LazyPoints<A> {
+    fn new(
+        mmap: &Arc<Mmap>,
+        range: Range<usize>,
+        len: usize,
+        point_size: usize,
+        parse: fn(&[u8], usize) -> Result<Vec<A>, SerializationError>,
+    ) -> ZKeyParserResult<Self> {
+        if range.len() != len * point_size {
+            return Err(ZKeyParserError::CorruptedBinFile(format!(
+                "expected a section with {len} points"
+            )));
+        }
+        Ok(Self {
+            mmap: Arc::clone(mmap),
+            offset: range.start,
+            len,
+            point_size,
+            parse,
+        })
+    }
+
+    /// Returns the number of points.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no points.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Parses the points in the given range of indices. Only the parsed points are held in memory, so the memory consumption is proportional to the length of the range.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds.
+    pub fn get(&self, range: Range<usize>) -> ZKeyParserResult<Vec<A>> {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "range {range:?} out of bounds for {} points",
+            self.len
+        );
+        let start = self.offset + range.start * self.point_size;
+        let end = self.offset + range.end * self.point_size;
+        Ok((self.parse)(&self.mmap[start..end], range.len())?)
+    }
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> LazyZKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Memory-maps the zkey file and parses everything except the queries.
+    ///
+    /// The file must not be modified while the [`LazyZKey`] (or any [`LazyPoints`] obtained from it) is alive, see [`Mmap`].
+    pub fn from_file(file: &File) -> ZKeyParserResult<Self> {
+        // SAFETY: The mapping is read-only and we require that the file is not modified while it is mapped.
+        let mmap = Arc::new(unsafe { Mmap::map(file)? });
+        let (ftype, sections) = binfile::section_ranges(&mmap)?;
+        binfile::check_file_type(&ftype, ZKEY_FTYPE)?;
+        let section =
+            |id: usize| {
+                sections.get(id - 1).cloned().flatten().ok_or_else(|| {
+                    ZKeyParserError::CorruptedBinFile(format!("missing section {id}"))
+                })
+            };
+
+        ZKey::<P>::check_protocol_id(&mmap[section(1)?])?;
+        let header = HeaderGroth::<P>::read(&mut &mmap[section(2)?])?;
+        let n_vars = header.n_vars;
+        let n_public = header.n_public;
+        let domain_size = header.domain_size;
+        let n_private = ZKey::<P>::num_private(n_vars, n_public)?;
+
+        let g1_size = P::G1_SERIALIZED_BYTE_SIZE_UNCOMPRESSED;
+        let g2_size = P::G2_SERIALIZED_BYTE_SIZE_UNCOMPRESSED;
+        let a_query = LazyPoints::new(&mmap, section(5)?, n_vars, g1_size, |bytes, num| {
+            P::g1_vec_from_reader(bytes, num)
+        })?;
+        let b_g1_query = LazyPoints::new(&mmap, section(6)?, n_vars, g1_size, |bytes, num| {
+            P::g1_vec_from_reader(bytes, num)
+        })?;
+        let b_g2_query = LazyPoints::new(&mmap, section(7)?, n_vars, g2_size, |bytes, num| {
+            P::g2_vec_from_reader(bytes, num)
+        })?;
+        let l_query = LazyPoints::new(&mmap, section(8)?, n_private, g1_size, |bytes, num| {
+            P::g1_vec_from_reader(bytes, num)
+        })?;
+        let h_query = LazyPoints::new(
+            &mmap,
+            section(9)?,
+            domain_size as usize,
+            g1_size,
+            |bytes, num| P::g1_vec_from_reader(bytes, num),
+        )?;
+
+        let vk = VerifyingKey {
+            alpha_g1: header.alpha_g1,
+            beta_g2: header.beta_g2,
+            gamma_g2: header.gamma_g2,
+            delta_g2: header.delta_g2,
+            gamma_abc_g1: ZKey::<P>::ic(n_public, &mmap[section(3)?])?,
+        };
+        let matrices = ZKey::<P>::matrices(n_vars, n_public, domain_size, &mmap[section(4)?])?;
+        tracing::debug!("groth16 lazy zkey parsing done!");
+        Ok(Self {
+            n_public,
+            pow: header.pow,
+            vk,
+            beta_g1: header.beta_g1,
+            delta_g1: header.delta_g1,
+            a_query,
+            b_g1_query,
+            b_g2_query,
+            h_query,
+            l_query,
+            matrices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use ark_ec::pairing::Pairing;
+
+    use crate::{
+        binfile,
+        groth16::ZKey,
+        traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+    };
+
+    use super::{LazyPoints, LazyZKey};
+
+    fn assert_points_eq<A: PartialEq + std::fmt::Debug>(lazy: &LazyPoints<A>, expected: &[A]) {
+        assert_eq!(lazy.len(), expected.len());
+        assert_eq!(lazy.get(0..lazy.len()).unwrap(), expected);
+        // parse in chunks of different sizes
+        for chunk_size in 1..=expected.len() {
+            let mut parsed = Vec::with_capacity(expected.len());
+            for start in (0..lazy.len()).step_by(chunk_size) {
+                let end = (start + chunk_size).min(lazy.len());
+                parsed.extend(lazy.get(start..end).unwrap());
+            }
+            assert_eq!(parsed, expected);
+        }
+    }
+
+    fn lazy_zkey_matches_zkey<P: Pairing + CircomArkworksPairingBridge>(path: &str)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let zkey = ZKey::<P>::from_reader(File::open(path).unwrap()).unwrap();
+        let lazy_zkey = LazyZKey::<P>::from_file(&File::open(path).unwrap()).unwrap();
+        assert_eq!(lazy_zkey.n_public, zkey.n_public);
+        assert_eq!(lazy_zkey.pow, zkey.pow);
+        assert_eq!(lazy_zkey.beta_g1, zkey.beta_g1);
+        assert_eq!(lazy_zkey.delta_g1, zkey.delta_g1);
+        assert_eq!(lazy_zkey.vk.alpha_g1, zkey.vk.alpha_g1);
+        assert_eq!(lazy_zkey.vk.beta_g2, zkey.vk.beta_g2);
+        assert_eq!(lazy_zkey.vk.gamma_g2, zkey.vk.gamma_g2);
+        assert_eq!(lazy_zkey.vk.delta_g2, zkey.vk.delta_g2);
+        assert_eq!(lazy_zkey.vk.gamma_abc_g1, zkey.vk.gamma_abc_g1);
+        assert_eq!(lazy_zkey.matrices.a, zkey.matrices.a);
+        assert_eq!(lazy_zkey.matrices.b, zkey.matrices.b);
+        assert_eq!(
+            lazy_zkey.matrices.num_constraints,
+            zkey.matrices.num_constraints
+        );
+        assert_points_eq(&lazy_zkey.a_query, &zkey.a_query);
+        assert_points_eq(&lazy_zkey.b_g1_query, &zkey.b_g1_query);
+        assert_points_eq(&lazy_zkey.b_g2_query, &zkey.b_g2_query);
+        assert_points_eq(&lazy_zkey.h_query, &zkey.h_query);
+        assert_points_eq(&lazy_zkey.l_query, &zkey.l_query);
+    }
+
+    #[test]
+    fn lazy_zkey_matches_zkey_bn254() {
+        lazy_zkey_matches_zkey::<Bn254>(
+            "../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey",
+        );
+    }
+
+    #[test]
+    fn lazy_zkey_matches_zkey_bls12_381() {
+        lazy_zkey_matches_zkey::<Bls12_381>(
+            "../../test_vectors/Groth16/bls12_381/multiplier2/circuit.zkey",
+        );
+    }
+
+    const ZKEY_PATH: &str = "../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey";
+
+    /// Corrupts the zkey and checks that both [`ZKey`] and [`LazyZKey`] reject it with the expected error.
+    fn rejects_corrupted_zkey(name: &str, corrupt: impl FnOnce(&mut Vec<u8>), expected: &str) {
+        let mut bytes = std::fs::read(ZKEY_PATH).unwrap();
+        corrupt(&mut bytes);
+        let err = ZKey::<Bn254>::from_reader(bytes.as_slice())
+            .err()
+            .expect("corrupted zkey must be rejected");
+        assert!(err.to_string().contains(expected), "{err}");
+
+        let path =
+            std::env::temp_dir().join(format!("circom-types-{}-{name}.zkey", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+        let result = LazyZKey::<Bn254>::from_file(&File::open(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+        let err = result.err().expect("corrupted zkey must be rejected");
+        assert!(err.to_string().contains(expected), "{err}");
+    }
+
+    /// Returns the offset of the section with the given id.
+    fn section_start(bytes: &[u8], id: usize) -> usize {
+        let (_, sections) = binfile::section_ranges(bytes).unwrap();
+        sections[id - 1].clone().unwrap().start
+    }
+
+    fn write_u32(bytes: &mut [u8], offset: usize, value: u32) {
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn rejects_other_file_type() {
+        rejects_corrupted_zkey(
+            "ftype",
+            |bytes| bytes[..4].copy_from_slice(b"ptau"),
+            "expected file type \"zkey\" but got \"ptau\"",
+        );
+    }
+
+    #[test]
+    fn rejects_other_protocol() {
+        rejects_corrupted_zkey(
+            "protocol",
+            |bytes| {
+                let protocol = section_start(bytes, 1);
+                write_u32(bytes, protocol, 2);
+            },
+            "expected Groth16 zkey (protocol id 1) but got protocol id 2",
+        );
+    }
+
+    #[test]
+    fn rejects_more_public_inputs_than_variables() {
+        rejects_corrupted_zkey(
+            "n-public",
+            |bytes| {
+                let header = section_start(bytes, 2);
+                let n8q = read_u32(bytes, header) as usize;
+                let n8r = read_u32(bytes, header + 4 + n8q) as usize;
+                let n_vars_offset = header + 8 + n8q + n8r;
+                let n_vars = read_u32(bytes, n_vars_offset);
+                write_u32(bytes, n_vars_offset + 4, n_vars);
+            },
+            "cannot hold",
+        );
+    }
+
+    #[test]
+    fn rejects_huge_section_id() {
+        rejects_corrupted_zkey(
+            "section-id",
+            // the id of the first section follows the magic number, the version and the number of sections
+            |bytes| write_u32(bytes, 12, u32::MAX),
+            "exceeds the maximum",
+        );
+    }
+}
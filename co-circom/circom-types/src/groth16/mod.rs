@@ -1,13 +1,18 @@
 //! This module defines types related to Groth16 used in circom and utilities to read these types from files.
+mod lazy_zkey;
 mod proof;
 mod public_input;
+mod setup;
 mod verification_key;
 mod zkey;
 
+pub use lazy_zkey::{LazyPoints, LazyZKey};
 pub use proof::Groth16Proof;
 pub use public_input::JsonPublicInput;
 pub use verification_key::JsonVerificationKey;
-pub use zkey::ZKey;
+pub use zkey::{Beacon, Contribution, MpcParams, ZKey};
+
+pub(crate) use zkey::{read_contribution_params, write_contribution_params};
 
 #[cfg(test)]
 pub(crate) mod test_utils {
@@ -0,0 +1,668 @@
+//! This module implements the circuit specific Groth16 setup of snarkjs. [`ZKey::from_r1cs_and_ptau`] corresponds to
+//! `snarkjs groth16 setup`, [`ZKey::contribute`] to `snarkjs zkey contribute`, and [`ZKey::apply_beacon`] to
+//! `snarkjs zkey beacon`. The resulting zkeys (including the hashes of the contributions) are accepted by
+//! `snarkjs zkey verify`.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{FftField, Field, One, UniformRand, Zero};
+use ark_relations::r1cs::ConstraintMatrices;
+use ark_std::rand::{CryptoRng, Rng};
+use blake2::{Blake2b512, Digest};
+use rayon::prelude::*;
+use sha2::Sha256;
+
+use crate::{
+    ptau::{lagrange_domain, PowersOfTau, SetupError},
+    snarkjs_rng::{point_to_uncompressed, prime_field_from_rng, SnarkjsChaCha},
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+    R1CS,
+};
+
+use super::{zkey::VerifyingKey, Beacon, Contribution, MpcParams, ZKey};
+
+// snarkjs hashes the points of the H query in chunks of this size
+const H_HASH_CHUNK_SIZE: usize = 1 << 14;
+const MAX_BEACON_HASH_LEN: usize = 255;
+const MIN_BEACON_ITERATIONS_EXP: u8 = 10;
+const MAX_BEACON_ITERATIONS_EXP: u8 = 63;
+
+type SignalCoeffs<F> = Vec<Vec<(usize, F)>>;
+
+impl<P: Pairing + CircomArkworksPairingBridge> ZKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Creates the initial zkey of the circuit specific setup for the given [`R1CS`] from a [`PowersOfTau`]
+    /// that is prepared for phase 2. The zkey has no contributions yet, so it is NOT secure to use it for
+    /// proving before at least one contribution was applied via [`ZKey::contribute`].
+    pub fn from_r1cs_and_ptau(r1cs: &R1CS<P>, ptau: &PowersOfTau<P>) -> Result<Self, SetupError> {
+        let lagrange = ptau.lagrange.as_ref().ok_or(SetupError::PtauNotPrepared)?;
+        let n_public =
+            usize::try_from(r1cs.n_pub_out + r1cs.n_pub_in).expect("u32 fits into usize");
+        let n_constraints = r1cs.constraints.len();
+        let n_vars = r1cs.num_variables;
+        // we need room for the constraints and one additional constraint per public input (including the 1)
+        let pow = usize::try_from(usize::BITS - (n_constraints + n_public).leading_zeros())
+            .expect("u32 fits into usize");
+        // the H query lives in the domain of size 2 * domain_size
+        if pow + 1 > usize::try_from(P::ScalarField::TWO_ADICITY).expect("u32 fits into usize") {
+            return Err(SetupError::CircuitTooBig);
+        }
+        if pow > ptau.power {
+            return Err(SetupError::PtauTooSmall(pow, ptau.power));
+        }
+        let domain_size = 1 << pow;
+        tracing::debug!(
+            "groth16 setup with {n_constraints} constraints, {n_vars} variables and domain size {domain_size}"
+        );
+
+        let (a_coeffs, b_coeffs, c_coeffs, matrices) =
+            Self::transpose_constraints(r1cs, n_public, n_vars);
+
+        let l_tau_g1 = lagrange_domain(&lagrange.tau_g1, pow);
+        let l_tau_g2 = lagrange_domain(&lagrange.tau_g2, pow);
+        let l_alpha_tau_g1 = lagrange_domain(&lagrange.alpha_tau_g1, pow);
+        let l_beta_tau_g1 = lagrange_domain(&lagrange.beta_tau_g1, pow);
+
+        tracing::debug!("computing queries...");
+        let a_query = P::G1::normalize_batch(
+            &(0..n_vars)
+                .into_par_iter()
+                .map(|s| msm(l_tau_g1, &a_coeffs[s]))
+                .collect::<Vec<_>>(),
+        );
+        let b_g1_query = P::G1::normalize_batch(
+            &(0..n_vars)
+                .into_par_iter()
+                .map(|s| msm(l_tau_g1, &b_coeffs[s]))
+                .collect::<Vec<_>>(),
+        );
+        let b_g2_query = P::G2::normalize_batch(
+            &(0..n_vars)
+                .into_par_iter()
+                .map(|s| msm(l_tau_g2, &b_coeffs[s]))
+                .collect::<Vec<_>>(),
+        );
+        let mut l_query = P::G1::normalize_batch(
+            &(0..n_vars)
+                .into_par_iter()
+                .map(|s| {
+                    msm(l_beta_tau_g1, &a_coeffs[s])
+                        + msm(l_alpha_tau_g1, &b_coeffs[s])
+                        + msm(l_tau_g1, &c_coeffs[s])
+                })
+                .collect::<Vec<_>>(),
+        );
+        let ic = l_query.drain(..=n_public).collect::<Vec<_>>();
+        // the odd Lagrange polynomials of the domain of double size
+        let h_query = lagrange_domain(&lagrange.tau_g1, pow + 1)
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut zkey = ZKey {
+            n_public,
+            pow,
+            vk: VerifyingKey {
+                alpha_g1: ptau.alpha_tau_g1[0],
+                beta_g2: ptau.beta_g2,
+                gamma_g2: P::G2Affine::generator(),
+                delta_g2: P::G2Affine::generator(),
+                gamma_abc_g1: ic,
+            },
+            beta_g1: ptau.beta_tau_g1[0],
+            delta_g1: P::G1Affine::generator(),
+            a_query,
+            b_g1_query,
+            b_g2_query,
+            h_query,
+            l_query,
+            matrices,
+            mpc_params: MpcParams {
+                cs_hash: [0; 64],
+                contributions: vec![],
+            },
+        };
+        zkey.mpc_params.cs_hash = zkey.cs_hash(ptau)?;
+        tracing::debug!("groth16 setup done!");
+        Ok(zkey)
+    }
+
+    // Collects the coefficients of the constraints per signal. The public input constraints
+    // (one per public signal) are added to the A coefficients, like in snarkjs.
+    #[allow(clippy::type_complexity)]
+    fn transpose_constraints(
+        r1cs: &R1CS<P>,
+        n_public: usize,
+        n_vars: usize,
+    ) -> (
+        SignalCoeffs<P::ScalarField>,
+        SignalCoeffs<P::ScalarField>,
+        SignalCoeffs<P::ScalarField>,
+        ConstraintMatrices<P::ScalarField>,
+    ) {
+        let n_constraints = r1cs.constraints.len();
+        let mut a_coeffs = vec![vec![]; n_vars];
+        let mut b_coeffs = vec![vec![]; n_vars];
+        let mut c_coeffs = vec![vec![]; n_vars];
+        let mut a_matrix = Vec::with_capacity(n_constraints);
+        let mut b_matrix = Vec::with_capacity(n_constraints);
+        // snarkjs holds the linear combinations in objects keyed by the signal, so it always
+        // visits the signals in ascending order
+        let sorted = |lc: &[(usize, P::ScalarField)]| {
+            let mut lc = lc.to_vec();
+            lc.sort_by_key(|(signal, _)| *signal);
+            lc
+        };
+        for (constraint, (a, b, c)) in r1cs.constraints.iter().enumerate() {
+            let (a, b, c) = (sorted(a), sorted(b), sorted(c));
+            for (signal, value) in a.iter() {
+                a_coeffs[*signal].push((constraint, *value));
+            }
+            for (signal, value) in b.iter() {
+                b_coeffs[*signal].push((constraint, *value));
+            }
+            for (signal, value) in c.iter() {
+                c_coeffs[*signal].push((constraint, *value));
+            }
+            a_matrix.push(a.into_iter().map(|(s, v)| (v, s)).collect::<Vec<_>>());
+            b_matrix.push(b.into_iter().map(|(s, v)| (v, s)).collect::<Vec<_>>());
+        }
+        for (signal, coeffs) in a_coeffs.iter_mut().enumerate().take(n_public + 1) {
+            coeffs.push((n_constraints + signal, P::ScalarField::one()));
+        }
+        let a_num_non_zero = a_matrix.iter().map(Vec::len).sum();
+        let b_num_non_zero = b_matrix.iter().map(Vec::len).sum();
+        let matrices = ConstraintMatrices {
+            num_instance_variables: n_public + 1,
+            num_witness_variables: n_vars - n_public,
+            num_constraints: n_constraints,
+            a_num_non_zero,
+            b_num_non_zero,
+            c_num_non_zero: 0,
+            a: a_matrix,
+            b: b_matrix,
+            c: vec![],
+        };
+        (a_coeffs, b_coeffs, c_coeffs, matrices)
+    }
+
+    // The hash snarkjs computes over the initial zkey. Instead of the H query, snarkjs hashes the
+    // points tau^i * (tau^n - 1) * G1 taken from the powers of tau.
+    fn cs_hash(&self, ptau: &PowersOfTau<P>) -> Result<[u8; 64], SetupError> {
+        let tau_g1 = &ptau.tau_g1;
+        let domain_size = 1 << self.pow;
+        let mut hasher = Blake2b512::new();
+        hash_point(&mut hasher, &self.vk.alpha_g1);
+        hash_point(&mut hasher, &self.beta_g1);
+        hash_point(&mut hasher, &self.vk.beta_g2);
+        hash_point(&mut hasher, &self.vk.gamma_g2);
+        hash_point(&mut hasher, &self.delta_g1);
+        hash_point(&mut hasher, &self.vk.delta_g2);
+        hash_points(&mut hasher, &self.vk.gamma_abc_g1);
+
+        // snarkjs uses min(domain_size - 1, chunk size) points for every chunk, therefore the last
+        // chunk may reach over domain_size - 1 points for large circuits
+        let indices = (0..domain_size - 1)
+            .step_by(H_HASH_CHUNK_SIZE)
+            .flat_map(|start| start..start + (domain_size - 1).min(H_HASH_CHUNK_SIZE))
+            .collect::<Vec<_>>();
+        if indices
+            .last()
+            .is_some_and(|i| i + domain_size >= tau_g1.len())
+        {
+            return Err(SetupError::PtauTooSmall(self.pow + 1, ptau.power));
+        }
+        let h_diff = P::G1::normalize_batch(
+            &indices
+                .par_iter()
+                .map(|i| tau_g1[i + domain_size].into_group() - tau_g1[*i])
+                .collect::<Vec<_>>(),
+        );
+        hasher.update(usize_to_u32(domain_size - 1).to_be_bytes());
+        for p in h_diff.iter() {
+            hash_point(&mut hasher, p);
+        }
+
+        hash_points(&mut hasher, &self.l_query);
+        hash_points(&mut hasher, &self.a_query);
+        hash_points(&mut hasher, &self.b_g1_query);
+        hash_points(&mut hasher, &self.b_g2_query);
+        Ok(finalize(hasher))
+    }
+
+    /// Applies a contribution with a random secret to the circuit specific setup and returns the hash of the contribution.
+    pub fn contribute<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        name: Option<String>,
+    ) -> [u8; 64] {
+        let delta = loop {
+            let delta = P::ScalarField::rand(rng);
+            if !delta.is_zero() {
+                break delta;
+            }
+        };
+        let g1_s = P::G1::rand(rng).into_affine();
+        self.add_contribution(delta, g1_s, name, None)
+    }
+
+    /// Applies a contribution derived from a random beacon to the circuit specific setup and returns the hash of the
+    /// contribution. The beacon hash is hashed 2^`num_iterations_exp` times with SHA-256 to derive the secret.
+    pub fn apply_beacon(
+        &mut self,
+        beacon_hash: Vec<u8>,
+        num_iterations_exp: u8,
+        name: Option<String>,
+    ) -> Result<[u8; 64], SetupError> {
+        if beacon_hash.is_empty() {
+            return Err(SetupError::InvalidBeacon("beacon hash is empty".to_owned()));
+        }
+        if beacon_hash.len() > MAX_BEACON_HASH_LEN {
+            return Err(SetupError::InvalidBeacon(format!(
+                "beacon hash must not be longer than {MAX_BEACON_HASH_LEN} bytes"
+            )));
+        }
+        if !(MIN_BEACON_ITERATIONS_EXP..=MAX_BEACON_ITERATIONS_EXP).contains(&num_iterations_exp) {
+            return Err(SetupError::InvalidBeacon(format!(
+                "num_iterations_exp must be between {MIN_BEACON_ITERATIONS_EXP} and {MAX_BEACON_ITERATIONS_EXP}"
+            )));
+        }
+        let mut rng = beacon_rng(&beacon_hash, num_iterations_exp);
+        let delta = prime_field_from_rng::<P::ScalarField>(&mut rng);
+        let g1_s = P::g1_from_snarkjs_rng(&mut rng);
+        let beacon = Beacon {
+            num_iterations_exp,
+            hash: beacon_hash,
+        };
+        Ok(self.add_contribution(delta, g1_s, name, Some(beacon)))
+    }
+
+    fn add_contribution(
+        &mut self,
+        delta: P::ScalarField,
+        g1_s: P::G1Affine,
+        name: Option<String>,
+        beacon: Option<Beacon>,
+    ) -> [u8; 64] {
+        let mut transcript_hasher = Blake2b512::new();
+        transcript_hasher.update(self.mpc_params.cs_hash);
+        for contribution in self.mpc_params.contributions.iter() {
+            hash_pub_key::<P>(&mut transcript_hasher, contribution);
+        }
+        let g1_sx = (g1_s * delta).into_affine();
+        hash_point(&mut transcript_hasher, &g1_s);
+        hash_point(&mut transcript_hasher, &g1_sx);
+        let transcript = finalize(transcript_hasher);
+        let g2_sp = P::g2_from_snarkjs_rng(&mut SnarkjsChaCha::from_hash(&transcript));
+        let g2_spx = (g2_sp * delta).into_affine();
+
+        self.delta_g1 = (self.delta_g1 * delta).into_affine();
+        self.vk.delta_g2 = (self.vk.delta_g2 * delta).into_affine();
+        let delta_inv = delta.inverse().expect("delta is not zero");
+        self.l_query = scale_points(&self.l_query, delta_inv);
+        self.h_query = scale_points(&self.h_query, delta_inv);
+
+        self.mpc_params.contributions.push(Contribution {
+            delta_after: self.delta_g1,
+            g1_s,
+            g1_sx,
+            g2_spx,
+            transcript,
+            name,
+            beacon,
+        });
+        let mut contribution_hasher = Blake2b512::new();
+        hash_pub_key::<P>(
+            &mut contribution_hasher,
+            self.mpc_params.contributions.last().expect("just pushed"),
+        );
+        finalize(contribution_hasher)
+    }
+}
+
+fn msm<G: AffineRepr>(bases: &[G], coeffs: &[(usize, G::ScalarField)]) -> G::Group {
+    let (bases, scalars): (Vec<_>, Vec<_>) = coeffs.iter().map(|(i, v)| (bases[*i], *v)).unzip();
+    G::Group::msm_unchecked(&bases, &scalars)
+}
+
+fn scale_points<G: AffineRepr>(points: &[G], factor: G::ScalarField) -> Vec<G> {
+    G::Group::normalize_batch(&points.par_iter().map(|p| *p * factor).collect::<Vec<_>>())
+}
+
+fn usize_to_u32(x: usize) -> u32 {
+    u32::try_from(x).expect("fits into u32")
+}
+
+fn finalize(hasher: Blake2b512) -> [u8; 64] {
+    let mut hash = [0; 64];
+    hash.copy_from_slice(&hasher.finalize());
+    hash
+}
+
+fn hash_point<G: AffineRepr>(hasher: &mut Blake2b512, p: &G) {
+    let mut buf = Vec::new();
+    point_to_uncompressed(p, &mut buf);
+    hasher.update(buf);
+}
+
+fn hash_points<G: AffineRepr>(hasher: &mut Blake2b512, points: &[G]) {
+    hasher.update(usize_to_u32(points.len()).to_be_bytes());
+    let mut buf = Vec::new();
+    for p in points {
+        point_to_uncompressed(p, &mut buf);
+    }
+    hasher.update(buf);
+}
+
+fn hash_pub_key<P: Pairing>(hasher: &mut Blake2b512, contribution: &Contribution<P>) {
+    hash_point(hasher, &contribution.delta_after);
+    hash_point(hasher, &contribution.g1_s);
+    hash_point(hasher, &contribution.g1_sx);
+    hash_point(hasher, &contribution.g2_spx);
+    hasher.update(contribution.transcript);
+}
+
+fn beacon_rng(beacon_hash: &[u8], num_iterations_exp: u8) -> SnarkjsChaCha {
+    let mut cur_hash = beacon_hash.to_vec();
+    for _ in 0..1u64 << num_iterations_exp {
+        cur_hash = Sha256::digest(&cur_hash).to_vec();
+    }
+    SnarkjsChaCha::from_hash(&cur_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptau::evaluation_domain;
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use ark_poly::EvaluationDomain;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use std::fs::File;
+
+    fn to_g1<P: Pairing>(s: P::ScalarField) -> P::G1Affine {
+        (P::G1Affine::generator() * s).into_affine()
+    }
+
+    fn setup_matches_secrets<P: Pairing + CircomArkworksPairingBridge>(
+        power: usize,
+        r1cs: &str,
+        zkey: &str,
+    ) where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let mut rng = ark_std::test_rng();
+        let tau = P::ScalarField::rand(&mut rng);
+        let alpha = P::ScalarField::rand(&mut rng);
+        let beta = P::ScalarField::rand(&mut rng);
+        let ptau = PowersOfTau::<P>::from_secrets(power, tau, alpha, beta);
+        let r1cs = R1CS::<P>::from_reader(File::open(r1cs).unwrap()).unwrap();
+        let snarkjs_zkey = ZKey::<P>::from_reader(File::open(zkey).unwrap()).unwrap();
+        let zkey = ZKey::from_r1cs_and_ptau(&r1cs, &ptau).unwrap();
+
+        assert_eq!(zkey.pow, snarkjs_zkey.pow);
+        assert_eq!(zkey.n_public, snarkjs_zkey.n_public);
+        assert_eq!(zkey.matrices.a, snarkjs_zkey.matrices.a);
+        assert_eq!(zkey.matrices.b, snarkjs_zkey.matrices.b);
+        assert_eq!(
+            zkey.matrices.num_constraints,
+            snarkjs_zkey.matrices.num_constraints
+        );
+        assert!(zkey.mpc_params.contributions.is_empty());
+
+        let n = 1 << zkey.pow;
+        let l = evaluation_domain::<P::ScalarField>(n)
+            .unwrap()
+            .evaluate_all_lagrange_coefficients(tau);
+        let n_constraints = r1cs.constraints.len();
+        let eval = |lc: &[(usize, P::ScalarField)], s: usize| {
+            lc.iter()
+                .filter(|(signal, _)| *signal == s)
+                .map(|(_, v)| *v)
+                .sum::<P::ScalarField>()
+        };
+        for s in 0..r1cs.num_variables {
+            let (mut u, mut v, mut w) = (
+                P::ScalarField::zero(),
+                P::ScalarField::zero(),
+                P::ScalarField::zero(),
+            );
+            for (c, (a, b, cc)) in r1cs.constraints.iter().enumerate() {
+                u += eval(a, s) * l[c];
+                v += eval(b, s) * l[c];
+                w += eval(cc, s) * l[c];
+            }
+            if s <= zkey.n_public {
+                u += l[n_constraints + s];
+            }
+            assert_eq!(zkey.a_query[s], to_g1::<P>(u));
+            assert_eq!(zkey.b_g1_query[s], to_g1::<P>(v));
+            assert_eq!(
+                zkey.b_g2_query[s],
+                (P::G2Affine::generator() * v).into_affine()
+            );
+            let expected = to_g1::<P>(beta * u + alpha * v + w);
+            if s <= zkey.n_public {
+                assert_eq!(zkey.vk.gamma_abc_g1[s], expected);
+            } else {
+                assert_eq!(zkey.l_query[s - zkey.n_public - 1], expected);
+            }
+        }
+        let l2 = evaluation_domain::<P::ScalarField>(2 * n)
+            .unwrap()
+            .evaluate_all_lagrange_coefficients(tau);
+        let h_query = (0..n)
+            .map(|i| to_g1::<P>(l2[2 * i + 1]))
+            .collect::<Vec<_>>();
+        assert_eq!(zkey.h_query, h_query);
+        assert_eq!(zkey.vk.alpha_g1, to_g1::<P>(alpha));
+        assert_eq!(zkey.beta_g1, to_g1::<P>(beta));
+
+        // the initial zkey survives serialization
+        let mut bytes = Vec::new();
+        zkey.to_writer(&mut bytes).unwrap();
+        let read = ZKey::<P>::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(read.mpc_params, zkey.mpc_params);
+        assert_eq!(read.a_query, zkey.a_query);
+        assert_eq!(read.l_query, zkey.l_query);
+        assert_eq!(read.h_query, zkey.h_query);
+        assert_eq!(read.matrices.a, zkey.matrices.a);
+    }
+
+    #[test]
+    fn setup_bn254_mult2() {
+        setup_matches_secrets::<Bn254>(
+            3,
+            "../../test_vectors/Groth16/bn254/multiplier2/circuit.r1cs",
+            "../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey",
+        );
+    }
+
+    #[test]
+    fn setup_bn254_poseidon() {
+        setup_matches_secrets::<Bn254>(
+            9,
+            "../../test_vectors/Groth16/bn254/poseidon/circuit.r1cs",
+            "../../test_vectors/Groth16/bn254/poseidon/circuit.zkey",
+        );
+    }
+
+    #[test]
+    fn setup_bls12_381_mult2() {
+        setup_matches_secrets::<Bls12_381>(
+            3,
+            "../../test_vectors/Groth16/bls12_381/multiplier2/circuit.r1cs",
+            "../../test_vectors/Groth16/bls12_381/multiplier2/circuit.zkey",
+        );
+    }
+
+    #[test]
+    fn setup_fails_for_small_or_unprepared_ptau() {
+        let mut rng = ark_std::test_rng();
+        let r1cs = R1CS::<Bn254>::from_reader(
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.r1cs").unwrap(),
+        )
+        .unwrap();
+        let (tau, alpha, beta) = (
+            ark_bn254::Fr::rand(&mut rng),
+            ark_bn254::Fr::rand(&mut rng),
+            ark_bn254::Fr::rand(&mut rng),
+        );
+        let ptau = PowersOfTau::<Bn254>::from_secrets(1, tau, alpha, beta);
+        assert!(matches!(
+            ZKey::from_r1cs_and_ptau(&r1cs, &ptau),
+            Err(SetupError::PtauTooSmall(2, 1))
+        ));
+        let mut ptau = PowersOfTau::<Bn254>::from_secrets(2, tau, alpha, beta);
+        ptau.lagrange = None;
+        assert!(matches!(
+            ZKey::from_r1cs_and_ptau(&r1cs, &ptau),
+            Err(SetupError::PtauNotPrepared)
+        ));
+    }
+
+    // the checks of "snarkjs zkey verify" for the contributions
+    fn verify_contributions<P: Pairing + CircomArkworksPairingBridge>(zkey: &ZKey<P>)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let mut transcript_hasher = Blake2b512::new();
+        transcript_hasher.update(zkey.mpc_params.cs_hash);
+        let mut delta_before = P::G1Affine::generator();
+        for contribution in zkey.mpc_params.contributions.iter() {
+            let mut hasher = transcript_hasher.clone();
+            hash_point(&mut hasher, &contribution.g1_s);
+            hash_point(&mut hasher, &contribution.g1_sx);
+            assert_eq!(finalize(hasher), contribution.transcript);
+            let g2_sp =
+                P::g2_from_snarkjs_rng(&mut SnarkjsChaCha::from_hash(&contribution.transcript));
+            assert_eq!(
+                P::pairing(contribution.g1_s, contribution.g2_spx),
+                P::pairing(contribution.g1_sx, g2_sp)
+            );
+            assert_eq!(
+                P::pairing(delta_before, contribution.g2_spx),
+                P::pairing(contribution.delta_after, g2_sp)
+            );
+            hash_pub_key::<P>(&mut transcript_hasher, contribution);
+            delta_before = contribution.delta_after;
+        }
+        assert_eq!(delta_before, zkey.delta_g1);
+        assert_eq!(
+            P::pairing(zkey.delta_g1, P::G2Affine::generator()),
+            P::pairing(P::G1Affine::generator(), zkey.vk.delta_g2)
+        );
+    }
+
+    #[test]
+    fn snarkjs_contributions_verify() {
+        for zkey in [
+            "../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey",
+            "../../test_vectors/Groth16/bn254/poseidon/circuit.zkey",
+        ] {
+            verify_contributions(&ZKey::<Bn254>::from_reader(File::open(zkey).unwrap()).unwrap());
+        }
+        for zkey in [
+            "../../test_vectors/Groth16/bls12_381/multiplier2/circuit.zkey",
+            "../../test_vectors/Groth16/bls12_381/poseidon/circuit.zkey",
+        ] {
+            verify_contributions(
+                &ZKey::<Bls12_381>::from_reader(File::open(zkey).unwrap()).unwrap(),
+            );
+        }
+    }
+
+    fn contribute_and_beacon<P: Pairing + CircomArkworksPairingBridge>(r1cs: &str)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let mut rng = ark_std::test_rng();
+        let ptau = PowersOfTau::<P>::from_secrets(
+            3,
+            P::ScalarField::rand(&mut rng),
+            P::ScalarField::rand(&mut rng),
+            P::ScalarField::rand(&mut rng),
+        );
+        let r1cs = R1CS::<P>::from_reader(File::open(r1cs).unwrap()).unwrap();
+        let initial = ZKey::from_r1cs_and_ptau(&r1cs, &ptau).unwrap();
+        let mut zkey = initial.clone();
+        let mut rng = StdRng::seed_from_u64(42);
+        zkey.contribute(&mut rng, Some("first".to_owned()));
+        zkey.contribute(&mut rng, None);
+        zkey.apply_beacon(vec![0x42; 32], 10, Some("final beacon".to_owned()))
+            .unwrap();
+        verify_contributions(&zkey);
+        assert_eq!(zkey.mpc_params.contributions.len(), 3);
+        assert_eq!(
+            zkey.mpc_params.contributions[2].beacon,
+            Some(Beacon {
+                num_iterations_exp: 10,
+                hash: vec![0x42; 32]
+            })
+        );
+        // L and H are divided by delta
+        for (before, after) in initial.h_query.iter().zip(zkey.h_query.iter()) {
+            assert_eq!(
+                P::pairing(*before, P::G2Affine::generator()),
+                P::pairing(*after, zkey.vk.delta_g2)
+            );
+        }
+        for (before, after) in initial.l_query.iter().zip(zkey.l_query.iter()) {
+            assert_eq!(
+                P::pairing(*before, P::G2Affine::generator()),
+                P::pairing(*after, zkey.vk.delta_g2)
+            );
+        }
+        assert_eq!(initial.a_query, zkey.a_query);
+        // the beacon is deterministic
+        let mut other = initial.clone();
+        let hash = other.apply_beacon(vec![0x42; 32], 10, None).unwrap();
+        let mut again = initial;
+        assert_eq!(again.apply_beacon(vec![0x42; 32], 10, None).unwrap(), hash);
+        assert_eq!(again.delta_g1, other.delta_g1);
+
+        let mut bytes = Vec::new();
+        zkey.to_writer(&mut bytes).unwrap();
+        let read = ZKey::<P>::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(read.mpc_params, zkey.mpc_params);
+    }
+
+    #[test]
+    fn contribute_and_beacon_bn254() {
+        contribute_and_beacon::<Bn254>("../../test_vectors/Groth16/bn254/multiplier2/circuit.r1cs");
+    }
+
+    #[test]
+    fn contribute_and_beacon_bls12_381() {
+        contribute_and_beacon::<Bls12_381>(
+            "../../test_vectors/Groth16/bls12_381/multiplier2/circuit.r1cs",
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_beacon() {
+        let mut rng = ark_std::test_rng();
+        let ptau = PowersOfTau::<Bn254>::from_secrets(
+            2,
+            ark_bn254::Fr::rand(&mut rng),
+            ark_bn254::Fr::rand(&mut rng),
+            ark_bn254::Fr::rand(&mut rng),
+        );
+        let r1cs = R1CS::<Bn254>::from_reader(
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.r1cs").unwrap(),
+        )
+        .unwrap();
+        let mut zkey = ZKey::from_r1cs_and_ptau(&r1cs, &ptau).unwrap();
+        assert!(zkey.apply_beacon(vec![], 10, None).is_err());
+        assert!(zkey.apply_beacon(vec![1; 256], 10, None).is_err());
+        assert!(zkey.apply_beacon(vec![1; 32], 9, None).is_err());
+        assert!(zkey.apply_beacon(vec![1; 32], 64, None).is_err());
+        assert!(zkey.mpc_params.contributions.is_empty());
+    }
+}
@@ -24,17 +24,19 @@
 //IN CONNECTION WITH THE SOFTWARE O THE USE OR OTHER
 //DEALINGS IN THE SOFTWARE.R
 
-//! This module defines the [`ZKey`] struct that implements deserialization of circom zkey files via [`ZKey::from_reader`].
+//! This module defines the [`ZKey`] struct that implements deserialization of circom zkey files via [`ZKey::from_reader`]
+//! and serialization via [`ZKey::to_writer`].
 //! Inspired by <https://github.com/arkworks-rs/circom-compat/blob/170b10fc9ed182b5f72ecf379033dda023d0bf07/src/zkey.rs>
 use ark_ec::pairing::Pairing;
-use ark_ff::PrimeField;
+use ark_ff::{One, PrimeField};
 use ark_relations::r1cs::ConstraintMatrices;
 use ark_serialize::CanonicalDeserialize;
+use byteorder::{LittleEndian, WriteBytesExt};
 
-use std::io::Read;
+use std::io::{Read, Write};
 
 use crate::{
-    binfile::{BinFile, ZKeyParserError, ZKeyParserResult},
+    binfile::{self, BinFile, ZKeyParserError, ZKeyParserResult},
     traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
 };
 
@@ -43,6 +45,20 @@ macro_rules! u32_to_usize {
         usize::try_from($x).expect("u32 fits into usize")
     };
 }
+
+macro_rules! usize_to_u32 {
+    ($x: expr) => {
+        u32::try_from($x).expect("circom zkeys use u32 for sizes and indices")
+    };
+}
+
+pub(crate) const ZKEY_FTYPE: &str = "zkey";
+const ZKEY_VERSION: u32 = 1;
+const GROTH16_PROTOCOL_ID: u32 = 1;
+// snarkjs writes the sections of a fresh zkey in this order. Every contribution rewrites
+// the zkey with the sections sorted by their id.
+const NEW_ZKEY_SECTION_ORDER: [u32; 10] = [1, 2, 4, 3, 9, 8, 5, 6, 7, 10];
+const CONTRIBUTED_ZKEY_SECTION_ORDER: [u32; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
 /// Represents a zkey in the format defined by circom. Implements [`ZKey::from_reader`] to deserialize a zkey from a reader.
 #[derive(Clone)]
 pub struct ZKey<P: Pairing> {
@@ -68,6 +84,45 @@ pub struct ZKey<P: Pairing> {
     pub l_query: Vec<P::G1Affine>,
     /// The constraint matrices A, B, and C
     pub matrices: ConstraintMatrices<P::ScalarField>,
+    /// The contributions to the circuit specific part of the setup
+    pub mpc_params: MpcParams<P>,
+}
+
+/// The parameters of the circuit specific (phase 2) setup ceremony, as stored in section 10 of a zkey.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MpcParams<P: Pairing> {
+    /// The hash of the initial zkey before any contributions
+    pub cs_hash: [u8; 64],
+    /// The contributions in the order they were applied
+    pub contributions: Vec<Contribution<P>>,
+}
+
+/// A single contribution to the circuit specific setup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contribution<P: Pairing> {
+    /// delta in G1 after applying this contribution
+    pub delta_after: P::G1Affine,
+    /// The random point s in G1 of the proof of knowledge
+    pub g1_s: P::G1Affine,
+    /// s multiplied with the contributed secret
+    pub g1_sx: P::G1Affine,
+    /// The point in G2 derived from the transcript, multiplied with the contributed secret
+    pub g2_spx: P::G2Affine,
+    /// The hash of the transcript up to this contribution
+    pub transcript: [u8; 64],
+    /// The (optional) name of the contributor
+    pub name: Option<String>,
+    /// The beacon parameters, if the contribution was derived from a random beacon
+    pub beacon: Option<Beacon>,
+}
+
+/// The parameters of a random beacon contribution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Beacon {
+    /// ld of the number of hash iterations
+    pub num_iterations_exp: u8,
+    /// The beacon value
+    pub hash: Vec<u8>,
 }
 
 /// The verifying key encapsulated in the zkey. This is NOT the key used for verifying (although it has the same values).
@@ -87,17 +142,17 @@ pub struct VerifyingKey<P: Pairing> {
 }
 
 #[derive(Clone, Debug)]
-struct HeaderGroth<P: Pairing> {
-    n_vars: usize,
-    n_public: usize,
-    domain_size: u32,
-    pow: usize,
-    alpha_g1: P::G1Affine,
-    beta_g1: P::G1Affine,
-    beta_g2: P::G2Affine,
-    gamma_g2: P::G2Affine,
-    delta_g1: P::G1Affine,
-    delta_g2: P::G2Affine,
+pub(crate) struct HeaderGroth<P: Pairing> {
+    pub(crate) n_vars: usize,
+    pub(crate) n_public: usize,
+    pub(crate) domain_size: u32,
+    pub(crate) pow: usize,
+    pub(crate) alpha_g1: P::G1Affine,
+    pub(crate) beta_g1: P::G1Affine,
+    pub(crate) beta_g2: P::G2Affine,
+    pub(crate) gamma_g2: P::G2Affine,
+    pub(crate) delta_g1: P::G1Affine,
+    pub(crate) delta_g2: P::G2Affine,
 }
 
 impl<P: Pairing + CircomArkworksPairingBridge> ZKey<P>
@@ -110,7 +165,28 @@ where
         BinFile::<P>::new(&mut reader)?.try_into()
     }
 
-    fn ic<R: Read>(n_public: usize, reader: R) -> ZKeyParserResult<Vec<P::G1Affine>> {
+    /// Checks that the protocol section of the zkey is the one of Groth16.
+    pub(crate) fn check_protocol_id<R: Read>(mut protocol_section: R) -> ZKeyParserResult<()> {
+        let protocol_id = u32::deserialize_uncompressed(&mut protocol_section)?;
+        if protocol_id != GROTH16_PROTOCOL_ID {
+            return Err(ZKeyParserError::CorruptedBinFile(format!(
+                "expected Groth16 zkey (protocol id {GROTH16_PROTOCOL_ID}) but got protocol id {protocol_id}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the number of private variables, i.e., the length of the l query.
+    pub(crate) fn num_private(n_vars: usize, n_public: usize) -> ZKeyParserResult<usize> {
+        // the first variable is the constant 1
+        n_vars.checked_sub(n_public + 1).ok_or_else(|| {
+            ZKeyParserError::CorruptedBinFile(format!(
+                "{n_vars} variables cannot hold {n_public} public inputs and the constant 1"
+            ))
+        })
+    }
+
+    pub(crate) fn ic<R: Read>(n_public: usize, reader: R) -> ZKeyParserResult<Vec<P::G1Affine>> {
         // the range is non-inclusive so we do +1 to get all inputs
         Ok(P::g1_vec_from_reader(reader, n_public + 1)?)
     }
@@ -134,6 +210,312 @@ where
     fn h_query<R: Read>(n_vars: usize, reader: R) -> ZKeyParserResult<Vec<P::G1Affine>> {
         Ok(P::g1_vec_from_reader(reader, n_vars)?)
     }
+
+    pub(crate) fn matrices<R: Read>(
+        n_vars: usize,
+        n_public: usize,
+        domain_size: u32,
+        mut matrices_section: R,
+    ) -> ZKeyParserResult<ConstraintMatrices<P::ScalarField>> {
+        // this function (an all following uses) assumes that values are encoded in little-endian
+        let num_coeffs = u32::deserialize_uncompressed(&mut matrices_section)?;
+
+        // instantiate AB
+        let mut matrices = vec![vec![vec![]; domain_size as usize]; 2];
+        let mut max_constraint_index = 0;
+        for _ in 0..num_coeffs {
+            let matrix = u32::deserialize_uncompressed(&mut matrices_section)?;
+            let constraint = u32::deserialize_uncompressed(&mut matrices_section)?;
+            let signal = u32::deserialize_uncompressed(&mut matrices_section)?;
+
+            let value = P::ScalarField::from_reader_for_groth16_zkey(&mut matrices_section)?;
+            max_constraint_index = std::cmp::max(max_constraint_index, constraint);
+            matrices
+                .get_mut(matrix as usize)
+                .and_then(|m| m.get_mut(constraint as usize))
+                .ok_or_else(|| {
+                    ZKeyParserError::CorruptedBinFile(format!(
+                        "coefficient of constraint {constraint} in matrix {matrix} is out of bounds"
+                    ))
+                })?
+                .push((value, signal as usize));
+        }
+
+        let num_constraints = (max_constraint_index as usize)
+            .checked_sub(n_public)
+            .ok_or_else(|| {
+                ZKeyParserError::CorruptedBinFile("fewer constraints than public inputs".to_owned())
+            })?;
+        // Remove the public input constraints, Arkworks adds them later
+        matrices.iter_mut().for_each(|m| {
+            m.truncate(num_constraints);
+        });
+
+        // This is taken from Arkworks' to_matrices() function
+        let a = matrices[0].clone();
+        let b = matrices[1].clone();
+        let a_num_non_zero: usize = a.iter().map(|lc| lc.len()).sum();
+        let b_num_non_zero: usize = b.iter().map(|lc| lc.len()).sum();
+
+        Ok(ConstraintMatrices {
+            num_instance_variables: n_public + 1,
+            num_witness_variables: n_vars - n_public,
+            num_constraints,
+
+            a_num_non_zero,
+            b_num_non_zero,
+            c_num_non_zero: 0,
+
+            a,
+            b,
+            c: vec![],
+        })
+    }
+
+    /// Serializes the [`ZKey`] into a writer. The output is byte-exact with the zkeys written by snarkjs.
+    pub fn to_writer<W: Write>(&self, writer: W) -> ZKeyParserResult<()> {
+        let mut protocol = Vec::new();
+        protocol.write_u32::<LittleEndian>(GROTH16_PROTOCOL_ID)?;
+
+        let mut header = Vec::new();
+        binfile::write_zkey_field_header::<P, _>(&mut header)?;
+        header.write_u32::<LittleEndian>(usize_to_u32!(self.a_query.len()))?;
+        header.write_u32::<LittleEndian>(usize_to_u32!(self.n_public))?;
+        header.write_u32::<LittleEndian>(usize_to_u32!(1_usize << self.pow))?;
+        P::g1_to_writer(&self.vk.alpha_g1, &mut header)?;
+        P::g1_to_writer(&self.beta_g1, &mut header)?;
+        P::g2_to_writer(&self.vk.beta_g2, &mut header)?;
+        P::g2_to_writer(&self.vk.gamma_g2, &mut header)?;
+        P::g1_to_writer(&self.delta_g1, &mut header)?;
+        P::g2_to_writer(&self.vk.delta_g2, &mut header)?;
+
+        let mut ic = Vec::new();
+        P::g1_vec_to_writer(&self.vk.gamma_abc_g1, &mut ic)?;
+        let mut a_query = Vec::new();
+        P::g1_vec_to_writer(&self.a_query, &mut a_query)?;
+        let mut b_g1_query = Vec::new();
+        P::g1_vec_to_writer(&self.b_g1_query, &mut b_g1_query)?;
+        let mut b_g2_query = Vec::new();
+        P::g2_vec_to_writer(&self.b_g2_query, &mut b_g2_query)?;
+        let mut l_query = Vec::new();
+        P::g1_vec_to_writer(&self.l_query, &mut l_query)?;
+        let mut h_query = Vec::new();
+        P::g1_vec_to_writer(&self.h_query, &mut h_query)?;
+
+        let mut mpc_params = Vec::new();
+        self.mpc_params.write(&mut mpc_params)?;
+
+        let mut sections = vec![
+            (1, protocol),
+            (2, header),
+            (3, ic),
+            (4, self.matrices_section()?),
+            (5, a_query),
+            (6, b_g1_query),
+            (7, b_g2_query),
+            (8, l_query),
+            (9, h_query),
+            (10, mpc_params),
+        ];
+        let order = if self.mpc_params.contributions.is_empty() {
+            NEW_ZKEY_SECTION_ORDER
+        } else {
+            CONTRIBUTED_ZKEY_SECTION_ORDER
+        };
+        sections.sort_by_key(|(id, _)| {
+            order
+                .iter()
+                .position(|x| x == id)
+                .expect("all sections are in order")
+        });
+        binfile::write_bin_file(writer, ZKEY_FTYPE, ZKEY_VERSION, &sections)?;
+        Ok(())
+    }
+
+    fn matrices_section(&self) -> ZKeyParserResult<Vec<u8>> {
+        let num_constraints = self.matrices.num_constraints;
+        let mut num_coeffs = 0;
+        let mut coeffs = Vec::new();
+        let mut write_coeff =
+            |matrix: u32, constraint: usize, signal: usize, value: &P::ScalarField| {
+                num_coeffs += 1;
+                coeffs.write_u32::<LittleEndian>(matrix)?;
+                coeffs.write_u32::<LittleEndian>(usize_to_u32!(constraint))?;
+                coeffs.write_u32::<LittleEndian>(usize_to_u32!(signal))?;
+                value.to_writer_for_groth16_zkey(&mut coeffs)?;
+                ZKeyParserResult::Ok(())
+            };
+        for constraint in 0..num_constraints {
+            for (value, signal) in self.matrices.a[constraint].iter() {
+                write_coeff(0, constraint, *signal, value)?;
+            }
+            for (value, signal) in self.matrices.b[constraint].iter() {
+                write_coeff(1, constraint, *signal, value)?;
+            }
+        }
+        // the public input constraints that we removed during parsing
+        for signal in 0..=self.n_public {
+            write_coeff(0, num_constraints + signal, signal, &P::ScalarField::one())?;
+        }
+        let mut section = Vec::with_capacity(4 + coeffs.len());
+        section.write_u32::<LittleEndian>(num_coeffs)?;
+        section.extend(coeffs);
+        Ok(section)
+    }
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> MpcParams<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn read<R: Read>(mut reader: R) -> ZKeyParserResult<Self> {
+        let mut cs_hash = [0u8; 64];
+        reader.read_exact(&mut cs_hash)?;
+        let num_contributions = u32::deserialize_uncompressed(&mut reader)?;
+        let contributions = (0..num_contributions)
+            .map(|_| Contribution::read(&mut reader))
+            .collect::<ZKeyParserResult<Vec<_>>>()?;
+        Ok(Self {
+            cs_hash,
+            contributions,
+        })
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> ZKeyParserResult<()> {
+        writer.write_all(&self.cs_hash)?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.contributions.len()))?;
+        for contribution in self.contributions.iter() {
+            contribution.write(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+// the ids of the optional parameters of a contribution
+const PARAM_NAME: u8 = 1;
+const PARAM_NUM_ITERATIONS_EXP: u8 = 2;
+const PARAM_BEACON_HASH: u8 = 3;
+const CONTRIBUTION_TYPE_BEACON: u32 = 1;
+
+fn split_length_prefixed(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (len, rest) = bytes.split_first()?;
+    let len = usize::from(*len);
+    if rest.len() < len {
+        None
+    } else {
+        Some(rest.split_at(len))
+    }
+}
+
+/// Reads the type and the optional parameters of a contribution. The encoding is the same for
+/// contributions to zkeys and ptaus.
+pub(crate) fn read_contribution_params<R: Read>(
+    mut reader: R,
+) -> ZKeyParserResult<(Option<String>, Option<Beacon>)> {
+    let contribution_type = u32::deserialize_uncompressed(&mut reader)?;
+    let params_len = u32_to_usize!(u32::deserialize_uncompressed(&mut reader)?);
+    let mut params = vec![0u8; params_len];
+    reader.read_exact(&mut params)?;
+
+    let corrupted = || ZKeyParserError::CorruptedBinFile("invalid contribution".to_owned());
+    let mut name = None;
+    let mut num_iterations_exp = None;
+    let mut beacon_hash = None;
+    let mut params = params.as_slice();
+    while let Some((param, rest)) = params.split_first() {
+        match *param {
+            PARAM_NAME => {
+                let (value, rest) = split_length_prefixed(rest).ok_or_else(corrupted)?;
+                name = Some(String::from_utf8(value.to_vec()).map_err(|_| corrupted())?);
+                params = rest;
+            }
+            PARAM_NUM_ITERATIONS_EXP => {
+                let (value, rest) = rest.split_first().ok_or_else(corrupted)?;
+                num_iterations_exp = Some(*value);
+                params = rest;
+            }
+            PARAM_BEACON_HASH => {
+                let (value, rest) = split_length_prefixed(rest).ok_or_else(corrupted)?;
+                beacon_hash = Some(value.to_vec());
+                params = rest;
+            }
+            _ => return Err(corrupted()),
+        }
+    }
+    let beacon = match (contribution_type, num_iterations_exp, beacon_hash) {
+        (0, _, _) => None,
+        (CONTRIBUTION_TYPE_BEACON, Some(num_iterations_exp), Some(hash)) => Some(Beacon {
+            num_iterations_exp,
+            hash,
+        }),
+        _ => return Err(corrupted()),
+    };
+    Ok((name, beacon))
+}
+
+/// Writes the type and the optional parameters of a contribution, i.e., the inverse of [`read_contribution_params`].
+pub(crate) fn write_contribution_params<W: Write>(
+    mut writer: W,
+    name: Option<&str>,
+    beacon: Option<&Beacon>,
+) -> ZKeyParserResult<()> {
+    let too_long =
+        || ZKeyParserError::CorruptedBinFile("contribution parameter too long".to_owned());
+    let mut params = Vec::new();
+    if let Some(name) = name {
+        params.push(PARAM_NAME);
+        params.push(u8::try_from(name.len()).map_err(|_| too_long())?);
+        params.extend(name.as_bytes());
+    }
+    if let Some(beacon) = beacon {
+        params.push(PARAM_NUM_ITERATIONS_EXP);
+        params.push(beacon.num_iterations_exp);
+        params.push(PARAM_BEACON_HASH);
+        params.push(u8::try_from(beacon.hash.len()).map_err(|_| too_long())?);
+        params.extend(&beacon.hash);
+        writer.write_u32::<LittleEndian>(CONTRIBUTION_TYPE_BEACON)?;
+    } else {
+        writer.write_u32::<LittleEndian>(0)?;
+    }
+    writer.write_u32::<LittleEndian>(usize_to_u32!(params.len()))?;
+    writer.write_all(&params)?;
+    Ok(())
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> Contribution<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn read<R: Read>(mut reader: R) -> ZKeyParserResult<Self> {
+        let delta_after = P::g1_from_reader(&mut reader)?;
+        let g1_s = P::g1_from_reader(&mut reader)?;
+        let g1_sx = P::g1_from_reader(&mut reader)?;
+        let g2_spx = P::g2_from_reader(&mut reader)?;
+        let mut transcript = [0u8; 64];
+        reader.read_exact(&mut transcript)?;
+        let (name, beacon) = read_contribution_params(&mut reader)?;
+        Ok(Self {
+            delta_after,
+            g1_s,
+            g1_sx,
+            g2_spx,
+            transcript,
+            name,
+            beacon,
+        })
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> ZKeyParserResult<()> {
+        P::g1_to_writer(&self.delta_after, &mut writer)?;
+        P::g1_to_writer(&self.g1_s, &mut writer)?;
+        P::g1_to_writer(&self.g1_sx, &mut writer)?;
+        P::g2_to_writer(&self.g2_spx, &mut writer)?;
+        writer.write_all(&self.transcript)?;
+
+        write_contribution_params(writer, self.name.as_deref(), self.beacon.as_ref())
+    }
 }
 
 impl<P: Pairing + CircomArkworksPairingBridge> TryFrom<BinFile<P>> for ZKey<P>
@@ -144,10 +526,13 @@ where
     type Error = ZKeyParserError;
     fn try_from(mut binfile: BinFile<P>) -> Result<Self, Self::Error> {
         tracing::debug!("start transforming bin file into zkey...");
+        binfile.check_file_type(ZKEY_FTYPE)?;
+        Self::check_protocol_id(binfile.take_section(1))?;
         let header = HeaderGroth::<P>::read(&mut binfile.take_section(2))?;
         let n_vars = header.n_vars;
         let n_public = header.n_public;
         let domain_size = header.domain_size;
+        let n_private = Self::num_private(n_vars, n_public)?;
 
         // parse proving key
 
@@ -171,7 +556,7 @@ where
             s.spawn(|_| a_query = Some(Self::a_query(n_vars, a_section)));
             s.spawn(|_| b_g1_query = Some(Self::b_g1_query(n_vars, b_g1_section)));
             s.spawn(|_| b_g2_query = Some(Self::b_g2_query(n_vars, b_g2_section)));
-            s.spawn(|_| l_query = Some(Self::l_query(n_vars - n_public - 1, l_section)));
+            s.spawn(|_| l_query = Some(Self::l_query(n_private, l_section)));
             s.spawn(|_| h_query = Some(Self::h_query(domain_size as usize, h_section)));
         });
         tracing::debug!("we are done with parsing sections!");
@@ -179,49 +564,7 @@ where
         // parse matrices
 
         tracing::debug!("reading matrices...");
-        let mut matrices_section = binfile.take_section(4);
-
-        // this function (an all following uses) assumes that values are encoded in little-endian
-        let num_coeffs = u32::deserialize_uncompressed(&mut matrices_section)?;
-
-        // instantiate AB
-        let mut matrices = vec![vec![vec![]; domain_size as usize]; 2];
-        let mut max_constraint_index = 0;
-        for _ in 0..num_coeffs {
-            let matrix = u32::deserialize_uncompressed(&mut matrices_section)?;
-            let constraint = u32::deserialize_uncompressed(&mut matrices_section)?;
-            let signal = u32::deserialize_uncompressed(&mut matrices_section)?;
-
-            let value = P::ScalarField::from_reader_for_groth16_zkey(&mut matrices_section)?;
-            max_constraint_index = std::cmp::max(max_constraint_index, constraint);
-            matrices[matrix as usize][constraint as usize].push((value, signal as usize));
-        }
-
-        let num_constraints = max_constraint_index as usize - n_public;
-        // Remove the public input constraints, Arkworks adds them later
-        matrices.iter_mut().for_each(|m| {
-            m.truncate(num_constraints);
-        });
-
-        // This is taken from Arkworks' to_matrices() function
-        let a = matrices[0].clone();
-        let b = matrices[1].clone();
-        let a_num_non_zero: usize = a.iter().map(|lc| lc.len()).sum();
-        let b_num_non_zero: usize = b.iter().map(|lc| lc.len()).sum();
-
-        let matrices = ConstraintMatrices {
-            num_instance_variables: n_public + 1,
-            num_witness_variables: n_vars - n_public,
-            num_constraints,
-
-            a_num_non_zero,
-            b_num_non_zero,
-            c_num_non_zero: 0,
-
-            a,
-            b,
-            c: vec![],
-        };
+        let matrices = Self::matrices(n_vars, n_public, domain_size, binfile.take_section(4))?;
         // this thread automatically joins on the rayon scope, therefore we can
         // only be here if the scope finished.
         let vk = VerifyingKey {
@@ -232,6 +575,7 @@ where
             // unwrap is fine, because we are guaranteed to have a Some value (rayon scope)
             gamma_abc_g1: ic.unwrap()?,
         };
+        let mpc_params = MpcParams::read(binfile.take_section(10))?;
         tracing::debug!("groth16 zkey parsing done!");
         Ok(ZKey {
             n_public: header.n_public,
@@ -246,6 +590,7 @@ where
             l_query: l_query.unwrap()?,
             matrices,
             vk,
+            mpc_params,
         })
     }
 }
@@ -255,7 +600,7 @@ where
     P::BaseField: CircomArkworksPrimeFieldBridge,
     P::ScalarField: CircomArkworksPrimeFieldBridge,
 {
-    fn read<R: Read>(mut reader: &mut R) -> ZKeyParserResult<Self> {
+    pub(crate) fn read<R: Read>(mut reader: &mut R) -> ZKeyParserResult<Self> {
         tracing::debug!("reading groth16 header..");
         let n8q: u32 = u32::deserialize_uncompressed(&mut reader)?;
         //modulus of BaseField
@@ -716,4 +1061,53 @@ mod tests {
             .unwrap();
         assert_eq!(expected, de);
     }
+
+    #[test]
+    fn can_deser_mpc_params() {
+        let zkey = File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey").unwrap();
+        let pk = ZKey::<Bn254>::from_reader(zkey).unwrap();
+        assert_eq!(pk.mpc_params.contributions.len(), 1);
+        let contribution = &pk.mpc_params.contributions[0];
+        assert_eq!(contribution.delta_after, pk.delta_g1);
+        assert_eq!(contribution.name.as_deref(), Some("1st Contributor Name"));
+        assert!(contribution.beacon.is_none());
+
+        let zkey = File::open("../../test_vectors/Groth16/bn254/poseidon/circuit.zkey").unwrap();
+        let pk = ZKey::<Bn254>::from_reader(zkey).unwrap();
+        assert!(pk.mpc_params.contributions.is_empty());
+    }
+
+    fn roundtrip<P: Pairing + CircomArkworksPairingBridge>(path: &str)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let bytes = std::fs::read(path).unwrap();
+        let zkey = ZKey::<P>::from_reader(bytes.as_slice()).unwrap();
+        let mut ser = Vec::new();
+        zkey.to_writer(&mut ser).unwrap();
+        assert_eq!(ser, bytes, "{path}");
+    }
+
+    #[test]
+    fn can_ser_zkey() {
+        for circuit in ["multiplier2", "poseidon"] {
+            roundtrip::<Bn254>(&format!(
+                "../../test_vectors/Groth16/bn254/{circuit}/circuit.zkey"
+            ));
+            roundtrip::<Bls12_381>(&format!(
+                "../../test_vectors/Groth16/bls12_381/{circuit}/circuit.zkey"
+            ));
+        }
+    }
+
+    #[test]
+    fn can_ser_g1_g2() {
+        let mut ser = Vec::new();
+        Bn254::g1_to_writer(&g1_one(), &mut ser).unwrap();
+        assert_eq!(ser, g1_buf());
+        let mut ser = Vec::new();
+        Bn254::g2_vec_to_writer(&[g2_one(), g2_one()], &mut ser).unwrap();
+        assert_eq!(ser, [g2_buf(), g2_buf()].concat());
+    }
 }
@@ -2,9 +2,12 @@
 #![allow(clippy::needless_borrows_for_generic_args)]
 //! This crate defines types used in circom and utilities to read these types from files.
 mod binfile;
+pub mod fflonk;
 pub mod groth16;
 pub mod plonk;
+pub mod ptau;
 mod r1cs;
+mod snarkjs_rng;
 pub mod traits;
 mod witness;
 
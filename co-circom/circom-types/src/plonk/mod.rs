@@ -1,6 +1,7 @@
 //! This module defines types related to Plonk used in circom and utilities to read these types from files.
 
 mod proof;
+pub(crate) mod setup;
 mod verification_key;
 mod zkey;
 
@@ -0,0 +1,654 @@
+//! This module implements the PLONK setup of snarkjs. [`ZKey::from_r1cs_and_ptau`] corresponds to
+//! `snarkjs plonk setup` and produces the same zkey as snarkjs for the same R1CS and powers of tau.
+use std::collections::{BTreeMap, VecDeque};
+
+use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
+use ark_ff::{One, PrimeField, Zero};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Radix2EvaluationDomain};
+use rayon::prelude::*;
+
+use crate::{
+    ptau::{evaluation_domain, lagrange_domain, PowersOfTau, SetupError},
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+    R1CS,
+};
+
+use super::{Additions, CircomPolynomial, VerifyingKey, ZKey};
+
+// snarkjs needs at least a domain of size 8, as the quotient polynomial has degree n + 5
+pub(crate) const MIN_POWER: usize = 3;
+// the zkey contains domain_size + 6 powers of tau, which the prover needs for the blinded polynomials
+const ADDITIONAL_TAUS: usize = 6;
+
+// snarkjs stores linear combinations in objects keyed by the signal, so they are always sorted by signal
+type LinearCombination<F> = BTreeMap<usize, F>;
+
+// A PLONK gate qm * a * b + ql * a + qr * b + qo * c + qc = 0 with the wires a, b and c
+pub(crate) struct Gate<F> {
+    pub(crate) a: usize,
+    pub(crate) b: usize,
+    pub(crate) c: usize,
+    pub(crate) qm: F,
+    pub(crate) ql: F,
+    pub(crate) qr: F,
+    pub(crate) qo: F,
+    pub(crate) qc: F,
+}
+
+// A linear combination reduced to at most `max` signals plus a constant
+struct ReducedLinearCombination<F> {
+    constant: F,
+    signals: Vec<usize>,
+    coeffs: Vec<F>,
+}
+
+enum LinearCombinationType {
+    Empty,
+    Constant,
+    Signals,
+}
+
+// Translates the R1CS constraints into PLONK gates. Linear combinations with too many signals
+// are reduced with additional addition gates, which introduce new (internal) signals. FFLONK uses
+// the same gates as PLONK.
+pub(crate) struct GateBuilder<F: PrimeField> {
+    pub(crate) n_vars: usize,
+    pub(crate) gates: Vec<Gate<F>>,
+    pub(crate) additions: Vec<(usize, usize, F, F)>,
+}
+
+impl<F: PrimeField> GateBuilder<F> {
+    pub(crate) fn from_r1cs<P: Pairing<ScalarField = F>>(r1cs: &R1CS<P>) -> Self {
+        let mut builder = Self::new(r1cs.num_variables, n_public(r1cs));
+        for (a, b, c) in r1cs.constraints.iter() {
+            let to_lc =
+                |lc: &[(usize, F)]| -> LinearCombination<F> { lc.iter().copied().collect() };
+            builder.process(&to_lc(a), &to_lc(b), &to_lc(c));
+        }
+        builder
+    }
+
+    fn new(n_vars: usize, n_public: usize) -> Self {
+        // every public input gets its own gate, so the prover can enforce them with the Lagrange polynomials
+        let gates = (1..=n_public)
+            .map(|signal| Gate {
+                a: signal,
+                b: 0,
+                c: 0,
+                qm: F::zero(),
+                ql: F::one(),
+                qr: F::zero(),
+                qo: F::zero(),
+                qc: F::zero(),
+            })
+            .collect();
+        Self {
+            n_vars,
+            gates,
+            additions: vec![],
+        }
+    }
+
+    fn process(
+        &mut self,
+        lc_a: &LinearCombination<F>,
+        lc_b: &LinearCombination<F>,
+        lc_c: &LinearCombination<F>,
+    ) {
+        match (Self::lc_type(lc_a), Self::lc_type(lc_b)) {
+            (LinearCombinationType::Empty, _) | (_, LinearCombinationType::Empty) => {
+                self.add_sum_gate(lc_c)
+            }
+            (LinearCombinationType::Constant, _) => {
+                let lc = Self::join(lc_b, lc_a[&0], lc_c);
+                self.add_sum_gate(&lc)
+            }
+            (_, LinearCombinationType::Constant) => {
+                let lc = Self::join(lc_a, lc_b[&0], lc_c);
+                self.add_sum_gate(&lc)
+            }
+            _ => self.add_mul_gate(lc_a, lc_b, lc_c),
+        }
+    }
+
+    // snarkjs classifies the linear combinations only by the signals they contain and not by the
+    // values of the coefficients, therefore we do the same
+    fn lc_type(lc: &LinearCombination<F>) -> LinearCombinationType {
+        if lc.keys().any(|signal| *signal != 0) {
+            LinearCombinationType::Signals
+        } else if lc.is_empty() {
+            LinearCombinationType::Empty
+        } else {
+            LinearCombinationType::Constant
+        }
+    }
+
+    // computes k * lc1 - lc2
+    fn join(lc1: &LinearCombination<F>, k: F, lc2: &LinearCombination<F>) -> LinearCombination<F> {
+        let mut result = lc1
+            .iter()
+            .map(|(signal, value)| (*signal, k * value))
+            .collect::<LinearCombination<F>>();
+        for (signal, value) in lc2.iter() {
+            *result.entry(*signal).or_insert_with(F::zero) -= value;
+        }
+        result
+    }
+
+    fn reduce(&mut self, lc: &LinearCombination<F>, max: usize) -> ReducedLinearCombination<F> {
+        let constant = lc.get(&0).copied().unwrap_or_else(F::zero);
+        let mut queue = lc
+            .iter()
+            .filter(|(signal, _)| **signal != 0)
+            .map(|(signal, value)| (*signal, *value))
+            .collect::<VecDeque<_>>();
+        while queue.len() > max {
+            let (sl, coeff_l) = queue.pop_front().expect("queue has more than max elements");
+            let (sr, coeff_r) = queue.pop_front().expect("queue has more than max elements");
+            let so = self.n_vars;
+            self.n_vars += 1;
+            self.gates.push(Gate {
+                a: sl,
+                b: sr,
+                c: so,
+                qm: F::zero(),
+                ql: -coeff_l,
+                qr: -coeff_r,
+                qo: F::one(),
+                qc: F::zero(),
+            });
+            self.additions.push((sl, sr, coeff_l, coeff_r));
+            queue.push_back((so, F::one()));
+        }
+        let (mut signals, mut coeffs): (Vec<_>, Vec<_>) = queue.into_iter().unzip();
+        signals.resize(max, 0);
+        coeffs.resize(max, F::zero());
+        ReducedLinearCombination {
+            constant,
+            signals,
+            coeffs,
+        }
+    }
+
+    fn add_sum_gate(&mut self, lc: &LinearCombination<F>) {
+        let c = self.reduce(lc, 3);
+        self.gates.push(Gate {
+            a: c.signals[0],
+            b: c.signals[1],
+            c: c.signals[2],
+            qm: F::zero(),
+            ql: c.coeffs[0],
+            qr: c.coeffs[1],
+            qo: c.coeffs[2],
+            qc: c.constant,
+        });
+    }
+
+    fn add_mul_gate(
+        &mut self,
+        lc_a: &LinearCombination<F>,
+        lc_b: &LinearCombination<F>,
+        lc_c: &LinearCombination<F>,
+    ) {
+        let a = self.reduce(lc_a, 1);
+        let b = self.reduce(lc_b, 1);
+        let c = self.reduce(lc_c, 1);
+        self.gates.push(Gate {
+            a: a.signals[0],
+            b: b.signals[0],
+            c: c.signals[0],
+            qm: a.coeffs[0] * b.coeffs[0],
+            ql: a.coeffs[0] * b.constant,
+            qr: a.constant * b.coeffs[0],
+            qo: -c.coeffs[0],
+            qc: a.constant * b.constant - c.constant,
+        });
+    }
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> ZKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Creates the PLONK zkey for the given [`R1CS`] from a [`PowersOfTau`] that is prepared for phase 2.
+    /// In contrast to Groth16, PLONK has no circuit specific secrets, so the zkey needs no contributions.
+    pub fn from_r1cs_and_ptau(r1cs: &R1CS<P>, ptau: &PowersOfTau<P>) -> Result<Self, SetupError> {
+        let lagrange = ptau.lagrange.as_ref().ok_or(SetupError::PtauNotPrepared)?;
+        let n_public = n_public(r1cs);
+        let GateBuilder {
+            n_vars,
+            gates,
+            additions,
+        } = GateBuilder::from_r1cs(r1cs);
+        let n_constraints = gates.len();
+
+        let power = domain_power(n_constraints);
+        if power > ptau.power {
+            return Err(SetupError::PtauTooSmall(power, ptau.power));
+        }
+        let domain_size = 1 << power;
+        let domain =
+            evaluation_domain::<P::ScalarField>(domain_size).ok_or(SetupError::CircuitTooBig)?;
+        let extended_domain = evaluation_domain::<P::ScalarField>(domain_size * 4)
+            .ok_or(SetupError::CircuitTooBig)?;
+        if ptau.tau_g1.len() < domain_size + ADDITIONAL_TAUS || ptau.tau_g2.len() < 2 {
+            return Err(SetupError::PtauTooSmall(power, ptau.power));
+        }
+        tracing::debug!(
+            "plonk setup with {n_constraints} gates, {} additions and domain size {domain_size}",
+            additions.len()
+        );
+
+        let (k1, k2) = k1_k2(&domain);
+        let mut evaluations = vec![
+            selector(&gates, domain_size, |g| g.qm),
+            selector(&gates, domain_size, |g| g.ql),
+            selector(&gates, domain_size, |g| g.qr),
+            selector(&gates, domain_size, |g| g.qo),
+            selector(&gates, domain_size, |g| g.qc),
+        ];
+        evaluations.extend(sigma(&gates, n_vars, &domain, k1, k2));
+
+        tracing::debug!("computing polynomials and commitments...");
+        let l_tau_g1 = lagrange_domain(&lagrange.tau_g1, power);
+        let polys = evaluations
+            .par_iter()
+            .map(|evals| polynomial(evals, &domain, &extended_domain))
+            .collect::<Vec<_>>();
+        let commitments = P::G1::normalize_batch(
+            &evaluations
+                .par_iter()
+                .map(|evals| {
+                    let scalars = evals.iter().map(|x| x.into_bigint()).collect::<Vec<_>>();
+                    P::G1::msm_bigint(l_tau_g1, &scalars)
+                })
+                .collect::<Vec<_>>(),
+        );
+        let lagrange_polys = (0..n_public.max(1))
+            .into_par_iter()
+            .map(|i| {
+                let mut evals = vec![P::ScalarField::zero(); domain_size];
+                evals[i] = P::ScalarField::one();
+                polynomial(&evals, &domain, &extended_domain)
+            })
+            .collect::<Vec<_>>();
+
+        let mut polys = polys.into_iter();
+        let mut next_poly = || polys.next().expect("we have 8 polynomials");
+        let zkey = ZKey {
+            n_vars,
+            n_public,
+            domain_size,
+            power,
+            n_additions: additions.len(),
+            n_constraints,
+            verifying_key: VerifyingKey {
+                k1,
+                k2,
+                qm: commitments[0],
+                ql: commitments[1],
+                qr: commitments[2],
+                qo: commitments[3],
+                qc: commitments[4],
+                s1: commitments[5],
+                s2: commitments[6],
+                s3: commitments[7],
+                x_2: ptau.tau_g2[1],
+            },
+            additions: additions
+                .into_iter()
+                .map(|(sl, sr, factor1, factor2)| Additions {
+                    signal_id1: usize_to_u32(sl),
+                    signal_id2: usize_to_u32(sr),
+                    factor1,
+                    factor2,
+                })
+                .collect(),
+            map_a: gates.iter().map(|g| g.a).collect(),
+            map_b: gates.iter().map(|g| g.b).collect(),
+            map_c: gates.iter().map(|g| g.c).collect(),
+            qm_poly: next_poly(),
+            ql_poly: next_poly(),
+            qr_poly: next_poly(),
+            qo_poly: next_poly(),
+            qc_poly: next_poly(),
+            s1_poly: next_poly(),
+            s2_poly: next_poly(),
+            s3_poly: next_poly(),
+            lagrange: lagrange_polys,
+            p_tau: ptau.tau_g1[..domain_size + ADDITIONAL_TAUS].to_vec(),
+        };
+        tracing::debug!("plonk setup done!");
+        Ok(zkey)
+    }
+}
+
+// k1 and k2 must be chosen such that the cosets k1 * H and k2 * H are distinct from each other and
+// from the domain H. snarkjs starts the search at 2.
+pub(crate) fn k1_k2<F: PrimeField>(domain: &Radix2EvaluationDomain<F>) -> (F, F) {
+    let is_included = |k: F, others: &[F]| {
+        domain
+            .elements()
+            .any(|w| k == w || others.iter().any(|other| k == *other * w))
+    };
+    let mut k1 = F::from(2u64);
+    while is_included(k1, &[]) {
+        k1 += F::one();
+    }
+    let mut k2 = k1 + F::one();
+    while is_included(k2, &[k1]) {
+        k2 += F::one();
+    }
+    (k1, k2)
+}
+
+// The evaluations of the three permutation polynomials. Every position of the wires a, b and c
+// is identified with w^i, k1 * w^i and k2 * w^i. All positions of a signal form a cycle, where
+// each position points to the previous occurrence and the first one to the last occurrence.
+pub(crate) fn sigma<F: PrimeField>(
+    gates: &[Gate<F>],
+    n_vars: usize,
+    domain: &Radix2EvaluationDomain<F>,
+    k1: F,
+    k2: F,
+) -> Vec<Vec<F>> {
+    let domain_size = domain.size();
+    let mut sigma = vec![F::zero(); domain_size * 3];
+    let mut first_pos = vec![None; n_vars];
+    let mut last_appearance = vec![None; n_vars];
+    for (i, w) in domain.elements().enumerate() {
+        let (a, b, c) = gates.get(i).map(|g| (g.a, g.b, g.c)).unwrap_or_default();
+        for (pos, signal, value) in [
+            (i, a, w),
+            (domain_size + i, b, w * k1),
+            (domain_size * 2 + i, c, w * k2),
+        ] {
+            match last_appearance[signal] {
+                Some(last) => sigma[pos] = last,
+                None => first_pos[signal] = Some(pos),
+            }
+            last_appearance[signal] = Some(value);
+        }
+    }
+    for (signal, (first, last)) in first_pos.into_iter().zip(last_appearance).enumerate() {
+        match (first, last) {
+            (Some(first), Some(last)) => sigma[first] = last,
+            _ => tracing::debug!("signal {signal} is not used"),
+        }
+    }
+    sigma.chunks_exact(domain_size).map(<[_]>::to_vec).collect()
+}
+
+// The coefficients and the evaluations over the domain of size 4n of the polynomial with the
+// provided evaluations over the domain
+pub(crate) fn polynomial<F: PrimeField>(
+    evaluations: &[F],
+    domain: &Radix2EvaluationDomain<F>,
+    extended_domain: &Radix2EvaluationDomain<F>,
+) -> CircomPolynomial<F> {
+    let coeffs = domain.ifft(evaluations);
+    let evaluations = extended_domain.fft(&coeffs);
+    CircomPolynomial {
+        coeffs: DensePolynomial { coeffs },
+        evaluations,
+    }
+}
+
+pub(crate) fn n_public<P: Pairing>(r1cs: &R1CS<P>) -> usize {
+    usize::try_from(r1cs.n_pub_out + r1cs.n_pub_in).expect("u32 fits into usize")
+}
+
+// The smallest power of two that fits all gates
+pub(crate) fn domain_power(n_constraints: usize) -> usize {
+    usize::try_from(usize::BITS - n_constraints.saturating_sub(1).leading_zeros())
+        .expect("u32 fits into usize")
+        .max(MIN_POWER)
+}
+
+// The evaluations of a selector over the domain
+pub(crate) fn selector<F: PrimeField>(
+    gates: &[Gate<F>],
+    domain_size: usize,
+    f: fn(&Gate<F>) -> F,
+) -> Vec<F> {
+    let mut evals = gates.iter().map(f).collect::<Vec<_>>();
+    evals.resize(domain_size, F::zero());
+    evals
+}
+
+pub(crate) fn usize_to_u32(x: usize) -> u32 {
+    u32::try_from(x).expect("circom zkeys use u32 for signal indices")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptau::LagrangeBasis;
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use ark_ec::AffineRepr;
+    use ark_std::UniformRand;
+    use std::fs::File;
+
+    // A ptau that contains only what the PLONK setup needs from the powers of tau, which are also
+    // contained in the zkey
+    fn ptau_from_zkey<P: Pairing + CircomArkworksPairingBridge>(zkey: &ZKey<P>) -> PowersOfTau<P>
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let power = zkey.power;
+        let domain = evaluation_domain::<P::ScalarField>(zkey.domain_size).unwrap();
+        let taus = zkey.p_tau[..zkey.domain_size]
+            .iter()
+            .map(|p| p.into_group())
+            .collect::<Vec<_>>();
+        let mut lagrange_g1 = vec![P::G1Affine::zero(); (1 << power) - 1];
+        lagrange_g1.extend(P::G1::normalize_batch(&domain.ifft(&taus)));
+        PowersOfTau {
+            power,
+            ceremony_power: power,
+            tau_g1: zkey.p_tau.clone(),
+            tau_g2: vec![P::G2Affine::generator(), zkey.verifying_key.x_2],
+            alpha_tau_g1: vec![],
+            beta_tau_g1: vec![],
+            beta_g2: P::G2Affine::generator(),
+            contributions: vec![],
+            lagrange: Some(LagrangeBasis {
+                tau_g1: lagrange_g1,
+                tau_g2: vec![],
+                alpha_tau_g1: vec![],
+                beta_tau_g1: vec![],
+            }),
+        }
+    }
+
+    fn setup_matches_snarkjs<P: Pairing + CircomArkworksPairingBridge>(r1cs: &str, zkey: &str)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let r1cs = R1CS::<P>::from_reader(File::open(r1cs).unwrap()).unwrap();
+        let expected = std::fs::read(zkey).unwrap();
+        let ptau = ptau_from_zkey(&ZKey::<P>::from_reader(expected.as_slice()).unwrap());
+        let result = ZKey::<P>::from_r1cs_and_ptau(&r1cs, &ptau).unwrap();
+        let mut ser = Vec::new();
+        result.to_writer(&mut ser).unwrap();
+        assert_eq!(ser, expected, "{zkey}");
+    }
+
+    #[test]
+    fn setup_matches_snarkjs_bn254() {
+        setup_matches_snarkjs::<Bn254>(
+            "../../test_vectors/Plonk/bn254/multiplier2/circuit.r1cs",
+            "../../test_vectors/Plonk/bn254/multiplier2/circuit.zkey",
+        );
+        // kyc needs additions
+        setup_matches_snarkjs::<Bn254>(
+            "../co-circom/examples/plonk/test_vectors/kyc/bn254/kyc.r1cs",
+            "../co-circom/examples/plonk/test_vectors/kyc/bn254/kyc.zkey",
+        );
+    }
+
+    #[test]
+    fn setup_matches_snarkjs_bls12_381() {
+        setup_matches_snarkjs::<Bls12_381>(
+            "../../test_vectors/Plonk/bls12_381/multiplier2/circuit.r1cs",
+            "../../test_vectors/Plonk/bls12_381/multiplier2/circuit.zkey",
+        );
+        setup_matches_snarkjs::<Bls12_381>(
+            "../co-circom/examples/plonk/test_vectors/kyc/bls12/kyc.r1cs",
+            "../co-circom/examples/plonk/test_vectors/kyc/bls12/kyc.zkey",
+        );
+    }
+
+    // out = 3 * (x + y) with the private inputs in1, in2, x and the intermediate signals y and z, where
+    // z = (in1 + 2 * in2 + 3 * x + 1) * (in2 + y) and in1 + in2 + x + y + 5 = 0. The linear combinations
+    // have too many signals for a single gate and need additions.
+    fn circuit_with_additions<P: Pairing>() -> (R1CS<P>, Vec<P::ScalarField>) {
+        let f = |x: u64| P::ScalarField::from(x);
+        let constraints = vec![
+            (
+                vec![(0, f(1)), (2, f(1)), (3, f(2)), (4, f(3))],
+                vec![(3, f(1)), (5, f(1))],
+                vec![(6, f(1))],
+            ),
+            (
+                vec![],
+                vec![],
+                vec![(0, f(5)), (2, f(1)), (3, f(1)), (4, f(1)), (5, f(1))],
+            ),
+            (vec![(0, f(3))], vec![(4, f(1)), (5, f(1))], vec![(1, f(1))]),
+        ];
+        let r1cs = R1CS {
+            num_inputs: 2,
+            num_aux: 5,
+            num_variables: 7,
+            n_constraints: constraints.len(),
+            constraints,
+            wire_mapping: (0..7).collect(),
+            n_pub_out: 1,
+            n_pub_in: 0,
+            n_prv_in: 3,
+            n_labels: 7,
+        };
+        let (in1, in2, x) = (f(7), f(11), f(13));
+        let y = -(in1 + in2 + x + f(5));
+        let z = (in1 + f(2) * in2 + f(3) * x + f(1)) * (in2 + y);
+        let out = f(3) * (x + y);
+        (r1cs, vec![f(1), out, in1, in2, x, y, z])
+    }
+
+    fn setup_with_additions<P: Pairing + CircomArkworksPairingBridge>()
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let mut rng = ark_std::test_rng();
+        let tau = P::ScalarField::rand(&mut rng);
+        let (r1cs, mut witness) = circuit_with_additions::<P>();
+        let ptau = PowersOfTau::<P>::from_secrets(
+            4,
+            tau,
+            P::ScalarField::rand(&mut rng),
+            P::ScalarField::rand(&mut rng),
+        );
+        let zkey = ZKey::<P>::from_r1cs_and_ptau(&r1cs, &ptau).unwrap();
+        assert_eq!(zkey.additions.len(), 4);
+        assert_eq!(zkey.n_vars, r1cs.num_variables + zkey.additions.len());
+        assert_eq!(zkey.n_constraints, zkey.map_a.len());
+
+        // all gates (except the ones of the public inputs) are satisfied by the witness extended
+        // with the additions
+        for addition in zkey.additions.iter() {
+            let sl = witness[addition.signal_id1 as usize];
+            let sr = witness[addition.signal_id2 as usize];
+            witness.push(addition.factor1 * sl + addition.factor2 * sr);
+        }
+        let domain = evaluation_domain::<P::ScalarField>(zkey.domain_size).unwrap();
+        let evals = |poly: &CircomPolynomial<P::ScalarField>| domain.fft(&poly.coeffs);
+        let (qm, ql, qr, qo, qc) = (
+            evals(&zkey.qm_poly),
+            evals(&zkey.ql_poly),
+            evals(&zkey.qr_poly),
+            evals(&zkey.qo_poly),
+            evals(&zkey.qc_poly),
+        );
+        for i in zkey.n_public..zkey.n_constraints {
+            let (a, b, c) = (
+                witness[zkey.map_a[i]],
+                witness[zkey.map_b[i]],
+                witness[zkey.map_c[i]],
+            );
+            assert!((qm[i] * a * b + ql[i] * a + qr[i] * b + qo[i] * c + qc[i]).is_zero());
+        }
+
+        let g1 = P::G1Affine::generator();
+        let vk = &zkey.verifying_key;
+        for (poly, commitment) in [
+            (&zkey.qm_poly, vk.qm),
+            (&zkey.ql_poly, vk.ql),
+            (&zkey.qr_poly, vk.qr),
+            (&zkey.qo_poly, vk.qo),
+            (&zkey.qc_poly, vk.qc),
+            (&zkey.s1_poly, vk.s1),
+            (&zkey.s2_poly, vk.s2),
+            (&zkey.s3_poly, vk.s3),
+        ] {
+            assert_eq!((g1 * poly.evaluate(&tau)).into_affine(), commitment);
+        }
+
+        // the permutation only maps positions onto positions of the same signal
+        let mut signal_of = std::collections::HashMap::new();
+        for (i, w) in domain.elements().enumerate() {
+            let signal = |map: &[usize]| map.get(i).copied().unwrap_or_default();
+            signal_of.insert(w, signal(&zkey.map_a));
+            signal_of.insert(w * vk.k1, signal(&zkey.map_b));
+            signal_of.insert(w * vk.k2, signal(&zkey.map_c));
+        }
+        for (sigma, k) in [
+            (&zkey.s1_poly, P::ScalarField::one()),
+            (&zkey.s2_poly, vk.k1),
+            (&zkey.s3_poly, vk.k2),
+        ] {
+            for (w, permuted) in domain.elements().zip(evals(sigma)) {
+                assert_eq!(signal_of[&permuted], signal_of[&(w * k)]);
+            }
+        }
+    }
+
+    #[test]
+    fn setup_with_additions_bn254() {
+        setup_with_additions::<Bn254>();
+    }
+
+    #[test]
+    fn setup_with_additions_bls12_381() {
+        setup_with_additions::<Bls12_381>();
+    }
+
+    #[test]
+    fn setup_fails_for_small_or_unprepared_ptau() {
+        let r1cs = R1CS::<Bn254>::from_reader(
+            File::open("../../test_vectors/Plonk/bn254/poseidon/circuit.r1cs").unwrap(),
+        )
+        .unwrap();
+        let mut rng = ark_std::test_rng();
+        let mut ptau = PowersOfTau::<Bn254>::from_secrets(
+            3,
+            UniformRand::rand(&mut rng),
+            UniformRand::rand(&mut rng),
+            UniformRand::rand(&mut rng),
+        );
+        assert!(matches!(
+            ZKey::from_r1cs_and_ptau(&r1cs, &ptau),
+            Err(SetupError::PtauTooSmall(_, 3))
+        ));
+        ptau.lagrange = None;
+        assert!(matches!(
+            ZKey::from_r1cs_and_ptau(&r1cs, &ptau),
+            Err(SetupError::PtauNotPrepared)
+        ));
+    }
+}
@@ -24,15 +24,17 @@
 //IN CONNECTION WITH THE SOFTWARE O THE USE OR OTHER
 //DEALINGS IN THE SOFTWARE.R
 
-//! This module defines the [`ZKey`] struct that implements deserialization of circom zkey files via [`ZKey::from_reader`].
+//! This module defines the [`ZKey`] struct that implements deserialization of circom zkey files via [`ZKey::from_reader`]
+//! and serialization via [`ZKey::to_writer`].
 use ark_ec::pairing::Pairing;
 use ark_ff::PrimeField;
 use ark_poly::{univariate::DensePolynomial, Polynomial};
 use ark_serialize::CanonicalDeserialize;
-use std::io::{Cursor, Read};
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::io::{Cursor, Read, Write};
 
 use crate::{
-    binfile::{BinFile, ZKeyParserError, ZKeyParserResult},
+    binfile::{self, BinFile, ZKeyParserError, ZKeyParserResult},
     traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
 };
 
@@ -42,6 +44,18 @@ macro_rules! u32_to_usize {
     };
 }
 
+macro_rules! usize_to_u32 {
+    ($x: expr) => {
+        u32::try_from($x).expect("circom zkeys use u32 for sizes and indices")
+    };
+}
+
+const ZKEY_FTYPE: &str = "zkey";
+const ZKEY_VERSION: u32 = 1;
+const PLONK_PROTOCOL_ID: u32 = 2;
+// snarkjs writes the header sections of PLONK zkeys after all other sections
+const SECTION_ORDER: [u32; 14] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 1, 2];
+
 /// Represents a zkey in the format defined by circom. Implements [`ZKey::from_reader`] to deserialize a zkey from a reader.
 #[derive(Clone)]
 pub struct ZKey<P: Pairing> {
@@ -156,7 +170,7 @@ where
         BinFile::<P>::new(&mut reader)?.try_into()
     }
 
-    fn additions_indices<R: Read>(
+    pub(crate) fn additions_indices<R: Read>(
         n_additions: usize,
         mut reader: R,
     ) -> ZKeyParserResult<Vec<Additions<P>>> {
@@ -176,7 +190,10 @@ where
         Ok(additions)
     }
 
-    fn id_map<R: Read>(n_constraints: usize, mut reader: R) -> ZKeyParserResult<Vec<usize>> {
+    pub(crate) fn id_map<R: Read>(
+        n_constraints: usize,
+        mut reader: R,
+    ) -> ZKeyParserResult<Vec<usize>> {
         let mut map = Vec::with_capacity(n_constraints);
         for _ in 0..n_constraints {
             map.push(u32_to_usize!(u32::deserialize_uncompressed(&mut reader)?));
@@ -184,7 +201,7 @@ where
         Ok(map)
     }
 
-    fn evaluations<R: Read>(
+    pub(crate) fn evaluations<R: Read>(
         domain_size: usize,
         mut reader: R,
     ) -> ZKeyParserResult<CircomPolynomial<P::ScalarField>> {
@@ -207,7 +224,7 @@ where
         })
     }
 
-    fn lagrange<R: Read>(
+    pub(crate) fn lagrange<R: Read>(
         n_public: usize,
         domain_size: usize,
         mut reader: R,
@@ -223,6 +240,78 @@ where
         // //TODO: why domain size + 6?
         Ok(P::g1_vec_from_reader(reader, domain_size + 6)?)
     }
+
+    /// Serializes the [`ZKey`] into a writer. The output is byte-exact with the zkeys written by snarkjs.
+    pub fn to_writer<W: Write>(&self, writer: W) -> ZKeyParserResult<()> {
+        let mut sections = Vec::with_capacity(SECTION_ORDER.len());
+        for id in SECTION_ORDER {
+            let mut section = Vec::new();
+            match id {
+                1 => section.write_u32::<LittleEndian>(PLONK_PROTOCOL_ID)?,
+                2 => self.write_header(&mut section)?,
+                3 => {
+                    for addition in self.additions.iter() {
+                        section.write_u32::<LittleEndian>(addition.signal_id1)?;
+                        section.write_u32::<LittleEndian>(addition.signal_id2)?;
+                        addition.factor1.montgomery_bigint_to_writer(&mut section)?;
+                        addition.factor2.montgomery_bigint_to_writer(&mut section)?;
+                    }
+                }
+                4 => Self::write_id_map(&self.map_a, &mut section)?,
+                5 => Self::write_id_map(&self.map_b, &mut section)?,
+                6 => Self::write_id_map(&self.map_c, &mut section)?,
+                7 => self.qm_poly.write(&mut section)?,
+                8 => self.ql_poly.write(&mut section)?,
+                9 => self.qr_poly.write(&mut section)?,
+                10 => self.qo_poly.write(&mut section)?,
+                11 => self.qc_poly.write(&mut section)?,
+                12 => {
+                    self.s1_poly.write(&mut section)?;
+                    self.s2_poly.write(&mut section)?;
+                    self.s3_poly.write(&mut section)?;
+                }
+                13 => {
+                    for lagrange in self.lagrange.iter() {
+                        lagrange.write(&mut section)?;
+                    }
+                }
+                14 => P::g1_vec_to_writer(&self.p_tau, &mut section)?,
+                _ => unreachable!("PLONK zkeys have 14 sections"),
+            }
+            sections.push((id, section));
+        }
+        binfile::write_bin_file(writer, ZKEY_FTYPE, ZKEY_VERSION, &sections)?;
+        Ok(())
+    }
+
+    fn write_header<W: Write>(&self, mut writer: W) -> ZKeyParserResult<()> {
+        binfile::write_zkey_field_header::<P, _>(&mut writer)?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.n_vars))?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.n_public))?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.domain_size))?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.n_additions))?;
+        writer.write_u32::<LittleEndian>(usize_to_u32!(self.n_constraints))?;
+        self.verifying_key.write(writer)
+    }
+
+    pub(crate) fn write_id_map<W: Write>(map: &[usize], mut writer: W) -> ZKeyParserResult<()> {
+        for id in map {
+            writer.write_u32::<LittleEndian>(usize_to_u32!(*id))?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: CircomArkworksPrimeFieldBridge> CircomPolynomial<F> {
+    pub(crate) fn write<W: Write>(&self, mut writer: W) -> ZKeyParserResult<()> {
+        for coeff in self.coeffs.coeffs.iter() {
+            coeff.montgomery_bigint_to_writer(&mut writer)?;
+        }
+        for evaluation in self.evaluations.iter() {
+            evaluation.montgomery_bigint_to_writer(&mut writer)?;
+        }
+        Ok(())
+    }
 }
 
 impl<P: Pairing + CircomArkworksPairingBridge> TryFrom<BinFile<P>> for ZKey<P>
@@ -353,6 +442,21 @@ where
             x_2: x2,
         })
     }
+
+    fn write<W: Write>(&self, mut writer: W) -> ZKeyParserResult<()> {
+        self.k1.montgomery_bigint_to_writer(&mut writer)?;
+        self.k2.montgomery_bigint_to_writer(&mut writer)?;
+        P::g1_to_writer(&self.qm, &mut writer)?;
+        P::g1_to_writer(&self.ql, &mut writer)?;
+        P::g1_to_writer(&self.qr, &mut writer)?;
+        P::g1_to_writer(&self.qo, &mut writer)?;
+        P::g1_to_writer(&self.qc, &mut writer)?;
+        P::g1_to_writer(&self.s1, &mut writer)?;
+        P::g1_to_writer(&self.s2, &mut writer)?;
+        P::g1_to_writer(&self.s3, &mut writer)?;
+        P::g2_to_writer(&self.x_2, &mut writer)?;
+        Ok(())
+    }
 }
 
 #[derive(Clone)]
@@ -373,30 +477,7 @@ where
     P::ScalarField: CircomArkworksPrimeFieldBridge,
 {
     fn read<R: Read>(mut reader: &mut R) -> ZKeyParserResult<Self> {
-        let n8q: u32 = u32::deserialize_uncompressed(&mut reader)?;
-        //modulus of BaseField
-        let q = <P::BaseField as PrimeField>::BigInt::deserialize_uncompressed(&mut reader)?;
-        let expected_n8q = P::BaseField::MODULUS_BIT_SIZE.div_ceil(8);
-        tracing::debug!("base field byte size: {n8q}");
-        if n8q != expected_n8q {
-            return Err(ZKeyParserError::UnexpectedByteSize(expected_n8q, n8q));
-        }
-        let modulus = <P::BaseField as PrimeField>::MODULUS;
-        if q != modulus {
-            return Err(ZKeyParserError::InvalidPrimeInHeader);
-        }
-        let n8r = u32::deserialize_uncompressed(&mut reader)?;
-        //modulus of ScalarField
-        let r = <P::ScalarField as PrimeField>::BigInt::deserialize_uncompressed(&mut reader)?;
-        tracing::debug!("scalar field byte size: {n8r}");
-        let expected_n8r = P::ScalarField::MODULUS_BIT_SIZE.div_ceil(8);
-        if n8r != expected_n8r {
-            return Err(ZKeyParserError::UnexpectedByteSize(expected_n8r, n8r));
-        }
-        let modulus = <P::ScalarField as PrimeField>::MODULUS;
-        if r != modulus {
-            return Err(ZKeyParserError::InvalidPrimeInHeader);
-        }
+        let n8r = binfile::read_zkey_field_header::<P, _>(&mut reader)?;
         let n_vars = u32::deserialize_uncompressed(&mut reader)?;
         let n_public = u32::deserialize_uncompressed(&mut reader)?;
         let domain_size = u32::deserialize_uncompressed(&mut reader)?;
@@ -407,7 +488,7 @@ where
         if domain_size & (domain_size - 1) == 0 && domain_size > 0 {
             tracing::debug!("read header done!");
             Ok(Self {
-                n8r: u32_to_usize!(n8r),
+                n8r,
                 n_vars: u32_to_usize!(n_vars),
                 n_public: u32_to_usize!(n_public),
                 domain_size: u32_to_usize!(domain_size),
@@ -423,3 +504,28 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+
+    fn roundtrip<P: Pairing + CircomArkworksPairingBridge>(path: &str)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let bytes = std::fs::read(path).unwrap();
+        let zkey = ZKey::<P>::from_reader(bytes.as_slice()).unwrap();
+        let mut ser = Vec::new();
+        zkey.to_writer(&mut ser).unwrap();
+        assert_eq!(ser, bytes, "{path}");
+    }
+
+    #[test]
+    fn can_ser_zkey() {
+        roundtrip::<Bn254>("../../test_vectors/Plonk/bn254/multiplier2/circuit.zkey");
+        roundtrip::<Bls12_381>("../../test_vectors/Plonk/bls12_381/multiplier2/circuit.zkey");
+    }
+}
@@ -0,0 +1,580 @@
+//! This module defines the [`PowersOfTau`] struct that implements deserialization of the powers of tau files (.ptau)
+//! created by snarkjs via [`PowersOfTau::from_reader`] and serialization via [`PowersOfTau::to_writer`].
+//!
+//! The circuit specific setups only use the points in Lagrange basis, which snarkjs adds to the ptau with
+//! `snarkjs powersoftau prepare phase2`. Therefore, the ptau must be prepared before it can be used for a setup.
+//! For testing, [`PowersOfTau::insecure_setup`] creates a prepared ptau from local randomness.
+use std::io::{Read, Write};
+
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{FftField, Field, LegendreSymbol, PrimeField, UniformRand};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{CryptoRng, Rng};
+use byteorder::{LittleEndian, WriteBytesExt};
+use rayon::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    binfile::{self, BinFile, ZKeyParserError, ZKeyParserResult},
+    groth16::{read_contribution_params, write_contribution_params, Beacon},
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+
+const PTAU_FTYPE: &str = "ptau";
+const PTAU_VERSION: u32 = 1;
+const HEADER_SECTION: usize = 1;
+const TAU_G1_SECTION: usize = 2;
+const TAU_G2_SECTION: usize = 3;
+const ALPHA_TAU_G1_SECTION: usize = 4;
+const BETA_TAU_G1_SECTION: usize = 5;
+const BETA_G2_SECTION: usize = 6;
+const CONTRIBUTIONS_SECTION: usize = 7;
+const LAGRANGE_TAU_G1_SECTION: usize = 12;
+const LAGRANGE_TAU_G2_SECTION: usize = 13;
+const LAGRANGE_ALPHA_TAU_G1_SECTION: usize = 14;
+const LAGRANGE_BETA_TAU_G1_SECTION: usize = 15;
+
+/// Error type describing errors during the circuit specific setup from a [`PowersOfTau`]
+#[derive(Debug, Error)]
+pub enum SetupError {
+    /// The circuit needs more powers than the ptau provides
+    #[error("circuit needs a ptau with power {0}, but the provided ptau only has power {1}")]
+    PtauTooSmall(usize, usize),
+    /// The ptau does not contain the points in Lagrange basis
+    #[error("ptau is not prepared for phase 2, use \"snarkjs powersoftau prepare phase2\"")]
+    PtauNotPrepared,
+    /// The domain of the circuit exceeds the two-adicity of the scalar field
+    #[error("circuit too big for this curve")]
+    CircuitTooBig,
+    /// The parameters of a random beacon are invalid
+    #[error("invalid beacon: {0}")]
+    InvalidBeacon(String),
+}
+
+/// Represents a powers of tau file (.ptau) as created by snarkjs. Implements [`PowersOfTau::from_reader`] to deserialize a ptau from a reader.
+#[derive(Clone)]
+pub struct PowersOfTau<P: Pairing> {
+    /// ld of the number of powers in this ptau
+    pub power: usize,
+    /// ld of the max number of powers of the ceremony
+    pub ceremony_power: usize,
+    /// tau^i * G1 for i in 0..2^(power + 1) - 1
+    pub tau_g1: Vec<P::G1Affine>,
+    /// tau^i * G2 for i in 0..2^power
+    pub tau_g2: Vec<P::G2Affine>,
+    /// alpha * tau^i * G1 for i in 0..2^power
+    pub alpha_tau_g1: Vec<P::G1Affine>,
+    /// beta * tau^i * G1 for i in 0..2^power
+    pub beta_tau_g1: Vec<P::G1Affine>,
+    /// beta * G2
+    pub beta_g2: P::G2Affine,
+    /// The contributions to the ceremony
+    pub contributions: Vec<PtauContribution<P>>,
+    /// The points in Lagrange basis, only present if the ptau is prepared for phase 2
+    pub lagrange: Option<LagrangeBasis<P>>,
+}
+
+/// The points of a prepared ptau in Lagrange basis. For every domain size 2^p, the vectors contain the
+/// evaluations of the 2^p Lagrange polynomials at tau, starting at index 2^p - 1. The domain sizes go
+/// up to 2^power (2^(power + 1) for `tau_g1`).
+#[derive(Clone)]
+pub struct LagrangeBasis<P: Pairing> {
+    /// L_i(tau) * G1
+    pub tau_g1: Vec<P::G1Affine>,
+    /// L_i(tau) * G2
+    pub tau_g2: Vec<P::G2Affine>,
+    /// alpha * L_i(tau) * G1
+    pub alpha_tau_g1: Vec<P::G1Affine>,
+    /// beta * L_i(tau) * G1
+    pub beta_tau_g1: Vec<P::G1Affine>,
+}
+
+/// A single contribution to a powers of tau ceremony.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PtauContribution<P: Pairing> {
+    /// tau * G1 after applying this contribution
+    pub tau_g1: P::G1Affine,
+    /// tau * G2 after applying this contribution
+    pub tau_g2: P::G2Affine,
+    /// alpha * G1 after applying this contribution
+    pub alpha_g1: P::G1Affine,
+    /// beta * G1 after applying this contribution
+    pub beta_g1: P::G1Affine,
+    /// beta * G2 after applying this contribution
+    pub beta_g2: P::G2Affine,
+    /// The proofs of knowledge of the contributed secrets
+    pub key: PtauPublicKey<P>,
+    /// The internal state of the Blake2b hasher after hashing the response of the contributor
+    pub partial_hash: [u8; 216],
+    /// The challenge for the next contributor
+    pub next_challenge: [u8; 64],
+    /// The (optional) name of the contributor
+    pub name: Option<String>,
+    /// The beacon parameters, if the contribution was derived from a random beacon
+    pub beacon: Option<Beacon>,
+}
+
+/// The public key of a powers of tau contribution, consisting of a proof of knowledge for each contributed secret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PtauPublicKey<P: Pairing> {
+    /// The proof of knowledge of tau
+    pub tau: ProofOfKnowledge<P>,
+    /// The proof of knowledge of alpha
+    pub alpha: ProofOfKnowledge<P>,
+    /// The proof of knowledge of beta
+    pub beta: ProofOfKnowledge<P>,
+}
+
+/// A proof of knowledge of a secret x.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofOfKnowledge<P: Pairing> {
+    /// A random point s in G1
+    pub g1_s: P::G1Affine,
+    /// s * x
+    pub g1_sx: P::G1Affine,
+    /// The point in G2 derived from the challenge, multiplied with x
+    pub g2_spx: P::G2Affine,
+}
+
+/// Returns the points for the domain of size 2^pow from a section in Lagrange basis.
+pub(crate) fn lagrange_domain<T>(points: &[T], pow: usize) -> &[T] {
+    &points[(1 << pow) - 1..(1 << (pow + 1)) - 1]
+}
+
+/// Returns the evaluation domain of the given size with the root of unity that snarkjs uses. The
+/// Lagrange basis of the ptau is defined over this domain. For some curves (e.g., BLS12-381) it
+/// differs from the root of unity of arkworks, because ffjavascript derives the roots from the
+/// smallest quadratic non-residue.
+pub(crate) fn evaluation_domain<F: PrimeField>(size: usize) -> Option<Radix2EvaluationDomain<F>> {
+    let mut domain = Radix2EvaluationDomain::<F>::new(size)?;
+    let mut qnr = F::one();
+    while qnr.legendre() != LegendreSymbol::QuadraticNonResidue {
+        qnr += F::one();
+    }
+    let mut root = qnr.pow(F::TRACE);
+    for _ in domain.log_size_of_group..F::TWO_ADICITY {
+        root.square_in_place();
+    }
+    domain.group_gen = root;
+    domain.group_gen_inv = root.inverse().expect("roots of unity are not zero");
+    Some(domain)
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> PowersOfTau<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Deserializes a [`PowersOfTau`] from a reader.
+    pub fn from_reader<R: Read>(mut reader: R) -> ZKeyParserResult<Self> {
+        let mut binfile = BinFile::<P>::new(&mut reader)?;
+        binfile.check_file_type(PTAU_FTYPE)?;
+        let (power, ceremony_power) = Self::read_header(binfile.take_section(HEADER_SECTION))?;
+        let num_powers = 1 << power;
+        tracing::debug!("reading ptau with power {power} (ceremony power {ceremony_power})");
+
+        let tau_g1 =
+            P::g1_vec_from_reader(binfile.take_section(TAU_G1_SECTION), 2 * num_powers - 1)?;
+        let tau_g2 = P::g2_vec_from_reader(binfile.take_section(TAU_G2_SECTION), num_powers)?;
+        let alpha_tau_g1 =
+            P::g1_vec_from_reader(binfile.take_section(ALPHA_TAU_G1_SECTION), num_powers)?;
+        let beta_tau_g1 =
+            P::g1_vec_from_reader(binfile.take_section(BETA_TAU_G1_SECTION), num_powers)?;
+        let beta_g2 = P::g2_from_reader(binfile.take_section(BETA_G2_SECTION))?;
+
+        let mut contributions_section = binfile.take_section(CONTRIBUTIONS_SECTION);
+        let num_contributions = u32::deserialize_uncompressed(&mut contributions_section)?;
+        let contributions = (0..num_contributions)
+            .map(|_| PtauContribution::read(&mut contributions_section))
+            .collect::<ZKeyParserResult<Vec<_>>>()?;
+
+        let lagrange = if binfile.has_section(LAGRANGE_TAU_G1_SECTION) {
+            tracing::debug!("ptau is prepared for phase 2, reading Lagrange basis");
+            Some(LagrangeBasis {
+                tau_g1: P::g1_vec_from_reader(
+                    binfile.take_section(LAGRANGE_TAU_G1_SECTION),
+                    4 * num_powers - 1,
+                )?,
+                tau_g2: P::g2_vec_from_reader(
+                    binfile.take_section(LAGRANGE_TAU_G2_SECTION),
+                    2 * num_powers - 1,
+                )?,
+                alpha_tau_g1: P::g1_vec_from_reader(
+                    binfile.take_section(LAGRANGE_ALPHA_TAU_G1_SECTION),
+                    2 * num_powers - 1,
+                )?,
+                beta_tau_g1: P::g1_vec_from_reader(
+                    binfile.take_section(LAGRANGE_BETA_TAU_G1_SECTION),
+                    2 * num_powers - 1,
+                )?,
+            })
+        } else {
+            None
+        };
+        tracing::debug!("ptau parsing done!");
+        Ok(Self {
+            power,
+            ceremony_power,
+            tau_g1,
+            tau_g2,
+            alpha_tau_g1,
+            beta_tau_g1,
+            beta_g2,
+            contributions,
+            lagrange,
+        })
+    }
+
+    fn read_header<R: Read>(mut reader: R) -> ZKeyParserResult<(usize, usize)> {
+        let n8 = u32::deserialize_uncompressed(&mut reader)?;
+        let expected_n8 = P::BaseField::MODULUS_BIT_SIZE.div_ceil(8);
+        if n8 != expected_n8 {
+            return Err(ZKeyParserError::UnexpectedByteSize(expected_n8, n8));
+        }
+        let q = <P::BaseField as PrimeField>::BigInt::deserialize_uncompressed(&mut reader)?;
+        if q != P::BaseField::MODULUS {
+            return Err(ZKeyParserError::InvalidPrimeInHeader);
+        }
+        let power = u32::deserialize_uncompressed(&mut reader)?;
+        let ceremony_power = u32::deserialize_uncompressed(&mut reader)?;
+        Ok((
+            usize::try_from(power).expect("u32 fits into usize"),
+            usize::try_from(ceremony_power).expect("u32 fits into usize"),
+        ))
+    }
+
+    /// Serializes the [`PowersOfTau`] into a writer, using the same layout as snarkjs.
+    pub fn to_writer<W: Write>(&self, writer: W) -> ZKeyParserResult<()> {
+        let mut header = Vec::new();
+        header.write_u32::<LittleEndian>(P::BaseField::MODULUS_BIT_SIZE.div_ceil(8))?;
+        P::BaseField::MODULUS.serialize_uncompressed(&mut header)?;
+        header
+            .write_u32::<LittleEndian>(u32::try_from(self.power).expect("power fits into u32"))?;
+        header.write_u32::<LittleEndian>(
+            u32::try_from(self.ceremony_power).expect("power fits into u32"),
+        )?;
+
+        let g1_section = |points: &[P::G1Affine]| {
+            let mut section = Vec::new();
+            P::g1_vec_to_writer(points, &mut section)?;
+            ZKeyParserResult::Ok(section)
+        };
+        let g2_section = |points: &[P::G2Affine]| {
+            let mut section = Vec::new();
+            P::g2_vec_to_writer(points, &mut section)?;
+            ZKeyParserResult::Ok(section)
+        };
+
+        let mut contributions = Vec::new();
+        contributions.write_u32::<LittleEndian>(
+            u32::try_from(self.contributions.len()).expect("number of contributions fits into u32"),
+        )?;
+        for contribution in self.contributions.iter() {
+            contribution.write(&mut contributions)?;
+        }
+
+        let mut sections = vec![
+            (HEADER_SECTION, header),
+            (TAU_G1_SECTION, g1_section(&self.tau_g1)?),
+            (TAU_G2_SECTION, g2_section(&self.tau_g2)?),
+            (ALPHA_TAU_G1_SECTION, g1_section(&self.alpha_tau_g1)?),
+            (BETA_TAU_G1_SECTION, g1_section(&self.beta_tau_g1)?),
+            (BETA_G2_SECTION, g2_section(&[self.beta_g2])?),
+            (CONTRIBUTIONS_SECTION, contributions),
+        ];
+        if let Some(lagrange) = &self.lagrange {
+            sections.extend([
+                (LAGRANGE_TAU_G1_SECTION, g1_section(&lagrange.tau_g1)?),
+                (LAGRANGE_TAU_G2_SECTION, g2_section(&lagrange.tau_g2)?),
+                (
+                    LAGRANGE_ALPHA_TAU_G1_SECTION,
+                    g1_section(&lagrange.alpha_tau_g1)?,
+                ),
+                (
+                    LAGRANGE_BETA_TAU_G1_SECTION,
+                    g1_section(&lagrange.beta_tau_g1)?,
+                ),
+            ]);
+        }
+        let sections = sections
+            .into_iter()
+            .map(|(id, section)| {
+                (
+                    u32::try_from(id).expect("section id fits into u32"),
+                    section,
+                )
+            })
+            .collect::<Vec<_>>();
+        binfile::write_bin_file(writer, PTAU_FTYPE, PTAU_VERSION, &sections)?;
+        Ok(())
+    }
+}
+
+impl<P: Pairing> PowersOfTau<P> {
+    /// Creates a ptau with the given power from local randomness. The ptau has no contributions
+    /// and is already prepared for phase 2.
+    ///
+    /// **This ptau is insecure**, as whoever knows the secrets can forge proofs for every circuit
+    /// that is set up with it. Only use it for testing, otherwise use the ptau of a public
+    /// ceremony.
+    ///
+    /// # Panics
+    /// Panics if the scalar field has no evaluation domain of size 2^(power + 1).
+    pub fn insecure_setup<R: Rng + CryptoRng>(rng: &mut R, power: usize) -> Self {
+        let tau = P::ScalarField::rand(rng);
+        let alpha = P::ScalarField::rand(rng);
+        let beta = P::ScalarField::rand(rng);
+        Self::from_secrets(power, tau, alpha, beta)
+    }
+
+    /// Creates a ptau prepared for phase 2 with the provided secrets (without contributions).
+    pub(crate) fn from_secrets(
+        power: usize,
+        tau: P::ScalarField,
+        alpha: P::ScalarField,
+        beta: P::ScalarField,
+    ) -> Self {
+        assert!(
+            power < usize::try_from(P::ScalarField::TWO_ADICITY).expect("u32 fits into usize"),
+            "power {power} too big for this curve"
+        );
+        let tau_powers = powers(tau, 2 * (1 << power) - 1);
+        let scaled = |factor: P::ScalarField, v: &[P::ScalarField]| {
+            v.iter().map(|x| *x * factor).collect::<Vec<_>>()
+        };
+        let n = 1 << power;
+        let lagrange_g1 = lagrange(&tau_powers, power + 1);
+        let lagrange_n = lagrange(&tau_powers[..n], power);
+        Self {
+            power,
+            ceremony_power: power,
+            tau_g1: to_group::<P::G1Affine>(&tau_powers),
+            tau_g2: to_group::<P::G2Affine>(&tau_powers[..n]),
+            alpha_tau_g1: to_group::<P::G1Affine>(&scaled(alpha, &tau_powers[..n])),
+            beta_tau_g1: to_group::<P::G1Affine>(&scaled(beta, &tau_powers[..n])),
+            beta_g2: (P::G2Affine::generator() * beta).into_affine(),
+            contributions: vec![],
+            lagrange: Some(LagrangeBasis {
+                tau_g1: to_group::<P::G1Affine>(&lagrange_g1),
+                tau_g2: to_group::<P::G2Affine>(&lagrange_n),
+                alpha_tau_g1: to_group::<P::G1Affine>(&scaled(alpha, &lagrange_n)),
+                beta_tau_g1: to_group::<P::G1Affine>(&scaled(beta, &lagrange_n)),
+            }),
+        }
+    }
+}
+
+fn powers<F: Field>(x: F, n: usize) -> Vec<F> {
+    std::iter::successors(Some(F::one()), |p| Some(*p * x))
+        .take(n)
+        .collect()
+}
+
+fn to_group<G: AffineRepr>(scalars: &[G::ScalarField]) -> Vec<G> {
+    let points = scalars
+        .par_iter()
+        .map(|s| G::generator() * s)
+        .collect::<Vec<_>>();
+    G::Group::normalize_batch(&points)
+}
+
+// the evaluations of the Lagrange polynomials for all domains up to 2^max_pow, where the powers
+// that are not part of the ptau are zero
+fn lagrange<F: PrimeField>(powers: &[F], max_pow: usize) -> Vec<F> {
+    let mut result = Vec::new();
+    for p in 0..=max_pow {
+        let mut evals = vec![F::zero(); 1 << p];
+        let len = powers.len().min(1 << p);
+        evals[..len].copy_from_slice(&powers[..len]);
+        let domain = evaluation_domain::<F>(1 << p).expect("power checked above");
+        result.extend(domain.ifft(&evals));
+    }
+    result
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> PtauContribution<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn read<R: Read>(mut reader: R) -> ZKeyParserResult<Self> {
+        let tau_g1 = P::g1_from_reader(&mut reader)?;
+        let tau_g2 = P::g2_from_reader(&mut reader)?;
+        let alpha_g1 = P::g1_from_reader(&mut reader)?;
+        let beta_g1 = P::g1_from_reader(&mut reader)?;
+        let beta_g2 = P::g2_from_reader(&mut reader)?;
+        // snarkjs stores the G1 points of all proofs first, followed by the G2 points
+        let mut g1 = Vec::with_capacity(6);
+        for _ in 0..6 {
+            g1.push(P::g1_from_reader(&mut reader)?);
+        }
+        let mut proof = |g1_s, g1_sx| {
+            ZKeyParserResult::Ok(ProofOfKnowledge {
+                g1_s,
+                g1_sx,
+                g2_spx: P::g2_from_reader(&mut reader)?,
+            })
+        };
+        let key = PtauPublicKey {
+            tau: proof(g1[0], g1[1])?,
+            alpha: proof(g1[2], g1[3])?,
+            beta: proof(g1[4], g1[5])?,
+        };
+        let mut partial_hash = [0u8; 216];
+        reader.read_exact(&mut partial_hash)?;
+        let mut next_challenge = [0u8; 64];
+        reader.read_exact(&mut next_challenge)?;
+        let (name, beacon) = read_contribution_params(&mut reader)?;
+        Ok(Self {
+            tau_g1,
+            tau_g2,
+            alpha_g1,
+            beta_g1,
+            beta_g2,
+            key,
+            partial_hash,
+            next_challenge,
+            name,
+            beacon,
+        })
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> ZKeyParserResult<()> {
+        P::g1_to_writer(&self.tau_g1, &mut writer)?;
+        P::g2_to_writer(&self.tau_g2, &mut writer)?;
+        P::g1_to_writer(&self.alpha_g1, &mut writer)?;
+        P::g1_to_writer(&self.beta_g1, &mut writer)?;
+        P::g2_to_writer(&self.beta_g2, &mut writer)?;
+        let proofs = [&self.key.tau, &self.key.alpha, &self.key.beta];
+        for proof in proofs {
+            P::g1_to_writer(&proof.g1_s, &mut writer)?;
+            P::g1_to_writer(&proof.g1_sx, &mut writer)?;
+        }
+        for proof in proofs {
+            P::g2_to_writer(&proof.g2_spx, &mut writer)?;
+        }
+        writer.write_all(&self.partial_hash)?;
+        writer.write_all(&self.next_challenge)?;
+        write_contribution_params(writer, self.name.as_deref(), self.beacon.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    fn roundtrip<P: Pairing + CircomArkworksPairingBridge>()
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let mut rng = ark_std::test_rng();
+        let mut ptau = PowersOfTau::<P>::from_secrets(
+            3,
+            P::ScalarField::rand(&mut rng),
+            P::ScalarField::rand(&mut rng),
+            P::ScalarField::rand(&mut rng),
+        );
+        let g1 = P::G1Affine::generator();
+        let g2 = P::G2Affine::generator();
+        let proof = ProofOfKnowledge::<P> {
+            g1_s: g1,
+            g1_sx: (g1 * P::ScalarField::from(2u64)).into_affine(),
+            g2_spx: g2,
+        };
+        ptau.contributions.push(PtauContribution {
+            tau_g1: ptau.tau_g1[1],
+            tau_g2: ptau.tau_g2[1],
+            alpha_g1: ptau.alpha_tau_g1[0],
+            beta_g1: ptau.beta_tau_g1[0],
+            beta_g2: ptau.beta_g2,
+            key: PtauPublicKey {
+                tau: proof.clone(),
+                alpha: proof.clone(),
+                beta: proof,
+            },
+            partial_hash: [1; 216],
+            next_challenge: [2; 64],
+            name: Some("first".to_owned()),
+            beacon: Some(Beacon {
+                num_iterations_exp: 10,
+                hash: vec![3; 32],
+            }),
+        });
+        let mut bytes = Vec::new();
+        ptau.to_writer(&mut bytes).unwrap();
+        let read = PowersOfTau::<P>::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(read.power, ptau.power);
+        assert_eq!(read.ceremony_power, ptau.ceremony_power);
+        assert_eq!(read.tau_g1, ptau.tau_g1);
+        assert_eq!(read.tau_g2, ptau.tau_g2);
+        assert_eq!(read.alpha_tau_g1, ptau.alpha_tau_g1);
+        assert_eq!(read.beta_tau_g1, ptau.beta_tau_g1);
+        assert_eq!(read.beta_g2, ptau.beta_g2);
+        assert_eq!(read.contributions, ptau.contributions);
+        let (read, expected) = (read.lagrange.unwrap(), ptau.lagrange.unwrap());
+        assert_eq!(read.tau_g1, expected.tau_g1);
+        assert_eq!(read.tau_g2, expected.tau_g2);
+        assert_eq!(read.alpha_tau_g1, expected.alpha_tau_g1);
+        assert_eq!(read.beta_tau_g1, expected.beta_tau_g1);
+        let mut bytes2 = Vec::new();
+        PowersOfTau::<P>::from_reader(bytes.as_slice())
+            .unwrap()
+            .to_writer(&mut bytes2)
+            .unwrap();
+        assert_eq!(bytes, bytes2);
+    }
+
+    #[test]
+    fn can_ser_ptau_bn254() {
+        roundtrip::<Bn254>();
+    }
+
+    #[test]
+    fn can_ser_ptau_bls12_381() {
+        roundtrip::<Bls12_381>();
+    }
+
+    #[test]
+    fn rejects_zkey_as_ptau() {
+        let zkey = std::fs::File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey")
+            .unwrap();
+        assert!(PowersOfTau::<Bn254>::from_reader(zkey).is_err());
+    }
+
+    #[test]
+    fn insecure_setup_is_consistent() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let ptau = PowersOfTau::<Bn254>::insecure_setup(&mut rng, 2);
+        let g1 = <Bn254 as Pairing>::G1Affine::generator();
+        let g2 = <Bn254 as Pairing>::G2Affine::generator();
+        assert_eq!(ptau.tau_g1.len(), 7);
+        assert_eq!(ptau.tau_g2.len(), 4);
+        assert_eq!(ptau.tau_g1[0], g1);
+        for i in 1..4 {
+            assert_eq!(
+                Bn254::pairing(ptau.tau_g1[i], g2),
+                Bn254::pairing(g1, ptau.tau_g2[i])
+            );
+            assert_eq!(
+                Bn254::pairing(ptau.beta_tau_g1[i], g2),
+                Bn254::pairing(ptau.tau_g1[i], ptau.beta_g2)
+            );
+        }
+        let lagrange = ptau.lagrange.as_ref().unwrap();
+        // the Lagrange polynomials of a domain sum up to one
+        let sum = lagrange_domain(&lagrange.tau_g1, 2)
+            .iter()
+            .fold(<Bn254 as Pairing>::G1::default(), |acc, p| acc + p);
+        assert_eq!(sum.into_affine(), g1);
+
+        let mut bytes = Vec::new();
+        ptau.to_writer(&mut bytes).unwrap();
+        let read = PowersOfTau::<Bn254>::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(read.tau_g1, ptau.tau_g1);
+    }
+}
@@ -24,26 +24,30 @@
 //IN CONNECTION WITH THE SOFTWARE O THE USE OR OTHER
 //DEALINGS IN THE SOFTWARE.R
 
-//! This module provides the [`R1CS`] type which implements [`R1CS::from_reader`] for parsing the R1CS file format used by circom.
+//! This module provides the [`R1CS`] type which implements [`R1CS::from_reader`] for parsing and [`R1CS::to_writer`] for writing the R1CS file format used by circom.
 //! Inspired by <https://github.com/arkworks-rs/circom-compat/blob/170b10fc9ed182b5f72ecf379033dda023d0bf07/src/circom/r1cs_reader.rs>
 use ark_ff::PrimeField;
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::io::{Error, ErrorKind};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{Error, ErrorKind, Write};
 use thiserror::Error;
 
 use ark_ec::pairing::Pairing;
-use ark_serialize::{CanonicalDeserialize, SerializationError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use ark_std::io::{Read, Seek, SeekFrom};
 
 use std::collections::HashMap;
 
 use crate::{
+    binfile,
     reader_utils::{self, InvalidHeaderError},
     traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
 };
 
 const R1CS_HEADER: &str = "r1cs";
 const MAX_VERSION: u32 = 1;
+const HEADER_SECTION_ID: u32 = 1;
+const CONSTRAINT_SECTION_ID: u32 = 2;
+const WIRE2LABEL_SECTION_ID: u32 = 3;
 type Result<T> = std::result::Result<T, R1CSParserError>;
 
 //TODO maybe write something better that is not so convoluted to access
@@ -124,9 +128,9 @@ where
             reader.seek(SeekFrom::Current(sec_size as i64))?;
         }
 
-        let header_type = 1;
-        let constraint_type = 2;
-        let wire2label_type = 3;
+        let header_type = HEADER_SECTION_ID;
+        let constraint_type = CONSTRAINT_SECTION_ID;
+        let wire2label_type = WIRE2LABEL_SECTION_ID;
 
         let header_offset = sec_offsets.get(&header_type).ok_or_else(|| {
             Error::new(
@@ -213,6 +217,69 @@ where
             n_constraints,
         })
     }
+
+    /// Serializes the [`R1CS`] into a writer. Like circom, we write the constraint section
+    /// first, followed by the header and the wire to label mapping.
+    pub fn to_writer<W: Write>(&self, writer: W) -> Result<()> {
+        let mut header = Vec::new();
+        header.write_u32::<LittleEndian>(
+            u32::try_from(P::ScalarField::SERIALIZED_BYTE_SIZE).expect("field size fits into u32"),
+        )?;
+        P::ScalarField::MODULUS.serialize_uncompressed(&mut header)?;
+        header.write_u32::<LittleEndian>(usize_to_u32(self.num_variables)?)?;
+        header.write_u32::<LittleEndian>(self.n_pub_out)?;
+        header.write_u32::<LittleEndian>(self.n_pub_in)?;
+        header.write_u32::<LittleEndian>(self.n_prv_in)?;
+        header.write_u64::<LittleEndian>(self.n_labels)?;
+        header.write_u32::<LittleEndian>(usize_to_u32(self.n_constraints)?)?;
+
+        let mut constraints = Vec::new();
+        for (a, b, c) in self.constraints.iter() {
+            write_constraint_vec::<_, P>(&mut constraints, a)?;
+            write_constraint_vec::<_, P>(&mut constraints, b)?;
+            write_constraint_vec::<_, P>(&mut constraints, c)?;
+        }
+
+        let mut wire_mapping = Vec::with_capacity(self.wire_mapping.len() * 8);
+        for label in self.wire_mapping.iter() {
+            wire_mapping
+                .write_u64::<LittleEndian>(u64::try_from(*label).expect("usize fits into u64"))?;
+        }
+
+        let sections = [
+            (CONSTRAINT_SECTION_ID, constraints),
+            (HEADER_SECTION_ID, header),
+            (WIRE2LABEL_SECTION_ID, wire_mapping),
+        ];
+        binfile::write_bin_file(writer, R1CS_HEADER, MAX_VERSION, &sections)?;
+        Ok(())
+    }
+}
+
+fn usize_to_u32(x: usize) -> Result<u32> {
+    u32::try_from(x).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            "R1CS files use u32 for sizes and indices",
+        )
+        .into()
+    })
+}
+
+fn write_constraint_vec<W: Write, P: Pairing + CircomArkworksPairingBridge>(
+    mut writer: W,
+    vec: &ConstraintVec<P>,
+) -> Result<()>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    writer.write_u32::<LittleEndian>(usize_to_u32(vec.len())?)?;
+    for (signal, value) in vec.iter() {
+        writer.write_u32::<LittleEndian>(usize_to_u32(*signal)?)?;
+        value.to_writer(&mut writer)?;
+    }
+    Ok(())
 }
 
 fn read_constraint_vec<R: Read, P: Pairing + CircomArkworksPairingBridge>(
@@ -333,4 +400,33 @@ mod tests {
         );
         assert_eq!(r1cs.wire_mapping, vec![0, 1, 2, 3]);
     }
+
+    fn roundtrip<P: Pairing + CircomArkworksPairingBridge>(path: &str)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let bytes = std::fs::read(path).unwrap();
+        let r1cs = R1CS::<P>::from_reader(std::io::Cursor::new(&bytes)).unwrap();
+        let mut ser = Vec::new();
+        r1cs.to_writer(&mut ser).unwrap();
+        assert_eq!(ser, bytes, "{path}");
+    }
+
+    #[test]
+    fn can_ser_r1cs() {
+        for curve in ["bn254", "bls12_381"] {
+            for proof_system in ["Groth16", "Plonk"] {
+                for circuit in ["multiplier2", "poseidon"] {
+                    let path =
+                        format!("../../test_vectors/{proof_system}/{curve}/{circuit}/circuit.r1cs");
+                    if curve == "bn254" {
+                        roundtrip::<Bn254>(&path);
+                    } else {
+                        roundtrip::<Bls12_381>(&path);
+                    }
+                }
+            }
+        }
+    }
 }
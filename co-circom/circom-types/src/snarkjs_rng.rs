@@ -0,0 +1,190 @@
+//! snarkjs derives some of the values of a setup ceremony deterministically (e.g., the point in G2 that
+//! is bound to the transcript of a contribution, or the whole contribution of a random beacon). To create
+//! contributions that snarkjs accepts, we have to sample these values exactly like ffjavascript does. This
+//! module provides the ChaCha based rng of ffjavascript and the corresponding `fromRng` implementations.
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use ark_std::rand::{self, RngCore};
+
+const CHACHA_CONSTANTS: [u32; 4] = [0x61707865, 0x3320646E, 0x79622D32, 0x6B206574];
+
+/// The ChaCha20 based rng of ffjavascript. In contrast to the rand implementations, `next_u64` takes the
+/// high bits from the first `u32`.
+pub(crate) struct SnarkjsChaCha {
+    state: [u32; 16],
+    buf: [u32; 16],
+    idx: usize,
+}
+
+impl SnarkjsChaCha {
+    pub(crate) fn new(seed: [u32; 8]) -> Self {
+        let mut state = [0; 16];
+        state[..4].copy_from_slice(&CHACHA_CONSTANTS);
+        state[4..12].copy_from_slice(&seed);
+        Self {
+            state,
+            buf: [0; 16],
+            idx: 16,
+        }
+    }
+
+    /// Creates the rng from the first 32 bytes of a hash, which are interpreted as big-endian `u32`s.
+    pub(crate) fn from_hash(hash: &[u8]) -> Self {
+        let mut seed = [0; 8];
+        for (s, chunk) in seed.iter_mut().zip(hash.chunks_exact(4)) {
+            *s = u32::from_be_bytes(chunk.try_into().expect("chunks have size 4"));
+        }
+        Self::new(seed)
+    }
+
+    fn update(&mut self) {
+        let mut buf = self.state;
+        for _ in 0..10 {
+            quarter_round(&mut buf, 0, 4, 8, 12);
+            quarter_round(&mut buf, 1, 5, 9, 13);
+            quarter_round(&mut buf, 2, 6, 10, 14);
+            quarter_round(&mut buf, 3, 7, 11, 15);
+            quarter_round(&mut buf, 0, 5, 10, 15);
+            quarter_round(&mut buf, 1, 6, 11, 12);
+            quarter_round(&mut buf, 2, 7, 8, 13);
+            quarter_round(&mut buf, 3, 4, 9, 14);
+        }
+        for (b, s) in buf.iter_mut().zip(self.state.iter()) {
+            *b = b.wrapping_add(*s);
+        }
+        self.buf = buf;
+        self.idx = 0;
+        // 128 bit block counter
+        for counter in self.state[12..].iter_mut() {
+            *counter = counter.wrapping_add(1);
+            if *counter != 0 {
+                break;
+            }
+        }
+    }
+}
+
+fn quarter_round(st: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    st[a] = st[a].wrapping_add(st[b]);
+    st[d] = (st[d] ^ st[a]).rotate_left(16);
+    st[c] = st[c].wrapping_add(st[d]);
+    st[b] = (st[b] ^ st[c]).rotate_left(12);
+    st[a] = st[a].wrapping_add(st[b]);
+    st[d] = (st[d] ^ st[a]).rotate_left(8);
+    st[c] = st[c].wrapping_add(st[d]);
+    st[b] = (st[b] ^ st[c]).rotate_left(7);
+}
+
+impl RngCore for SnarkjsChaCha {
+    fn next_u32(&mut self) -> u32 {
+        if self.idx == 16 {
+            self.update();
+        }
+        let next = self.buf[self.idx];
+        self.idx += 1;
+        next
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let high = u64::from(self.next_u32());
+        let low = u64::from(self.next_u32());
+        (high << 32) | low
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Samples a prime field element like ffjavascript. ffjavascript fills the montgomery representation
+/// with random bits, i.e., the sampled integer v corresponds to the element v * R^-1.
+pub(crate) fn prime_field_from_rng<F: PrimeField>(rng: &mut impl RngCore) -> F {
+    let mask_bits = F::MODULUS_BIT_SIZE % 64;
+    let num_bits = 64 * F::BigInt::default().as_ref().len();
+    let r_inv = F::from(2u64)
+        .pow([u64::try_from(num_bits).expect("fits into u64")])
+        .inverse()
+        .expect("R is invertible");
+    loop {
+        let mut bigint = F::BigInt::default();
+        for limb in bigint.as_mut().iter_mut() {
+            *limb = rng.next_u64();
+        }
+        if mask_bits != 0 {
+            let last = bigint.as_mut().last_mut().expect("at least one limb");
+            *last &= (1 << mask_bits) - 1;
+        }
+        if bigint < F::MODULUS {
+            return F::from_bigint(bigint).expect("is reduced") * r_inv;
+        }
+    }
+}
+
+/// Samples an element of a (possibly) extension field like ffjavascript, i.e., the coefficients one after another.
+pub(crate) fn field_from_rng<F: Field>(rng: &mut impl RngCore) -> F {
+    let elements = (0..F::extension_degree())
+        .map(|_| prime_field_from_rng::<F::BasePrimeField>(rng))
+        .collect::<Vec<_>>();
+    F::from_base_prime_field_elems(&elements).expect("correct extension degree")
+}
+
+// ffjavascript calls an element negative if its largest non-zero coefficient is larger than (p-1)/2
+fn is_negative<F: Field>(x: &F) -> bool {
+    x.to_base_prime_field_elements()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .find(|c| !c.is_zero())
+        .map(|c| c.into_bigint() > F::BasePrimeField::MODULUS_MINUS_ONE_DIV_TWO)
+        .unwrap_or(false)
+}
+
+/// Samples a point of the curve like ffjavascript's `fromRng`. We sample x-coordinates until we find a point
+/// on the curve, fix the sign of y with an additional random bit and finally clear the cofactor.
+pub(crate) fn point_from_rng<C: SWCurveConfig>(rng: &mut impl RngCore) -> Affine<C> {
+    loop {
+        let x = field_from_rng::<C::BaseField>(rng);
+        let greatest = rng.next_u32() & 1 == 1;
+        let x3b = x.square() * x + C::COEFF_A * x + C::COEFF_B;
+        if let Some(y) = x3b.sqrt() {
+            let y = if is_negative(&y) != greatest { -y } else { y };
+            return Affine::<C>::new_unchecked(x, y)
+                .mul_by_cofactor_to_group()
+                .into_affine();
+        }
+    }
+}
+
+/// Writes the point in the uncompressed format of ffjavascript (big-endian coordinates in standard form,
+/// highest coefficients first). The point at infinity is encoded as zeros with the infinity flag set.
+pub(crate) fn point_to_uncompressed<G: AffineRepr>(p: &G, buf: &mut Vec<u8>) {
+    let mut coordinates = Vec::new();
+    let (x, y) = match p.xy() {
+        Some((x, y)) => (*x, *y),
+        None => (G::BaseField::ZERO, G::BaseField::ZERO),
+    };
+    for coordinate in [x, y] {
+        let mut elements = coordinate
+            .to_base_prime_field_elements()
+            .collect::<Vec<_>>();
+        elements.reverse();
+        for element in elements {
+            coordinates.extend(element.into_bigint().to_bytes_be());
+        }
+    }
+    if p.is_zero() {
+        coordinates[0] |= 0x40;
+    }
+    buf.extend(coordinates);
+}
@@ -1,10 +1,11 @@
 //! This module contains traits for serializing and deserializing field elements and curve points into and from circom files to arkworks representation.
-use std::io::Read;
+use std::io::{Read, Write};
 use std::marker::PhantomData;
 
 use ark_ec::{pairing::Pairing, AffineRepr};
 use ark_ff::{PrimeField, Zero};
 use ark_serialize::SerializationError;
+use ark_std::rand::RngCore;
 use rayon::prelude::*;
 use serde::ser::SerializeSeq;
 use serde::{de, Serializer};
@@ -40,7 +41,7 @@ mod $mod_name {
 
     use $curve::{$config, Fq, Fq2, Fr};
     use ark_ff::BigInt;
-    use ark_serialize::{CanonicalDeserialize, SerializationError};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
     use serde::ser::SerializeSeq;
 
     use super::*;
@@ -66,6 +67,24 @@ mod $mod_name {
                 Ok(Self::new_unchecked(Self::montgomery_bigint_from_reader(reader)?.into_bigint()))
             }
 
+            #[inline]
+            fn to_writer(&self, writer: impl Write) -> IoResult<()> {
+                self.into_bigint().serialize_uncompressed(writer)
+            }
+
+            #[inline]
+            fn montgomery_bigint_to_writer(&self, writer: impl Write) -> IoResult<()> {
+                self.0.serialize_uncompressed(writer)
+            }
+
+            #[inline]
+            fn to_writer_for_groth16_zkey(&self, writer: impl Write) -> IoResult<()> {
+                // inverse of from_reader_for_groth16_zkey, i.e., we write self * R^2
+                Self::from_bigint(self.0)
+                    .expect("montgomery form is reduced")
+                    .montgomery_bigint_to_writer(writer)
+            }
+
         }
         impl CircomArkworksPrimeFieldBridge for Fq {
             const SERIALIZED_BYTE_SIZE: usize = $field_size;
@@ -88,6 +107,24 @@ mod $mod_name {
             fn from_reader_for_groth16_zkey(reader: impl Read) -> IoResult<Self> {
                 Ok(Self::new_unchecked(Self::montgomery_bigint_from_reader(reader)?.into_bigint()))
             }
+
+            #[inline]
+            fn to_writer(&self, writer: impl Write) -> IoResult<()> {
+                self.into_bigint().serialize_uncompressed(writer)
+            }
+
+            #[inline]
+            fn montgomery_bigint_to_writer(&self, writer: impl Write) -> IoResult<()> {
+                self.0.serialize_uncompressed(writer)
+            }
+
+            #[inline]
+            fn to_writer_for_groth16_zkey(&self, writer: impl Write) -> IoResult<()> {
+                // inverse of from_reader_for_groth16_zkey, i.e., we write self * R^2
+                Self::from_bigint(self.0)
+                    .expect("montgomery form is reduced")
+                    .montgomery_bigint_to_writer(writer)
+            }
         }
 
         impl CircomArkworksPairingBridge for $config {
@@ -166,6 +203,29 @@ mod $mod_name {
                 Self::g2_from_bytes(&buf)
             }
 
+            fn g1_to_writer(p: &Self::G1Affine, mut writer: impl Write) -> IoResult<()> {
+                //circom encodes the point at infinity as (0, 0)
+                let (x, y) = p.xy().map(|(x, y)| (*x, *y)).unwrap_or((Fq::zero(), Fq::zero()));
+                x.montgomery_bigint_to_writer(&mut writer)?;
+                y.montgomery_bigint_to_writer(&mut writer)
+            }
+
+            fn g2_to_writer(p: &Self::G2Affine, mut writer: impl Write) -> IoResult<()> {
+                let (x, y) = p.xy().map(|(x, y)| (*x, *y)).unwrap_or((Fq2::zero(), Fq2::zero()));
+                x.c0.montgomery_bigint_to_writer(&mut writer)?;
+                x.c1.montgomery_bigint_to_writer(&mut writer)?;
+                y.c0.montgomery_bigint_to_writer(&mut writer)?;
+                y.c1.montgomery_bigint_to_writer(&mut writer)
+            }
+
+            fn g1_from_snarkjs_rng(rng: &mut impl RngCore) -> Self::G1Affine {
+                crate::snarkjs_rng::point_from_rng::<$curve::g1::Config>(rng)
+            }
+
+            fn g2_from_snarkjs_rng(rng: &mut impl RngCore) -> Self::G2Affine {
+                crate::snarkjs_rng::point_from_rng::<$curve::g2::Config>(rng)
+            }
+
             fn g1_from_strings_projective(x: &str, y: &str, z: &str) -> IoResult<Self::G1Affine> {
                 let x = parse_field(x)?;
                 let y = parse_field(y)?;
@@ -259,8 +319,13 @@ mod $mod_name {
                 seq.end()
             }
             fn serialize_fr<S: Serializer>(p: &Self::ScalarField, ser: S) -> Result<S::Ok, S::Error> {
-                ser.serialize_str(&p.to_string())
+                // arkworks prints zero as an empty string, snarkjs expects "0"
+                if p.is_zero() {
+                    ser.serialize_str("0")
+                } else {
+                    ser.serialize_str(&p.to_string())
                 }
+            }
 
             fn deserialize_gt_element<'de, D>(
                 deserializer: D,
@@ -568,6 +633,32 @@ where
             .map(|chunk| Self::g2_from_bytes(chunk))
             .collect::<Result<Vec<_>, SerializationError>>()
     }
+    /// Serializes element of G1 into writer in montgomery form, which is the inverse of [`Self::g1_from_reader`]
+    fn g1_to_writer(p: &Self::G1Affine, writer: impl Write) -> IoResult<()>;
+    /// Serializes element of G2 into writer in montgomery form, which is the inverse of [`Self::g2_from_reader`]
+    fn g2_to_writer(p: &Self::G2Affine, writer: impl Write) -> IoResult<()>;
+    /// Samples an element of G1 like snarkjs does for deterministic values in setup ceremonies
+    fn g1_from_snarkjs_rng(rng: &mut impl RngCore) -> Self::G1Affine;
+    /// Samples an element of G2 like snarkjs does for deterministic values in setup ceremonies
+    fn g2_from_snarkjs_rng(rng: &mut impl RngCore) -> Self::G2Affine;
+    /// Serializes vec of G1 into writer in montgomery form
+    /// The default implementation runs multithreaded using rayon
+    fn g1_vec_to_writer(points: &[Self::G1Affine], mut writer: impl Write) -> IoResult<()> {
+        let mut buf = vec![0u8; Self::G1_SERIALIZED_BYTE_SIZE_UNCOMPRESSED * points.len()];
+        buf.par_chunks_exact_mut(Self::G1_SERIALIZED_BYTE_SIZE_UNCOMPRESSED)
+            .zip(points.par_iter())
+            .try_for_each(|(chunk, p)| Self::g1_to_writer(p, chunk))?;
+        Ok(writer.write_all(&buf)?)
+    }
+    /// Serializes vec of G2 into writer in montgomery form
+    /// The default implementation runs multithreaded using rayon
+    fn g2_vec_to_writer(points: &[Self::G2Affine], mut writer: impl Write) -> IoResult<()> {
+        let mut buf = vec![0u8; Self::G2_SERIALIZED_BYTE_SIZE_UNCOMPRESSED * points.len()];
+        buf.par_chunks_exact_mut(Self::G2_SERIALIZED_BYTE_SIZE_UNCOMPRESSED)
+            .zip(points.par_iter())
+            .try_for_each(|(chunk, p)| Self::g2_to_writer(p, chunk))?;
+        Ok(writer.write_all(&buf)?)
+    }
     /// Deserializes element of G1 from strings representing projective coordinates
     fn g1_from_strings_projective(x: &str, y: &str, z: &str) -> IoResult<Self::G1Affine>;
     /// Deserializes element of G2 from strings representing projective coordinates
@@ -623,7 +714,7 @@ where
     fn serialize_fr<S: Serializer>(p: &Self::ScalarField, ser: S) -> Result<S::Ok, S::Error>;
 }
 
-/// Bridge trait to deserialize field elements contained in circom files into [`ark_ff::PrimeField`] representation
+/// Bridge trait to serialize and deserialize field elements contained in circom files into and from [`ark_ff::PrimeField`] representation
 pub trait CircomArkworksPrimeFieldBridge: PrimeField {
     /// Size of serialized field element in bytes
     const SERIALIZED_BYTE_SIZE: usize;
@@ -634,6 +725,12 @@ pub trait CircomArkworksPrimeFieldBridge: PrimeField {
     fn montgomery_bigint_from_reader(reader: impl Read) -> IoResult<Self>;
     /// deserializes field elements that are multiplied by R^2 already (elements in Groth16 zkey are of this form)
     fn from_reader_for_groth16_zkey(reader: impl Read) -> IoResult<Self>;
+    /// Serializes field elements in standard (non-montgomery) form, which is the inverse of [`Self::from_reader`]
+    fn to_writer(&self, writer: impl Write) -> IoResult<()>;
+    /// Serializes the big int of the montgomery form of the field element, which is the inverse of [`Self::montgomery_bigint_from_reader`]
+    fn montgomery_bigint_to_writer(&self, writer: impl Write) -> IoResult<()>;
+    /// Serializes field elements multiplied by R^2, which is the inverse of [`Self::from_reader_for_groth16_zkey`]
+    fn to_writer_for_groth16_zkey(&self, writer: impl Write) -> IoResult<()>;
 }
 
 impl_bn256!();
@@ -1,9 +1,10 @@
-//! This module defines the [`Witness`] struct that implements deserialization of circom witness files via [`Witness::from_reader`].
+//! This module defines the [`Witness`] struct that implements deserialization of circom witness files via [`Witness::from_reader`]
+//! and serialization via [`Witness::to_writer`].
 
-use std::io;
+use std::io::{self, Write};
 
-use ark_serialize::{Read, SerializationError};
-use byteorder::{LittleEndian, ReadBytesExt};
+use ark_serialize::{CanonicalSerialize, Read, SerializationError};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use thiserror::Error;
 
 use crate::traits::CircomArkworksPrimeFieldBridge;
@@ -15,6 +16,8 @@ type Result<T> = std::result::Result<T, WitnessParserError>;
 const WITNESS_HEADER: &str = "wtns";
 const MAX_VERSION: u32 = 2;
 const N_SECTIONS: u32 = 2;
+const HEADER_SECTION_ID: u32 = 1;
+const WITNESS_SECTION_ID: u32 = 2;
 
 /// Error type describing errors during parsing witness files
 #[derive(Debug, Error)]
@@ -89,6 +92,30 @@ impl<F: CircomArkworksPrimeFieldBridge> Witness<F> {
                 .collect::<Result<Vec<F>>>()?,
         })
     }
+
+    /// Serializes the [`Witness`] into a writer. The output has the same format as the `.wtns` files written by circom and snarkjs.
+    pub fn to_writer<W: Write>(&self, mut writer: W) -> Result<()> {
+        let n8 = u32::try_from(F::SERIALIZED_BYTE_SIZE).expect("field size fits into u32");
+        let n_witness = u32::try_from(self.values.len()).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, "too many witness elements")
+        })?;
+        writer.write_all(WITNESS_HEADER.as_bytes())?;
+        writer.write_u32::<LittleEndian>(MAX_VERSION)?;
+        writer.write_u32::<LittleEndian>(N_SECTIONS)?;
+        // header section: n8, the modulus and the number of witness elements
+        writer.write_u32::<LittleEndian>(HEADER_SECTION_ID)?;
+        writer.write_u64::<LittleEndian>(u64::from(n8) + 8)?;
+        writer.write_u32::<LittleEndian>(n8)?;
+        F::MODULUS.serialize_uncompressed(&mut writer)?;
+        writer.write_u32::<LittleEndian>(n_witness)?;
+        // witness section: the witness elements in (non-montgomery) little endian form
+        writer.write_u32::<LittleEndian>(WITNESS_SECTION_ID)?;
+        writer.write_u64::<LittleEndian>(u64::from(n8) * u64::from(n_witness))?;
+        for value in self.values.iter() {
+            value.to_writer(&mut writer)?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -132,4 +159,24 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn can_ser_witness_bn254() {
+        let bytes =
+            std::fs::read("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+        let witness = Witness::<ark_bn254::Fr>::from_reader(bytes.as_slice()).unwrap();
+        let mut ser = Vec::new();
+        witness.to_writer(&mut ser).unwrap();
+        assert_eq!(ser, bytes);
+    }
+
+    #[test]
+    fn can_ser_witness_bls12381() {
+        let bytes =
+            std::fs::read("../../test_vectors/Groth16/bls12_381/multiplier2/witness.wtns").unwrap();
+        let witness = Witness::<ark_bls12_381::Fr>::from_reader(bytes.as_slice()).unwrap();
+        let mut ser = Vec::new();
+        witness.to_writer(&mut ser).unwrap();
+        assert_eq!(ser, bytes);
+    }
 }
@@ -18,6 +18,25 @@ use std::collections::BTreeMap;
 type FieldShareVec<T, P> = <T as PrimeFieldMpcProtocol<<P as Pairing>::ScalarField>>::FieldShareVec;
 
 mod serde_compat;
+pub mod share_file;
+
+pub use share_file::{ShareFileError, ShareKind, ShareMetadata, ShareProtocol};
+
+/// Checks that `num_parties` parties can compute on Shamir shares of the given threshold (the
+/// degree of the sharing polynomials), i.e., that the threshold is at least 1 and that there are at
+/// least `2 * threshold + 1` parties, as multiplications double the degree.
+pub fn check_shamir_parameters(threshold: usize, num_parties: usize) -> eyre::Result<()> {
+    if threshold < 1 {
+        eyre::bail!("SHAMIR requires the threshold to be at least 1");
+    }
+    if num_parties < 2 * threshold + 1 {
+        eyre::bail!(
+            "SHAMIR requires at least 2 * threshold + 1 = {} parties, got {num_parties}",
+            2 * threshold + 1
+        );
+    }
+    Ok(())
+}
 
 /// A shared witness in the circom ecosystem.
 #[derive(Debug, Serialize, Deserialize)]
@@ -173,24 +192,83 @@ impl<N: Rep3Network, P: Pairing> SharedWitness<Rep3Protocol<P::ScalarField, N>,
 }
 
 impl<N: ShamirNetwork, P: Pairing> SharedWitness<ShamirProtocol<P::ScalarField, N>, P> {
-    /// Shares a given witness and public input vector using the Shamir protocol.
+    /// Shares a given witness and public input vector using the Shamir protocol. Fails if the
+    /// parameters are rejected by [check_shamir_parameters].
     pub fn share_shamir<R: Rng + CryptoRng>(
         witness: Witness<P::ScalarField>,
         num_pub_inputs: usize,
         degree: usize,
         num_parties: usize,
         rng: &mut R,
-    ) -> Vec<Self> {
+    ) -> eyre::Result<Vec<Self>> {
+        check_shamir_parameters(degree, num_parties)?;
         let public_inputs = &witness.values[..num_pub_inputs];
         let witness = &witness.values[num_pub_inputs..];
         let shares = shamir::utils::share_field_elements(witness, degree, num_parties, rng);
-        shares
+        Ok(shares
             .into_iter()
             .map(|share| Self {
                 public_inputs: public_inputs.to_vec(),
                 witness: share,
             })
-            .collect()
+            .collect())
+    }
+}
+
+impl<N: Rep3Network, P: Pairing> SharedInput<Rep3Protocol<P::ScalarField, N>, P> {
+    /// Shares a given input map using the Rep3 protocol. Inputs whose names are contained
+    /// in `public_inputs` are not shared but copied to every party.
+    pub fn share_rep3<R: Rng + CryptoRng>(
+        input: BTreeMap<String, Vec<P::ScalarField>>,
+        public_inputs: &[String],
+        rng: &mut R,
+    ) -> [Self; 3] {
+        let mut shares = [Self::default(), Self::default(), Self::default()];
+        for (name, vals) in input {
+            if public_inputs.contains(&name) {
+                shares[0].add_public_input(name.clone(), vals.clone());
+                shares[1].add_public_input(name.clone(), vals.clone());
+                shares[2].add_public_input(name, vals);
+            } else {
+                let [share0, share1, share2] = rep3::utils::share_field_elements(&vals, rng);
+                shares[0].add_shared_input(name.clone(), share0);
+                shares[1].add_shared_input(name.clone(), share1);
+                shares[2].add_shared_input(name, share2);
+            }
+        }
+        shares
+    }
+}
+
+impl<N: ShamirNetwork, P: Pairing> SharedInput<ShamirProtocol<P::ScalarField, N>, P> {
+    /// Shares a given input map using the Shamir protocol. Inputs whose names are contained
+    /// in `public_inputs` are not shared but copied to every party. Fails if the parameters are
+    /// rejected by [check_shamir_parameters].
+    pub fn share_shamir<R: Rng + CryptoRng>(
+        input: BTreeMap<String, Vec<P::ScalarField>>,
+        public_inputs: &[String],
+        degree: usize,
+        num_parties: usize,
+        rng: &mut R,
+    ) -> eyre::Result<Vec<Self>> {
+        check_shamir_parameters(degree, num_parties)?;
+        let mut shares = (0..num_parties)
+            .map(|_| Self::default())
+            .collect::<Vec<_>>();
+        for (name, vals) in input {
+            if public_inputs.contains(&name) {
+                for share in shares.iter_mut() {
+                    share.add_public_input(name.clone(), vals.clone());
+                }
+            } else {
+                let input_shares =
+                    shamir::utils::share_field_elements(&vals, degree, num_parties, rng);
+                for (share, input_share) in shares.iter_mut().zip(input_shares) {
+                    share.add_shared_input(name.clone(), input_share);
+                }
+            }
+        }
+        Ok(shares)
     }
 }
 
@@ -220,3 +298,159 @@ pub mod utils {
         (q, roots)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Bn254;
+    use mpc_core::protocols::{rep3::network::Rep3MpcNet, shamir::network::ShamirMpcNet};
+    use std::fs::File;
+
+    type Rep3Witness = SharedWitness<Rep3Protocol<ark_bn254::Fr, Rep3MpcNet>, Bn254>;
+    type ShamirWitness = SharedWitness<ShamirProtocol<ark_bn254::Fr, ShamirMpcNet>, Bn254>;
+    type Rep3Input = SharedInput<Rep3Protocol<ark_bn254::Fr, Rep3MpcNet>, Bn254>;
+    type ShamirInput = SharedInput<ShamirProtocol<ark_bn254::Fr, ShamirMpcNet>, Bn254>;
+
+    const NUM_PUB_INPUTS: usize = 2;
+
+    fn witness() -> Witness<ark_bn254::Fr> {
+        let witness_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+        Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap()
+    }
+
+    fn input() -> BTreeMap<String, Vec<ark_bn254::Fr>> {
+        BTreeMap::from([
+            ("a".to_owned(), vec![3.into()]),
+            ("b".to_owned(), vec![11.into(), 5.into()]),
+            ("c".to_owned(), vec![33.into()]),
+        ])
+    }
+
+    #[test]
+    fn rep3_witness_shares_reconstruct() {
+        let witness = witness();
+        let [share1, share2, share3] =
+            Rep3Witness::share_rep3(witness.clone(), NUM_PUB_INPUTS, &mut rand::thread_rng());
+        for share in [&share1, &share2, &share3] {
+            assert_eq!(share.public_inputs, witness.values[..NUM_PUB_INPUTS]);
+        }
+        let reconstructed =
+            rep3::utils::combine_field_elements(share1.witness, share2.witness, share3.witness);
+        assert_eq!(reconstructed, witness.values[NUM_PUB_INPUTS..]);
+    }
+
+    #[test]
+    fn shamir_witness_shares_reconstruct() {
+        let witness = witness();
+        for (threshold, num_parties) in [(1, 3), (1, 4), (2, 5), (3, 7)] {
+            let shares = ShamirWitness::share_shamir(
+                witness.clone(),
+                NUM_PUB_INPUTS,
+                threshold,
+                num_parties,
+                &mut rand::thread_rng(),
+            )
+            .unwrap();
+            assert_eq!(shares.len(), num_parties);
+            for share in &shares {
+                assert_eq!(share.public_inputs, witness.values[..NUM_PUB_INPUTS]);
+            }
+            // any threshold + 1 parties can reconstruct, take the last ones
+            let (coeffs, shares): (Vec<_>, Vec<_>) = shares
+                .into_iter()
+                .enumerate()
+                .skip(num_parties - threshold - 1)
+                .map(|(i, share)| (i + 1, share.witness))
+                .unzip();
+            let reconstructed =
+                shamir::utils::combine_field_elements(&shares, &coeffs, threshold).unwrap();
+            assert_eq!(reconstructed, witness.values[NUM_PUB_INPUTS..]);
+        }
+    }
+
+    #[test]
+    fn rep3_input_shares_reconstruct() {
+        let input = input();
+        let public_inputs = ["c".to_owned()];
+        let [share1, share2, share3] =
+            Rep3Input::share_rep3(input.clone(), &public_inputs, &mut rand::thread_rng());
+        for share in [&share1, &share2, &share3] {
+            assert_eq!(
+                share.public_inputs,
+                BTreeMap::from([("c".to_owned(), input["c"].clone())])
+            );
+            assert_eq!(share.shared_inputs.len(), 2);
+        }
+        for name in ["a", "b"] {
+            let reconstructed = rep3::utils::combine_field_elements(
+                share1.shared_inputs[name].clone(),
+                share2.shared_inputs[name].clone(),
+                share3.shared_inputs[name].clone(),
+            );
+            assert_eq!(reconstructed, input[name]);
+        }
+    }
+
+    #[test]
+    fn shamir_input_shares_reconstruct() {
+        let input = input();
+        let public_inputs = ["c".to_owned()];
+        let (threshold, num_parties) = (2, 5);
+        let shares = ShamirInput::share_shamir(
+            input.clone(),
+            &public_inputs,
+            threshold,
+            num_parties,
+            &mut rand::thread_rng(),
+        )
+        .unwrap();
+        assert_eq!(shares.len(), num_parties);
+        for share in &shares {
+            assert_eq!(
+                share.public_inputs,
+                BTreeMap::from([("c".to_owned(), input["c"].clone())])
+            );
+            assert_eq!(share.shared_inputs.len(), 2);
+        }
+        for name in ["a", "b"] {
+            let name_shares = shares
+                .iter()
+                .map(|share| share.shared_inputs[name].clone())
+                .collect::<Vec<_>>();
+            let coeffs = (1..=num_parties).collect::<Vec<_>>();
+            let reconstructed =
+                shamir::utils::combine_field_elements(&name_shares, &coeffs, threshold).unwrap();
+            assert_eq!(reconstructed, input[name]);
+        }
+    }
+
+    #[test]
+    fn shamir_rejects_invalid_thresholds() {
+        let mut rng = rand::thread_rng();
+        for (threshold, num_parties) in [(0, 3), (1, 2), (2, 4), (3, 6)] {
+            let err = ShamirWitness::share_shamir(
+                witness(),
+                NUM_PUB_INPUTS,
+                threshold,
+                num_parties,
+                &mut rng,
+            )
+            .err()
+            .expect("the parameters are rejected");
+            let expected = if threshold == 0 {
+                "SHAMIR requires the threshold to be at least 1".to_owned()
+            } else {
+                format!(
+                    "SHAMIR requires at least 2 * threshold + 1 = {} parties, got {num_parties}",
+                    2 * threshold + 1
+                )
+            };
+            assert_eq!(err.to_string(), expected);
+            let err = ShamirInput::share_shamir(input(), &[], threshold, num_parties, &mut rng)
+                .err()
+                .expect("the parameters are rejected");
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+}
@@ -0,0 +1,511 @@
+//! This module defines the versioned container format for share files.
+//!
+//! A share file consists of the [`SHARE_FILE_MAGIC`] bytes, the format version as little-endian `u32`,
+//! the bincode-encoded [`ShareMetadata`] and finally the bincode-encoded share itself
+//! (a [`SharedInput`](crate::SharedInput) or a [`SharedWitness`](crate::SharedWitness)).
+//! The metadata allows to detect mixed-up share files before any MPC computation is started.
+//!
+//! Share files can optionally be encrypted to the receiving party, see the [`encryption`] module.
+
+use std::io::{self, Read, Write};
+
+use ark_ec::pairing::Pairing;
+use circom_types::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
+use rand::{CryptoRng, RngCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use encryption::{ShareDecryptionKey, ShareEncryptionKey, ENCRYPTED_SHARE_FILE_MAGIC};
+
+pub mod encryption;
+
+/// The magic bytes at the beginning of every share file.
+pub const SHARE_FILE_MAGIC: [u8; 8] = *b"coshare\0";
+/// The current version of the share file format. Version 2 added the zkey hash to the
+/// [`ShareMetadata`], share files of version 1 are read without it.
+pub const SHARE_FILE_VERSION: u32 = 2;
+
+/// The errors that may arise while reading, writing or validating a share file.
+#[derive(Debug, thiserror::Error)]
+pub enum ShareFileError {
+    /// The file does not start with [`SHARE_FILE_MAGIC`].
+    #[error("not a share file (invalid magic bytes)")]
+    InvalidMagic,
+    /// The file was written by a newer version of the format.
+    #[error("unsupported share file version {0}, expected at most {SHARE_FILE_VERSION}")]
+    UnsupportedVersion(u32),
+    /// The metadata of the share file does not match the expected value.
+    #[error("share file mismatch in {field}: expected {expected}, got {found}")]
+    Mismatch {
+        /// The name of the mismatching field
+        field: &'static str,
+        /// The expected value
+        expected: String,
+        /// The value found in the share file
+        found: String,
+    },
+    /// The share file does not record the hash of the circuit or zkey to check against.
+    #[error("share file does not record a {0}")]
+    MissingHash(&'static str),
+    /// The share file is encrypted, but no decryption key was provided.
+    #[error("share file is encrypted, but no decryption key was provided")]
+    MissingDecryptionKey,
+    /// The provided certificate or key could not be parsed.
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+    /// Encrypting the share file failed.
+    #[error("could not encrypt share file")]
+    Encryption,
+    /// Decrypting the share file failed, e.g., because it was encrypted to another party.
+    #[error("could not decrypt share file, is it encrypted to this party?")]
+    Decryption,
+    /// Error during (de)serialization of the metadata or the share.
+    #[error(transparent)]
+    Bincode(#[from] bincode::Error),
+    /// An [io::Error].
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+}
+
+/// The kind of share stored in a share file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareKind {
+    /// A [`SharedInput`](crate::SharedInput)
+    Input,
+    /// A [`SharedWitness`](crate::SharedWitness)
+    Witness,
+}
+
+/// The MPC protocol a share was created for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareProtocol {
+    /// Replicated secret sharing
+    Rep3,
+    /// Shamir secret sharing
+    Shamir,
+}
+
+impl std::fmt::Display for ShareKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareKind::Input => write!(f, "input"),
+            ShareKind::Witness => write!(f, "witness"),
+        }
+    }
+}
+
+impl std::fmt::Display for ShareProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareProtocol::Rep3 => write!(f, "REP3"),
+            ShareProtocol::Shamir => write!(f, "SHAMIR"),
+        }
+    }
+}
+
+/// The metadata stored in front of every share.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareMetadata {
+    /// The kind of the share
+    pub kind: ShareKind,
+    /// The MPC protocol the share was created for
+    pub protocol: ShareProtocol,
+    /// The name of the curve as defined in circom
+    pub curve: String,
+    /// The id of the party this share belongs to
+    pub party_id: usize,
+    /// The number of parties
+    pub num_parties: usize,
+    /// The threshold of tolerated colluding parties
+    pub threshold: usize,
+    /// The SHA-256 hash of the circuit this share was created for, if known
+    pub circuit_hash: Option<[u8; 32]>,
+    /// The SHA-256 hash of the zkey this share is meant to be proven with, if known
+    pub zkey_hash: Option<[u8; 32]>,
+}
+
+/// The metadata of share files of version 1, which did not record the zkey hash.
+#[derive(Deserialize)]
+struct ShareMetadataV1 {
+    kind: ShareKind,
+    protocol: ShareProtocol,
+    curve: String,
+    party_id: usize,
+    num_parties: usize,
+    threshold: usize,
+    circuit_hash: Option<[u8; 32]>,
+}
+
+impl From<ShareMetadataV1> for ShareMetadata {
+    fn from(metadata: ShareMetadataV1) -> Self {
+        Self {
+            kind: metadata.kind,
+            protocol: metadata.protocol,
+            curve: metadata.curve,
+            party_id: metadata.party_id,
+            num_parties: metadata.num_parties,
+            threshold: metadata.threshold,
+            circuit_hash: metadata.circuit_hash,
+            zkey_hash: None,
+        }
+    }
+}
+
+fn check_field<T: PartialEq + std::fmt::Display>(
+    field: &'static str,
+    expected: T,
+    found: T,
+) -> Result<(), ShareFileError> {
+    if expected != found {
+        return Err(ShareFileError::Mismatch {
+            field,
+            expected: expected.to_string(),
+            found: found.to_string(),
+        });
+    }
+    Ok(())
+}
+
+impl ShareMetadata {
+    /// Creates new metadata for a share over the curve `P`, without a circuit and zkey hash.
+    pub fn new<P: Pairing + CircomArkworksPairingBridge>(
+        kind: ShareKind,
+        protocol: ShareProtocol,
+        party_id: usize,
+        num_parties: usize,
+        threshold: usize,
+    ) -> Self
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        Self {
+            kind,
+            protocol,
+            curve: P::get_circom_name(),
+            party_id,
+            num_parties,
+            threshold,
+            circuit_hash: None,
+            zkey_hash: None,
+        }
+    }
+
+    /// Sets the circuit hash of the metadata.
+    pub fn with_circuit_hash(mut self, circuit_hash: Option<[u8; 32]>) -> Self {
+        self.circuit_hash = circuit_hash;
+        self
+    }
+
+    /// Sets the zkey hash of the metadata.
+    pub fn with_zkey_hash(mut self, zkey_hash: Option<[u8; 32]>) -> Self {
+        self.zkey_hash = zkey_hash;
+        self
+    }
+
+    /// Checks that the share is of the given kind.
+    pub fn check_kind(&self, kind: ShareKind) -> Result<(), ShareFileError> {
+        check_field("kind", kind, self.kind)
+    }
+
+    /// Checks that the share was created for the given protocol.
+    pub fn check_protocol(&self, protocol: ShareProtocol) -> Result<(), ShareFileError> {
+        check_field("protocol", protocol, self.protocol)
+    }
+
+    /// Checks that the share was created for the curve `P`.
+    pub fn check_curve<P: Pairing + CircomArkworksPairingBridge>(
+        &self,
+    ) -> Result<(), ShareFileError>
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        check_field("curve", P::get_circom_name(), self.curve.clone())
+    }
+
+    /// Checks that the share belongs to the party with the given id.
+    pub fn check_party_id(&self, party_id: usize) -> Result<(), ShareFileError> {
+        check_field("party id", party_id, self.party_id)
+    }
+
+    /// Checks that the share was created for the given number of parties.
+    pub fn check_num_parties(&self, num_parties: usize) -> Result<(), ShareFileError> {
+        check_field("number of parties", num_parties, self.num_parties)
+    }
+
+    /// Checks that the share was created with the given threshold.
+    pub fn check_threshold(&self, threshold: usize) -> Result<(), ShareFileError> {
+        check_field("threshold", threshold, self.threshold)
+    }
+
+    /// Checks that the share was created for the circuit with the given hash. Shares without
+    /// a circuit hash are rejected, unless `allow_missing` is set.
+    pub fn check_circuit_hash(
+        &self,
+        circuit_hash: &[u8; 32],
+        allow_missing: bool,
+    ) -> Result<(), ShareFileError> {
+        check_hash(
+            "circuit hash",
+            circuit_hash,
+            self.circuit_hash.as_ref(),
+            allow_missing,
+        )
+    }
+
+    /// Checks that the share is meant to be proven with the zkey with the given hash. Shares
+    /// without a zkey hash are rejected, unless `allow_missing` is set.
+    pub fn check_zkey_hash(
+        &self,
+        zkey_hash: &[u8; 32],
+        allow_missing: bool,
+    ) -> Result<(), ShareFileError> {
+        check_hash(
+            "zkey hash",
+            zkey_hash,
+            self.zkey_hash.as_ref(),
+            allow_missing,
+        )
+    }
+}
+
+fn check_hash(
+    field: &'static str,
+    expected: &[u8; 32],
+    found: Option<&[u8; 32]>,
+    allow_missing: bool,
+) -> Result<(), ShareFileError> {
+    match found {
+        Some(found) => check_field(field, to_hex(expected), to_hex(found)),
+        None if allow_missing => Ok(()),
+        None => Err(ShareFileError::MissingHash(field)),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Computes the SHA-256 hash of a circuit (or any other file) read from the provided reader.
+pub fn hash_circuit<R: Read>(mut reader: R) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
+/// Writes a share together with its metadata to the provided writer.
+pub fn write_share<W: Write, S: Serialize>(
+    mut writer: W,
+    metadata: &ShareMetadata,
+    share: &S,
+) -> Result<(), ShareFileError> {
+    writer.write_all(&SHARE_FILE_MAGIC)?;
+    writer.write_all(&SHARE_FILE_VERSION.to_le_bytes())?;
+    bincode::serialize_into(&mut writer, metadata)?;
+    bincode::serialize_into(&mut writer, share)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes a share together with its metadata to the provided writer, encrypted to the recipient.
+pub fn write_share_encrypted<W: Write, S: Serialize, R: RngCore + CryptoRng>(
+    mut writer: W,
+    metadata: &ShareMetadata,
+    share: &S,
+    recipient: &ShareEncryptionKey,
+    rng: &mut R,
+) -> Result<(), ShareFileError> {
+    let mut plaintext = Vec::new();
+    write_share(&mut plaintext, metadata, share)?;
+    writer.write_all(&encryption::encrypt(&plaintext, recipient, rng)?)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a share together with its metadata from the provided reader. Fails with
+/// [`ShareFileError::MissingDecryptionKey`] if the share file is encrypted.
+pub fn read_share<R: Read, S: DeserializeOwned>(
+    reader: R,
+) -> Result<(ShareMetadata, S), ShareFileError> {
+    read_share_with_key(reader, None)
+}
+
+/// Reads a possibly encrypted share together with its metadata from the provided reader.
+/// Encrypted share files are decrypted with the provided key.
+pub fn read_share_with_key<R: Read, S: DeserializeOwned>(
+    mut reader: R,
+    key: Option<&ShareDecryptionKey>,
+) -> Result<(ShareMetadata, S), ShareFileError> {
+    let mut magic = [0u8; SHARE_FILE_MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if magic == ENCRYPTED_SHARE_FILE_MAGIC {
+        let key = key.ok_or(ShareFileError::MissingDecryptionKey)?;
+        let mut envelope = magic.to_vec();
+        reader.read_to_end(&mut envelope)?;
+        let plaintext = encryption::decrypt(&envelope, key)?;
+        return read_share_with_key(plaintext.as_slice(), None);
+    }
+    if magic != SHARE_FILE_MAGIC {
+        return Err(ShareFileError::InvalidMagic);
+    }
+    let mut version = [0u8; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version > SHARE_FILE_VERSION {
+        return Err(ShareFileError::UnsupportedVersion(version));
+    }
+    let metadata = if version == 1 {
+        bincode::deserialize_from::<_, ShareMetadataV1>(&mut reader)?.into()
+    } else {
+        bincode::deserialize_from(&mut reader)?
+    };
+    let share = bincode::deserialize_from(&mut reader)?;
+    Ok((metadata, share))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SharedWitness;
+    use ark_bn254::Bn254;
+    use circom_types::Witness;
+    use mpc_core::protocols::rep3::{network::Rep3MpcNet, Rep3Protocol};
+    use std::fs::File;
+
+    type Rep3Witness = SharedWitness<Rep3Protocol<ark_bn254::Fr, Rep3MpcNet>, Bn254>;
+
+    fn shared_witness() -> [Rep3Witness; 3] {
+        let witness_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+        let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+        Rep3Witness::share_rep3(witness, 2, &mut rand::thread_rng())
+    }
+
+    #[test]
+    fn roundtrip_share_file() {
+        let [share, _, _] = shared_witness();
+        let metadata =
+            ShareMetadata::new::<Bn254>(ShareKind::Witness, ShareProtocol::Rep3, 0, 3, 1)
+                .with_circuit_hash(Some([42; 32]))
+                .with_zkey_hash(Some([43; 32]));
+        let mut bytes = vec![];
+        write_share(&mut bytes, &metadata, &share).unwrap();
+        let (read_metadata, read_share): (_, Rep3Witness) = read_share(bytes.as_slice()).unwrap();
+        assert_eq!(metadata, read_metadata);
+        assert_eq!(share.public_inputs, read_share.public_inputs);
+        assert_eq!(share.witness, read_share.witness);
+    }
+
+    #[test]
+    fn detect_mismatches() {
+        let metadata =
+            ShareMetadata::new::<Bn254>(ShareKind::Witness, ShareProtocol::Rep3, 1, 3, 1)
+                .with_circuit_hash(Some([42; 32]))
+                .with_zkey_hash(Some([43; 32]));
+        metadata.check_kind(ShareKind::Witness).unwrap();
+        metadata.check_protocol(ShareProtocol::Rep3).unwrap();
+        metadata.check_curve::<Bn254>().unwrap();
+        metadata.check_party_id(1).unwrap();
+        metadata.check_num_parties(3).unwrap();
+        metadata.check_threshold(1).unwrap();
+        metadata.check_circuit_hash(&[42; 32], false).unwrap();
+        metadata.check_zkey_hash(&[43; 32], false).unwrap();
+        assert!(metadata.check_kind(ShareKind::Input).is_err());
+        assert!(metadata.check_protocol(ShareProtocol::Shamir).is_err());
+        assert!(metadata.check_curve::<ark_bls12_381::Bls12_381>().is_err());
+        assert!(metadata.check_party_id(2).is_err());
+        assert!(metadata.check_num_parties(4).is_err());
+        assert!(metadata.check_threshold(2).is_err());
+        assert!(metadata.check_circuit_hash(&[0; 32], false).is_err());
+        assert!(metadata.check_zkey_hash(&[0; 32], true).is_err());
+    }
+
+    #[test]
+    fn reject_missing_hashes() {
+        let metadata =
+            ShareMetadata::new::<Bn254>(ShareKind::Witness, ShareProtocol::Rep3, 1, 3, 1);
+        assert!(matches!(
+            metadata.check_circuit_hash(&[42; 32], false),
+            Err(ShareFileError::MissingHash("circuit hash"))
+        ));
+        assert!(matches!(
+            metadata.check_zkey_hash(&[43; 32], false),
+            Err(ShareFileError::MissingHash("zkey hash"))
+        ));
+        metadata.check_circuit_hash(&[42; 32], true).unwrap();
+        metadata.check_zkey_hash(&[43; 32], true).unwrap();
+    }
+
+    #[test]
+    fn read_version_1_share_file() {
+        #[derive(Serialize)]
+        struct MetadataV1 {
+            kind: ShareKind,
+            protocol: ShareProtocol,
+            curve: String,
+            party_id: usize,
+            num_parties: usize,
+            threshold: usize,
+            circuit_hash: Option<[u8; 32]>,
+        }
+        let [share, _, _] = shared_witness();
+        let mut bytes = SHARE_FILE_MAGIC.to_vec();
+        bytes.extend(1u32.to_le_bytes());
+        let metadata = MetadataV1 {
+            kind: ShareKind::Witness,
+            protocol: ShareProtocol::Rep3,
+            curve: "bn128".to_owned(),
+            party_id: 0,
+            num_parties: 3,
+            threshold: 1,
+            circuit_hash: Some([42; 32]),
+        };
+        bincode::serialize_into(&mut bytes, &metadata).unwrap();
+        bincode::serialize_into(&mut bytes, &share).unwrap();
+        let (read_metadata, read_share): (_, Rep3Witness) = read_share(bytes.as_slice()).unwrap();
+        assert_eq!(
+            read_metadata,
+            ShareMetadata::new::<Bn254>(ShareKind::Witness, ShareProtocol::Rep3, 0, 3, 1)
+                .with_circuit_hash(Some([42; 32]))
+        );
+        assert_eq!(share.witness, read_share.witness);
+    }
+
+    #[test]
+    fn roundtrip_encrypted_share_file() {
+        let [share, _, _] = shared_witness();
+        let metadata =
+            ShareMetadata::new::<Bn254>(ShareKind::Witness, ShareProtocol::Rep3, 0, 3, 1);
+        let recipient =
+            ShareEncryptionKey::from_cert_file("../co-circom/examples/data/cert0.der").unwrap();
+        let key = ShareDecryptionKey::from_key_file("../co-circom/examples/data/key0.der").unwrap();
+        let mut bytes = vec![];
+        write_share_encrypted(
+            &mut bytes,
+            &metadata,
+            &share,
+            &recipient,
+            &mut rand::thread_rng(),
+        )
+        .unwrap();
+        assert!(matches!(
+            read_share::<_, Rep3Witness>(bytes.as_slice()),
+            Err(ShareFileError::MissingDecryptionKey)
+        ));
+        let (read_metadata, read_share): (_, Rep3Witness) =
+            read_share_with_key(bytes.as_slice(), Some(&key)).unwrap();
+        assert_eq!(metadata, read_metadata);
+        assert_eq!(share.witness, read_share.witness);
+    }
+
+    #[test]
+    fn reject_bare_bincode() {
+        let [share, _, _] = shared_witness();
+        let bytes = bincode::serialize(&share).unwrap();
+        assert!(matches!(
+            read_share::<_, Rep3Witness>(bytes.as_slice()),
+            Err(ShareFileError::InvalidMagic)
+        ));
+    }
+}
@@ -0,0 +1,252 @@
+//! This module implements the optional encryption of share files to the public key of the receiving party.
+//!
+//! The keys are the same ones that are used for the network (see `NetworkConfig` in `mpc-net`), i.e., the
+//! recipient's public key is taken from its X.509 certificate and the private key is read from the key file,
+//! both DER or PEM encoded. At the moment only ECDSA P-256 keys (as generated by `gen_cert` and
+//! `co-circom network init`) are supported, the private key in PKCS#8 or SEC1 format.
+//!
+//! We use ECIES: a fresh ephemeral P-256 key is used for an ECDH with the recipient's public key, the shared
+//! secret is expanded with HKDF-SHA256 and the share file is encrypted with AES-256-GCM.
+//! An encrypted share file consists of the [`ENCRYPTED_SHARE_FILE_MAGIC`] bytes, the format version as
+//! little-endian `u32`, the compressed SEC1 encoding of the ephemeral public key, the nonce and the ciphertext.
+
+use std::{fs, io, path::Path};
+
+use aes_gcm::{
+    aead::{Aead, Payload},
+    Aes256Gcm, KeyInit, Nonce,
+};
+use hkdf::Hkdf;
+use p256::{
+    ecdh::EphemeralSecret,
+    elliptic_curve::sec1::ToEncodedPoint,
+    pkcs8::{DecodePrivateKey, DecodePublicKey},
+    PublicKey, SecretKey,
+};
+use rand::{CryptoRng, RngCore};
+use sha2::Sha256;
+use x509_cert::der::{Decode, DecodePem, Encode};
+
+use super::ShareFileError;
+
+/// The magic bytes at the beginning of every encrypted share file.
+pub const ENCRYPTED_SHARE_FILE_MAGIC: [u8; 8] = *b"coshenc\0";
+/// The current version of the encrypted share file format.
+pub const ENCRYPTED_SHARE_FILE_VERSION: u32 = 1;
+
+const HKDF_INFO: &[u8] = b"co-circom share encryption";
+const NONCE_SIZE: usize = 12;
+const COMPRESSED_POINT_SIZE: usize = 33;
+const HEADER_SIZE: usize = ENCRYPTED_SHARE_FILE_MAGIC.len() + 4 + COMPRESSED_POINT_SIZE;
+
+/// The public key a share is encrypted to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareEncryptionKey(PublicKey);
+
+/// The private key used to decrypt a share.
+#[derive(Clone)]
+pub struct ShareDecryptionKey(SecretKey);
+
+impl ShareEncryptionKey {
+    /// Extracts the public key from a DER-encoded X.509 certificate.
+    pub fn from_cert_der(der: &[u8]) -> Result<Self, ShareFileError> {
+        let cert = x509_cert::Certificate::from_der(der)
+            .map_err(|e| ShareFileError::InvalidKey(e.to_string()))?;
+        Self::from_cert(&cert)
+    }
+
+    /// Extracts the public key from a PEM-encoded X.509 certificate.
+    pub fn from_cert_pem(pem: &[u8]) -> Result<Self, ShareFileError> {
+        let cert = x509_cert::Certificate::from_pem(pem)
+            .map_err(|e| ShareFileError::InvalidKey(e.to_string()))?;
+        Self::from_cert(&cert)
+    }
+
+    fn from_cert(cert: &x509_cert::Certificate) -> Result<Self, ShareFileError> {
+        let spki = cert
+            .tbs_certificate
+            .subject_public_key_info
+            .to_der()
+            .map_err(|e| ShareFileError::InvalidKey(e.to_string()))?;
+        let key = PublicKey::from_public_key_der(&spki)
+            .map_err(|e| ShareFileError::InvalidKey(e.to_string()))?;
+        Ok(Self(key))
+    }
+
+    /// Reads the public key from a DER- or PEM-encoded X.509 certificate file.
+    pub fn from_cert_file(path: impl AsRef<Path>) -> Result<Self, ShareFileError> {
+        let data = fs::read(path)?;
+        if is_pem(&data) {
+            Self::from_cert_pem(&data)
+        } else {
+            Self::from_cert_der(&data)
+        }
+    }
+}
+
+impl ShareDecryptionKey {
+    /// Parses a PKCS#8 DER-encoded private key.
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self, ShareFileError> {
+        let key = SecretKey::from_pkcs8_der(der)
+            .map_err(|e| ShareFileError::InvalidKey(e.to_string()))?;
+        Ok(Self(key))
+    }
+
+    /// Parses a PEM-encoded private key in PKCS#8 (`PRIVATE KEY`) or SEC1 (`EC PRIVATE KEY`) format.
+    pub fn from_pem(pem: &str) -> Result<Self, ShareFileError> {
+        let key = SecretKey::from_pkcs8_pem(pem)
+            .or_else(|_| SecretKey::from_sec1_pem(pem))
+            .map_err(|e| ShareFileError::InvalidKey(e.to_string()))?;
+        Ok(Self(key))
+    }
+
+    /// Reads a private key file, either PKCS#8 DER-encoded or PEM-encoded (see [`Self::from_pem`]).
+    pub fn from_key_file(path: impl AsRef<Path>) -> Result<Self, ShareFileError> {
+        let data = fs::read(path)?;
+        if is_pem(&data) {
+            let pem = std::str::from_utf8(&data)
+                .map_err(|e| ShareFileError::InvalidKey(e.to_string()))?;
+            Self::from_pem(pem)
+        } else {
+            Self::from_pkcs8_der(&data)
+        }
+    }
+
+    /// Returns the corresponding [`ShareEncryptionKey`].
+    pub fn encryption_key(&self) -> ShareEncryptionKey {
+        ShareEncryptionKey(self.0.public_key())
+    }
+}
+
+fn is_pem(data: &[u8]) -> bool {
+    data.iter()
+        .skip_while(|b| b.is_ascii_whitespace())
+        .take(10)
+        .eq(b"-----BEGIN")
+}
+
+fn derive_cipher(
+    shared_secret: &p256::ecdh::SharedSecret,
+    ephemeral: &[u8],
+    recipient: &PublicKey,
+) -> Aes256Gcm {
+    let mut salt = ephemeral.to_vec();
+    salt.extend_from_slice(recipient.to_encoded_point(true).as_bytes());
+    let hkdf = Hkdf::<Sha256>::new(Some(&salt), shared_secret.raw_secret_bytes());
+    let mut key = [0u8; 32];
+    hkdf.expand(HKDF_INFO, &mut key)
+        .expect("32 is a valid length for HKDF-SHA256");
+    Aes256Gcm::new(&key.into())
+}
+
+/// Encrypts a serialized share file to the provided recipient.
+pub fn encrypt<R: RngCore + CryptoRng>(
+    plaintext: &[u8],
+    recipient: &ShareEncryptionKey,
+    rng: &mut R,
+) -> Result<Vec<u8>, ShareFileError> {
+    let ephemeral = EphemeralSecret::random(rng);
+    let ephemeral_public = ephemeral.public_key().to_encoded_point(true);
+    let shared_secret = ephemeral.diffie_hellman(&recipient.0);
+    let cipher = derive_cipher(&shared_secret, ephemeral_public.as_bytes(), &recipient.0);
+
+    let mut nonce = [0u8; NONCE_SIZE];
+    rng.fill_bytes(&mut nonce);
+
+    let mut envelope = Vec::with_capacity(HEADER_SIZE + NONCE_SIZE + plaintext.len() + 16);
+    envelope.extend_from_slice(&ENCRYPTED_SHARE_FILE_MAGIC);
+    envelope.extend_from_slice(&ENCRYPTED_SHARE_FILE_VERSION.to_le_bytes());
+    envelope.extend_from_slice(ephemeral_public.as_bytes());
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &envelope,
+            },
+        )
+        .map_err(|_| ShareFileError::Encryption)?;
+    envelope.extend_from_slice(&nonce);
+    envelope.extend_from_slice(&ciphertext);
+    Ok(envelope)
+}
+
+/// Decrypts an encrypted share file with the provided key.
+pub fn decrypt(envelope: &[u8], key: &ShareDecryptionKey) -> Result<Vec<u8>, ShareFileError> {
+    if envelope.len() < HEADER_SIZE + NONCE_SIZE {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    let (header, rest) = envelope.split_at(HEADER_SIZE);
+    let (magic, header_rest) = header.split_at(ENCRYPTED_SHARE_FILE_MAGIC.len());
+    if magic != ENCRYPTED_SHARE_FILE_MAGIC {
+        return Err(ShareFileError::InvalidMagic);
+    }
+    let (version, ephemeral) = header_rest.split_at(4);
+    let version = u32::from_le_bytes(version.try_into().expect("we split at 4"));
+    if version > ENCRYPTED_SHARE_FILE_VERSION {
+        return Err(ShareFileError::UnsupportedVersion(version));
+    }
+    let ephemeral_public =
+        PublicKey::from_sec1_bytes(ephemeral).map_err(|_| ShareFileError::Decryption)?;
+    let shared_secret =
+        p256::ecdh::diffie_hellman(key.0.to_nonzero_scalar(), ephemeral_public.as_affine());
+    let cipher = derive_cipher(&shared_secret, ephemeral, &key.0.public_key());
+
+    let (nonce, ciphertext) = rest.split_at(NONCE_SIZE);
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| ShareFileError::Decryption)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CERT0: &str = "../co-circom/examples/data/cert0.der";
+    const KEY0: &str = "../co-circom/examples/data/key0.der";
+    const KEY1: &str = "../co-circom/examples/data/key1.der";
+
+    #[test]
+    fn encrypt_decrypt_with_network_keys() {
+        let recipient = ShareEncryptionKey::from_cert_file(CERT0).unwrap();
+        let key = ShareDecryptionKey::from_key_file(KEY0).unwrap();
+        assert_eq!(recipient, key.encryption_key());
+
+        let msg = b"this is a very secret share".to_vec();
+        let envelope = encrypt(&msg, &recipient, &mut rand::thread_rng()).unwrap();
+        assert_eq!(decrypt(&envelope, &key).unwrap(), msg);
+
+        let wrong_key = ShareDecryptionKey::from_key_file(KEY1).unwrap();
+        assert!(matches!(
+            decrypt(&envelope, &wrong_key),
+            Err(ShareFileError::Decryption)
+        ));
+
+        let mut tampered = envelope.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            decrypt(&tampered, &key),
+            Err(ShareFileError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn pem_keys() {
+        use p256::pkcs8::{EncodePrivateKey, LineEnding};
+
+        let key = ShareDecryptionKey::from_key_file(KEY0).unwrap();
+        let pkcs8 = key.0.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let sec1 = key.0.to_sec1_pem(LineEnding::LF).unwrap();
+        for pem in [pkcs8.as_str(), sec1.as_str()] {
+            let pem_key = ShareDecryptionKey::from_pem(pem).unwrap();
+            assert_eq!(pem_key.encryption_key(), key.encryption_key());
+        }
+        assert!(ShareDecryptionKey::from_pem("-----BEGIN GARBAGE-----").is_err());
+    }
+}
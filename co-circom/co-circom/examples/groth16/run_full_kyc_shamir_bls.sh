@@ -1,9 +1,9 @@
 # split input into shares
 cargo run --release --bin co-circom -- split-input --circuit test_vectors/kyc/circuit.circom  --input test_vectors/kyc/input.json --protocol REP3 --curve BLS12-381 --out-dir test_vectors/kyc --config test_vectors/kyc/config.toml
 # run witness extension in MPC
-cargo run --release --bin co-circom -- generate-witness --input test_vectors/kyc/input.json.0.shared --circuit test_vectors/kyc/circuit.circom --protocol REP3 --curve BLS12-381 --config ../configs/party1.toml --out test_vectors/kyc/witness.wtns.0.shared &
-cargo run --release --bin co-circom -- generate-witness --input test_vectors/kyc/input.json.1.shared --circuit test_vectors/kyc/circuit.circom --protocol REP3 --curve BLS12-381 --config ../configs/party2.toml --out test_vectors/kyc/witness.wtns.1.shared &
-cargo run --release --bin co-circom -- generate-witness --input test_vectors/kyc/input.json.2.shared --circuit test_vectors/kyc/circuit.circom --protocol REP3 --curve BLS12-381 --config ../configs/party3.toml --out test_vectors/kyc/witness.wtns.2.shared
+cargo run --release --bin co-circom -- generate-witness --input test_vectors/kyc/input.json.0.shared --circuit test_vectors/kyc/circuit.circom --zkey test_vectors/kyc/bls12/kyc.zkey --protocol REP3 --curve BLS12-381 --config ../configs/party1.toml --out test_vectors/kyc/witness.wtns.0.shared &
+cargo run --release --bin co-circom -- generate-witness --input test_vectors/kyc/input.json.1.shared --circuit test_vectors/kyc/circuit.circom --zkey test_vectors/kyc/bls12/kyc.zkey --protocol REP3 --curve BLS12-381 --config ../configs/party2.toml --out test_vectors/kyc/witness.wtns.1.shared &
+cargo run --release --bin co-circom -- generate-witness --input test_vectors/kyc/input.json.2.shared --circuit test_vectors/kyc/circuit.circom --zkey test_vectors/kyc/bls12/kyc.zkey --protocol REP3 --curve BLS12-381 --config ../configs/party3.toml --out test_vectors/kyc/witness.wtns.2.shared
 # run translation from REP3 to Shamir
 cargo run --release --bin co-circom -- translate-witness --witness test_vectors/kyc/witness.wtns.0.shared --src-protocol REP3 --target-protocol SHAMIR --curve BLS12-381 --config ../configs/party1.toml --out test_vectors/kyc/shamir_witness.wtns.0.shared &
 cargo run --release --bin co-circom -- translate-witness --witness test_vectors/kyc/witness.wtns.1.shared --src-protocol REP3 --target-protocol SHAMIR --curve BLS12-381 --config ../configs/party2.toml --out test_vectors/kyc/shamir_witness.wtns.1.shared &
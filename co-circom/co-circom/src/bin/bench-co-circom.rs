@@ -2,15 +2,37 @@ use clap::Parser;
 use color_eyre::eyre::{eyre, Context, Ok};
 use std::io::Write;
 use std::path::Path;
-use std::process::Stdio;
 use std::sync::mpsc::channel;
 use std::time::Duration;
 use std::{
-    fs::File, io::BufWriter, path::PathBuf, process::Command, process::ExitCode, thread,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+    process::Command,
+    process::ExitCode,
+    thread,
     time::Instant,
 };
 
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
+use circom_types::{
+    groth16::ZKey as Groth16ZKey,
+    plonk::ZKey as PlonkZKey,
+    ptau::PowersOfTau,
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+    Witness, R1CS,
+};
 use co_circom::file_utils::{check_dir_exists, check_file_exists};
+use co_circom_snarks::SharedWitness;
+use co_groth16::CoGroth16;
+use co_plonk::CoPlonk;
+use mpc_core::protocols::{
+    rep3::{network::Rep3MpcNet, Rep3Protocol},
+    shamir::{network::ShamirMpcNet, ShamirProtocol},
+};
+use mpc_net::{config::NetworkProfile, MpcNetworkHandler};
 
 fn install_tracing() {
     use tracing_subscriber::prelude::*;
@@ -52,7 +74,7 @@ struct Cli {
     /// Benchmarks witness generation in addition to proof generation and verification
     #[arg(long, default_value = "false")]
     gen_wtns: bool,
-    /// Generates the zkey file and stores it in the path specified by --zkey in case that file does not exist yet. Use --pot-power to specify the power of tau to use.
+    /// Generates the zkey file and stores it in the path specified by --zkey in case that file does not exist yet. Use --pot-power to specify the power of tau to use or --ptau to use an existing powers of tau file.
     #[arg(long, default_value = "false", requires("gen_wtns"))]
     gen_zkey: bool,
     /// Like --gen-zkey, but overwrites the zkey file if it already exists.
@@ -61,6 +83,9 @@ struct Cli {
     /// The power to use for the powers of tau ceremony (see <https://docs.circom.io/getting-started/proving-circuits/#powers-of-tau>)
     #[arg(long, default_value = "10")]
     pot_power: u16,
+    /// An existing powers of tau file that is prepared for phase 2. If passed, --gen-zkey uses this file instead of creating an insecure powers of tau from local randomness
+    #[arg(long)]
+    ptau: Option<PathBuf>,
     /// MPC protocol for co-circom
     #[arg(long, default_value = "REP3")]
     protocol: String,
@@ -133,18 +158,9 @@ struct Cli {
     /// Intermediate output: Generate witness: The path to the generated witness file from circom (and for co-circom wtns files that expand to gen_wtns_file.party_id.shared)
     #[arg(long, default_value = "tmp_circom_generated.wtns")]
     gen_wtns_file: PathBuf,
-    /// Intermediate output: Generate zkey: The path to the "pot12_0000.ptau" file (see <https://docs.circom.io/getting-started/proving-circuits/#powers-of-tau>)
-    #[arg(long, default_value = "tmp_pot_0000.ptau")]
-    pot_0000: PathBuf,
-    /// Intermediate output: Generate zkey: The path to the "pot12_0001.ptau" file (see <https://docs.circom.io/getting-started/proving-circuits/#powers-of-tau>)
-    #[arg(long, default_value = "tmp_pot_0001.ptau")]
-    pot_0001: PathBuf,
-    /// Intermediate output: Generate zkey: The path to the "pot12_final.ptau" file (see <https://docs.circom.io/getting-started/proving-circuits/#phase-2>)
+    /// Intermediate output: Generate zkey: The path to the insecure powers of tau file that is created if --ptau is not passed, already prepared for phase 2 (see <https://docs.circom.io/getting-started/proving-circuits/#phase-2>)
     #[arg(long, default_value = "tmp_pot_final.ptau")]
     pot_final: PathBuf,
-    /// Intermediate output: Generate zkey: The path to the "multiplier2_0000.zkey" file (see <https://docs.circom.io/getting-started/proving-circuits/#phase-2>)
-    #[arg(long, default_value = "tmp_circ_0000.zkey")]
-    circ_0000: PathBuf,
     /// Intermediate output: Requirement for generating zkey: The path to the generated r1cs file
     #[arg(long, default_value = "tmp_generated_for_zkey_gen.r1cs")]
     r1cs_gen: PathBuf,
@@ -166,6 +182,9 @@ struct Cli {
     /// Keep the generated input share files. Only applicable when --input is provided
     #[arg(long, default_value = "false")]
     keep_inp_shr: bool,
+    /// Input: The path to a network profile (.toml) with the latency, jitter and bandwidth of the links between the parties. If passed, all parties of the co-circom prover run in this process over an emulated network with this profile, instead of benching the co-circom binary against snarkjs. Needs --witness and --r1cs
+    #[arg(long, conflicts_with = "gen_wtns")]
+    emulate: Option<PathBuf>,
 }
 
 // filenames for certificates and keys that must be present inside the data directory
@@ -235,13 +254,12 @@ struct Config {
     gen_inp_shr_1: Option<PathBuf>,
     gen_inp_shr_2: Option<PathBuf>,
     gen_inp_shr_3: Option<PathBuf>,
-    pot_0000: PathBuf,
-    pot_0001: PathBuf,
     pot_final: PathBuf,
-    circ_0000: PathBuf,
     r1cs_gen: PathBuf,
     r1cs_gen_path: PathBuf,
     pot_power: u16,
+    ptau: Option<PathBuf>,
+    emulate: Option<PathBuf>,
 }
 
 impl From<Cli> for Config {
@@ -477,13 +495,12 @@ impl From<Cli> for Config {
             gen_inp_shr_1,
             gen_inp_shr_2,
             gen_inp_shr_3,
-            pot_0000: cli.pot_0000,
-            pot_0001: cli.pot_0001,
             pot_final: cli.pot_final,
-            circ_0000: cli.circ_0000,
             r1cs_gen: cli.r1cs_gen,
             r1cs_gen_path,
             pot_power: cli.pot_power,
+            ptau: cli.ptau,
+            emulate: cli.emulate,
         }
     }
 }
@@ -511,6 +528,34 @@ impl std::fmt::Display for BenchResult {
     }
 }
 
+/// The number of parties in the emulated network.
+const NUM_EMULATED_PARTIES: usize = 3;
+
+struct EmulationResult {
+    prove: Duration,
+    parties: Vec<PartyStats>,
+}
+
+struct PartyStats {
+    rounds: u64,
+    sent: u64,
+    received: u64,
+}
+
+impl std::fmt::Display for EmulationResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Prover Time: {:?}", self.prove)?;
+        for (i, party) in self.parties.iter().enumerate() {
+            write!(
+                f,
+                ", Party {}: {} rounds, {} bytes sent, {} bytes received",
+                i, party.rounds, party.sent, party.received
+            )?;
+        }
+        Ok(())
+    }
+}
+
 fn bench_circom_snarkjs(conf: &Config) -> color_eyre::Result<BenchResult> {
     let mut wtns_gen_time = None;
     if conf.do_gen_witness {
@@ -803,6 +848,8 @@ fn bench_co_circom_gen_wtns_one_party(
             .arg(inp_shr.as_ref().expect("gen witness is true").as_path())
             .arg("--circuit")
             .arg(conf.circom.as_ref().expect("gen witness is true").as_path())
+            .arg("--zkey")
+            .arg(conf.zkey.as_path())
             .arg("--protocol")
             .arg(&conf.protocol)
             .arg("--curve")
@@ -934,6 +981,8 @@ fn bench_co_circom(conf: &Config) -> color_eyre::Result<BenchResult> {
             )
             .arg("--r1cs")
             .arg(conf.r1cs.as_ref().expect("gen witness is false").as_path())
+            .arg("--zkey")
+            .arg(conf.zkey.as_path())
             .arg("--protocol")
             .arg(&conf.protocol)
             .arg("--curve")
@@ -992,6 +1041,163 @@ fn bench_co_circom(conf: &Config) -> color_eyre::Result<BenchResult> {
     })
 }
 
+/// Runs `party` for every party in its own thread and returns the results ordered by party id.
+fn run_parties<I: Send, T: Send>(
+    inputs: Vec<I>,
+    party: impl Fn(I) -> color_eyre::Result<T> + Sync,
+) -> color_eyre::Result<Vec<T>> {
+    thread::scope(|s| {
+        let handles = inputs
+            .into_iter()
+            .map(|input| s.spawn(|| party(input)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().map_err(|_| eyre!("a party panicked"))?)
+            .collect()
+    })
+}
+
+fn bench_co_circom_emulated<P: Pairing + CircomArkworksPairingBridge>(
+    conf: &Config,
+    profile: &NetworkProfile,
+) -> color_eyre::Result<EmulationResult>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    tracing::trace!("Splitting witness ..");
+    let witness_file = BufReader::new(
+        File::open(conf.witness.as_ref().expect("gen witness is false"))
+            .context("while opening witness file")?,
+    );
+    let witness = Witness::<P::ScalarField>::from_reader(witness_file)
+        .context("while parsing witness file")?;
+    let r1cs_file = BufReader::new(
+        File::open(conf.r1cs.as_ref().expect("gen witness is false"))
+            .context("while opening r1cs file")?,
+    );
+    let r1cs = R1CS::<P>::from_reader(r1cs_file).context("while parsing r1cs file")?;
+    let zkey_file = File::open(&conf.zkey).context("while opening zkey file")?;
+
+    let handlers = MpcNetworkHandler::emulated(NUM_EMULATED_PARTIES, profile)?;
+    // sessions share the connections and their statistics, so we keep one per party to read the
+    // statistics after the provers consumed the networks
+    let stats = handlers
+        .iter()
+        .map(|handler| handler.fork_session(1))
+        .collect::<Vec<_>>();
+
+    let mut rng = rand::thread_rng();
+    tracing::trace!("Starting co-circom prover in emulated network ..");
+    let (now, proofs_match) = match (conf.protocol.as_str(), conf.proof_system.as_str()) {
+        ("REP3", proof_system) => {
+            let shares = SharedWitness::<Rep3Protocol<P::ScalarField, Rep3MpcNet>, P>::share_rep3(
+                witness,
+                r1cs.num_inputs,
+                &mut rng,
+            );
+            let inputs = handlers.into_iter().zip(shares).collect::<Vec<_>>();
+            match proof_system {
+                "groth16" => {
+                    let zkey = Groth16ZKey::<P>::from_reader(zkey_file).context("reading zkey")?;
+                    let now = Instant::now();
+                    let proofs = run_parties(inputs, |(handler, share)| {
+                        let protocol = Rep3Protocol::new(Rep3MpcNet::from_session(handler)?)?;
+                        CoGroth16::new(protocol).prove(&zkey, share)
+                    })?;
+                    (now, proofs.windows(2).all(|w| w[0] == w[1]))
+                }
+                "plonk" => {
+                    let zkey = PlonkZKey::<P>::from_reader(zkey_file).context("reading zkey")?;
+                    let now = Instant::now();
+                    let proofs = run_parties(inputs, |(handler, share)| {
+                        let protocol = Rep3Protocol::new(Rep3MpcNet::from_session(handler)?)?;
+                        Ok(CoPlonk::new(protocol).prove(&zkey, share)?)
+                    })?;
+                    (now, proofs.windows(2).all(|w| w[0] == w[1]))
+                }
+                proof_system => return Err(eyre!("unsupported proof system: {proof_system}")),
+            }
+        }
+        ("SHAMIR", proof_system) => {
+            let threshold = 1;
+            let shares =
+                SharedWitness::<ShamirProtocol<P::ScalarField, ShamirMpcNet>, P>::share_shamir(
+                    witness,
+                    r1cs.num_inputs,
+                    threshold,
+                    NUM_EMULATED_PARTIES,
+                    &mut rng,
+                )?;
+            let inputs = handlers.into_iter().zip(shares).collect::<Vec<_>>();
+            match proof_system {
+                "groth16" => {
+                    let zkey = Groth16ZKey::<P>::from_reader(zkey_file).context("reading zkey")?;
+                    let now = Instant::now();
+                    let proofs = run_parties(inputs, |(handler, share)| {
+                        let net = ShamirMpcNet::from_session(handler)?;
+                        let protocol = ShamirProtocol::new(threshold, net)?;
+                        CoGroth16::new(protocol).prove(&zkey, share)
+                    })?;
+                    (now, proofs.windows(2).all(|w| w[0] == w[1]))
+                }
+                "plonk" => {
+                    let zkey = PlonkZKey::<P>::from_reader(zkey_file).context("reading zkey")?;
+                    let now = Instant::now();
+                    let proofs = run_parties(inputs, |(handler, share)| {
+                        let net = ShamirMpcNet::from_session(handler)?;
+                        let protocol = ShamirProtocol::new(threshold, net)?;
+                        Ok(CoPlonk::new(protocol).prove(&zkey, share)?)
+                    })?;
+                    (now, proofs.windows(2).all(|w| w[0] == w[1]))
+                }
+                proof_system => return Err(eyre!("unsupported proof system: {proof_system}")),
+            }
+        }
+        (protocol, _) => return Err(eyre!("unsupported MPC protocol: {protocol}")),
+    };
+    let prover_time = now.elapsed();
+    if !proofs_match {
+        return Err(eyre!("The parties computed different proofs"));
+    }
+
+    let parties = stats
+        .iter()
+        .map(|handler| {
+            let (sent, received) = (0..NUM_EMULATED_PARTIES)
+                .filter(|&i| i != handler.id())
+                .map(|i| handler.get_send_receive(i))
+                .try_fold((0, 0), |(sent, received), stats| {
+                    stats.map(|(s, r)| (sent + s, received + r))
+                })?;
+            Ok(PartyStats {
+                rounds: handler.get_rounds().unwrap_or_default(),
+                sent,
+                received,
+            })
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+    Ok(EmulationResult {
+        prove: prover_time,
+        parties,
+    })
+}
+
+fn run_emulated(conf: &Config, profile: &Path) -> color_eyre::Result<ExitCode> {
+    let profile = std::fs::read_to_string(profile).context("while reading network profile")?;
+    let profile: NetworkProfile =
+        toml::from_str(&profile).context("while parsing network profile")?;
+    let results = match conf.curve.as_str() {
+        "BN254" => bench_co_circom_emulated::<Bn254>(conf, &profile),
+        "BLS12-381" => bench_co_circom_emulated::<Bls12_381>(conf, &profile),
+        curve => Err(eyre!("unsupported curve: {curve}")),
+    }
+    .context("during benching co-circom in an emulated network")?;
+    tracing::info!("Results co-circom (emulated network): {}", results);
+    Ok(ExitCode::SUCCESS)
+}
+
 fn generate_r1cs_for_zkey_gen(conf: &Config) -> color_eyre::Result<()> {
     tracing::trace!("Generating r1cs ..");
     // to ensure proper naming of the output file, we copy the circom file to a new file with the name of the tmp r1cs file
@@ -1030,141 +1236,75 @@ fn generate_zkey(conf: &Config) -> color_eyre::Result<()> {
     let now = Instant::now();
     tracing::info!("Generating zkey using power {} ..", conf.pot_power);
     generate_r1cs_for_zkey_gen(conf).context("during generating r1cs")?;
-    tracing::trace!("Phase 1: Starting \"snarkjs powersoftau new\" ..");
-    // snarkjs powersoftau new bn128 12 pot12_0000.ptau -v
-    let out_1 = Command::new(&conf.snarkjs_bin)
-        .arg("powersoftau")
-        .arg("new")
-        .arg("bn128")
-        .arg(conf.pot_power.to_string())
-        .arg(conf.pot_0000.as_path())
-        .arg("-v")
-        .output()
-        .context("during executing \"snarkjs powersoftau new\"")?;
-    if !out_1.status.success() {
-        tracing::error!(
-            "Failed on \"snarkjs powersoftau new\": \nstdout:\n{}\nstderr:\n{}",
-            std::str::from_utf8(&out_1.stdout)?,
-            std::str::from_utf8(&out_1.stderr)?
-        );
-        return Err(eyre!("Failed on \"snarkjs powersoftau new\""));
-    }
-    tracing::trace!("Phase 1: Starting \"snarkjs powersoftau contribute\" ..");
-    // snarkjs powersoftau contribute pot12_0000.ptau pot12_0001.ptau --name="First contribution" -v
-    let mut spawn_2 = Command::new(&conf.snarkjs_bin)
-        .arg("powersoftau")
-        .arg("contribute")
-        .arg(conf.pot_0000.as_path())
-        .arg(conf.pot_0001.as_path())
-        .arg("--name=\"First contribution\"")
-        .arg("-v")
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .context("during executing \"snarkjs powersoftau contribute\"")?;
-    if let Some(mut stdin) = spawn_2.stdin.take() {
-        stdin
-            .write_all(b"nice randomness!\n")
-            .context("during writing to stdin")?;
-    } else {
-        spawn_2.kill().context("during killing process because failed to write to stdin during executing \"snarkjs powersoftau contribute\"")?;
-        return Err(eyre!(
-            "Failed to write to stdin during executing \"snarkjs powersoftau contribute\""
-        ));
-    }
-    let out_2 = spawn_2
-        .wait_with_output()
-        .context("during waiting for output of \"snarkjs powersoftau contribute\"")?;
-    if !out_2.status.success() {
-        tracing::error!(
-            "Failed on \"snarkjs powersoftau contribute\": \nstdout:\n{}\nstderr:\n{}",
-            std::str::from_utf8(&out_2.stdout)?,
-            std::str::from_utf8(&out_2.stderr)?
-        );
-        return Err(eyre!("Failed on \"snarkjs powersoftau contribute\""));
-    }
-    // Phase 2
-    tracing::trace!("Phase 2: Starting \"snarkjs powersoftau prepare phase2\" ..");
-    // snarkjs powersoftau prepare phase2 pot12_0001.ptau pot12_final.ptau -v
-    let out_3 = Command::new(&conf.snarkjs_bin)
-        .arg("powersoftau")
-        .arg("prepare")
-        .arg("phase2")
-        .arg(conf.pot_0001.as_path())
-        .arg(conf.pot_final.as_path())
-        .arg("-v")
-        .output()
-        .context("during executing \"snarkjs powersoftau prepare phase2\"")?;
-    if !out_3.status.success() {
-        tracing::error!(
-            "Failed on \"snarkjs powersoftau prepare phase2\": \nstdout:\n{}\nstderr:\n{}",
-            std::str::from_utf8(&out_3.stdout)?,
-            std::str::from_utf8(&out_3.stderr)?
-        );
-        return Err(eyre!("Failed on \"snarkjs powersoftau prepare phase2\""));
-    }
-    tracing::trace!("Phase 2: Starting \"snarkjs groth16 setup\" ..");
-    // snarkjs groth16 setup multiplier2.r1cs pot12_final.ptau multiplier2_0000.zkey
-    let out_4 = Command::new(&conf.snarkjs_bin)
-        .arg("groth16")
+    let ptau = match &conf.ptau {
+        Some(ptau) => ptau.to_owned(),
+        None => {
+            generate_ptau(conf).context("during generating ptau")?;
+            conf.pot_final.to_owned()
+        }
+    };
+    tracing::trace!(
+        "Phase 2: Starting \"co-circom setup {}\" ..",
+        conf.proof_system
+    );
+    // co-circom setup groth16 --r1cs multiplier2.r1cs --ptau pot12_final.ptau --curve BN254 --out multiplier2.zkey --name "1st Contributor Name"
+    let mut setup = Command::new(&conf.co_circom_bin);
+    setup
         .arg("setup")
+        .arg(&conf.proof_system)
+        .arg("--r1cs")
         .arg(conf.r1cs_gen.as_path())
-        .arg(conf.pot_final.as_path())
-        .arg(conf.circ_0000.as_path())
+        .arg("--ptau")
+        .arg(ptau.as_path())
+        .arg("--curve")
+        .arg(&conf.curve)
+        .arg("--out")
+        .arg(conf.zkey.as_path());
+    // only the Groth16 setup has contributions
+    if conf.proof_system == "groth16" {
+        setup.arg("--name").arg("1st Contributor Name");
+    }
+    let out_4 = setup
         .output()
-        .context("during executing \"snarkjs groth16 setup\"")?;
+        .context("during executing \"co-circom setup\"")?;
     if !out_4.status.success() {
         tracing::error!(
-            "Failed on \"snarkjs groth16 setup\": \nstdout:\n{}\nstderr:\n{}",
+            "Failed on \"co-circom setup\": \nstdout:\n{}\nstderr:\n{}",
             std::str::from_utf8(&out_4.stdout)?,
             std::str::from_utf8(&out_4.stderr)?
         );
-        return Err(eyre!("Failed on \"snarkjs groth16 setup\""));
-    }
-    tracing::trace!("Phase 2: Starting \"snarkjs zkey contribute\" ..");
-    // snarkjs zkey contribute multiplier2_0000.zkey multiplier2_0001.zkey --name="1st Contributor Name" -v
-    let mut spawn_5 = Command::new(&conf.snarkjs_bin)
-        .arg("zkey")
-        .arg("contribute")
-        .arg(conf.circ_0000.as_path())
-        .arg(conf.zkey.as_path())
-        .arg("--name=\"1st Contributor Name\"")
-        .arg("-v")
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .context("during executing \"snarkjs zkey contribute\"")?;
-    if let Some(mut stdin) = spawn_5.stdin.take() {
-        stdin
-            .write_all(b"nice other randomness!\n")
-            .context("during writing to stdin")?;
-    } else {
-        spawn_5.kill().context("during killing process because failed to write to stdin during executing \"snarkjs zkey contribute\"")?;
-        return Err(eyre!(
-            "Failed to write to stdin during executing \"snarkjs zkey contribute\""
-        ));
-    }
-    let out_5 = spawn_5
-        .wait_with_output()
-        .context("during waiting for output of \"snarkjs zkey contribute\"")?;
-    if !out_5.status.success() {
-        tracing::error!(
-            "Failed on \"snarkjs zkey contribute\": \nstdout:\n{}\nstderr:\n{}",
-            std::str::from_utf8(&out_5.stdout)?,
-            std::str::from_utf8(&out_5.stderr)?
-        );
-        return Err(eyre!("Failed on \"snarkjs zkey contribute\""));
+        return Err(eyre!("Failed on \"co-circom setup\""));
     }
     let zkey_time = now.elapsed();
     tracing::info!(".. generating zkey took {:?}", zkey_time);
     Ok(())
 }
 
+fn generate_ptau(conf: &Config) -> color_eyre::Result<()> {
+    match conf.curve.as_str() {
+        "BN254" => generate_insecure_ptau::<Bn254>(conf),
+        "BLS12-381" => generate_insecure_ptau::<Bls12_381>(conf),
+        curve => Err(eyre!("unsupported curve: {curve}")),
+    }
+}
+
+fn generate_insecure_ptau<P: Pairing + CircomArkworksPairingBridge>(
+    conf: &Config,
+) -> color_eyre::Result<()>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    tracing::trace!("Phase 1: Creating an insecure powers of tau with local randomness ..");
+    let ptau = PowersOfTau::<P>::insecure_setup(&mut rand::thread_rng(), conf.pot_power.into());
+    let out = BufWriter::new(File::create(&conf.pot_final).context("while creating ptau file")?);
+    ptau.to_writer(out).context("while writing ptau file")?;
+    Ok(())
+}
+
 fn prepare(conf: &Config) -> color_eyre::Result<()> {
     if conf.gen_zkey {
-        generate_zkey(conf).context("during generating zkey (consider using --pot-power <NUM> in case snarkjs complains about the ceremony not being large enough)")?;
+        generate_zkey(conf).context("during generating zkey (consider using --pot-power <NUM> in case the setup complains about the ptau being too small)")?;
     }
     tracing::trace!("Exporting verification key ..");
     let out_snarkjs = Command::new("snarkjs")
@@ -1186,6 +1326,17 @@ fn prepare(conf: &Config) -> color_eyre::Result<()> {
     Ok(())
 }
 
+fn check_emulate_args(conf: &Config, profile: &Path) -> color_eyre::Result<()> {
+    tracing::trace!("Checking args ..");
+    check_file_exists(profile).context("checking existence of network profile")?;
+    check_file_exists(&conf.zkey).context("checking existence of zkey file")?;
+    check_file_exists(conf.witness.as_ref().expect("gen witness is false"))
+        .context("checking existence of witness file")?;
+    check_file_exists(conf.r1cs.as_ref().expect("gen witness is false"))
+        .context("checking existence of r1cs file")?;
+    Ok(())
+}
+
 fn check_args(conf: &Config) -> color_eyre::Result<()> {
     tracing::trace!("Checking args ..");
     if conf.do_gen_witness {
@@ -1329,10 +1480,7 @@ fn cleanup(conf: &Config) -> color_eyre::Result<()> {
             std::fs::remove_dir(&generate_witness_js_folder)
                 .context("during removing circom_js directory for the circom witness generation")?;
         }
-        rm_file!(&conf.pot_0000);
-        rm_file!(&conf.pot_0001);
         rm_file!(&conf.pot_final);
-        rm_file!(&conf.circ_0000);
         rm_file!(&conf.r1cs_gen);
     }
     Ok(())
@@ -1342,6 +1490,10 @@ fn main() -> color_eyre::Result<ExitCode> {
     install_tracing();
     let args = Cli::parse();
     let conf = Config::from(args);
+    if let Some(profile) = &conf.emulate {
+        check_emulate_args(&conf, profile).context("during checking arguments")?;
+        return run_emulated(&conf, profile);
+    }
     check_args(&conf).context("during checking arguments")?;
     let result = run_with_generating_artifacts(&conf);
     cleanup(&conf).context("during cleanup")?;
@@ -2,35 +2,68 @@ use ark_bls12_381::Bls12_381;
 use ark_bn254::Bn254;
 use ark_ec::pairing::Pairing;
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use circom_mpc_compiler::CoCircomCompiler;
 use circom_types::R1CS;
 use num_traits::Zero;
 
 use circom_types::{
+    fflonk::{FflonkProof, JsonVerificationKey as FflonkJsonVerificationKey, ZKey as FflonkZKey},
     groth16::{
-        Groth16Proof, JsonVerificationKey as Groth16JsonVerificationKey, ZKey as Groth16ZKey,
+        Groth16Proof, JsonVerificationKey as Groth16JsonVerificationKey, LazyZKey,
+        ZKey as Groth16ZKey,
     },
     plonk::{JsonVerificationKey as PlonkJsonVerificationKey, PlonkProof, ZKey as PlonkZKey},
+    ptau::PowersOfTau,
     traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
     Witness,
 };
 use clap::{Parser, Subcommand};
+use co_circom::AggregateProofsCli;
+use co_circom::AggregateProofsConfig;
+use co_circom::AggregationSetupCli;
+use co_circom::AggregationSetupConfig;
+use co_circom::CombineInputCli;
+use co_circom::CombineInputConfig;
+use co_circom::CombineWitnessCli;
+use co_circom::CombineWitnessConfig;
+use co_circom::ContributeCli;
+use co_circom::ContributeConfig;
+use co_circom::ExportCalldataCli;
+use co_circom::ExportCalldataConfig;
+use co_circom::ExportVerifierCli;
+use co_circom::ExportVerifierConfig;
 use co_circom::GenerateProofCli;
 use co_circom::GenerateProofConfig;
 use co_circom::GenerateWitnessCli;
 use co_circom::GenerateWitnessConfig;
 use co_circom::MergeInputSharesCli;
 use co_circom::MergeInputSharesConfig;
+use co_circom::NetworkInitCli;
+use co_circom::NetworkInitConfig;
+use co_circom::SetupCli;
+use co_circom::SetupConfig;
 use co_circom::SplitInputCli;
 use co_circom::SplitInputConfig;
 use co_circom::SplitWitnessCli;
 use co_circom::SplitWitnessConfig;
 use co_circom::TranslateWitnessCli;
 use co_circom::TranslateWitnessConfig;
+use co_circom::VerifyAggregateCli;
+use co_circom::VerifyAggregateConfig;
 use co_circom::VerifyCli;
 use co_circom::VerifyConfig;
-use co_circom::{file_utils, MPCCurve, MPCProtocol, ProofSystem};
-use co_circom_snarks::{SharedInput, SharedWitness};
+use co_circom::{file_utils, solidity, MPCCurve, MPCProtocol, ProofSystem};
+use co_circom_snarks::{
+    share_file::{
+        self,
+        encryption::{ShareDecryptionKey, ShareEncryptionKey},
+    },
+    ShareKind, ShareMetadata, SharedInput, SharedWitness,
+};
+use co_fflonk::CoFflonk;
+use co_fflonk::Fflonk;
+use co_groth16::aggregation::{AggregateProof, AggregationSrs};
 use co_groth16::CoGroth16;
 use co_groth16::Groth16;
 use co_plonk::CoPlonk;
@@ -40,18 +73,25 @@ use mpc_core::protocols::rep3::network::Rep3Network;
 use mpc_core::protocols::shamir::network::ShamirNetwork;
 use mpc_core::{
     protocols::{
-        rep3::{self, network::Rep3MpcNet, Rep3Protocol},
-        shamir::{network::ShamirMpcNet, ShamirProtocol},
+        rep3::{self, fieldshare::Rep3PrimeFieldShareVec, network::Rep3MpcNet, Rep3Protocol},
+        shamir::{
+            self, fieldshare::ShamirPrimeFieldShareVec, network::ShamirMpcNet, ShamirProtocol,
+        },
     },
-    traits::PrimeFieldMpcProtocol,
+    traits::{FFTProvider, MSMProvider, PairingEcMpcProtocol, PrimeFieldMpcProtocol},
+};
+use mpc_net::{
+    config::{Address, NetworkConfig, NetworkParty},
+    keys,
 };
 use num_bigint::BigUint;
 use num_traits::Num;
+use std::collections::BTreeMap;
 use std::time::Instant;
 use std::{
     fs::File,
     io::{BufReader, BufWriter},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::ExitCode,
 };
 use tracing::instrument;
@@ -90,10 +130,37 @@ enum Commands {
     GenerateWitness(GenerateWitnessCli),
     /// Translates the witness generated with one MPC protocol to a witness for a different one
     TranslateWitness(TranslateWitnessCli),
+    /// Reconstructs a plain circom witness file from the witness shares of the parties
+    CombineWitness(CombineWitnessCli),
+    /// Reconstructs a plain JSON input file from the input shares of the parties
+    CombineInput(CombineInputCli),
     /// Evaluates the prover algorithm for the specified circuit and witness share in MPC
     GenerateProof(GenerateProofCli),
     /// Verification of a circom proof.
     Verify(VerifyCli),
+    /// Creates the proving key (.zkey) for a circuit from its R1CS and a powers of tau file
+    Setup(SetupCli),
+    /// Adds a contribution (or a final random beacon) to the circuit specific setup of a Groth16 proving key
+    Contribute(ContributeCli),
+    /// Exports a Solidity verifier contract for a BN254 verification key
+    ExportVerifier(ExportVerifierCli),
+    /// Exports the calldata for calling the Solidity verifier with a BN254 proof
+    ExportCalldata(ExportCalldataCli),
+    /// Creates the SRS for aggregating Groth16 proofs from two powers of tau files
+    AggregationSetup(AggregationSetupCli),
+    /// Aggregates Groth16 proofs for the same verification key into a single SnarkPack proof
+    AggregateProofs(AggregateProofsCli),
+    /// Verification of an aggregate Groth16 proof
+    VerifyAggregate(VerifyAggregateCli),
+    /// Helpers for setting up the network of the parties
+    #[command(subcommand)]
+    Network(NetworkCommands),
+}
+
+#[derive(Subcommand)]
+enum NetworkCommands {
+    /// Generates the certificates, keys and network configs of all parties of a network
+    Init(NetworkInitCli),
 }
 
 fn main() -> color_eyre::Result<ExitCode> {
@@ -136,6 +203,20 @@ fn main() -> color_eyre::Result<ExitCode> {
                 MPCCurve::BLS12_381 => run_translate_witness::<Bls12_381>(config),
             }
         }
+        Commands::CombineWitness(cli) => {
+            let config = CombineWitnessConfig::parse(cli).context("while parsing config")?;
+            match config.curve {
+                MPCCurve::BN254 => run_combine_witness::<Bn254>(config),
+                MPCCurve::BLS12_381 => run_combine_witness::<Bls12_381>(config),
+            }
+        }
+        Commands::CombineInput(cli) => {
+            let config = CombineInputConfig::parse(cli).context("while parsing config")?;
+            match config.curve {
+                MPCCurve::BN254 => run_combine_input::<Bn254>(config),
+                MPCCurve::BLS12_381 => run_combine_input::<Bls12_381>(config),
+            }
+        }
         Commands::GenerateProof(cli) => {
             let config = GenerateProofConfig::parse(cli).context("while parsing config")?;
             match config.curve {
@@ -150,6 +231,53 @@ fn main() -> color_eyre::Result<ExitCode> {
                 MPCCurve::BLS12_381 => run_verify::<Bls12_381>(config),
             }
         }
+        Commands::Setup(cli) => {
+            let config = SetupConfig::parse(cli).context("while parsing config")?;
+            match config.curve {
+                MPCCurve::BN254 => run_setup::<Bn254>(config),
+                MPCCurve::BLS12_381 => run_setup::<Bls12_381>(config),
+            }
+        }
+        Commands::Contribute(cli) => {
+            let config = ContributeConfig::parse(cli).context("while parsing config")?;
+            match config.curve {
+                MPCCurve::BN254 => run_contribute::<Bn254>(config),
+                MPCCurve::BLS12_381 => run_contribute::<Bls12_381>(config),
+            }
+        }
+        Commands::ExportVerifier(cli) => {
+            let config = ExportVerifierConfig::parse(cli).context("while parsing config")?;
+            run_export_verifier(config)
+        }
+        Commands::ExportCalldata(cli) => {
+            let config = ExportCalldataConfig::parse(cli).context("while parsing config")?;
+            run_export_calldata(config)
+        }
+        Commands::AggregationSetup(cli) => {
+            let config = AggregationSetupConfig::parse(cli).context("while parsing config")?;
+            match config.curve {
+                MPCCurve::BN254 => run_aggregation_setup::<Bn254>(config),
+                MPCCurve::BLS12_381 => run_aggregation_setup::<Bls12_381>(config),
+            }
+        }
+        Commands::AggregateProofs(cli) => {
+            let config = AggregateProofsConfig::parse(cli).context("while parsing config")?;
+            match config.curve {
+                MPCCurve::BN254 => run_aggregate_proofs::<Bn254>(config),
+                MPCCurve::BLS12_381 => run_aggregate_proofs::<Bls12_381>(config),
+            }
+        }
+        Commands::VerifyAggregate(cli) => {
+            let config = VerifyAggregateConfig::parse(cli).context("while parsing config")?;
+            match config.curve {
+                MPCCurve::BN254 => run_verify_aggregate::<Bn254>(config),
+                MPCCurve::BLS12_381 => run_verify_aggregate::<Bls12_381>(config),
+            }
+        }
+        Commands::Network(NetworkCommands::Init(cli)) => {
+            let config = NetworkInitConfig::parse(cli).context("while parsing config")?;
+            run_network_init(config)
+        }
     }
 }
 
@@ -171,6 +299,20 @@ where
     file_utils::check_file_exists(&witness_path)?;
     file_utils::check_file_exists(&r1cs)?;
     file_utils::check_dir_exists(&out_dir)?;
+    let circuit_hash = match &config.circuit {
+        Some(circuit) => {
+            file_utils::check_file_exists(circuit)?;
+            Some(co_circom::hash_circuit_file(circuit)?)
+        }
+        None => None,
+    };
+    let zkey_hash = match &config.zkey {
+        Some(zkey) => {
+            file_utils::check_file_exists(zkey)?;
+            Some(co_circom::hash_zkey_file(zkey)?)
+        }
+        None => None,
+    };
 
     // read the circom witness file
     let witness_file =
@@ -212,7 +354,11 @@ where
                 let path = out_dir.join(format!("{}.{}.shared", base_name, i));
                 let out_file =
                     BufWriter::new(File::create(&path).context("while creating output file")?);
-                bincode::serialize_into(out_file, share)
+                let metadata =
+                    ShareMetadata::new::<P>(ShareKind::Witness, protocol.into(), i, n, t)
+                        .with_circuit_hash(circuit_hash)
+                        .with_zkey_hash(zkey_hash);
+                share_file::write_share(out_file, &metadata, share)
                     .context("while serializing witness share")?;
                 tracing::info!("Wrote witness share {} to file {}", i, path.display());
             }
@@ -227,7 +373,7 @@ where
                     t,
                     n,
                     &mut rng,
-                );
+                )?;
             let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
             tracing::info!("Sharing took {} ms", duration_ms);
 
@@ -241,7 +387,11 @@ where
                 let path = out_dir.join(format!("{}.{}.shared", base_name, i));
                 let out_file =
                     BufWriter::new(File::create(&path).context("while creating output file")?);
-                bincode::serialize_into(out_file, share)
+                let metadata =
+                    ShareMetadata::new::<P>(ShareKind::Witness, protocol.into(), i, n, t)
+                        .with_circuit_hash(circuit_hash)
+                        .with_zkey_hash(zkey_hash);
+                share_file::write_share(out_file, &metadata, share)
                     .context("while serializing witness share")?;
                 tracing::info!("Wrote witness share {} to file {}", i, path.display());
             }
@@ -263,17 +413,45 @@ where
     let circuit = config.circuit;
     let protocol = config.protocol;
     let out_dir = config.out_dir;
+    let t = config.threshold;
+    let n = config.num_parties;
 
-    if protocol != MPCProtocol::REP3 {
-        return Err(eyre!(
-            "Only REP3 protocol is supported for splitting inputs"
-        ));
+    if protocol == MPCProtocol::REP3 {
+        if t != 1 {
+            return Err(eyre!("REP3 only allows the threshold to be 1"));
+        }
+        if n != 3 {
+            return Err(eyre!("REP3 only allows the number of parties to be 3"));
+        }
+    }
+    if protocol == MPCProtocol::SHAMIR {
+        co_circom_snarks::check_shamir_parameters(t, n)?;
     }
     file_utils::check_file_exists(&input)?;
     let circuit_path = PathBuf::from(&circuit);
     file_utils::check_file_exists(&circuit_path)?;
     file_utils::check_dir_exists(&out_dir)?;
 
+    // read the public keys of the parties if the shares should be encrypted
+    if !config.recipient_certs.is_empty() && config.recipient_certs.len() != n {
+        return Err(eyre!(
+            "Expected {} recipient certificates, got {}",
+            n,
+            config.recipient_certs.len()
+        ));
+    }
+    let recipients = config
+        .recipient_certs
+        .iter()
+        .map(|cert| {
+            file_utils::check_file_exists(cert)?;
+            ShareEncryptionKey::from_cert_file(cert)
+                .with_context(|| format!("while reading public key from {}", cert.display()))
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    let circuit_hash = co_circom::hash_circuit_file(&circuit_path)?;
+
     //get the public inputs if any from parser
     let public_inputs = CoCircomCompiler::<P>::get_public_inputs(circuit, config.compiler)
         .context("while reading public inputs from circuit")?;
@@ -284,51 +462,58 @@ where
     let input_json: serde_json::Map<String, serde_json::Value> =
         serde_json::from_reader(input_file).context("while parsing input file")?;
 
-    // create input shares
-    let mut shares = [
-        SharedInput::<Rep3Protocol<P::ScalarField, Rep3MpcNet>, P>::default(),
-        SharedInput::<Rep3Protocol<P::ScalarField, Rep3MpcNet>, P>::default(),
-        SharedInput::<Rep3Protocol<P::ScalarField, Rep3MpcNet>, P>::default(),
-    ];
-
-    let mut rng = rand::thread_rng();
-    let start = Instant::now();
+    let mut parsed_input = BTreeMap::new();
     for (name, val) in input_json {
         let parsed_vals = if val.is_array() {
             parse_array(&val)?
         } else {
             vec![parse_field(&val)?]
         };
-        if public_inputs.contains(&name) {
-            shares[0]
-                .public_inputs
-                .insert(name.clone(), parsed_vals.clone());
-            shares[1]
-                .public_inputs
-                .insert(name.clone(), parsed_vals.clone());
-            shares[2].public_inputs.insert(name.clone(), parsed_vals);
-        } else {
-            let [share0, share1, share2] =
-                rep3::utils::share_field_elements(&parsed_vals, &mut rng);
-            shares[0].shared_inputs.insert(name.clone(), share0);
-            shares[1].shared_inputs.insert(name.clone(), share1);
-            shares[2].shared_inputs.insert(name.clone(), share2);
-        }
+        parsed_input.insert(name, parsed_vals);
     }
-    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
-    tracing::info!("Sharing took {} ms", duration_ms);
 
-    // write out the shares to the output directory
     let base_name = input
         .file_name()
         .context("we have a file name")?
         .to_str()
         .context("input file name is not valid UTF-8")?;
-    for (i, share) in shares.iter().enumerate() {
-        let path = out_dir.join(format!("{}.{}.shared", base_name, i));
-        let out_file = BufWriter::new(File::create(&path).context("while creating output file")?);
-        bincode::serialize_into(out_file, share).context("while serializing witness share")?;
-        tracing::info!("Wrote input share {} to file {}", i, path.display());
+
+    let metadata = ShareMetadata::new::<P>(ShareKind::Input, protocol.into(), 0, n, t)
+        .with_circuit_hash(Some(circuit_hash));
+
+    let mut rng = rand::thread_rng();
+    match protocol {
+        MPCProtocol::REP3 => {
+            // create input shares
+            let start = Instant::now();
+            let shares = SharedInput::<Rep3Protocol<P::ScalarField, Rep3MpcNet>, P>::share_rep3(
+                parsed_input,
+                &public_inputs,
+                &mut rng,
+            );
+            let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+            tracing::info!("Sharing took {} ms", duration_ms);
+
+            // write out the shares to the output directory
+            write_input_shares(&shares, &out_dir, base_name, &metadata, &recipients)?;
+        }
+        MPCProtocol::SHAMIR => {
+            // create input shares
+            let start = Instant::now();
+            let shares =
+                SharedInput::<ShamirProtocol<P::ScalarField, ShamirMpcNet>, P>::share_shamir(
+                    parsed_input,
+                    &public_inputs,
+                    t,
+                    n,
+                    &mut rng,
+                )?;
+            let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+            tracing::info!("Sharing took {} ms", duration_ms);
+
+            // write out the shares to the output directory
+            write_input_shares(&shares, &out_dir, base_name, &metadata, &recipients)?;
+        }
     }
     tracing::info!("Split input into shares successfully");
     Ok(ExitCode::SUCCESS)
@@ -346,6 +531,14 @@ where
     let protocol = config.protocol;
     let out = config.out;
 
+    let key = config
+        .key_path
+        .map(|key_path| {
+            file_utils::check_file_exists(&key_path)?;
+            ShareDecryptionKey::from_key_file(&key_path).context("while reading private key")
+        })
+        .transpose()?;
+
     if inputs.len() < 2 {
         return Err(eyre!("Need at least two input shares to merge"));
     }
@@ -355,10 +548,20 @@ where
 
     match protocol {
         MPCProtocol::REP3 => {
-            merge_input_shares::<P, Rep3Protocol<P::ScalarField, Rep3MpcNet>>(inputs, out)?;
+            merge_input_shares::<P, Rep3Protocol<P::ScalarField, Rep3MpcNet>>(
+                inputs,
+                protocol,
+                key.as_ref(),
+                out,
+            )?;
         }
         MPCProtocol::SHAMIR => {
-            merge_input_shares::<P, ShamirProtocol<P::ScalarField, ShamirMpcNet>>(inputs, out)?;
+            merge_input_shares::<P, ShamirProtocol<P::ScalarField, ShamirMpcNet>>(
+                inputs,
+                protocol,
+                key.as_ref(),
+                out,
+            )?;
         }
     }
 
@@ -390,14 +593,44 @@ where
     // parse input shares
     let input_share_file =
         BufReader::new(File::open(&input).context("while opening input share file")?);
-    let input_share = co_circom::parse_shared_input(input_share_file)?;
+    // the network key is only needed for encrypted input shares, so we do not fail here
+    let key = match ShareDecryptionKey::from_key_file(&config.network.key_path) {
+        Ok(key) => Some(key),
+        Err(err) => {
+            tracing::warn!("cannot use network key to decrypt input shares: {err}");
+            None
+        }
+    };
+    let (metadata, input_share) =
+        co_circom::parse_shared_input::<_, P, _>(input_share_file, key.as_ref())?;
+    let circuit_hash = co_circom::hash_circuit_file(&circuit_path)?;
+    metadata
+        .check_protocol(protocol.into())
+        .and_then(|_| metadata.check_curve::<P>())
+        .and_then(|_| metadata.check_party_id(config.network.my_id))
+        .and_then(|_| metadata.check_num_parties(config.network.parties.len()))
+        .and_then(|_| metadata.check_circuit_hash(&circuit_hash, config.allow_missing_hashes))
+        .context("while validating input share file")?;
+    let zkey_hash = match &config.zkey {
+        Some(zkey) => {
+            file_utils::check_file_exists(zkey)?;
+            Some(co_circom::hash_zkey_file(zkey)?)
+        }
+        None => None,
+    };
 
     // Extend the witness
     let result_witness_share = co_circom::generate_witness_rep3::<P>(circuit, input_share, config)?;
 
     // write result to output file
     let out_file = BufWriter::new(std::fs::File::create(&out)?);
-    bincode::serialize_into(out_file, &result_witness_share)?;
+    let metadata = ShareMetadata {
+        kind: ShareKind::Witness,
+        circuit_hash: Some(circuit_hash),
+        zkey_hash,
+        ..metadata
+    };
+    share_file::write_share(out_file, &metadata, &result_witness_share)?;
     tracing::info!("Witness successfully written to {}", out.display());
     Ok(ExitCode::SUCCESS)
 }
@@ -423,8 +656,15 @@ where
     // parse witness shares
     let witness_file =
         BufReader::new(File::open(witness).context("trying to open witness share file")?);
-    let witness_share: SharedWitness<Rep3Protocol<P::ScalarField, Rep3MpcNet>, P> =
-        co_circom::parse_witness_share(witness_file)?;
+    let (metadata, witness_share): (
+        _,
+        SharedWitness<Rep3Protocol<P::ScalarField, Rep3MpcNet>, P>,
+    ) = co_circom::parse_witness_share(witness_file)?;
+    metadata
+        .check_protocol(src_protocol.into())
+        .and_then(|_| metadata.check_curve::<P>())
+        .and_then(|_| metadata.check_party_id(config.network.my_id))
+        .context("while validating witness share file")?;
 
     // connect to network
     let net = Rep3MpcNet::new(config.network)?;
@@ -446,11 +686,173 @@ where
 
     // write result to output file
     let out_file = BufWriter::new(std::fs::File::create(&out)?);
-    bincode::serialize_into(out_file, &shamir_witness_share)?;
+    let metadata = ShareMetadata {
+        protocol: target_protocol.into(),
+        ..metadata
+    };
+    share_file::write_share(out_file, &metadata, &shamir_witness_share)?;
     tracing::info!("Witness successfully written to {}", out.display());
     Ok(ExitCode::SUCCESS)
 }
 
+#[instrument(skip(config))]
+fn run_combine_witness<P: Pairing + CircomArkworksPairingBridge>(
+    config: CombineWitnessConfig,
+) -> color_eyre::Result<ExitCode>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    let witnesses = config.witnesses;
+    let protocol = config.protocol;
+    let out = config.out;
+
+    for witness in &witnesses {
+        file_utils::check_file_exists(witness)?;
+    }
+
+    let start = Instant::now();
+    let (public_inputs, witness) = match protocol {
+        MPCProtocol::REP3 => {
+            let (party_ids, _, shares) = read_shares_to_combine::<P, _>(
+                &witnesses,
+                protocol,
+                co_circom::parse_witness_share::<_, P, Rep3Protocol<P::ScalarField, Rep3MpcNet>>,
+            )?;
+            let public_inputs = check_public_witness(&shares)?;
+            let witness = combine_rep3_shares(
+                &party_ids,
+                shares.into_iter().map(|share| share.witness).collect(),
+            )?;
+            (public_inputs, witness)
+        }
+        MPCProtocol::SHAMIR => {
+            let (party_ids, metadata, shares) = read_shares_to_combine::<P, _>(
+                &witnesses,
+                protocol,
+                co_circom::parse_witness_share::<_, P, ShamirProtocol<P::ScalarField, ShamirMpcNet>>,
+            )?;
+            let public_inputs = check_public_witness(&shares)?;
+            let witness = combine_shamir_shares(
+                &party_ids,
+                shares.into_iter().map(|share| share.witness).collect(),
+                metadata.threshold,
+            )?;
+            (public_inputs, witness)
+        }
+    };
+    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+    tracing::info!("Combining witness shares took {} ms", duration_ms);
+
+    let witness = Witness {
+        values: public_inputs.into_iter().chain(witness).collect(),
+    };
+    let out_file = BufWriter::new(File::create(&out).context("while creating output file")?);
+    witness
+        .to_writer(out_file)
+        .context("while writing witness file")?;
+    tracing::info!("Wrote combined witness to file {}", out.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+#[instrument(skip(config))]
+fn run_combine_input<P: Pairing + CircomArkworksPairingBridge>(
+    config: CombineInputConfig,
+) -> color_eyre::Result<ExitCode>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    let inputs = config.inputs;
+    let protocol = config.protocol;
+    let out = config.out;
+
+    for input in &inputs {
+        file_utils::check_file_exists(input)?;
+    }
+
+    let start = Instant::now();
+    let combined = match protocol {
+        MPCProtocol::REP3 => {
+            let (party_ids, _, shares) =
+                read_shares_to_combine::<P, _>(&inputs, protocol, |reader| {
+                    co_circom::parse_shared_input::<_, P, Rep3Protocol<P::ScalarField, Rep3MpcNet>>(
+                        reader, None,
+                    )
+                })?;
+            combine_input_shares(shares, |shares| combine_rep3_shares(&party_ids, shares))?
+        }
+        MPCProtocol::SHAMIR => {
+            let (party_ids, metadata, shares) =
+                read_shares_to_combine::<P, _>(&inputs, protocol, |reader| {
+                    co_circom::parse_shared_input::<
+                        _,
+                        P,
+                        ShamirProtocol<P::ScalarField, ShamirMpcNet>,
+                    >(reader, None)
+                })?;
+            combine_input_shares(shares, |shares| {
+                combine_shamir_shares(&party_ids, shares, metadata.threshold)
+            })?
+        }
+    };
+    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+    tracing::info!("Combining input shares took {} ms", duration_ms);
+
+    let json = combined
+        .into_iter()
+        .map(|(name, vals)| {
+            let mut vals = vals
+                .into_iter()
+                .map(|val| serde_json::Value::String(Into::<BigUint>::into(val).to_string()))
+                .collect::<Vec<_>>();
+            let val = if vals.len() == 1 {
+                vals.pop().expect("we checked the length")
+            } else {
+                serde_json::Value::Array(vals)
+            };
+            (name, val)
+        })
+        .collect::<serde_json::Map<_, _>>();
+    let out_file = BufWriter::new(File::create(&out).context("while creating output file")?);
+    serde_json::to_writer_pretty(out_file, &json).context("while writing input file")?;
+    tracing::info!("Wrote combined input to file {}", out.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The Groth16 proving key, which is either loaded into memory or memory-mapped and processed in chunks of the given size.
+enum Groth16ProvingKey<P: Pairing> {
+    InMemory(Groth16ZKey<P>),
+    Lazy(LazyZKey<P>, usize),
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> Groth16ProvingKey<P>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    fn prove<T>(
+        &self,
+        prover: &mut CoGroth16<T, P>,
+        witness_share: SharedWitness<T, P>,
+    ) -> color_eyre::Result<Groth16Proof<P>>
+    where
+        T: PrimeFieldMpcProtocol<P::ScalarField>
+            + PairingEcMpcProtocol<P>
+            + FFTProvider<P::ScalarField>
+            + MSMProvider<P::G1>
+            + MSMProvider<P::G2>
+            + Sync,
+    {
+        match self {
+            Groth16ProvingKey::InMemory(zkey) => prover.prove(zkey, witness_share),
+            Groth16ProvingKey::Lazy(zkey, chunk_size) => {
+                prover.prove_with_lazy_zkey(zkey, *chunk_size, witness_share)
+            }
+        }
+    }
+}
+
 #[instrument(skip(config))]
 fn run_generate_proof<P: Pairing + CircomArkworksPairingBridge>(
     config: GenerateProofConfig,
@@ -465,10 +867,34 @@ where
     let protocol = config.protocol;
     let out = config.out;
     let public_input_filename = config.public_input;
+    let stats_out = config.stats;
     let t = config.threshold;
 
     file_utils::check_file_exists(&witness)?;
     file_utils::check_file_exists(&zkey)?;
+    let circuit_hash = match &config.circuit {
+        Some(circuit) => {
+            file_utils::check_file_exists(circuit)?;
+            Some(co_circom::hash_circuit_file(circuit)?)
+        }
+        None => None,
+    };
+    let expected = ExpectedWitnessShare {
+        protocol,
+        threshold: t,
+        party_id: config.network.my_id,
+        num_parties: config.network.parties.len(),
+        circuit_hash,
+        zkey_hash: co_circom::hash_zkey_file(&zkey)?,
+        allow_missing_hashes: config.allow_missing_hashes,
+    };
+
+    if let Some(threads) = config.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .context("while setting up the thread pool")?;
+    }
 
     // parse witness shares
     let witness_file =
@@ -477,20 +903,38 @@ where
     // parse Circom zkey file
     let zkey_file = File::open(zkey)?;
 
-    let public_input = match proof_system {
+    if config.msm_chunk_size.is_some() && !matches!(proof_system, ProofSystem::Groth16) {
+        tracing::warn!("Chunked MSMs are only supported for Groth16, ignoring the chunk size");
+    }
+
+    let (public_input, stats) = match proof_system {
         ProofSystem::Groth16 => {
-            let zkey = Groth16ZKey::<P>::from_reader(zkey_file).context("reading zkey")?;
+            let zkey = match config.msm_chunk_size {
+                Some(chunk_size) => Groth16ProvingKey::Lazy(
+                    LazyZKey::<P>::from_file(&zkey_file).context("reading zkey")?,
+                    chunk_size,
+                ),
+                None => Groth16ProvingKey::InMemory(
+                    Groth16ZKey::<P>::from_reader(zkey_file).context("reading zkey")?,
+                ),
+            };
+            let n_public = match &zkey {
+                Groth16ProvingKey::InMemory(zkey) => zkey.n_public,
+                Groth16ProvingKey::Lazy(zkey, _) => zkey.n_public,
+            };
 
-            let (proof, public_input) = match protocol {
+            let (proof, public_input, stats) = match protocol {
                 MPCProtocol::REP3 => {
                     if t != 1 {
                         return Err(eyre!("REP3 only allows the threshold to be 1"));
                     }
 
-                    let witness_share = co_circom::parse_witness_share(witness_file)?;
+                    let witness_share =
+                        parse_and_check_witness_share::<P, _>(witness_file, &expected, n_public)?;
                     let public_input = witness_share.public_inputs.clone();
                     // connect to network
                     let net = Rep3MpcNet::new(config.network)?;
+                    let stats = net.communication_stats();
                     let id = usize::from(net.get_id());
 
                     // init MPC protocol
@@ -501,18 +945,20 @@ where
                     // execute prover in MPC
                     tracing::info!("Party {}: starting proof generation..", id);
                     let start = Instant::now();
-                    let proof = prover.prove(&zkey, witness_share)?;
+                    let proof = zkey.prove(&mut prover, witness_share)?;
                     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
                     tracing::info!("Party {}: Proof generation took {} ms", id, duration_ms);
 
-                    (proof, public_input)
+                    (proof, public_input, stats)
                 }
                 MPCProtocol::SHAMIR => {
-                    let witness_share = co_circom::parse_witness_share(witness_file)?;
+                    let witness_share =
+                        parse_and_check_witness_share::<P, _>(witness_file, &expected, n_public)?;
                     let public_input = witness_share.public_inputs.clone();
 
                     // connect to network
                     let net = ShamirMpcNet::new(config.network)?;
+                    let stats = net.communication_stats();
                     let id = net.get_id();
 
                     // init MPC protocol
@@ -523,11 +969,11 @@ where
                     // execute prover in MPC
                     tracing::info!("Party {}: starting proof generation..", id);
                     let start = Instant::now();
-                    let proof = prover.prove(&zkey, witness_share)?;
+                    let proof = zkey.prove(&mut prover, witness_share)?;
                     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
                     tracing::info!("Party {}: Proof generation took {} ms", id, duration_ms);
 
-                    (proof, public_input)
+                    (proof, public_input, stats)
                 }
             };
 
@@ -541,21 +987,26 @@ where
                     .context("while serializing proof to JSON file")?;
                 tracing::info!("Wrote proof to file {}", out.display());
             }
-            public_input
+            (public_input, stats)
         }
         ProofSystem::Plonk => {
             let pk = PlonkZKey::<P>::from_reader(zkey_file).context("while parsing zkey")?;
 
-            let (proof, public_input) = match protocol {
+            let (proof, public_input, stats) = match protocol {
                 MPCProtocol::REP3 => {
                     if t != 1 {
                         return Err(eyre!("REP3 only allows the threshold to be 1"));
                     }
 
-                    let witness_share = co_circom::parse_witness_share(witness_file)?;
+                    let witness_share = parse_and_check_witness_share::<P, _>(
+                        witness_file,
+                        &expected,
+                        pk.n_public,
+                    )?;
                     let public_input = witness_share.public_inputs.clone();
                     // connect to network
                     let net = Rep3MpcNet::new(config.network)?;
+                    let stats = net.communication_stats();
                     let id = usize::from(net.get_id());
 
                     // init MPC protocol
@@ -569,14 +1020,19 @@ where
                     let proof = prover.prove(&pk, witness_share)?;
                     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
                     tracing::info!("Party {}: Proof generation took {} ms", id, duration_ms);
-                    (proof, public_input)
+                    (proof, public_input, stats)
                 }
                 MPCProtocol::SHAMIR => {
-                    let witness_share = co_circom::parse_witness_share(witness_file)?;
+                    let witness_share = parse_and_check_witness_share::<P, _>(
+                        witness_file,
+                        &expected,
+                        pk.n_public,
+                    )?;
                     let public_input = witness_share.public_inputs.clone();
 
                     // connect to network
                     let net = ShamirMpcNet::new(config.network)?;
+                    let stats = net.communication_stats();
                     let id = net.get_id();
 
                     // init MPC protocol
@@ -590,7 +1046,80 @@ where
                     let proof = prover.prove(&pk, witness_share)?;
                     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
                     tracing::info!("Party {}: Proof generation took {} ms", id, duration_ms);
-                    (proof, public_input)
+                    (proof, public_input, stats)
+                }
+            };
+
+            // write result to output file
+            if let Some(out) = out {
+                let out_file = BufWriter::new(
+                    std::fs::File::create(&out).context("while creating output file")?,
+                );
+
+                serde_json::to_writer(out_file, &proof)
+                    .context("while serializing proof to JSON file")?;
+                tracing::info!("Wrote proof to file {}", out.display());
+            }
+            (public_input, stats)
+        }
+        ProofSystem::Fflonk => {
+            let pk = FflonkZKey::<P>::from_reader(zkey_file).context("while parsing zkey")?;
+
+            let (proof, public_input, stats) = match protocol {
+                MPCProtocol::REP3 => {
+                    if t != 1 {
+                        return Err(eyre!("REP3 only allows the threshold to be 1"));
+                    }
+
+                    let witness_share = parse_and_check_witness_share::<P, _>(
+                        witness_file,
+                        &expected,
+                        pk.n_public,
+                    )?;
+                    let public_input = witness_share.public_inputs.clone();
+                    // connect to network
+                    let net = Rep3MpcNet::new(config.network)?;
+                    let stats = net.communication_stats();
+                    let id = usize::from(net.get_id());
+
+                    // init MPC protocol
+                    let protocol = Rep3Protocol::new(net)?;
+
+                    let prover = CoFflonk::new(protocol);
+
+                    // execute prover in MPC
+                    tracing::info!("Party {}: starting proof generation..", id);
+                    let start = Instant::now();
+                    let proof = prover.prove(&pk, witness_share)?;
+                    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+                    tracing::info!("Party {}: Proof generation took {} ms", id, duration_ms);
+                    (proof, public_input, stats)
+                }
+                MPCProtocol::SHAMIR => {
+                    let witness_share = parse_and_check_witness_share::<P, _>(
+                        witness_file,
+                        &expected,
+                        pk.n_public,
+                    )?;
+                    let public_input = witness_share.public_inputs.clone();
+
+                    // connect to network
+                    let net = ShamirMpcNet::new(config.network)?;
+                    let stats = net.communication_stats();
+                    let id = net.get_id();
+
+                    // init MPC protocol
+                    let protocol = ShamirProtocol::new(t, net)?;
+
+                    let prover = CoFflonk::new(protocol);
+
+                    // execute prover in MPC
+                    tracing::info!("Party {}: starting proof generation..", id);
+                    let start = Instant::now();
+                    let proof = prover.prove(&pk, witness_share)?;
+                    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+                    tracing::info!("Party {}: Proof generation took {} ms", id, duration_ms);
+                    (proof, public_input, stats)
                 }
             };
 
@@ -604,10 +1133,14 @@ where
                     .context("while serializing proof to JSON file")?;
                 tracing::info!("Wrote proof to file {}", out.display());
             }
-            public_input
+            (public_input, stats)
         }
     };
 
+    if let Some(stats_out) = stats_out {
+        co_circom::write_communication_stats(&stats_out, &stats)?;
+    }
+
     // write public input to output file
     if let Some(public_input_filename) = public_input_filename {
         let public_input_as_strings = public_input
@@ -645,79 +1178,699 @@ where
     P::BaseField: CircomArkworksPrimeFieldBridge,
 {
     let proofsystem = config.proof_system;
-    let proof = config.proof;
     let vk = config.vk;
-    let public_input = config.public_input;
 
-    file_utils::check_file_exists(&proof)?;
     file_utils::check_file_exists(&vk)?;
-    file_utils::check_file_exists(&public_input)?;
-
-    // parse circom proof file
-    let proof_file = BufReader::new(File::open(&proof).context("while opening proof file")?);
+    let proof_files = collect_proof_files(config.proof, config.public_input, config.batch_dir)?;
 
     // parse circom verification key file
     let vk_file = BufReader::new(File::open(&vk).context("while opening verification key file")?);
 
-    // parse public inputs
-    let public_inputs_file =
-        BufReader::new(File::open(&public_input).context("while opening public inputs file")?);
-    let public_inputs_as_strings: Vec<String> = serde_json::from_reader(public_inputs_file)
-        .context(
-            "while parsing public inputs, expect them to be array of stringified field elements",
-        )?;
-    // skip 1 atm
-    let public_inputs = public_inputs_as_strings
-        .into_iter()
-        .map(|s| {
-            s.parse::<P::ScalarField>()
-                .map_err(|_| eyre!("could not parse as field element: {}", s))
-        })
-        .collect::<Result<Vec<P::ScalarField>, _>>()
-        .context("while converting public input strings to field elements")?;
-
-    // verify proof
+    // verify proofs
+    let start = Instant::now();
     let res = match proofsystem {
         ProofSystem::Groth16 => {
-            let proof: Groth16Proof<P> = serde_json::from_reader(proof_file)
-                .context("while deserializing proof from file")?;
-
+            let pairs = parse_proof_pairs::<Groth16Proof<P>, P::ScalarField>(&proof_files);
             let vk: Groth16JsonVerificationKey<P> = serde_json::from_reader(vk_file)
                 .context("while deserializing verification key from file")?;
 
             // The actual verifier
-            let start = Instant::now();
-            let res = Groth16::<P>::verify(&vk, &proof, &public_inputs)
-                .context("while verifying proof")?;
-            let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
-            tracing::info!("Proof verification took {} ms", duration_ms);
-            res
+            verify_proofs(
+                pairs,
+                Some(|proofs: &[_], public_inputs: &[_]| {
+                    Groth16::<P>::batch_verify(&vk, proofs, public_inputs)
+                }),
+                |proof, public_inputs| Ok(Groth16::<P>::verify(&vk, proof, public_inputs)?),
+            )
         }
         ProofSystem::Plonk => {
-            let proof: PlonkProof<P> = serde_json::from_reader(proof_file)
-                .context("while deserializing proof from file")?;
-
+            let pairs = parse_proof_pairs::<PlonkProof<P>, P::ScalarField>(&proof_files);
             let vk: PlonkJsonVerificationKey<P> = serde_json::from_reader(vk_file)
                 .context("while deserializing verification key from file")?;
 
             // The actual verifier
-            let start = Instant::now();
-            let res =
-                Plonk::<P>::verify(&vk, &proof, &public_inputs).context("while verifying proof")?;
-            let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
-            tracing::info!("Proof verification took {} ms", duration_ms);
-            res
+            verify_proofs(
+                pairs,
+                Some(|proofs: &[_], public_inputs: &[_]| {
+                    Plonk::<P>::batch_verify(&vk, proofs, public_inputs)
+                }),
+                |proof, public_inputs| Plonk::<P>::verify(&vk, proof, public_inputs),
+            )
+        }
+        ProofSystem::Fflonk => {
+            let pairs = parse_proof_pairs::<FflonkProof<P>, P::ScalarField>(&proof_files);
+            let vk: FflonkJsonVerificationKey<P> = serde_json::from_reader(vk_file)
+                .context("while deserializing verification key from file")?;
+
+            // The actual verifier, there is no batch verification for FFLONK
+            verify_proofs(
+                pairs,
+                None::<fn(&[_], &[_]) -> _>,
+                |proof, public_inputs| Fflonk::<P>::verify(&vk, proof, public_inputs),
+            )
         }
     };
+    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+    tracing::info!("Proof verification took {} ms", duration_ms);
 
-    if res {
-        tracing::info!("Proof verified successfully");
+    let failed = proof_files
+        .iter()
+        .zip(res)
+        .filter(|(_, valid)| !valid)
+        .map(|(files, _)| files)
+        .collect::<Vec<_>>();
+    if failed.is_empty() {
+        if proof_files.len() == 1 {
+            tracing::info!("Proof verified successfully");
+        } else {
+            tracing::info!("All {} proofs verified successfully", proof_files.len());
+        }
         Ok(ExitCode::SUCCESS)
     } else {
-        tracing::error!("Proof verification failed");
-        Ok(ExitCode::FAILURE)
-    }
-}
+        if proof_files.len() == 1 {
+            tracing::error!("Proof verification failed");
+        } else {
+            for (proof, public_input) in failed.iter() {
+                tracing::error!(
+                    "Proof verification failed for {} with public input {}",
+                    proof.display(),
+                    public_input.display()
+                );
+            }
+            tracing::error!(
+                "{} of {} proofs failed verification",
+                failed.len(),
+                proof_files.len()
+            );
+        }
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Collects the (proof, public input) file pairs to verify, either from the given lists or from a batch directory
+fn collect_proof_files(
+    proofs: Vec<PathBuf>,
+    public_inputs: Vec<PathBuf>,
+    batch_dir: Option<PathBuf>,
+) -> color_eyre::Result<Vec<(PathBuf, PathBuf)>> {
+    let proof_files = if let Some(batch_dir) = batch_dir {
+        file_utils::check_dir_exists(&batch_dir)?;
+        let mut proof_files = Vec::new();
+        for entry in std::fs::read_dir(&batch_dir).context("while reading batch dir")? {
+            let proof = entry.context("while reading batch dir")?.path();
+            let name = proof
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".proof.json"));
+            if let Some(name) = name {
+                let public_input = batch_dir.join(format!("{name}.public.json"));
+                proof_files.push((proof, public_input));
+            }
+        }
+        proof_files.sort();
+        proof_files
+    } else {
+        if proofs.len() != public_inputs.len() {
+            return Err(eyre!(
+                "got {} proofs, but {} public inputs",
+                proofs.len(),
+                public_inputs.len()
+            ));
+        }
+        proofs.into_iter().zip(public_inputs).collect::<Vec<_>>()
+    };
+    if proof_files.is_empty() {
+        return Err(eyre!("no proofs to verify"));
+    }
+    for (proof, public_input) in proof_files.iter() {
+        file_utils::check_file_exists(proof)?;
+        file_utils::check_file_exists(public_input)?;
+    }
+    Ok(proof_files)
+}
+
+/// Parses a circom proof file
+fn parse_proof<T: serde::de::DeserializeOwned>(proof: &Path) -> color_eyre::Result<T> {
+    let proof_file = BufReader::new(File::open(proof).context("while opening proof file")?);
+    serde_json::from_reader(proof_file)
+        .with_context(|| format!("while deserializing proof from {}", proof.display()))
+}
+
+/// Parses the circom proof files
+fn parse_proofs<T: serde::de::DeserializeOwned>(
+    proof_files: &[(PathBuf, PathBuf)],
+) -> color_eyre::Result<Vec<T>> {
+    proof_files
+        .iter()
+        .map(|(proof, _)| parse_proof(proof))
+        .collect()
+}
+
+/// Parses the proof and the public input of every pair. A pair that cannot be parsed is reported and
+/// becomes `None`, such that it fails verification without aborting the verification of the others.
+fn parse_proof_pairs<T: serde::de::DeserializeOwned, F: PrimeField>(
+    proof_files: &[(PathBuf, PathBuf)],
+) -> Vec<Option<(T, Vec<F>)>> {
+    proof_files
+        .iter()
+        .map(|(proof, public_input)| {
+            let pair = parse_proof(proof)
+                .and_then(|proof| Ok((proof, parse_public_inputs(public_input)?)));
+            pair.map_err(|err| {
+                tracing::warn!(
+                    "Could not parse {} with public input {}: {err:#}",
+                    proof.display(),
+                    public_input.display()
+                )
+            })
+            .ok()
+        })
+        .collect()
+}
+
+/// Verifies all proofs, returning whether each proof is valid. Pairs that could not be parsed are
+/// invalid. A batch of proofs is verified at once first, only if the batch is rejected the proofs are
+/// verified individually to find the invalid ones.
+fn verify_proofs<T, F>(
+    pairs: Vec<Option<(T, Vec<F>)>>,
+    batch_verify: Option<impl Fn(&[T], &[Vec<F>]) -> color_eyre::Result<bool>>,
+    verify: impl Fn(&T, &[F]) -> color_eyre::Result<bool>,
+) -> Vec<bool> {
+    let mut valid = vec![false; pairs.len()];
+    let (indices, (proofs, public_inputs)): (Vec<_>, (Vec<_>, Vec<_>)) = pairs
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, pair)| pair.map(|pair| (i, pair)))
+        .unzip();
+    if proofs.len() > 1 {
+        if let Some(batch_verify) = batch_verify {
+            match batch_verify(&proofs, &public_inputs) {
+                Ok(true) => {
+                    for i in indices {
+                        valid[i] = true;
+                    }
+                    return valid;
+                }
+                Ok(false) => {
+                    tracing::warn!("Batch verification failed, verifying the proofs individually")
+                }
+                Err(err) => tracing::warn!(
+                    "Batch verification returned an error, verifying the proofs individually: {err:#}"
+                ),
+            }
+        }
+    }
+    // an error counts as a failed verification, such that the offending proof is reported
+    for (i, (proof, public_inputs)) in indices.into_iter().zip(proofs.iter().zip(&public_inputs)) {
+        valid[i] = verify(proof, public_inputs).unwrap_or_else(|err| {
+            tracing::warn!("Verification of proof {i} returned an error: {err:#}");
+            false
+        });
+    }
+    valid
+}
+
+#[instrument(skip(config))]
+fn run_setup<P: Pairing + CircomArkworksPairingBridge>(
+    config: SetupConfig,
+) -> color_eyre::Result<ExitCode>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    let r1cs = config.r1cs;
+    let ptau = config.ptau;
+    let out = config.out;
+
+    file_utils::check_file_exists(&r1cs)?;
+    file_utils::check_file_exists(&ptau)?;
+
+    let r1cs_file = BufReader::new(File::open(&r1cs).context("while opening r1cs file")?);
+    let r1cs = R1CS::<P>::from_reader(r1cs_file).context("while parsing r1cs file")?;
+    let ptau_file = BufReader::new(File::open(&ptau).context("while opening ptau file")?);
+    let start = Instant::now();
+    let ptau = PowersOfTau::<P>::from_reader(ptau_file).context("while parsing ptau file")?;
+    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+    tracing::info!("Parsing ptau took {} ms", duration_ms);
+
+    match config.proof_system {
+        ProofSystem::Groth16 => {
+            let start = Instant::now();
+            let mut zkey = Groth16ZKey::from_r1cs_and_ptau(&r1cs, &ptau)
+                .context("while running groth16 setup")?;
+            let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+            tracing::info!("Groth16 setup took {} ms", duration_ms);
+            if config.no_contribution {
+                tracing::warn!("The zkey has no contributions, do not use it before contributing");
+            } else {
+                let hash = zkey.contribute(&mut rand::thread_rng(), config.name);
+                log_contribution_hash(&hash);
+            }
+            let out_file =
+                BufWriter::new(File::create(&out).context("while creating output file")?);
+            zkey.to_writer(out_file)
+                .context("while writing zkey to file")?;
+        }
+        ProofSystem::Plonk => {
+            if config.name.is_some() || config.no_contribution {
+                tracing::warn!(
+                    "Plonk zkeys have no contributions, ignoring the contribution options"
+                );
+            }
+            let start = Instant::now();
+            let zkey =
+                PlonkZKey::from_r1cs_and_ptau(&r1cs, &ptau).context("while running plonk setup")?;
+            let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+            tracing::info!("Plonk setup took {} ms", duration_ms);
+            let out_file =
+                BufWriter::new(File::create(&out).context("while creating output file")?);
+            zkey.to_writer(out_file)
+                .context("while writing zkey to file")?;
+        }
+        ProofSystem::Fflonk => {
+            if config.name.is_some() || config.no_contribution {
+                tracing::warn!(
+                    "Fflonk zkeys have no contributions, ignoring the contribution options"
+                );
+            }
+            let start = Instant::now();
+            let zkey = FflonkZKey::from_r1cs_and_ptau(&r1cs, &ptau)
+                .context("while running fflonk setup")?;
+            let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+            tracing::info!("Fflonk setup took {} ms", duration_ms);
+            let out_file =
+                BufWriter::new(File::create(&out).context("while creating output file")?);
+            zkey.to_writer(out_file)
+                .context("while writing zkey to file")?;
+        }
+    }
+    tracing::info!("Wrote zkey to file {}", out.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+#[instrument(skip(config))]
+fn run_contribute<P: Pairing + CircomArkworksPairingBridge>(
+    config: ContributeConfig,
+) -> color_eyre::Result<ExitCode>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    let zkey = config.zkey;
+    let out = config.out;
+
+    file_utils::check_file_exists(&zkey)?;
+
+    let zkey_file = BufReader::new(File::open(&zkey).context("while opening zkey file")?);
+    let mut zkey = Groth16ZKey::<P>::from_reader(zkey_file).context("while parsing zkey")?;
+
+    let start = Instant::now();
+    let hash = match config.beacon {
+        Some(beacon) => {
+            let beacon = parse_hex(&beacon).context("while parsing beacon")?;
+            let num_iterations_exp = config
+                .num_iterations_exp
+                .context("num_iterations_exp is required for a beacon")?;
+            zkey.apply_beacon(beacon, num_iterations_exp, config.name)
+                .context("while applying beacon")?
+        }
+        None => zkey.contribute(&mut rand::thread_rng(), config.name),
+    };
+    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+    tracing::info!("Contribution took {} ms", duration_ms);
+    log_contribution_hash(&hash);
+
+    let out_file = BufWriter::new(File::create(&out).context("while creating output file")?);
+    zkey.to_writer(out_file)
+        .context("while writing zkey to file")?;
+    tracing::info!("Wrote zkey to file {}", out.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+#[instrument(skip(config))]
+fn run_aggregation_setup<P: Pairing + CircomArkworksPairingBridge>(
+    config: AggregationSetupConfig,
+) -> color_eyre::Result<ExitCode>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    let out = config.out;
+
+    let start = Instant::now();
+    let srs = match (config.insecure_max_proofs, config.ptau.as_slice()) {
+        (Some(max_proofs), []) => {
+            tracing::warn!("Creating an insecure aggregation SRS, only use it for testing");
+            AggregationSrs::<P>::insecure_setup(&mut rand::thread_rng(), max_proofs)
+                .context("while creating insecure SRS")?
+        }
+        (None, [ptau_alpha, ptau_beta]) => {
+            file_utils::check_file_exists(ptau_alpha)?;
+            file_utils::check_file_exists(ptau_beta)?;
+            let parse_ptau = |ptau: &PathBuf| {
+                let ptau_file =
+                    BufReader::new(File::open(ptau).context("while opening ptau file")?);
+                PowersOfTau::<P>::from_reader(ptau_file).context("while parsing ptau file")
+            };
+            let ptau_alpha = parse_ptau(ptau_alpha)?;
+            let ptau_beta = parse_ptau(ptau_beta)?;
+            AggregationSrs::<P>::from_powers_of_tau(&ptau_alpha, &ptau_beta)
+                .context("while creating SRS from ptau files")?
+        }
+        _ => {
+            return Err(eyre!(
+                "either two ptau files or insecure_max_proofs must be provided"
+            ))
+        }
+    };
+    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+    tracing::info!("Aggregation setup took {} ms", duration_ms);
+    tracing::info!(
+        "The SRS allows to aggregate up to {} proofs",
+        srs.max_proofs()
+    );
+
+    let out_file = BufWriter::new(File::create(&out).context("while creating output file")?);
+    srs.serialize_compressed(out_file)
+        .context("while writing SRS to file")?;
+    tracing::info!("Wrote aggregation SRS to file {}", out.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parses an aggregation SRS file written by `aggregation-setup`
+fn parse_aggregation_srs<P: Pairing>(srs: &Path) -> color_eyre::Result<AggregationSrs<P>> {
+    file_utils::check_file_exists(srs)?;
+    let srs_file = BufReader::new(File::open(srs).context("while opening SRS file")?);
+    let start = Instant::now();
+    let srs = AggregationSrs::<P>::deserialize_compressed(srs_file)
+        .context("while deserializing SRS from file")?;
+    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+    tracing::info!("Parsing SRS took {} ms", duration_ms);
+    Ok(srs)
+}
+
+/// SnarkPack halves the number of proofs in every round, so it only aggregates a power of two
+/// proofs. Checked before parsing the (large) SRS.
+fn check_num_aggregated_proofs(num_proofs: usize) -> color_eyre::Result<()> {
+    if num_proofs < 2 || !num_proofs.is_power_of_two() {
+        return Err(eyre!(
+            "got {num_proofs} proofs, but the number of aggregated proofs must be a power of two and at least 2. Split the proofs into batches of such sizes and aggregate every batch on its own"
+        ));
+    }
+    Ok(())
+}
+
+#[instrument(skip(config))]
+fn run_aggregate_proofs<P: Pairing + CircomArkworksPairingBridge>(
+    config: AggregateProofsConfig,
+) -> color_eyre::Result<ExitCode>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    let vk = config.vk;
+    let out = config.out;
+
+    file_utils::check_file_exists(&vk)?;
+    let proof_files = collect_proof_files(config.proof, config.public_input, config.batch_dir)?;
+    check_num_aggregated_proofs(proof_files.len())?;
+    let srs = parse_aggregation_srs::<P>(&config.srs)?
+        .specialize(proof_files.len())
+        .context("while specializing SRS")?;
+
+    let vk_file = BufReader::new(File::open(&vk).context("while opening verification key file")?);
+    let vk: Groth16JsonVerificationKey<P> = serde_json::from_reader(vk_file)
+        .context("while deserializing verification key from file")?;
+    let public_inputs = proof_files
+        .iter()
+        .map(|(_, public_input)| parse_public_inputs::<P::ScalarField>(public_input))
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+    let proofs = parse_proofs::<Groth16Proof<P>>(&proof_files)?;
+
+    let start = Instant::now();
+    let proof = Groth16::<P>::aggregate_proofs(&srs, &vk, &proofs, &public_inputs)
+        .context("while aggregating proofs")?;
+    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+    tracing::info!(
+        "Aggregating {} proofs took {} ms",
+        proof_files.len(),
+        duration_ms
+    );
+
+    let out_file = BufWriter::new(File::create(&out).context("while creating output file")?);
+    proof
+        .serialize_compressed(out_file)
+        .context("while writing aggregate proof to file")?;
+    tracing::info!("Wrote aggregate proof to file {}", out.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+#[instrument(skip(config))]
+fn run_verify_aggregate<P: Pairing + CircomArkworksPairingBridge>(
+    config: VerifyAggregateConfig,
+) -> color_eyre::Result<ExitCode>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    let vk = config.vk;
+    let proof = config.proof;
+
+    file_utils::check_file_exists(&vk)?;
+    file_utils::check_file_exists(&proof)?;
+    let public_input_files = if let Some(batch_dir) = config.batch_dir {
+        collect_proof_files(vec![], vec![], Some(batch_dir))?
+            .into_iter()
+            .map(|(_, public_input)| public_input)
+            .collect()
+    } else {
+        for public_input in config.public_input.iter() {
+            file_utils::check_file_exists(public_input)?;
+        }
+        config.public_input
+    };
+    check_num_aggregated_proofs(public_input_files.len())?;
+    let srs = parse_aggregation_srs::<P>(&config.srs)?.verifier_srs();
+
+    let vk_file = BufReader::new(File::open(&vk).context("while opening verification key file")?);
+    let vk: Groth16JsonVerificationKey<P> = serde_json::from_reader(vk_file)
+        .context("while deserializing verification key from file")?;
+    let public_inputs = public_input_files
+        .iter()
+        .map(|public_input| parse_public_inputs::<P::ScalarField>(public_input))
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+    let proof_file = BufReader::new(File::open(&proof).context("while opening proof file")?);
+    let proof = AggregateProof::<P>::deserialize_compressed(proof_file)
+        .context("while deserializing aggregate proof from file")?;
+
+    let start = Instant::now();
+    let res = Groth16::<P>::verify_aggregate_proof(&srs, &vk, &proof, &public_inputs)
+        .context("while verifying aggregate proof")?;
+    let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+    tracing::info!("Aggregate proof verification took {} ms", duration_ms);
+
+    if res {
+        tracing::info!(
+            "Aggregate proof of {} proofs verified successfully",
+            public_inputs.len()
+        );
+        Ok(ExitCode::SUCCESS)
+    } else {
+        tracing::error!("Aggregate proof verification failed");
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+#[instrument(skip(config))]
+fn run_network_init(config: NetworkInitConfig) -> color_eyre::Result<ExitCode> {
+    let num_parties = config.parties;
+    let out_dir = config.out_dir;
+    let ext = config.key_format.extension();
+
+    if num_parties < 2 {
+        return Err(eyre!("A network needs at least 2 parties"));
+    }
+    let addresses = if config.addresses.is_empty() {
+        (0..num_parties)
+            .map(|i| {
+                Ok(Address {
+                    hostname: "127.0.0.1".to_owned(),
+                    port: u16::try_from(10000 + i).context("too many parties")?,
+                })
+            })
+            .collect::<color_eyre::Result<Vec<_>>>()?
+    } else if config.addresses.len() == num_parties {
+        config.addresses
+    } else {
+        return Err(eyre!(
+            "Expected {num_parties} addresses, one for each party, but got {}",
+            config.addresses.len()
+        ));
+    };
+
+    std::fs::create_dir_all(&out_dir).context("while creating output directory")?;
+    let cert_paths = (0..num_parties)
+        .map(|i| out_dir.join(format!("cert{i}.{ext}")))
+        .collect::<Vec<_>>();
+    let key_paths = (0..num_parties)
+        .map(|i| out_dir.join(format!("key{i}.{ext}")))
+        .collect::<Vec<_>>();
+    let config_paths = (0..num_parties)
+        .map(|i| out_dir.join(format!("party{i}.toml")))
+        .collect::<Vec<_>>();
+    // we never overwrite the keys of an existing network
+    if let Some(existing) = cert_paths
+        .iter()
+        .chain(&key_paths)
+        .chain(&config_paths)
+        .find(|path| path.exists())
+    {
+        return Err(eyre!("{} already exists", existing.display()));
+    }
+
+    let mut parties = Vec::with_capacity(num_parties);
+    for (id, address) in addresses.into_iter().enumerate() {
+        // the certificate has to be valid for the hostname the other parties connect to
+        let (cert, key) =
+            keys::generate_self_signed(vec![address.hostname.clone()], config.key_format)
+                .with_context(|| format!("while generating the certificate of party {id}"))?;
+        std::fs::write(&cert_paths[id], cert).context("while writing certificate")?;
+        keys::write_private_key(&key_paths[id], &key).context("while writing private key")?;
+        parties.push(NetworkParty {
+            id,
+            dns_name: address,
+            cert_path: cert_paths[id].clone(),
+        });
+    }
+
+    #[derive(serde::Serialize)]
+    struct PartyConfig {
+        network: NetworkConfig,
+    }
+    for (id, key_path) in key_paths.into_iter().enumerate() {
+        let network = NetworkConfig::new(parties.clone(), id, key_path)?;
+        let toml = toml::to_string_pretty(&PartyConfig { network })
+            .context("while serializing network config")?;
+        std::fs::write(&config_paths[id], toml).context("while writing network config")?;
+    }
+    tracing::info!(
+        "Wrote certificates, keys and network configs of {num_parties} parties to {}",
+        out_dir.display()
+    );
+    Ok(ExitCode::SUCCESS)
+}
+
+#[instrument(skip(config))]
+fn run_export_verifier(config: ExportVerifierConfig) -> color_eyre::Result<ExitCode> {
+    let vk = config.vk;
+    let out = config.out;
+
+    file_utils::check_file_exists(&vk)?;
+    let vk_file = BufReader::new(File::open(&vk).context("while opening verification key file")?);
+
+    let contract = match config.proof_system {
+        ProofSystem::Groth16 => {
+            let vk: Groth16JsonVerificationKey<Bn254> = solidity::read_bn254_json(vk_file)
+                .context("while deserializing verification key from file")?;
+            solidity::groth16_verifier(&vk)
+        }
+        ProofSystem::Plonk => {
+            let vk: PlonkJsonVerificationKey<Bn254> = solidity::read_bn254_json(vk_file)
+                .context("while deserializing verification key from file")?;
+            solidity::plonk_verifier(&vk).context("while creating verifier contract")?
+        }
+        ProofSystem::Fflonk => {
+            return Err(eyre!(
+                "exporting a Solidity verifier is only supported for Groth16 and Plonk"
+            ));
+        }
+    };
+    std::fs::write(&out, contract).context("while writing verifier contract")?;
+    tracing::info!("Wrote Solidity verifier to file {}", out.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+#[instrument(skip(config))]
+fn run_export_calldata(config: ExportCalldataConfig) -> color_eyre::Result<ExitCode> {
+    let proof = config.proof;
+    let public_input = config.public_input;
+
+    file_utils::check_file_exists(&proof)?;
+    file_utils::check_file_exists(&public_input)?;
+    let proof_file = BufReader::new(File::open(&proof).context("while opening proof file")?);
+    let public_inputs = parse_public_inputs::<ark_bn254::Fr>(&public_input)?;
+
+    let calldata = match config.proof_system {
+        ProofSystem::Groth16 => {
+            let proof: Groth16Proof<Bn254> = solidity::read_bn254_json(proof_file)
+                .context("while deserializing proof from file")?;
+            solidity::groth16_calldata(&proof, &public_inputs)
+        }
+        ProofSystem::Plonk => {
+            let proof: PlonkProof<Bn254> = solidity::read_bn254_json(proof_file)
+                .context("while deserializing proof from file")?;
+            solidity::plonk_calldata(&proof, &public_inputs)
+        }
+        ProofSystem::Fflonk => {
+            return Err(eyre!(
+                "exporting calldata is only supported for Groth16 and Plonk"
+            ));
+        }
+    };
+    if let Some(out) = config.out {
+        std::fs::write(&out, calldata).context("while writing calldata")?;
+        tracing::info!("Wrote calldata to file {}", out.display());
+    } else {
+        println!("{calldata}");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parses a public input JSON file, an array of stringified field elements
+fn parse_public_inputs<F: PrimeField>(path: &Path) -> color_eyre::Result<Vec<F>> {
+    let public_inputs_file =
+        BufReader::new(File::open(path).context("while opening public inputs file")?);
+    let public_inputs_as_strings: Vec<String> = serde_json::from_reader(public_inputs_file)
+        .context(
+            "while parsing public inputs, expect them to be array of stringified field elements",
+        )?;
+    public_inputs_as_strings
+        .into_iter()
+        .map(|s| {
+            s.parse::<F>()
+                .map_err(|_| eyre!("could not parse as field element: {}", s))
+        })
+        .collect::<Result<Vec<F>, _>>()
+        .context("while converting public input strings to field elements")
+}
+
+fn log_contribution_hash(hash: &[u8; 64]) {
+    // same layout as snarkjs, so the hash can be compared with the output of "snarkjs zkey verify"
+    let hex = hash
+        .chunks(16)
+        .map(|line| {
+            line.chunks(4)
+                .map(|word| word.iter().map(|b| format!("{b:02x}")).collect::<String>())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\t\t");
+    tracing::info!("Contribution hash:\n\t\t{hex}");
+}
+
+fn parse_hex(hex: &str) -> color_eyre::Result<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.is_ascii() || hex.len() % 2 != 0 {
+        return Err(eyre!("invalid hex string: {}", hex));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| eyre!("invalid hex string: {}", hex))
+        })
+        .collect()
+}
 
 fn parse_field<F>(val: &serde_json::Value) -> color_eyre::Result<F>
 where
@@ -769,21 +1922,120 @@ fn parse_array<F: PrimeField>(val: &serde_json::Value) -> color_eyre::Result<Vec
     Ok(field_elements)
 }
 
-fn merge_input_shares<P: Pairing, T: PrimeFieldMpcProtocol<P::ScalarField>>(
+/// The parameters a witness share must have been created for to be proven with.
+struct ExpectedWitnessShare {
+    protocol: MPCProtocol,
+    threshold: usize,
+    party_id: usize,
+    num_parties: usize,
+    circuit_hash: Option<[u8; 32]>,
+    zkey_hash: [u8; 32],
+    allow_missing_hashes: bool,
+}
+
+fn parse_and_check_witness_share<P, T>(
+    witness_file: impl std::io::Read,
+    expected: &ExpectedWitnessShare,
+    n_public: usize,
+) -> color_eyre::Result<SharedWitness<T, P>>
+where
+    P: Pairing + CircomArkworksPairingBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    let (metadata, witness_share) = co_circom::parse_witness_share(witness_file)?;
+    let allow_missing = expected.allow_missing_hashes;
+    metadata
+        .check_protocol(expected.protocol.into())
+        .and_then(|_| metadata.check_curve::<P>())
+        .and_then(|_| metadata.check_party_id(expected.party_id))
+        .and_then(|_| metadata.check_num_parties(expected.num_parties))
+        .and_then(|_| metadata.check_threshold(expected.threshold))
+        .and_then(|_| match &expected.circuit_hash {
+            Some(circuit_hash) => metadata.check_circuit_hash(circuit_hash, allow_missing),
+            None => Ok(()),
+        })
+        .and_then(|_| metadata.check_zkey_hash(&expected.zkey_hash, allow_missing))
+        .context("while validating witness share file")?;
+    // the public inputs also contain the constant 1 at position 0
+    if witness_share.public_inputs.len() != n_public + 1 {
+        return Err(eyre!(
+            "Witness share has {} public inputs, but zkey expects {}",
+            witness_share.public_inputs.len().saturating_sub(1),
+            n_public
+        ));
+    }
+    Ok(witness_share)
+}
+
+fn write_input_shares<P: Pairing, T: PrimeFieldMpcProtocol<P::ScalarField>>(
+    shares: &[SharedInput<T, P>],
+    out_dir: &Path,
+    base_name: &str,
+    metadata: &ShareMetadata,
+    recipients: &[ShareEncryptionKey],
+) -> color_eyre::Result<()> {
+    let mut rng = rand::thread_rng();
+    for (i, share) in shares.iter().enumerate() {
+        let path = out_dir.join(format!("{}.{}.shared", base_name, i));
+        let out_file = BufWriter::new(File::create(&path).context("while creating output file")?);
+        let metadata = ShareMetadata {
+            party_id: i,
+            ..metadata.clone()
+        };
+        if let Some(recipient) = recipients.get(i) {
+            share_file::write_share_encrypted(out_file, &metadata, share, recipient, &mut rng)
+                .context("while serializing encrypted input share")?;
+            tracing::info!(
+                "Wrote encrypted input share {} to file {}",
+                i,
+                path.display()
+            );
+        } else {
+            share_file::write_share(out_file, &metadata, share)
+                .context("while serializing input share")?;
+            tracing::info!("Wrote input share {} to file {}", i, path.display());
+        }
+    }
+    Ok(())
+}
+
+fn merge_input_shares<P, T>(
     inputs: Vec<PathBuf>,
+    protocol: MPCProtocol,
+    key: Option<&ShareDecryptionKey>,
     out: PathBuf,
-) -> color_eyre::Result<()> {
+) -> color_eyre::Result<()>
+where
+    P: Pairing + CircomArkworksPairingBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
     let start = Instant::now();
-    let mut input_shares = inputs
+    let (metadata, mut input_shares): (Vec<_>, Vec<_>) = inputs
         .iter()
         .map(|input| {
             let input_share_file =
                 BufReader::new(File::open(input).context("while opening input share file")?);
-            let input_share: SharedInput<T, P> = bincode::deserialize_from(input_share_file)
-                .context("trying to parse input share file")?;
-            color_eyre::Result::<_>::Ok(input_share)
+            let (metadata, input_share) =
+                co_circom::parse_shared_input::<_, P, T>(input_share_file, key)?;
+            metadata
+                .check_protocol(protocol.into())
+                .and_then(|_| metadata.check_curve::<P>())
+                .with_context(|| format!("while validating {}", input.display()))?;
+            color_eyre::Result::<_>::Ok((metadata, input_share))
         })
-        .collect::<Result<Vec<_>, _>>()?;
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    let merged_metadata = metadata[0].clone();
+    if metadata.iter().any(|m| m != &merged_metadata) {
+        return Err(eyre!(
+            "Input shares to merge must belong to the same party, protocol and circuit"
+        ));
+    }
     let start_item = input_shares.pop().expect("we have at least two inputs");
     let merged = input_shares.into_iter().try_fold(start_item, |a, b| {
         a.merge(b).context("while merging input shares")
@@ -792,7 +2044,222 @@ fn merge_input_shares<P: Pairing, T: PrimeFieldMpcProtocol<P::ScalarField>>(
     tracing::info!("Merging took {} ms", duration_ms);
 
     let out_file = BufWriter::new(File::create(&out).context("while creating output file")?);
-    bincode::serialize_into(out_file, &merged).context("while serializing witness share")?;
+    share_file::write_share(out_file, &merged_metadata, &merged)
+        .context("while serializing input share")?;
     tracing::info!("Wrote merged input share to file {}", out.display());
     Ok(())
 }
+
+/// Reads the share files of the parties and checks that they belong to the same sharing.
+/// Returns the party ids, the common metadata and the shares, sorted by party id.
+fn read_shares_to_combine<P, S>(
+    paths: &[PathBuf],
+    protocol: MPCProtocol,
+    parse: impl Fn(BufReader<File>) -> color_eyre::Result<(ShareMetadata, S)>,
+) -> color_eyre::Result<(Vec<usize>, ShareMetadata, Vec<S>)>
+where
+    P: Pairing + CircomArkworksPairingBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    let mut shares = paths
+        .iter()
+        .map(|path| {
+            let share_file = BufReader::new(File::open(path).context("while opening share file")?);
+            let (metadata, share) = parse(share_file)?;
+            metadata
+                .check_protocol(protocol.into())
+                .and_then(|_| metadata.check_curve::<P>())
+                .with_context(|| format!("while validating {}", path.display()))?;
+            color_eyre::Result::<_>::Ok((metadata, share))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    shares.sort_by_key(|(metadata, _)| metadata.party_id);
+
+    let (metadata, shares): (Vec<_>, Vec<_>) = shares.into_iter().unzip();
+    let common = metadata.first().cloned().context("no shares provided")?;
+    if metadata.iter().any(|m| {
+        m.kind != common.kind
+            || m.num_parties != common.num_parties
+            || m.threshold != common.threshold
+            || m.circuit_hash != common.circuit_hash
+            || m.zkey_hash != common.zkey_hash
+    }) {
+        return Err(eyre!(
+            "Shares to combine must belong to the same sharing, parties and circuit"
+        ));
+    }
+    let party_ids = metadata.iter().map(|m| m.party_id).collect::<Vec<_>>();
+    if party_ids.windows(2).any(|ids| ids[0] == ids[1]) {
+        return Err(eyre!("Got multiple shares of the same party"));
+    }
+    if party_ids.iter().any(|id| *id >= common.num_parties) {
+        return Err(eyre!(
+            "Got a share of a party with id >= {}",
+            common.num_parties
+        ));
+    }
+    Ok((party_ids, common, shares))
+}
+
+fn check_public_witness<P: Pairing, T: PrimeFieldMpcProtocol<P::ScalarField>>(
+    shares: &[SharedWitness<T, P>],
+) -> color_eyre::Result<Vec<P::ScalarField>> {
+    let public_inputs = &shares[0].public_inputs;
+    if shares.iter().any(|s| &s.public_inputs != public_inputs) {
+        return Err(eyre!(
+            "Public inputs must be the same in all witness shares"
+        ));
+    }
+    Ok(public_inputs.to_owned())
+}
+
+fn combine_rep3_shares<F: PrimeField>(
+    party_ids: &[usize],
+    shares: Vec<Rep3PrimeFieldShareVec<F>>,
+) -> color_eyre::Result<Vec<F>> {
+    if party_ids != [0, 1, 2] {
+        return Err(eyre!(
+            "REP3 needs the shares of all three parties, got the shares of parties {:?}",
+            party_ids
+        ));
+    }
+    let [share1, share2, share3]: [_; 3] = shares.try_into().expect("we checked the length");
+    if share1.len() != share2.len() || share2.len() != share3.len() {
+        return Err(eyre!("The shares have different lengths"));
+    }
+    // every party holds the additive share of its predecessor as well, which we check
+    // here so inconsistent shares are reported instead of panicking while combining
+    let (a1, b1) = share1.clone().get_ab();
+    let (a2, b2) = share2.clone().get_ab();
+    let (a3, b3) = share3.clone().get_ab();
+    let inconsistent =
+        (0..a1.len()).position(|i| b1[i] != a3[i] || b2[i] != a1[i] || b3[i] != a2[i]);
+    if let Some(index) = inconsistent {
+        return Err(eyre!("The shares are inconsistent at index {}", index));
+    }
+    Ok(rep3::utils::combine_field_elements(share1, share2, share3))
+}
+
+fn combine_shamir_shares<F: PrimeField>(
+    party_ids: &[usize],
+    shares: Vec<ShamirPrimeFieldShareVec<F>>,
+    threshold: usize,
+) -> color_eyre::Result<Vec<F>> {
+    let coeffs = party_ids.iter().map(|id| id + 1).collect::<Vec<_>>();
+    shamir::utils::combine_field_elements(&shares, &coeffs, threshold)
+        .context("while combining Shamir shares")
+}
+
+fn combine_input_shares<P: Pairing, T: PrimeFieldMpcProtocol<P::ScalarField>>(
+    shares: Vec<SharedInput<T, P>>,
+    combine: impl Fn(Vec<T::FieldShareVec>) -> color_eyre::Result<Vec<P::ScalarField>>,
+) -> color_eyre::Result<BTreeMap<String, Vec<P::ScalarField>>> {
+    let mut combined = shares[0].public_inputs.clone();
+    if shares.iter().any(|s| s.public_inputs != combined) {
+        return Err(eyre!("Public inputs must be the same in all input shares"));
+    }
+    let names = shares[0].shared_inputs.keys().cloned().collect::<Vec<_>>();
+    if shares
+        .iter()
+        .any(|s| !s.shared_inputs.keys().eq(names.iter()))
+    {
+        return Err(eyre!("Shared inputs must be the same in all input shares"));
+    }
+    for name in names {
+        let input_shares = shares
+            .iter()
+            .map(|s| s.shared_inputs[&name].clone())
+            .collect();
+        let vals = combine(input_shares).with_context(|| format!("while combining \"{name}\""))?;
+        combined.insert(name, vals);
+    }
+    Ok(combined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::{
+        providers::{Format, Toml},
+        Figment,
+    };
+
+    #[derive(serde::Deserialize)]
+    struct PartyConfig {
+        network: NetworkConfig,
+    }
+
+    #[test]
+    fn verify_reports_unparsable_pairs_as_failed() {
+        let dir = PathBuf::from("../../test_vectors/Groth16/bn254/multiplier2");
+        let proof = dir.join("circom.proof");
+        let public_input = dir.join("public.json");
+        let proof_files = vec![
+            (proof.clone(), public_input.clone()),
+            (dir.join("missing.proof"), public_input.clone()),
+            (dir.join("verification_key.json"), public_input.clone()),
+            (proof.clone(), dir.join("missing.json")),
+            (proof.clone(), dir.join("circom.proof")),
+            (proof, public_input),
+        ];
+        let pairs = parse_proof_pairs::<Groth16Proof<Bn254>, ark_bn254::Fr>(&proof_files);
+        let vk_file = File::open(dir.join("verification_key.json")).unwrap();
+        let vk: Groth16JsonVerificationKey<Bn254> = serde_json::from_reader(vk_file).unwrap();
+        let valid = verify_proofs(
+            pairs,
+            Some(|proofs: &[_], public_inputs: &[_]| {
+                Groth16::<Bn254>::batch_verify(&vk, proofs, public_inputs)
+            }),
+            |proof, public_inputs| Ok(Groth16::<Bn254>::verify(&vk, proof, public_inputs)?),
+        );
+        assert_eq!(valid, [true, false, false, false, false, true]);
+    }
+
+    #[test]
+    fn network_init_writes_valid_configs() {
+        let out_dir =
+            std::env::temp_dir().join(format!("co-circom-network-{}", std::process::id()));
+        let config = NetworkInitConfig {
+            parties: 3,
+            addresses: vec![],
+            out_dir: out_dir.clone(),
+            key_format: keys::KeyFormat::Pem,
+        };
+        run_network_init(config).unwrap();
+
+        for id in 0..3 {
+            // the configs are loaded like the configs of the other commands
+            let party: PartyConfig = Figment::new()
+                .merge(Toml::file(out_dir.join(format!("party{id}.toml"))))
+                .extract()
+                .unwrap();
+            let network = party.network;
+            network.check_config().unwrap();
+            assert_eq!(network.my_id, id);
+            assert_eq!(network.parties.len(), 3);
+            for party in network.parties.iter() {
+                keys::read_certificate(&party.cert_path).unwrap();
+            }
+            keys::read_private_key(&network.key_path).unwrap();
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = std::fs::metadata(&network.key_path)
+                    .unwrap()
+                    .permissions()
+                    .mode();
+                assert_eq!(mode & 0o777, 0o600);
+            }
+        }
+        // an existing network is never overwritten
+        let config = NetworkInitConfig {
+            parties: 3,
+            addresses: vec![],
+            out_dir: out_dir.clone(),
+            key_format: keys::KeyFormat::Pem,
+        };
+        assert!(run_network_init(config).is_err());
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+}
@@ -1,6 +1,11 @@
 #![warn(missing_docs)]
 //! This crate provides a binary and associated helper library for running collaborative SNARK proofs.
-use std::{io::Read, path::PathBuf, time::Instant};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read},
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use ark_ec::pairing::Pairing;
 use circom_mpc_compiler::{CoCircomCompiler, CompilerConfig};
@@ -11,7 +16,10 @@ use circom_types::{
 };
 use clap::Args;
 use clap::ValueEnum;
-use co_circom_snarks::{SharedInput, SharedWitness};
+use co_circom_snarks::{
+    share_file::{self, encryption::ShareDecryptionKey},
+    ShareKind, ShareMetadata, ShareProtocol, SharedInput, SharedWitness,
+};
 use co_groth16::CoGroth16;
 use color_eyre::eyre::Context;
 use figment::{
@@ -19,17 +27,25 @@ use figment::{
     Figment,
 };
 use mpc_core::{
-    protocols::rep3::{
-        network::{Rep3MpcNet, Rep3Network},
-        Rep3Protocol,
+    protocols::{
+        communication::{CommunicationStats, PhaseStats},
+        rep3::{
+            network::{Rep3MpcNet, Rep3Network},
+            Rep3Protocol,
+        },
     },
     traits::PrimeFieldMpcProtocol,
 };
-use mpc_net::config::NetworkConfig;
+use mpc_net::{
+    config::{Address, NetworkConfig},
+    keys::KeyFormat,
+};
 use serde::{Deserialize, Serialize};
 
 /// A module for file utility functions.
 pub mod file_utils;
+/// A module for exporting Solidity verifiers and calldata.
+pub mod solidity;
 
 /// An enum representing the ZK proof system to use.
 #[derive(Debug, Clone, ValueEnum, Serialize, Deserialize)]
@@ -39,6 +55,8 @@ pub enum ProofSystem {
     Groth16,
     /// The Plonk proof system.
     Plonk,
+    /// The FFLONK proof system.
+    Fflonk,
 }
 
 impl std::fmt::Display for ProofSystem {
@@ -46,6 +64,7 @@ impl std::fmt::Display for ProofSystem {
         match self {
             ProofSystem::Groth16 => write!(f, "Plonk"),
             ProofSystem::Plonk => write!(f, "Groth16"),
+            ProofSystem::Fflonk => write!(f, "Fflonk"),
         }
     }
 }
@@ -102,6 +121,15 @@ impl std::fmt::Display for MPCProtocol {
     }
 }
 
+impl From<MPCProtocol> for ShareProtocol {
+    fn from(protocol: MPCProtocol) -> Self {
+        match protocol {
+            MPCProtocol::REP3 => ShareProtocol::Rep3,
+            MPCProtocol::SHAMIR => ShareProtocol::Shamir,
+        }
+    }
+}
+
 /// Cli arguments for `split_witness`
 #[derive(Debug, Default, Serialize, Args)]
 pub struct SplitWitnessCli {
@@ -117,6 +145,14 @@ pub struct SplitWitnessCli {
     #[arg(long)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
     pub r1cs: Option<PathBuf>,
+    /// The path to the circuit file. If passed, its hash is stored in the witness shares and checked during proof generation.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub circuit: Option<PathBuf>,
+    /// The path to the proving key (.zkey) file. If passed, its hash is stored in the witness shares and checked during proof generation.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub zkey: Option<PathBuf>,
     /// The MPC protocol to be used
     #[arg(long, value_enum)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
@@ -144,6 +180,10 @@ pub struct SplitWitnessConfig {
     pub witness: PathBuf,
     /// The path to the r1cs file, generated by Circom compiler
     pub r1cs: PathBuf,
+    /// The path to the circuit file. If passed, its hash is stored in the witness shares and checked during proof generation.
+    pub circuit: Option<PathBuf>,
+    /// The path to the proving key (.zkey) file. If passed, its hash is stored in the witness shares and checked during proof generation.
+    pub zkey: Option<PathBuf>,
     /// The MPC protocol to be used
     pub protocol: MPCProtocol,
     /// The pairing friendly curve to be used
@@ -183,6 +223,16 @@ pub struct SplitInputCli {
     #[arg(long)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
     pub out_dir: Option<PathBuf>,
+    /// The threshold of tolerated colluding parties
+    #[arg(short, long, default_value_t = 1)]
+    pub threshold: usize,
+    /// The number of parties
+    #[arg(short, long, default_value_t = 3)]
+    pub num_parties: usize,
+    /// The paths to the certificates of the parties (ordered by party id). If passed, each input share is encrypted to the public key of the respective party
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::vec::Vec::is_empty")]
+    pub recipient_certs: Vec<PathBuf>,
 }
 
 /// Config for `split_input`
@@ -198,6 +248,13 @@ pub struct SplitInputConfig {
     pub curve: MPCCurve,
     /// The path to the (existing) output directory
     pub out_dir: PathBuf,
+    /// The threshold of tolerated colluding parties
+    pub threshold: usize,
+    /// The number of parties
+    pub num_parties: usize,
+    /// The paths to the certificates of the parties (ordered by party id). If not empty, each input share is encrypted to the public key of the respective party
+    #[serde(default)]
+    pub recipient_certs: Vec<PathBuf>,
     /// MPC compiler config
     #[serde(default)]
     pub compiler: CompilerConfig,
@@ -225,6 +282,10 @@ pub struct MergeInputSharesCli {
     #[arg(long)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
     pub out: Option<PathBuf>,
+    /// The path to our private key file, used to decrypt encrypted input shares
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub key_path: Option<PathBuf>,
 }
 
 /// Config for `merge_input_shares`
@@ -238,6 +299,8 @@ pub struct MergeInputSharesConfig {
     pub curve: MPCCurve,
     /// The output file where the merged input share is written to
     pub out: PathBuf,
+    /// The path to our private key file, used to decrypt encrypted input shares
+    pub key_path: Option<PathBuf>,
 }
 
 /// Cli arguments for `generate_witness`
@@ -263,10 +326,21 @@ pub struct GenerateWitnessCli {
     #[arg(long, value_enum)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
     pub curve: Option<MPCCurve>,
+    /// The path to the proving key (.zkey) file. If passed, its hash is stored in the witness share and checked during proof generation.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub zkey: Option<PathBuf>,
     /// The output file where the final witness share is written to
     #[arg(long)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
     pub out: Option<PathBuf>,
+    /// The output JSON file where the communication statistics of this party are written to, split into the phases of the witness extension. If not passed, the statistics are not written.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub stats: Option<PathBuf>,
+    /// Accept an input share that does not record the hash of its circuit, e.g., one created by an older version
+    #[arg(long)]
+    pub allow_missing_hashes: bool,
 }
 
 /// Config for `generate_witness`
@@ -280,8 +354,14 @@ pub struct GenerateWitnessConfig {
     pub protocol: MPCProtocol,
     /// The pairing friendly curve to be used
     pub curve: MPCCurve,
+    /// The path to the proving key (.zkey) file. If passed, its hash is stored in the witness share and checked during proof generation.
+    pub zkey: Option<PathBuf>,
     /// The output file where the final witness share is written to
     pub out: PathBuf,
+    /// The output JSON file where the communication statistics of this party are written to, split into the phases of the witness extension. If not passed, the statistics are not written.
+    pub stats: Option<PathBuf>,
+    /// Accept an input share that does not record the hash of its circuit, e.g., one created by an older version
+    pub allow_missing_hashes: bool,
     /// MPC compiler config
     #[serde(default)]
     pub compiler: CompilerConfig,
@@ -338,6 +418,80 @@ pub struct TranslateWitnessConfig {
     pub network: NetworkConfig,
 }
 
+/// Cli arguments for `combine_witness`
+#[derive(Debug, Default, Serialize, Args)]
+pub struct CombineWitnessCli {
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The paths to the witness share files of the parties
+    #[arg(long)]
+    pub witnesses: Vec<PathBuf>,
+    /// The MPC protocol that was used to create the shares
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub protocol: Option<MPCProtocol>,
+    /// The pairing friendly curve to be used
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub curve: Option<MPCCurve>,
+    /// The output file where the reconstructed witness is written to, in circom's .wtns format
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub out: Option<PathBuf>,
+}
+
+/// Config for `combine_witness`
+#[derive(Debug, Deserialize)]
+pub struct CombineWitnessConfig {
+    /// The paths to the witness share files of the parties
+    pub witnesses: Vec<PathBuf>,
+    /// The MPC protocol that was used to create the shares
+    pub protocol: MPCProtocol,
+    /// The pairing friendly curve to be used
+    pub curve: MPCCurve,
+    /// The output file where the reconstructed witness is written to, in circom's .wtns format
+    pub out: PathBuf,
+}
+
+/// Cli arguments for `combine_input`
+#[derive(Debug, Default, Serialize, Args)]
+pub struct CombineInputCli {
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The paths to the input share files of the parties
+    #[arg(long)]
+    pub inputs: Vec<PathBuf>,
+    /// The MPC protocol that was used to create the shares
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub protocol: Option<MPCProtocol>,
+    /// The pairing friendly curve to be used
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub curve: Option<MPCCurve>,
+    /// The output file where the reconstructed JSON input is written to
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub out: Option<PathBuf>,
+}
+
+/// Config for `combine_input`
+#[derive(Debug, Deserialize)]
+pub struct CombineInputConfig {
+    /// The paths to the input share files of the parties
+    pub inputs: Vec<PathBuf>,
+    /// The MPC protocol that was used to create the shares
+    pub protocol: MPCProtocol,
+    /// The pairing friendly curve to be used
+    pub curve: MPCCurve,
+    /// The output file where the reconstructed JSON input is written to
+    pub out: PathBuf,
+}
+
 /// Cli arguments for `generate_proof`
 #[derive(Debug, Serialize, Args)]
 pub struct GenerateProofCli {
@@ -356,6 +510,10 @@ pub struct GenerateProofCli {
     #[arg(long)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
     pub zkey: Option<PathBuf>,
+    /// The path to the circuit file. If passed, the witness share is checked to be created for this circuit.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub circuit: Option<PathBuf>,
     /// The MPC protocol to be used
     #[arg(long, value_enum)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
@@ -375,6 +533,21 @@ pub struct GenerateProofCli {
     /// The threshold of tolerated colluding parties
     #[arg(short, long, default_value_t = 1)]
     pub threshold: usize,
+    /// The number of threads used for the local computations of this party. If not passed, one thread per available CPU core is used.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub threads: Option<usize>,
+    /// Only for Groth16: If passed, the zkey is memory-mapped instead of being loaded into memory, and the MSMs are computed in chunks of at most this many points. This bounds the memory required for the queries of the proving key, but not for the constraint matrices and the witness.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub msm_chunk_size: Option<usize>,
+    /// The output JSON file where the communication statistics of this party are written to, split into the phases of the prover. If not passed, the statistics are not written.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub stats: Option<PathBuf>,
+    /// Accept a witness share that does not record the hash of its zkey (or of its circuit, if `--circuit` is passed), e.g., one created without `--zkey` or by an older version
+    #[arg(long)]
+    pub allow_missing_hashes: bool,
 }
 
 /// Config for `generate_proof`
@@ -386,6 +559,8 @@ pub struct GenerateProofConfig {
     pub witness: PathBuf,
     /// The path to the proving key (.zkey) file, generated by snarkjs setup phase
     pub zkey: PathBuf,
+    /// The path to the circuit file. If passed, the witness share is checked to be created for this circuit.
+    pub circuit: Option<PathBuf>,
     /// The MPC protocol to be used
     pub protocol: MPCProtocol,
     /// The pairing friendly curve to be used
@@ -396,6 +571,14 @@ pub struct GenerateProofConfig {
     pub public_input: Option<PathBuf>,
     /// The threshold of tolerated colluding parties
     pub threshold: usize,
+    /// The number of threads used for the local computations of this party. If not passed, one thread per available CPU core is used.
+    pub threads: Option<usize>,
+    /// Only for Groth16: If passed, the zkey is memory-mapped instead of being loaded into memory, and the MSMs are computed in chunks of at most this many points. This bounds the memory required for the queries of the proving key, but not for the constraint matrices and the witness.
+    pub msm_chunk_size: Option<usize>,
+    /// The output JSON file where the communication statistics of this party are written to, split into the phases of the prover. If not passed, the statistics are not written.
+    pub stats: Option<PathBuf>,
+    /// Accept a witness share that does not record the hash of its zkey (or of its circuit, if `circuit` is set), e.g., one created without `--zkey` or by an older version
+    pub allow_missing_hashes: bool,
     /// Network config
     pub network: NetworkConfig,
 }
@@ -410,10 +593,10 @@ pub struct VerifyCli {
     #[arg(long)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
     pub config: Option<PathBuf>,
-    /// The path to the proof file
+    /// The path to the proof file. Can be repeated to verify a batch of proofs
     #[arg(long)]
-    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
-    pub proof: Option<PathBuf>,
+    #[serde(skip_serializing_if = "::std::vec::Vec::is_empty")]
+    pub proof: Vec<PathBuf>,
     /// The pairing friendly curve to be used
     #[arg(long, value_enum)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
@@ -422,10 +605,14 @@ pub struct VerifyCli {
     #[arg(long)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
     pub vk: Option<PathBuf>,
-    /// The path to the public input JSON file
+    /// The path to the public input JSON file. Can be repeated, the n-th public input belongs to the n-th proof
     #[arg(long)]
+    #[serde(skip_serializing_if = "::std::vec::Vec::is_empty")]
+    pub public_input: Vec<PathBuf>,
+    /// A directory with a batch of proofs to verify, where each `<name>.proof.json` is verified with the public input `<name>.public.json`
+    #[arg(long, conflicts_with_all = ["proof", "public_input"])]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
-    pub public_input: Option<PathBuf>,
+    pub batch_dir: Option<PathBuf>,
 }
 
 /// Config for `verify`
@@ -433,14 +620,392 @@ pub struct VerifyCli {
 pub struct VerifyConfig {
     /// The proof system to be used
     pub proof_system: ProofSystem,
-    /// The path to the proof file
-    pub proof: PathBuf,
+    /// The paths to the proof files
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub proof: Vec<PathBuf>,
     /// The pairing friendly curve to be used
     pub curve: MPCCurve,
     /// The path to the verification key file
     pub vk: PathBuf,
+    /// The paths to the public input JSON files, the n-th public input belongs to the n-th proof
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub public_input: Vec<PathBuf>,
+    /// A directory with a batch of proofs to verify, where each `<name>.proof.json` is verified with the public input `<name>.public.json`
+    pub batch_dir: Option<PathBuf>,
+}
+
+// Allows a single path (as in older configs) where a list of paths is expected
+fn one_or_many<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<PathBuf>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(PathBuf),
+        Many(Vec<PathBuf>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(path) => vec![path],
+        OneOrMany::Many(paths) => paths,
+    })
+}
+
+/// Cli arguments for `setup`
+#[derive(Debug, Serialize, Args)]
+pub struct SetupCli {
+    /// The proof system to be used
+    #[arg(value_enum)]
+    pub proof_system: ProofSystem,
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The path to the R1CS file of the circuit, generated by circom
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub r1cs: Option<PathBuf>,
+    /// The path to the powers of tau (.ptau) file, which must be prepared for phase 2
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub ptau: Option<PathBuf>,
+    /// The pairing friendly curve to be used
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub curve: Option<MPCCurve>,
+    /// The output file where the proving key (.zkey) is written to
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub out: Option<PathBuf>,
+    /// The name of the contributor that is stored in the zkey (Groth16 only)
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub name: Option<String>,
+    /// Write the zkey without a contribution (like snarkjs setup), e.g., to start a ceremony with `contribute` (Groth16 only)
+    #[arg(long)]
+    pub no_contribution: bool,
+}
+
+/// Config for `setup`
+#[derive(Debug, Deserialize)]
+pub struct SetupConfig {
+    /// The proof system to be used
+    pub proof_system: ProofSystem,
+    /// The path to the R1CS file of the circuit, generated by circom
+    pub r1cs: PathBuf,
+    /// The path to the powers of tau (.ptau) file, which must be prepared for phase 2
+    pub ptau: PathBuf,
+    /// The pairing friendly curve to be used
+    pub curve: MPCCurve,
+    /// The output file where the proving key (.zkey) is written to
+    pub out: PathBuf,
+    /// The name of the contributor that is stored in the zkey (Groth16 only)
+    pub name: Option<String>,
+    /// Write the zkey without a contribution (like snarkjs setup), e.g., to start a ceremony with `contribute` (Groth16 only)
+    pub no_contribution: bool,
+}
+
+/// Cli arguments for `contribute`
+#[derive(Debug, Default, Serialize, Args)]
+pub struct ContributeCli {
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The path to the Groth16 proving key (.zkey) file to contribute to
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub zkey: Option<PathBuf>,
+    /// The pairing friendly curve to be used
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub curve: Option<MPCCurve>,
+    /// The output file where the proving key (.zkey) with the new contribution is written to
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub out: Option<PathBuf>,
+    /// The name of the contributor that is stored in the zkey
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub name: Option<String>,
+    /// A hex encoded random beacon. If passed, the contribution is derived from the beacon instead of local randomness
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub beacon: Option<String>,
+    /// The beacon is hashed 2^num_iterations_exp times (between 10 and 63)
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub num_iterations_exp: Option<u8>,
+}
+
+/// Config for `contribute`
+#[derive(Debug, Deserialize)]
+pub struct ContributeConfig {
+    /// The path to the Groth16 proving key (.zkey) file to contribute to
+    pub zkey: PathBuf,
+    /// The pairing friendly curve to be used
+    pub curve: MPCCurve,
+    /// The output file where the proving key (.zkey) with the new contribution is written to
+    pub out: PathBuf,
+    /// The name of the contributor that is stored in the zkey
+    pub name: Option<String>,
+    /// A hex encoded random beacon. If passed, the contribution is derived from the beacon instead of local randomness
+    pub beacon: Option<String>,
+    /// The beacon is hashed 2^num_iterations_exp times (between 10 and 63)
+    pub num_iterations_exp: Option<u8>,
+}
+
+/// Cli arguments for `export-verifier`
+#[derive(Debug, Serialize, Args)]
+pub struct ExportVerifierCli {
+    /// The proof system to be used
+    #[arg(value_enum)]
+    pub proof_system: ProofSystem,
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The path to the verification key file (BN254 only)
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub vk: Option<PathBuf>,
+    /// The output file where the Solidity verifier contract is written to
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub out: Option<PathBuf>,
+}
+
+/// Config for `export-verifier`
+#[derive(Debug, Deserialize)]
+pub struct ExportVerifierConfig {
+    /// The proof system to be used
+    pub proof_system: ProofSystem,
+    /// The path to the verification key file (BN254 only)
+    pub vk: PathBuf,
+    /// The output file where the Solidity verifier contract is written to
+    pub out: PathBuf,
+}
+
+/// Cli arguments for `export-calldata`
+#[derive(Debug, Serialize, Args)]
+pub struct ExportCalldataCli {
+    /// The proof system to be used
+    #[arg(value_enum)]
+    pub proof_system: ProofSystem,
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The path to the proof file (BN254 only)
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub proof: Option<PathBuf>,
+    /// The path to the public input JSON file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub public_input: Option<PathBuf>,
+    /// The output file where the calldata is written to. If not set, it is printed to stdout
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub out: Option<PathBuf>,
+}
+
+/// Config for `export-calldata`
+#[derive(Debug, Deserialize)]
+pub struct ExportCalldataConfig {
+    /// The proof system to be used
+    pub proof_system: ProofSystem,
+    /// The path to the proof file (BN254 only)
+    pub proof: PathBuf,
     /// The path to the public input JSON file
     pub public_input: PathBuf,
+    /// The output file where the calldata is written to. If not set, it is printed to stdout
+    pub out: Option<PathBuf>,
+}
+
+/// Cli arguments for `aggregation-setup`
+#[derive(Debug, Default, Serialize, Args)]
+pub struct AggregationSetupCli {
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The pairing friendly curve to be used
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub curve: Option<MPCCurve>,
+    /// The paths to two powers of tau (.ptau) files of independent ceremonies. A ptau with power p allows to aggregate up to 2^(p-1) proofs
+    #[arg(long, num_args = 2)]
+    #[serde(skip_serializing_if = "::std::vec::Vec::is_empty")]
+    pub ptau: Vec<PathBuf>,
+    /// Creates an insecure SRS for up to this many proofs from local randomness instead of ptau files. Only use this for testing!
+    #[arg(long, conflicts_with = "ptau")]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub insecure_max_proofs: Option<usize>,
+    /// The output file where the aggregation SRS is written to
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub out: Option<PathBuf>,
+}
+
+/// Config for `aggregation-setup`
+#[derive(Debug, Deserialize)]
+pub struct AggregationSetupConfig {
+    /// The pairing friendly curve to be used
+    pub curve: MPCCurve,
+    /// The paths to two powers of tau (.ptau) files of independent ceremonies. A ptau with power p allows to aggregate up to 2^(p-1) proofs
+    #[serde(default)]
+    pub ptau: Vec<PathBuf>,
+    /// Creates an insecure SRS for up to this many proofs from local randomness instead of ptau files. Only use this for testing!
+    pub insecure_max_proofs: Option<usize>,
+    /// The output file where the aggregation SRS is written to
+    pub out: PathBuf,
+}
+
+/// Cli arguments for `aggregate-proofs`
+#[derive(Debug, Default, Serialize, Args)]
+pub struct AggregateProofsCli {
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The pairing friendly curve to be used
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub curve: Option<MPCCurve>,
+    /// The path to the aggregation SRS file, created by `aggregation-setup`
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub srs: Option<PathBuf>,
+    /// The path to the verification key file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub vk: Option<PathBuf>,
+    /// The path to a Groth16 proof file. Must be repeated for every proof, the number of proofs must be a power of two
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::vec::Vec::is_empty")]
+    pub proof: Vec<PathBuf>,
+    /// The path to the public input JSON file. Must be repeated, the n-th public input belongs to the n-th proof
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::vec::Vec::is_empty")]
+    pub public_input: Vec<PathBuf>,
+    /// A directory with the proofs to aggregate, where each `<name>.proof.json` belongs to the public input `<name>.public.json`. The number of proofs must be a power of two
+    #[arg(long, conflicts_with_all = ["proof", "public_input"])]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub batch_dir: Option<PathBuf>,
+    /// The output file where the aggregate proof is written to
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub out: Option<PathBuf>,
+}
+
+/// Config for `aggregate-proofs`
+#[derive(Debug, Deserialize)]
+pub struct AggregateProofsConfig {
+    /// The pairing friendly curve to be used
+    pub curve: MPCCurve,
+    /// The path to the aggregation SRS file, created by `aggregation-setup`
+    pub srs: PathBuf,
+    /// The path to the verification key file
+    pub vk: PathBuf,
+    /// The paths to the Groth16 proof files, the number of proofs must be a power of two
+    #[serde(default)]
+    pub proof: Vec<PathBuf>,
+    /// The paths to the public input JSON files, the n-th public input belongs to the n-th proof
+    #[serde(default)]
+    pub public_input: Vec<PathBuf>,
+    /// A directory with the proofs to aggregate, where each `<name>.proof.json` belongs to the public input `<name>.public.json`
+    pub batch_dir: Option<PathBuf>,
+    /// The output file where the aggregate proof is written to
+    pub out: PathBuf,
+}
+
+/// Cli arguments for `verify-aggregate`
+#[derive(Debug, Default, Serialize, Args)]
+pub struct VerifyAggregateCli {
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The pairing friendly curve to be used
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub curve: Option<MPCCurve>,
+    /// The path to the aggregation SRS file, created by `aggregation-setup`
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub srs: Option<PathBuf>,
+    /// The path to the verification key file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub vk: Option<PathBuf>,
+    /// The path to the aggregate proof file, created by `aggregate-proofs`
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub proof: Option<PathBuf>,
+    /// The path to the public input JSON file. Must be repeated in the same order as for `aggregate-proofs`
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::vec::Vec::is_empty")]
+    pub public_input: Vec<PathBuf>,
+    /// The directory that was passed to `aggregate-proofs`, the public inputs are taken from the `<name>.public.json` files of the proofs
+    #[arg(long, conflicts_with = "public_input")]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub batch_dir: Option<PathBuf>,
+}
+
+/// Config for `verify-aggregate`
+#[derive(Debug, Deserialize)]
+pub struct VerifyAggregateConfig {
+    /// The pairing friendly curve to be used
+    pub curve: MPCCurve,
+    /// The path to the aggregation SRS file, created by `aggregation-setup`
+    pub srs: PathBuf,
+    /// The path to the verification key file
+    pub vk: PathBuf,
+    /// The path to the aggregate proof file, created by `aggregate-proofs`
+    pub proof: PathBuf,
+    /// The paths to the public input JSON files in the same order as for `aggregate-proofs`
+    #[serde(default)]
+    pub public_input: Vec<PathBuf>,
+    /// The directory that was passed to `aggregate-proofs`, the public inputs are taken from the `<name>.public.json` files of the proofs
+    pub batch_dir: Option<PathBuf>,
+}
+
+/// Cli arguments for `network init`
+#[derive(Debug, Serialize, Args)]
+pub struct NetworkInitCli {
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The number of parties in the network
+    #[arg(long, default_value_t = 3)]
+    pub parties: usize,
+    /// The addresses (hostname:port, IPv6 addresses in brackets) of the parties, one per party in the order of their ids. If not passed, all parties run on 127.0.0.1 with the ports 10000, 10001, ...
+    #[arg(long)]
+    pub addresses: Vec<String>,
+    /// The directory where the certificates, keys and network configs are written to
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub out_dir: Option<PathBuf>,
+    /// The encoding of the certificates and keys
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub key_format: Option<KeyFormat>,
+}
+
+/// Config for `network init`
+#[derive(Debug, Deserialize)]
+pub struct NetworkInitConfig {
+    /// The number of parties in the network
+    pub parties: usize,
+    /// The addresses (hostname:port, IPv6 addresses in brackets) of the parties, one per party in the order of their ids. If empty, all parties run on 127.0.0.1 with the ports 10000, 10001, ...
+    #[serde(default)]
+    pub addresses: Vec<Address>,
+    /// The directory where the certificates, keys and network configs are written to
+    pub out_dir: PathBuf,
+    /// The encoding of the certificates and keys
+    #[serde(default)]
+    pub key_format: KeyFormat,
 }
 
 /// Prefix for config env variables
@@ -478,21 +1043,91 @@ impl_config!(SplitWitnessCli, SplitWitnessConfig);
 impl_config!(MergeInputSharesCli, MergeInputSharesConfig);
 impl_config!(GenerateWitnessCli, GenerateWitnessConfig);
 impl_config!(TranslateWitnessCli, TranslateWitnessConfig);
+impl_config!(CombineWitnessCli, CombineWitnessConfig);
+impl_config!(CombineInputCli, CombineInputConfig);
 impl_config!(GenerateProofCli, GenerateProofConfig);
 impl_config!(VerifyCli, VerifyConfig);
+impl_config!(SetupCli, SetupConfig);
+impl_config!(ContributeCli, ContributeConfig);
+impl_config!(ExportVerifierCli, ExportVerifierConfig);
+impl_config!(ExportCalldataCli, ExportCalldataConfig);
+impl_config!(AggregationSetupCli, AggregationSetupConfig);
+impl_config!(AggregateProofsCli, AggregateProofsConfig);
+impl_config!(VerifyAggregateCli, VerifyAggregateConfig);
+impl_config!(NetworkInitCli, NetworkInitConfig);
 
-/// Try to parse a [SharedWitness] from a [Read]er.
+/// Try to parse a [SharedWitness] and its [ShareMetadata] from a [Read]er.
 pub fn parse_witness_share<R: Read, P: Pairing, T: PrimeFieldMpcProtocol<P::ScalarField>>(
     reader: R,
-) -> color_eyre::Result<SharedWitness<T, P>> {
-    bincode::deserialize_from(reader).context("trying to parse witness share file")
+) -> color_eyre::Result<(ShareMetadata, SharedWitness<T, P>)> {
+    let (metadata, share) =
+        share_file::read_share(reader).context("trying to parse witness share file")?;
+    metadata
+        .check_kind(ShareKind::Witness)
+        .context("while validating witness share file")?;
+    Ok((metadata, share))
 }
 
-/// Try to parse a [SharedInput] from a [Read]er.
+/// Try to parse a [SharedInput] and its [ShareMetadata] from a [Read]er. Encrypted input shares
+/// are decrypted with the provided key.
 pub fn parse_shared_input<R: Read, P: Pairing, T: PrimeFieldMpcProtocol<P::ScalarField>>(
     reader: R,
-) -> color_eyre::Result<SharedInput<T, P>> {
-    bincode::deserialize_from(reader).context("trying to parse input share file")
+    key: Option<&ShareDecryptionKey>,
+) -> color_eyre::Result<(ShareMetadata, SharedInput<T, P>)> {
+    let (metadata, share) =
+        share_file::read_share_with_key(reader, key).context("trying to parse input share file")?;
+    metadata
+        .check_kind(ShareKind::Input)
+        .context("while validating input share file")?;
+    Ok((metadata, share))
+}
+
+/// Computes the SHA-256 hash of the circuit file at the provided path, which is stored in the
+/// [ShareMetadata] of input and witness shares.
+pub fn hash_circuit_file(circuit: &Path) -> color_eyre::Result<[u8; 32]> {
+    let circuit_file = BufReader::new(File::open(circuit).context("while opening circuit file")?);
+    share_file::hash_circuit(circuit_file).context("while hashing circuit file")
+}
+
+/// Computes the SHA-256 hash of the zkey file at the provided path, which is stored in the
+/// [ShareMetadata] of witness shares.
+pub fn hash_zkey_file(zkey: &Path) -> color_eyre::Result<[u8; 32]> {
+    let zkey_file = BufReader::new(File::open(zkey).context("while opening zkey file")?);
+    share_file::hash_circuit(zkey_file).context("while hashing zkey file")
+}
+
+/// Writes the [CommunicationStats] of a party as JSON to the provided path. The phases are written
+/// in the order they were started, followed by the totals over all phases.
+pub fn write_communication_stats(
+    path: &Path,
+    stats: &CommunicationStats,
+) -> color_eyre::Result<()> {
+    fn to_json(stats: &PhaseStats) -> serde_json::Value {
+        serde_json::json!({
+            "messages_sent": stats.messages_sent,
+            "messages_received": stats.messages_received,
+            "bytes_sent": stats.bytes_sent,
+            "bytes_received": stats.bytes_received,
+            "rounds": stats.rounds,
+        })
+    }
+    let phases = stats
+        .phases()
+        .iter()
+        .map(|(name, phase)| {
+            let mut json = to_json(phase);
+            json["phase"] = name.as_str().into();
+            json
+        })
+        .collect::<Vec<_>>();
+    let json = serde_json::json!({
+        "phases": phases,
+        "total": to_json(&stats.total()),
+    });
+    let file = BufWriter::new(File::create(path).context("while creating stats file")?);
+    serde_json::to_writer_pretty(file, &json).context("while writing stats file")?;
+    tracing::info!("Wrote communication statistics to {}", path.display());
+    Ok(())
 }
 
 /// Invoke the MPC witness generation process. It will return a [SharedWitness] if successful.
@@ -521,6 +1156,7 @@ where
     // connect to network
     let net = Rep3MpcNet::new(config.network).context("while connecting to network")?;
     let id = usize::from(net.get_id());
+    let stats = net.communication_stats();
 
     // init MPC protocol
     let rep3_vm = parsed_circom_circuit
@@ -534,6 +1170,9 @@ where
         .context("while running witness generation")?;
     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
     tracing::info!("Party {}: Witness extension took {} ms", id, duration_ms);
+    if let Some(stats_out) = config.stats {
+        write_communication_stats(&stats_out, &stats)?;
+    }
     Ok(result_witness_share.into_shared_witness())
 }
 
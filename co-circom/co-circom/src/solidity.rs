@@ -0,0 +1,462 @@
+//! Export of Solidity verifier contracts and the matching calldata, producing the same output as
+//! `snarkjs zkey export solidityverifier` and `snarkjs zkey export soliditycalldata`. The only
+//! difference are the points at infinity in verification keys, which snarkjs writes as (0, 1), but
+//! the EVM precompiles expect as (0, 0).
+//!
+//! The EVM only provides precompiles for BN254, therefore all functions in this module are
+//! restricted to this curve. Use [`read_bn254_json`] to read the verification keys and proofs, which
+//! rejects files of other curves.
+use std::{fmt::Write, io::Read};
+
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{FftField, PrimeField};
+use circom_types::{
+    groth16::{Groth16Proof, JsonVerificationKey as Groth16JsonVerificationKey},
+    plonk::{JsonVerificationKey as PlonkJsonVerificationKey, PlonkProof},
+};
+use num_bigint::BigUint;
+use serde::de::DeserializeOwned;
+
+const GROTH16_TEMPLATE: &str = include_str!("solidity/verifier_groth16.sol");
+const PLONK_TEMPLATE: &str = include_str!("solidity/verifier_plonk.sol");
+
+/// Error type for the Solidity export
+#[derive(Debug, thiserror::Error)]
+pub enum SolidityExportError {
+    /// The domain size of the verification key has no root of unity in the scalar field
+    #[error("invalid domain size 2^{0}")]
+    InvalidDomainSize(usize),
+    /// The verification key or proof is not over BN254
+    #[error("unsupported curve {0}, only bn128 is supported by the EVM")]
+    UnsupportedCurve(String),
+    /// The verification key or proof is not valid JSON of the expected type
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// The name of BN254 in the JSON files of snarkjs
+const BN254_NAME: &str = "bn128";
+
+/// Reads a verification key or proof in the JSON format of snarkjs, returning
+/// [`SolidityExportError::UnsupportedCurve`] if it is not over BN254.
+pub fn read_bn254_json<T: DeserializeOwned>(reader: impl Read) -> Result<T, SolidityExportError> {
+    let json: serde_json::Value = serde_json::from_reader(reader)?;
+    // the Groth16 verification key type has no curve field, so we check the raw JSON
+    match json.get("curve").and_then(serde_json::Value::as_str) {
+        Some(curve) if curve != BN254_NAME => {
+            Err(SolidityExportError::UnsupportedCurve(curve.to_owned()))
+        }
+        _ => Ok(serde_json::from_value(json)?),
+    }
+}
+
+fn fr_to_decimal(f: Fr) -> String {
+    BigUint::from(f.into_bigint()).to_string()
+}
+
+fn fq_to_decimal(f: Fq) -> String {
+    BigUint::from(f.into_bigint()).to_string()
+}
+
+// snarkjs pads every calldata element to 32 bytes and quotes it
+fn p256(f: BigUint) -> String {
+    format!("\"0x{f:0>64x}\"")
+}
+
+// The EVM precompiles encode the point at infinity as (0, 0). snarkjs writes (0, 1) instead, the
+// first two coordinates of its projective encoding, which the precompiles reject.
+fn g1_coordinates(p: &G1Affine) -> [String; 2] {
+    match p.xy() {
+        Some((x, y)) => [fq_to_decimal(*x), fq_to_decimal(*y)],
+        None => ["0".to_owned(), "0".to_owned()],
+    }
+}
+
+// Returns [[x.c0, x.c1], [y.c0, y.c1]], the same layout as the snarkjs JSON
+fn g2_coordinates(p: &G2Affine) -> [[String; 2]; 2] {
+    match p.xy() {
+        Some((x, y)) => [
+            [fq_to_decimal(x.c0), fq_to_decimal(x.c1)],
+            [fq_to_decimal(y.c0), fq_to_decimal(y.c1)],
+        ],
+        None => Default::default(),
+    }
+}
+
+fn g1_calldata(p: &G1Affine) -> [String; 2] {
+    match p.xy() {
+        Some((x, y)) => [p256(x.into_bigint().into()), p256(y.into_bigint().into())],
+        None => [p256(BigUint::default()), p256(BigUint::default())],
+    }
+}
+
+fn public_inputs_calldata(public_inputs: &[Fr]) -> String {
+    public_inputs
+        .iter()
+        .map(|p| p256(p.into_bigint().into()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Replaces all `{{key}}` placeholders of the template
+fn render<K: AsRef<str>>(template: &str, values: &[(K, String)]) -> String {
+    values
+        .iter()
+        .fold(template.to_owned(), |acc, (key, value)| {
+            acc.replace(&format!("{{{{{}}}}}", key.as_ref()), value)
+        })
+}
+
+/// Creates the Solidity source of a contract verifying Groth16 proofs for the given verification key.
+pub fn groth16_verifier(vk: &Groth16JsonVerificationKey<Bn254>) -> String {
+    let [alphax, alphay] = g1_coordinates(&vk.alpha_1);
+    let [[betax2, betax1], [betay2, betay1]] = g2_coordinates(&vk.beta_2);
+    let [[gammax2, gammax1], [gammay2, gammay1]] = g2_coordinates(&vk.gamma_2);
+    let [[deltax2, deltax1], [deltay2, deltay1]] = g2_coordinates(&vk.delta_2);
+
+    let mut ic_constants = String::new();
+    for (i, ic) in vk.ic.iter().enumerate() {
+        let [x, y] = g1_coordinates(ic);
+        writeln!(ic_constants, "    uint256 constant IC{i}x = {x};").expect("can write to string");
+        writeln!(ic_constants, "    uint256 constant IC{i}y = {y};").expect("can write to string");
+        ic_constants.push('\n');
+    }
+    let mut vk_x = String::new();
+    for i in 1..vk.ic.len() {
+        let offset = (i - 1) * 32;
+        writeln!(
+            vk_x,
+            "                g1_mulAccC(_pVk, IC{i}x, IC{i}y, calldataload(add(pubSignals, {offset})))"
+        )
+        .expect("can write to string");
+    }
+    let mut check_fields = String::new();
+    for i in 0..vk.ic.len() {
+        let offset = i * 32;
+        writeln!(
+            check_fields,
+            "            checkField(calldataload(add(_pubSignals, {offset})))"
+        )
+        .expect("can write to string");
+    }
+
+    render(
+        GROTH16_TEMPLATE,
+        &[
+            ("alphax", alphax),
+            ("alphay", alphay),
+            ("betax1", betax1),
+            ("betax2", betax2),
+            ("betay1", betay1),
+            ("betay2", betay2),
+            ("gammax1", gammax1),
+            ("gammax2", gammax2),
+            ("gammay1", gammay1),
+            ("gammay2", gammay2),
+            ("deltax1", deltax1),
+            ("deltax2", deltax2),
+            ("deltay1", deltay1),
+            ("deltay2", deltay2),
+            ("ic_constants", ic_constants.trim_end().to_owned()),
+            ("n_public", vk.ic.len().saturating_sub(1).to_string()),
+            ("vk_x", vk_x.trim_end().to_owned()),
+            ("check_fields", check_fields.trim_end().to_owned()),
+        ],
+    )
+}
+
+/// Creates the Solidity source of a contract verifying Plonk proofs for the given verification key.
+pub fn plonk_verifier(vk: &PlonkJsonVerificationKey<Bn254>) -> Result<String, SolidityExportError> {
+    let n = 1usize << vk.power;
+    let w1 =
+        Fr::get_root_of_unity(n as u64).ok_or(SolidityExportError::InvalidDomainSize(vk.power))?;
+    let n_lagrange = vk.n_public.max(1);
+
+    let mut values = vec![
+        ("w1".to_owned(), fr_to_decimal(w1)),
+        ("n".to_owned(), n.to_string()),
+        ("n_public".to_owned(), vk.n_public.to_string()),
+        ("n_lagrange".to_owned(), n_lagrange.to_string()),
+        ("k1".to_owned(), fr_to_decimal(vk.k1)),
+        ("k2".to_owned(), fr_to_decimal(vk.k2)),
+    ];
+    for (name, p) in [
+        ("Qm", &vk.qm),
+        ("Ql", &vk.ql),
+        ("Qr", &vk.qr),
+        ("Qo", &vk.qo),
+        ("Qc", &vk.qc),
+        ("S1", &vk.s1),
+        ("S2", &vk.s2),
+        ("S3", &vk.s3),
+    ] {
+        let [x, y] = g1_coordinates(p);
+        values.push((format!("{name}x"), x));
+        values.push((format!("{name}y"), y));
+    }
+    let [[x2x1, x2x2], [x2y1, x2y2]] = g2_coordinates(&vk.x2);
+    values.push(("X2x1".to_owned(), x2x1));
+    values.push(("X2x2".to_owned(), x2x2));
+    values.push(("X2y1".to_owned(), x2y1));
+    values.push(("X2y2".to_owned(), x2y2));
+
+    let mut lagrange_pointers = String::new();
+    for i in 1..=n_lagrange {
+        writeln!(
+            lagrange_pointers,
+            "    uint16 constant pEval_l{i} = {};",
+            768 + i * 32
+        )
+        .expect("can write to string");
+    }
+    values.push((
+        "lagrange_pointers".to_owned(),
+        lagrange_pointers.trim_end().to_owned(),
+    ));
+    values.push(("last_mem".to_owned(), (800 + 32 * n_lagrange).to_string()));
+
+    let mut transcript_public_inputs = String::new();
+    for i in 0..vk.n_public {
+        writeln!(
+            transcript_public_inputs,
+            "                mstore(add(mIn, {}), calldataload(add(pPublic, {})))",
+            512 + i * 32,
+            i * 32
+        )
+        .expect("can write to string");
+    }
+    values.push((
+        "transcript_public_inputs".to_owned(),
+        transcript_public_inputs.trim_end().to_owned(),
+    ));
+    let offset = 512 + vk.n_public * 32;
+    for (i, name) in [
+        "transcript_pa",
+        "transcript_pa_y",
+        "transcript_pb",
+        "transcript_pb_y",
+        "transcript_pc",
+        "transcript_pc_y",
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        values.push((name.to_owned(), (offset + i * 32).to_string()));
+    }
+    values.push((
+        "transcript_len".to_owned(),
+        (704 + 32 * vk.n_public).to_string(),
+    ));
+
+    let xin_squarings = "                aux:= mulmod(aux, aux, q)\n".repeat(vk.power);
+    values.push((
+        "xin_squarings".to_owned(),
+        xin_squarings.trim_end().to_owned(),
+    ));
+
+    // the denominators n * (xi - w^i) are inverted together with Z_H(xi) in one batch
+    let mut lagrange_denominators = String::new();
+    let mut lagrange_evaluations = String::new();
+    for i in 1..=n_lagrange {
+        writeln!(
+            lagrange_denominators,
+            "                mstore(add(pMem, pEval_l{i}), mulmod(n, mod(add(sub(mload(add(pMem, pXi)), w), q), q), q))"
+        )
+        .expect("can write to string");
+        writeln!(
+            lagrange_evaluations,
+            "                mstore(add(pMem, pEval_l{i}), mulmod(w, mulmod(mload(add(pMem, pEval_l{i})), zh, q), q))"
+        )
+        .expect("can write to string");
+        if i < n_lagrange {
+            let step = "                w := mulmod(w, w1, q)\n";
+            lagrange_denominators.push_str(step);
+            lagrange_evaluations.push_str(step);
+        }
+    }
+    values.push((
+        "lagrange_denominators".to_owned(),
+        lagrange_denominators.trim_end().to_owned(),
+    ));
+    values.push(("n_inverses".to_owned(), (n_lagrange + 1).to_string()));
+    values.push((
+        "lagrange_evaluations".to_owned(),
+        lagrange_evaluations.trim_end().to_owned(),
+    ));
+
+    let mut pi_terms = String::new();
+    for i in 0..vk.n_public {
+        writeln!(
+            pi_terms,
+            "                pl := mod(add(sub(pl, mulmod(mload(add(pMem, pEval_l{})), calldataload(add(pPub, {})), q)), q), q)",
+            i + 1,
+            i * 32
+        )
+        .expect("can write to string");
+    }
+    values.push(("pi_terms".to_owned(), pi_terms.trim_end().to_owned()));
+
+    Ok(render(PLONK_TEMPLATE, &values))
+}
+
+/// Creates the calldata for the `verifyProof` function of the Groth16 verifier contract.
+pub fn groth16_calldata(proof: &Groth16Proof<Bn254>, public_inputs: &[Fr]) -> String {
+    let [a0, a1] = g1_calldata(&proof.pi_a);
+    let [c0, c1] = g1_calldata(&proof.pi_c);
+    let [[b00, b01], [b10, b11]] = match proof.pi_b.xy() {
+        Some((x, y)) => [
+            [
+                p256(x.c0.into_bigint().into()),
+                p256(x.c1.into_bigint().into()),
+            ],
+            [
+                p256(y.c0.into_bigint().into()),
+                p256(y.c1.into_bigint().into()),
+            ],
+        ],
+        None => std::array::from_fn(|_| std::array::from_fn(|_| p256(BigUint::default()))),
+    };
+    let inputs = public_inputs_calldata(public_inputs);
+    format!("[{a0}, {a1}],[[{b01}, {b00}],[{b11}, {b10}]],[{c0}, {c1}],[{inputs}]")
+}
+
+/// Creates the calldata for the `verifyProof` function of the Plonk verifier contract.
+pub fn plonk_calldata(proof: &PlonkProof<Bn254>, public_inputs: &[Fr]) -> String {
+    let mut elements = Vec::with_capacity(24);
+    for p in [
+        &proof.a,
+        &proof.b,
+        &proof.c,
+        &proof.z,
+        &proof.t1,
+        &proof.t2,
+        &proof.t3,
+        &proof.wxi,
+        &proof.wxiw,
+    ] {
+        elements.extend(g1_calldata(p));
+    }
+    for eval in [
+        proof.eval_a,
+        proof.eval_b,
+        proof.eval_c,
+        proof.eval_s1,
+        proof.eval_s2,
+        proof.eval_zw,
+    ] {
+        elements.push(p256(eval.into_bigint().into()));
+    }
+    let inputs = public_inputs_calldata(public_inputs);
+    format!("[{}, {}],[{inputs}]", elements[0], elements[1..].join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, str::FromStr};
+
+    use super::*;
+
+    const GROTH16: &str = "../../test_vectors/Groth16/bn254/multiplier2";
+    const PLONK: &str = "../../test_vectors/Plonk/bn254/multiplier2";
+
+    fn read<T: DeserializeOwned>(path: String) -> Result<T, SolidityExportError> {
+        read_bn254_json(File::open(path).unwrap())
+    }
+
+    fn public_inputs(dir: &str) -> Vec<Fr> {
+        let inputs: Vec<String> = read(format!("{dir}/public.json")).unwrap();
+        inputs
+            .iter()
+            .map(|input| Fr::from_str(input).unwrap())
+            .collect()
+    }
+
+    // The golden files must be the unmodified output of `snarkjs zkey export solidityverifier` and
+    // `snarkjs zkey export soliditycalldata` for the test vectors, created by `generate_solidity.sh`.
+    // snarkjs renders its templates with EJS, which leaves whitespace-only lines, so we ignore
+    // these lines and compare all others exactly.
+    fn assert_golden(actual: &str, golden: &str) {
+        let lines = |s: &str| {
+            s.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines(actual), lines(golden));
+    }
+
+    fn read_golden(path: String) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn groth16_verifier_matches_snarkjs() {
+        let vk: Groth16JsonVerificationKey<Bn254> =
+            read(format!("{GROTH16}/verification_key.json")).unwrap();
+        let golden = read_golden(format!("{GROTH16}/verifier.sol"));
+        assert_golden(&groth16_verifier(&vk), &golden);
+    }
+
+    #[test]
+    fn groth16_calldata_matches_snarkjs() {
+        let proof: Groth16Proof<Bn254> = read(format!("{GROTH16}/circom.proof")).unwrap();
+        let calldata = groth16_calldata(&proof, &public_inputs(GROTH16));
+        assert_golden(&calldata, &read_golden(format!("{GROTH16}/calldata.txt")));
+    }
+
+    #[test]
+    fn plonk_verifier_matches_snarkjs() {
+        let vk: PlonkJsonVerificationKey<Bn254> =
+            read(format!("{PLONK}/verification_key.json")).unwrap();
+        let verifier = plonk_verifier(&vk).unwrap();
+        let mut golden = read_golden(format!("{PLONK}/verifier.sol"));
+        // Qr and Qc of the test vectors are the point at infinity, which snarkjs writes as (0, 1)
+        // and we write as (0, 0), the encoding of the EVM precompiles
+        assert!(vk.qr.is_zero() && vk.qc.is_zero());
+        for point in ["Qr", "Qc"] {
+            let snarkjs =
+                format!("uint256 constant {point}x = 0;\n    uint256 constant {point}y = 1;");
+            let ours =
+                format!("uint256 constant {point}x = 0;\n    uint256 constant {point}y = 0;");
+            assert_eq!(golden.matches(&snarkjs).count(), 1, "{point} in snarkjs");
+            assert_eq!(
+                verifier.matches(&ours).count(),
+                1,
+                "{point} in our verifier"
+            );
+            golden = golden.replace(&snarkjs, &ours);
+        }
+        assert_golden(&verifier, &golden);
+    }
+
+    #[test]
+    fn plonk_calldata_matches_snarkjs() {
+        let proof: PlonkProof<Bn254> = read(format!("{PLONK}/circom.proof")).unwrap();
+        let calldata = plonk_calldata(&proof, &public_inputs(PLONK));
+        assert_golden(&calldata, &read_golden(format!("{PLONK}/calldata.txt")));
+    }
+
+    fn assert_unsupported_curve<T: DeserializeOwned + std::fmt::Debug>(path: &str) {
+        let err = read::<T>(format!("../../test_vectors/{path}")).unwrap_err();
+        assert!(
+            matches!(&err, SolidityExportError::UnsupportedCurve(curve) if curve == "bls12381"),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn rejects_non_bn254_curves() {
+        // the BN254 types are used on purpose, the curve is checked before deserializing
+        assert_unsupported_curve::<Groth16JsonVerificationKey<Bn254>>(
+            "Groth16/bls12_381/multiplier2/verification_key.json",
+        );
+        assert_unsupported_curve::<Groth16Proof<Bn254>>(
+            "Groth16/bls12_381/multiplier2/circom.proof",
+        );
+        assert_unsupported_curve::<PlonkJsonVerificationKey<Bn254>>(
+            "Plonk/bls12_381/multiplier2/verification_key.json",
+        );
+        assert_unsupported_curve::<PlonkProof<Bn254>>("Plonk/bls12_381/multiplier2/circom.proof");
+    }
+}
@@ -0,0 +1,188 @@
+//! This crate defines the plain [Fflonk] type. Plain in this context means without MPC. For the
+//! co-FFLONK prover, see [CoFflonk].
+//!
+//! You will most likely need the plain FFLONK implementation to verify a proof from co-FFLONK. For that
+//! see the [`Fflonk::verify`] method.
+
+use crate::{
+    fflonk_utils::{self, OpeningValues},
+    types::{Domains, Keccak256Transcript, Roots},
+    CoFflonk,
+};
+use ark_ec::{pairing::Pairing, Group};
+use ark_ff::Field;
+use circom_types::{
+    fflonk::{FflonkProof, JsonVerificationKey},
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+use mpc_core::protocols::plain::PlainDriver;
+use num_traits::One;
+
+/// The plain [`Fflonk`] type.
+///
+/// This type is actually the [`CoFflonk`] type initialized with
+/// the [`PlainDriver`], a single party (you) MPC protocol (i.e., your everyday FFLONK).
+/// You can use this instance to create a proof, but we recommend against it for a real use-case.
+/// The co-FFLONK prover uses some MPC optimizations (for the product check), which are not optimal
+/// for a plain run.
+///
+/// More interesting is the [`Fflonk::verify`] method. You can verify any circom FFLONK proof, be it
+/// from snarkjs or one created by this project.
+pub type Fflonk<P> = CoFflonk<PlainDriver<<P as Pairing>::ScalarField>, P>;
+
+pub(crate) struct VerifierChallenges<P: Pairing> {
+    pub(super) beta: P::ScalarField,
+    pub(super) gamma: P::ScalarField,
+    pub(super) roots: Roots<P::ScalarField>,
+    pub(super) alpha: P::ScalarField,
+    pub(super) y: P::ScalarField,
+}
+
+impl<P: Pairing> VerifierChallenges<P>
+where
+    P: CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    pub(super) fn new(
+        vk: &JsonVerificationKey<P>,
+        proof: &FflonkProof<P>,
+        public_inputs: &[P::ScalarField],
+    ) -> Self {
+        let polys = &proof.polynomials;
+        let evals = &proof.evaluations;
+        let mut transcript = Keccak256Transcript::<P>::default();
+
+        // Challenge round 2: beta and gamma
+        transcript.add_point(vk.c0);
+        for p in public_inputs.iter().cloned() {
+            transcript.add_scalar(p);
+        }
+        transcript.add_point(polys.c1);
+        let beta = transcript.get_challenge();
+
+        let mut transcript = Keccak256Transcript::<P>::default();
+        transcript.add_scalar(beta);
+        let gamma = transcript.get_challenge();
+
+        // Challenge round 3: xi
+        let mut transcript = Keccak256Transcript::<P>::default();
+        transcript.add_scalar(gamma);
+        transcript.add_point(polys.c2);
+        let xi_seed = transcript.get_challenge();
+        let roots = fflonk_utils::calculate_roots(xi_seed, vk.w3, vk.w4, vk.w8, vk.wr);
+
+        // Challenge round 4: alpha
+        let mut transcript = Keccak256Transcript::<P>::default();
+        transcript.add_scalar(xi_seed);
+        transcript.add_scalar(evals.ql);
+        transcript.add_scalar(evals.qr);
+        transcript.add_scalar(evals.qm);
+        transcript.add_scalar(evals.qo);
+        transcript.add_scalar(evals.qc);
+        transcript.add_scalar(evals.s1);
+        transcript.add_scalar(evals.s2);
+        transcript.add_scalar(evals.s3);
+        transcript.add_scalar(evals.a);
+        transcript.add_scalar(evals.b);
+        transcript.add_scalar(evals.c);
+        transcript.add_scalar(evals.z);
+        transcript.add_scalar(evals.zw);
+        transcript.add_scalar(evals.t1w);
+        transcript.add_scalar(evals.t2w);
+        let alpha = transcript.get_challenge();
+
+        // Challenge round 5: y
+        let mut transcript = Keccak256Transcript::<P>::default();
+        transcript.add_scalar(alpha);
+        transcript.add_point(polys.w1);
+        let y = transcript.get_challenge();
+        Self {
+            beta,
+            gamma,
+            roots,
+            alpha,
+            y,
+        }
+    }
+}
+
+impl<P: Pairing> Fflonk<P>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P: Pairing + CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    /// Verifies a circom FFLONK proof. The method uses the same interface as snarkjs and it can verify
+    /// proofs generated by snarkjs and by this project.
+    pub fn verify(
+        vk: &JsonVerificationKey<P>,
+        proof: &FflonkProof<P>,
+        public_inputs: &[P::ScalarField],
+    ) -> Result<bool, eyre::Report> {
+        if vk.n_public != public_inputs.len() {
+            return Err(eyre::eyre!("Invalid number of public inputs"));
+        }
+
+        let challenges = VerifierChallenges::<P>::new(vk, proof, public_inputs);
+        let domains = Domains::<P::ScalarField>::new(1 << vk.power)?;
+        let root_of_unity = domains.root_of_unity_pow;
+        let xi = challenges.roots.xi;
+        let xiw = xi * root_of_unity;
+        let y = challenges.y;
+
+        let (l, zh) =
+            fflonk_utils::calculate_lagrange_evaluations(vk.power, vk.n_public, xi, root_of_unity);
+
+        // The proof contains the inverse of the product of all denominators
+        let denominators = fflonk_utils::denominator_product(
+            &challenges.roots,
+            y,
+            xiw,
+            zh,
+            vk.power,
+            vk.n_public,
+            root_of_unity,
+        );
+        if denominators * proof.evaluations.inv != P::ScalarField::one() {
+            tracing::debug!("invalid inverse in proof");
+            return Ok(false);
+        }
+
+        let pi = fflonk_utils::calculate_pi(public_inputs, &l);
+        let remainders = fflonk_utils::calculate_remainders(
+            &proof.evaluations,
+            &OpeningValues {
+                xi,
+                xiw,
+                beta: challenges.beta,
+                gamma: challenges.gamma,
+                k1: vk.k1,
+                k2: vk.k2,
+                pi,
+                l1: l[0],
+                zh,
+            },
+        );
+        let r0 = fflonk_utils::evaluate(&remainders.r0, y);
+        let r1 = fflonk_utils::evaluate(&remainders.r1, y);
+        let r2 = fflonk_utils::evaluate(&remainders.r2, y);
+
+        let alpha = challenges.alpha;
+        let mul_h0 = y.pow([8]) - xi;
+        let mul_h1 = y.pow([4]) - xi;
+        let mul_h2 = (y.pow([3]) - xi) * (y.pow([3]) - xiw);
+        let q1 = alpha * mul_h0 * mul_h1.inverse().expect("Highly unlikely to be zero");
+        let q2 = alpha.square() * mul_h0 * mul_h2.inverse().expect("Highly unlikely to be zero");
+
+        let polys = &proof.polynomials;
+        let f = polys.c1 * q1 + polys.c2 * q2 + vk.c0;
+        let e = P::G1::generator() * (r0 + q1 * r1 + q2 * r2);
+        let j = polys.w1 * mul_h0;
+        let b1 = f - e - j + polys.w2 * y;
+
+        let lhs = P::pairing(polys.w2, vk.x2);
+        let rhs = P::pairing(b1, P::G2::generator());
+        Ok(lhs == rhs)
+    }
+}
@@ -0,0 +1,519 @@
+//! A FFLONK proof protocol that uses a collaborative MPC protocol to generate the proof.
+
+#![warn(missing_docs)]
+use ark_ec::pairing::Pairing;
+use circom_types::fflonk::FflonkProof;
+use circom_types::fflonk::ZKey;
+use circom_types::traits::CircomArkworksPairingBridge;
+use circom_types::traits::CircomArkworksPrimeFieldBridge;
+use co_circom_snarks::SharedWitness;
+use mpc_core::traits::{FFTProvider, MSMProvider, PairingEcMpcProtocol, PrimeFieldMpcProtocol};
+use round1::Round1;
+use std::io;
+use std::marker::PhantomData;
+
+mod fflonk;
+mod round1;
+mod round2;
+mod round3;
+mod round4;
+mod round5;
+pub(crate) mod types;
+
+pub use fflonk::Fflonk;
+
+type FieldShare<T, P> = <T as PrimeFieldMpcProtocol<<P as Pairing>::ScalarField>>::FieldShare;
+type FieldShareVec<T, P> = <T as PrimeFieldMpcProtocol<<P as Pairing>::ScalarField>>::FieldShareVec;
+
+type FflonkProofResult<T> = std::result::Result<T, FflonkProofError>;
+
+/// The errors that may arise during the computation of a co-FFLONK proof.
+#[derive(Debug, thiserror::Error)]
+pub enum FflonkProofError {
+    /// Invalid domain size
+    #[error("Invalid domain size {0}. Must be power of two")]
+    InvalidDomainSize(usize),
+    /// Indicates that the witness is too small for the provided circuit.
+    #[error("Cannot index into witness {0}")]
+    CorruptedWitness(usize),
+    /// Indicates that the domain size from the zkey is corrupted.
+    #[error("Cannot create domain, Polynomial degree too large")]
+    PolynomialDegreeTooLarge,
+    /// An [io::Error]. Communication to another party failed.
+    #[error(transparent)]
+    IOError(#[from] io::Error),
+}
+
+/// A FFLONK proof protocol that uses a collaborative MPC protocol to generate the proof.
+pub struct CoFflonk<T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+{
+    pub(crate) driver: T,
+    phantom_data: PhantomData<P>,
+}
+
+impl<T, P> CoFflonk<T, P>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P: Pairing + CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    /// Creates a new [CoFflonk] protocol with a given MPC driver.
+    pub fn new(driver: T) -> Self {
+        Self {
+            driver,
+            phantom_data: PhantomData,
+        }
+    }
+
+    /// Execute the FFLONK prover using the internal MPC driver.
+    pub fn prove(
+        self,
+        zkey: &ZKey<P>,
+        witness: SharedWitness<T, P>,
+    ) -> FflonkProofResult<FflonkProof<P>> {
+        tracing::debug!("starting FFLONK prove..");
+        let state = Round1::init_round(self.driver, zkey, witness)?;
+        tracing::debug!("init round done..");
+        let state = state.round1()?;
+        tracing::debug!("round 1 done..");
+        let state = state.round2()?;
+        tracing::debug!("round 2 done..");
+        let state = state.round3()?;
+        tracing::debug!("round 3 done..");
+        let state = state.round4()?;
+        tracing::debug!("round 4 done..");
+        let result = state.round5();
+        tracing::debug!("round 5 done! We are done!");
+        result
+    }
+}
+
+mod fflonk_utils {
+    use ark_ec::pairing::Pairing;
+    use ark_ff::Field;
+    use circom_types::fflonk::{FflonkEvaluations, ZKey};
+    use circom_types::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
+    use mpc_core::traits::{FieldShareVecTrait, PrimeFieldMpcProtocol};
+    use num_traits::One;
+    use num_traits::Zero;
+
+    use crate::types::{FflonkWitness, Remainders, Roots};
+    use crate::{FflonkProofError, FflonkProofResult, FieldShare, FieldShareVec};
+
+    pub(crate) fn get_witness<T, P: Pairing>(
+        driver: &mut T,
+        witness: &FflonkWitness<T, P>,
+        zkey: &ZKey<P>,
+        index: usize,
+    ) -> FflonkProofResult<FieldShare<T, P>>
+    where
+        T: PrimeFieldMpcProtocol<P::ScalarField>,
+    {
+        tracing::trace!("get witness on {index}");
+        let result = if index <= zkey.n_public {
+            tracing::trace!("indexing public input!");
+            driver.promote_to_trivial_share(witness.public_inputs[index])
+        } else if index < zkey.n_vars - zkey.n_additions {
+            tracing::trace!("indexing private input!");
+            witness.witness.index(index - zkey.n_public - 1)
+        } else if index < zkey.n_vars {
+            tracing::trace!("indexing additions!");
+            witness.addition_witness[index + zkey.n_additions - zkey.n_vars].to_owned()
+        } else {
+            tracing::trace!("something is broken!");
+            return Err(FflonkProofError::CorruptedWitness(index));
+        };
+        Ok(result)
+    }
+
+    // Adds (coeff[0] + coeff[1] * X + ...) * (X^n - 1) to the polynomial. For convenience coeff is
+    // given in reverse order
+    pub(crate) fn blind_coefficients<T, P: Pairing>(
+        driver: &mut T,
+        poly: &FieldShareVec<T, P>,
+        coeff_rev: &[FieldShare<T, P>],
+    ) -> Vec<FieldShare<T, P>>
+    where
+        T: PrimeFieldMpcProtocol<P::ScalarField>,
+    {
+        let mut res = poly.clone().into_iter().collect::<Vec<_>>();
+        for (p, c) in res.iter_mut().zip(coeff_rev.iter().rev()) {
+            *p = driver.sub(p, c);
+        }
+        // Extend
+        res.reserve(coeff_rev.len());
+        for c in coeff_rev.iter().rev().cloned() {
+            res.push(c);
+        }
+        res
+    }
+
+    // Divides the polynomial by X^n - beta. The polynomial must be divisible, the remainder is dropped.
+    pub(crate) fn div_by_zerofier<T, P: Pairing>(
+        driver: &mut T,
+        inout: &mut Vec<FieldShare<T, P>>,
+        n: usize,
+        beta: P::ScalarField,
+    ) where
+        T: PrimeFieldMpcProtocol<P::ScalarField>,
+    {
+        let inv_beta = beta.inverse().expect("Highly unlikely to be zero");
+        let inv_beta_neg = -inv_beta;
+
+        for el in inout.iter_mut().take(n) {
+            *el = driver.mul_with_public(&inv_beta_neg, el);
+        }
+        for i in n..inout.len() {
+            let element = driver.sub(&inout[i - n], &inout[i]);
+            inout[i] = driver.mul_with_public(&inv_beta, &element);
+        }
+        // We cannot check whether the polyonmial is divisible by the zerofier, but we resize accordingly
+        inout.resize(inout.len() - n, FieldShare::<T, P>::default());
+    }
+
+    // Same as div_by_zerofier for public polynomials
+    pub(crate) fn div_by_zerofier_public<F: Field>(inout: &mut Vec<F>, n: usize, beta: F) {
+        let inv_beta = beta.inverse().expect("Highly unlikely to be zero");
+        for el in inout.iter_mut().take(n) {
+            *el *= -inv_beta;
+        }
+        for i in n..inout.len() {
+            inout[i] = (inout[i - n] - inout[i]) * inv_beta;
+        }
+        inout.resize(inout.len() - n, F::zero());
+    }
+
+    pub(crate) fn evaluate<F: Field>(coeffs: &[F], x: F) -> F {
+        coeffs
+            .iter()
+            .rev()
+            .fold(F::zero(), |acc, coeff| acc * x + coeff)
+    }
+
+    // The roots of the opening sets from the challenge xi_seed. The evaluation point is xi = xi_seed^24.
+    pub(crate) fn calculate_roots<F: Field>(xi_seed: F, w3: F, w4: F, w8: F, wr: F) -> Roots<F> {
+        let xi_seed2 = xi_seed.square();
+        let h0 = xi_seed2 * xi_seed;
+        let h1 = h0.square();
+        let h2 = h1 * xi_seed2;
+        let h3 = h2 * wr;
+        let coset = |h: F, w: F, out: &mut [F]| {
+            let mut x = h;
+            for el in out.iter_mut() {
+                *el = x;
+                x *= w;
+            }
+        };
+        let mut roots = Roots {
+            h0w8: [F::zero(); 8],
+            h1w4: [F::zero(); 4],
+            h2w3: [F::zero(); 3],
+            h3w3: [F::zero(); 3],
+            xi: h2.square() * h2,
+        };
+        coset(h0, w8, &mut roots.h0w8);
+        coset(h1, w4, &mut roots.h1w4);
+        coset(h2, w3, &mut roots.h2w3);
+        coset(h3, w3, &mut roots.h3w3);
+        roots
+    }
+
+    // Returns the evaluations of the first max(1, n_public) Lagrange polynomials at xi and Z_H(xi)
+    pub(crate) fn calculate_lagrange_evaluations<F: Field>(
+        power: usize,
+        n_public: usize,
+        xi: F,
+        root_of_unity: F,
+    ) -> (Vec<F>, F) {
+        let mut xin = xi;
+        let mut domain_size = 1;
+        for _ in 0..power {
+            xin.square_in_place();
+            domain_size *= 2;
+        }
+        let zh = xin - F::one();
+        let l_length = usize::max(1, n_public);
+        let mut l = Vec::with_capacity(l_length);
+
+        let n = F::from(domain_size as u64);
+        let mut w = F::one();
+        for _ in 0..l_length {
+            l.push((w * zh) / (n * (xi - w)));
+            w *= root_of_unity;
+        }
+        (l, zh)
+    }
+
+    pub(crate) fn calculate_pi<F: Field>(public_inputs: &[F], l: &[F]) -> F {
+        let mut pi = F::zero();
+        for (val, l) in public_inputs.iter().zip(l) {
+            pi -= *l * val;
+        }
+        pi
+    }
+
+    /// The public values from which the verifier reconstructs the remainders r0, r1 and r2.
+    pub(crate) struct OpeningValues<F> {
+        pub(crate) xi: F,
+        pub(crate) xiw: F,
+        pub(crate) beta: F,
+        pub(crate) gamma: F,
+        pub(crate) k1: F,
+        pub(crate) k2: F,
+        pub(crate) pi: F,
+        pub(crate) l1: F,
+        pub(crate) zh: F,
+    }
+
+    // The coefficients of r0(X) = C0(X) mod (X^8 - xi), r1(X) = C1(X) mod (X^4 - xi) and
+    // r2(X) = C2(X) mod ((X^3 - xi) (X^3 - xi * w)), which follow from the evaluations
+    pub(crate) fn calculate_remainders<P: Pairing + CircomArkworksPairingBridge>(
+        evals: &FflonkEvaluations<P>,
+        values: &OpeningValues<P::ScalarField>,
+    ) -> Remainders<P::ScalarField>
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let OpeningValues {
+            xi,
+            xiw,
+            beta,
+            gamma,
+            k1,
+            k2,
+            pi,
+            l1,
+            zh,
+        } = *values;
+        let zh_inv = zh.inverse().expect("Highly unlikely to be zero");
+        let r0 = [
+            evals.ql, evals.qr, evals.qo, evals.qm, evals.qc, evals.s1, evals.s2, evals.s3,
+        ];
+
+        // T0(xi) = (ql * a + qr * b + qm * a * b + qo * c + qc + PI) / Z_H(xi)
+        let t0 = (evals.ql * evals.a
+            + evals.qr * evals.b
+            + evals.qm * evals.a * evals.b
+            + evals.qo * evals.c
+            + evals.qc
+            + pi)
+            * zh_inv;
+        let r1 = [evals.a, evals.b, evals.c, t0];
+
+        // T1(xi) = (z - 1) * L1 / Z_H(xi)
+        let t1 = (evals.z - P::ScalarField::one()) * l1 * zh_inv;
+        // T2(xi) = (z * (a + beta * xi + gamma)(b + beta * k1 * xi + gamma)(c + beta * k2 * xi + gamma)
+        //  - zw * (a + beta * s1 + gamma)(b + beta * s2 + gamma)(c + beta * s3 + gamma)) / Z_H(xi)
+        let betaxi = beta * xi;
+        let num = (evals.a + betaxi + gamma)
+            * (evals.b + betaxi * k1 + gamma)
+            * (evals.c + betaxi * k2 + gamma)
+            * evals.z;
+        let den = (evals.a + beta * evals.s1 + gamma)
+            * (evals.b + beta * evals.s2 + gamma)
+            * (evals.c + beta * evals.s3 + gamma)
+            * evals.zw;
+        let t2 = (num - den) * zh_inv;
+
+        // r2 interpolates p1 = z + X t1 + X^2 t2 on the roots of X^3 - xi and
+        // p2 = zw + X t1w + X^2 t2w on the roots of X^3 - xi * w:
+        // r2 = (p1 (X^3 - xi * w) - p2 (X^3 - xi)) / (xi - xi * w)
+        let inv = (xi - xiw).inverse().expect("Highly unlikely to be zero");
+        let p1 = [evals.z, t1, t2];
+        let p2 = [evals.zw, evals.t1w, evals.t2w];
+        let mut r2 = [P::ScalarField::zero(); 6];
+        for i in 0..3 {
+            r2[i] = (xi * p2[i] - xiw * p1[i]) * inv;
+            r2[i + 3] = (p1[i] - p2[i]) * inv;
+        }
+        Remainders { r0, r1, r2 }
+    }
+
+    // The product of all denominators the verifier of snarkjs needs to invert. The proof contains
+    // the inverse of this product.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn denominator_product<F: Field>(
+        roots: &Roots<F>,
+        y: F,
+        xiw: F,
+        zh: F,
+        power: usize,
+        n_public: usize,
+        root_of_unity: F,
+    ) -> F {
+        let xi = roots.xi;
+        let mut acc = zh;
+        // Z_T1(y) and Z_T2(y)
+        acc *= roots.h1w4.iter().map(|h| y - h).product::<F>();
+        acc *= roots
+            .h2w3
+            .iter()
+            .chain(roots.h3w3.iter())
+            .map(|h| y - h)
+            .product::<F>();
+        // the denominators of the Lagrange polynomials of the opening sets
+        for h in roots.h0w8.iter() {
+            acc *= F::from(8u64) * h.pow([7]) * (y - h);
+        }
+        for h in roots.h1w4.iter() {
+            acc *= F::from(4u64) * h.pow([3]) * (y - h);
+        }
+        for h in roots.h2w3.iter() {
+            acc *= F::from(3u64) * h.square() * (xi - xiw) * (y - h);
+        }
+        for h in roots.h3w3.iter() {
+            acc *= F::from(3u64) * h.square() * (xiw - xi) * (y - h);
+        }
+        // the denominators of the Lagrange polynomials of the public inputs
+        let n = F::from(1u64 << power);
+        let mut w = F::one();
+        for _ in 0..usize::max(1, n_public) {
+            acc *= n * (xi - w);
+            w *= root_of_unity;
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use ark_ec::pairing::Pairing;
+    use ark_ec::scalar_mul::fixed_base::FixedBase;
+    use ark_ec::{AffineRepr, CurveGroup, Group};
+    use ark_ff::{Field, PrimeField};
+    use circom_types::fflonk::{FflonkProof, JsonVerificationKey, ZKey};
+    use circom_types::groth16::JsonPublicInput;
+    use circom_types::ptau::PowersOfTau;
+    use circom_types::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
+    use circom_types::{Witness, R1CS};
+    use co_circom_snarks::SharedWitness;
+    use mpc_core::protocols::plain::PlainDriver;
+    use std::fs::File;
+
+    use crate::Fflonk;
+
+    // A ptau of the given power for the known secret tau. It only contains the first two powers in G2,
+    // which is enough to run the FFLONK setup.
+    fn ptau_from_tau<P: Pairing>(power: usize, tau: P::ScalarField) -> PowersOfTau<P> {
+        let powers = (0..(1 << (power + 1)) - 1)
+            .scan(P::ScalarField::ONE, |acc, _| {
+                let current = *acc;
+                *acc *= tau;
+                Some(current)
+            })
+            .collect::<Vec<_>>();
+        let scalar_size = P::ScalarField::MODULUS_BIT_SIZE as usize;
+        let window = FixedBase::get_mul_window_size(powers.len());
+        let table = FixedBase::get_window_table(scalar_size, window, P::G1::generator());
+        let tau_g1 = FixedBase::msm::<P::G1>(scalar_size, window, &table, &powers);
+        let g2 = P::G2Affine::generator();
+        PowersOfTau {
+            power,
+            ceremony_power: power,
+            tau_g1: P::G1::normalize_batch(&tau_g1),
+            tau_g2: vec![g2, (g2 * tau).into_affine()],
+            alpha_tau_g1: vec![],
+            beta_tau_g1: vec![],
+            beta_g2: g2,
+            contributions: vec![],
+            lagrange: None,
+        }
+    }
+
+    fn prove_and_verify<P: Pairing + CircomArkworksPairingBridge>(dir: &str, ptau_power: usize)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let r1cs =
+            R1CS::<P>::from_reader(File::open(format!("{dir}/circuit.r1cs")).unwrap()).unwrap();
+        let ptau = ptau_from_tau::<P>(ptau_power, P::ScalarField::from(42u64));
+        let zkey = ZKey::<P>::from_r1cs_and_ptau(&r1cs, &ptau).unwrap();
+        let vk = JsonVerificationKey::from(&zkey);
+        let witness = Witness::<P::ScalarField>::from_reader(
+            File::open(format!("{dir}/witness.wtns")).unwrap(),
+        )
+        .unwrap();
+        let public_inputs = witness.values[1..=zkey.n_public].to_vec();
+        let witness = SharedWitness::<PlainDriver<P::ScalarField>, P> {
+            public_inputs: witness.values[..=zkey.n_public].to_vec(),
+            witness: witness.values[zkey.n_public + 1..].to_vec(),
+        };
+
+        let fflonk = Fflonk::<P>::new(PlainDriver::default());
+        let proof = fflonk.prove(&zkey, witness).unwrap();
+
+        let mut proof_bytes = vec![];
+        serde_json::to_writer(&mut proof_bytes, &proof).unwrap();
+        let proof: FflonkProof<P> = serde_json::from_reader(proof_bytes.as_slice()).unwrap();
+        assert!(Fflonk::<P>::verify(&vk, &proof, &public_inputs).unwrap());
+
+        let mut wrong_inputs = public_inputs.clone();
+        wrong_inputs[0] += P::ScalarField::ONE;
+        assert!(!Fflonk::<P>::verify(&vk, &proof, &wrong_inputs).unwrap());
+    }
+
+    #[test]
+    fn test_multiplier2_bn254() {
+        prove_and_verify::<Bn254>("../../test_vectors/Plonk/bn254/multiplier2", 6);
+    }
+
+    #[test]
+    fn test_multiplier2_bls12_381() {
+        prove_and_verify::<Bls12_381>("../../test_vectors/Plonk/bls12_381/multiplier2", 6);
+    }
+
+    #[test]
+    fn test_poseidon_bn254() {
+        prove_and_verify::<Bn254>("../../test_vectors/Plonk/bn254/poseidon", 15);
+    }
+
+    #[test]
+    #[ignore = "needs the snarkjs vectors created by test_vectors/Fflonk/bn254/multiplier2/generate.sh"]
+    fn test_snarkjs_multiplier2_bn254() {
+        let dir = "../../test_vectors/Fflonk/bn254/multiplier2";
+        let zkey =
+            ZKey::<Bn254>::from_reader(File::open(format!("{dir}/circuit.zkey")).unwrap()).unwrap();
+        let vk: JsonVerificationKey<Bn254> =
+            serde_json::from_reader(File::open(format!("{dir}/verification_key.json")).unwrap())
+                .unwrap();
+        assert_eq!(JsonVerificationKey::from(&zkey), vk);
+        let snarkjs_proof: FflonkProof<Bn254> =
+            serde_json::from_reader(File::open(format!("{dir}/circom.proof")).unwrap()).unwrap();
+        let public_inputs: JsonPublicInput<ark_bn254::Fr> =
+            serde_json::from_reader(File::open(format!("{dir}/public.json")).unwrap()).unwrap();
+        assert!(Fflonk::<Bn254>::verify(&vk, &snarkjs_proof, &public_inputs.values).unwrap());
+        // the proof of co-circom that generate.sh checked with `snarkjs fflonk verify`
+        let co_circom_proof: FflonkProof<Bn254> =
+            serde_json::from_reader(File::open(format!("{dir}/co-circom.proof")).unwrap()).unwrap();
+        assert!(Fflonk::<Bn254>::verify(&vk, &co_circom_proof, &public_inputs.values).unwrap());
+
+        // our proof for the zkey of snarkjs verifies with the verification key of snarkjs
+        let witness = Witness::<ark_bn254::Fr>::from_reader(
+            File::open("../../test_vectors/Plonk/bn254/multiplier2/witness.wtns").unwrap(),
+        )
+        .unwrap();
+        let witness = SharedWitness::<PlainDriver<ark_bn254::Fr>, Bn254> {
+            public_inputs: witness.values[..=zkey.n_public].to_vec(),
+            witness: witness.values[zkey.n_public + 1..].to_vec(),
+        };
+        let proof = Fflonk::<Bn254>::new(PlainDriver::default())
+            .prove(&zkey, witness)
+            .unwrap();
+        assert!(Fflonk::<Bn254>::verify(&vk, &proof, &public_inputs.values).unwrap());
+    }
+}
@@ -0,0 +1,333 @@
+use ark_ec::pairing::Pairing;
+use ark_ec::CurveGroup;
+use circom_types::fflonk::ZKey;
+use co_circom_snarks::SharedWitness;
+use mpc_core::traits::{
+    FFTProvider, FieldShareVecTrait, MSMProvider, PairingEcMpcProtocol, PrimeFieldMpcProtocol,
+};
+
+use crate::{
+    fflonk_utils,
+    round2::Round2,
+    types::{Domains, FflonkData, FflonkWitness},
+    FflonkProofError, FflonkProofResult, FieldShare, FieldShareVec,
+};
+
+// Round 1 of https://eprint.iacr.org/2021/1167.pdf (page 16)
+pub(super) struct Round1<'a, T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+{
+    pub(super) driver: T,
+    pub(super) domains: Domains<P::ScalarField>,
+    pub(super) challenges: Round1Challenges<T, P>,
+    pub(super) data: FflonkDataRound1<'a, T, P>,
+}
+
+pub(super) struct FflonkDataRound1<'a, T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    witness: FflonkWitness<T, P>,
+    zkey: &'a ZKey<P>,
+}
+
+impl<'a, T, P: Pairing> From<FflonkDataRound1<'a, T, P>> for FflonkData<'a, T, P>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    fn from(mut data: FflonkDataRound1<'a, T, P>) -> Self {
+        //when we are done, we remove the leading zero of the public inputs
+        data.witness.public_inputs = data.witness.public_inputs[1..].to_vec();
+        Self {
+            witness: data.witness,
+            zkey: data.zkey,
+        }
+    }
+}
+
+pub(super) struct Round1Challenges<T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    pub(super) b: [T::FieldShare; 9],
+}
+
+pub(super) struct Round1Proof<P: Pairing> {
+    pub(super) commit_c1: P::G1,
+}
+
+pub(super) struct Round1Polys<T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    pub(super) buffer_a: FieldShareVec<T, P>,
+    pub(super) buffer_b: FieldShareVec<T, P>,
+    pub(super) buffer_c: FieldShareVec<T, P>,
+    pub(super) a: FieldShareVec<T, P>,
+    pub(super) b: FieldShareVec<T, P>,
+    pub(super) c: FieldShareVec<T, P>,
+    pub(super) c1: FieldShareVec<T, P>,
+}
+
+impl<P: Pairing> std::fmt::Display for Round1Proof<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "Round1Proof(C1: {})", self.commit_c1.into_affine())
+    }
+}
+
+impl<T, P: Pairing> Round1Challenges<T, P>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    pub(super) fn random(driver: &mut T) -> FflonkProofResult<Self> {
+        let mut b = core::array::from_fn(|_| T::FieldShare::default());
+        for x in b.iter_mut() {
+            *x = driver.rand()?;
+        }
+        Ok(Self { b })
+    }
+}
+
+// Round 1 of https://eprint.iacr.org/2021/1167.pdf (page 16)
+impl<'a, T, P: Pairing> Round1<'a, T, P>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+{
+    // Essentially the ifft of the trace columns, blinded with two coefficients each
+    fn compute_wire_polynomials(
+        driver: &mut T,
+        domains: &Domains<P::ScalarField>,
+        challenges: &Round1Challenges<T, P>,
+        zkey: &ZKey<P>,
+        witness: &FflonkWitness<T, P>,
+    ) -> FflonkProofResult<Round1Polys<T, P>> {
+        tracing::debug!("computing wire polynomials...");
+        let num_constraints = zkey.n_constraints;
+
+        let mut buffer_a = Vec::with_capacity(zkey.domain_size);
+        let mut buffer_b = Vec::with_capacity(zkey.domain_size);
+        let mut buffer_c = Vec::with_capacity(zkey.domain_size);
+
+        for i in 0..num_constraints {
+            buffer_a.push(fflonk_utils::get_witness(
+                driver,
+                witness,
+                zkey,
+                zkey.map_a[i],
+            )?);
+            buffer_b.push(fflonk_utils::get_witness(
+                driver,
+                witness,
+                zkey,
+                zkey.map_b[i],
+            )?);
+            buffer_c.push(fflonk_utils::get_witness(
+                driver,
+                witness,
+                zkey,
+                zkey.map_c[i],
+            )?);
+        }
+        buffer_a.resize(zkey.domain_size, FieldShare::<T, P>::default());
+        buffer_b.resize(zkey.domain_size, FieldShare::<T, P>::default());
+        buffer_c.resize(zkey.domain_size, FieldShare::<T, P>::default());
+
+        let buffer_a = FieldShareVec::<T, P>::from(buffer_a);
+        let buffer_b = FieldShareVec::<T, P>::from(buffer_b);
+        let buffer_c = FieldShareVec::<T, P>::from(buffer_c);
+
+        tracing::debug!("iffts for buffers..");
+        // Compute the coefficients of the wire polynomials a(X), b(X) and c(X) from A,B & C buffers
+        let poly_a = driver.ifft(&buffer_a, &domains.domain);
+        let poly_b = driver.ifft(&buffer_b, &domains.domain);
+        let poly_c = driver.ifft(&buffer_c, &domains.domain);
+
+        tracing::debug!("blinding coefficients");
+        let poly_a = fflonk_utils::blind_coefficients::<T, P>(driver, &poly_a, &challenges.b[..2]);
+        let poly_b = fflonk_utils::blind_coefficients::<T, P>(driver, &poly_b, &challenges.b[2..4]);
+        let poly_c = fflonk_utils::blind_coefficients::<T, P>(driver, &poly_c, &challenges.b[4..6]);
+
+        if poly_a.len() > zkey.domain_size + 2
+            || poly_b.len() > zkey.domain_size + 2
+            || poly_c.len() > zkey.domain_size + 2
+        {
+            return Err(FflonkProofError::PolynomialDegreeTooLarge);
+        }
+        tracing::debug!("computing wire polys done!");
+        Ok(Round1Polys {
+            buffer_a,
+            buffer_b,
+            buffer_c,
+            a: poly_a.into(),
+            b: poly_b.into(),
+            c: poly_c.into(),
+            c1: Vec::new().into(),
+        })
+    }
+
+    // Computes the quotient polynomial T0(X) of the gate constraints, i.e.,
+    // (ql a + qr b + qm a b + qo c + qc + PI) / Z_H
+    fn compute_t0(
+        driver: &mut T,
+        domains: &Domains<P::ScalarField>,
+        zkey: &ZKey<P>,
+        polys: &Round1Polys<T, P>,
+        public_inputs: &[P::ScalarField],
+    ) -> FflonkProofResult<Vec<FieldShare<T, P>>> {
+        tracing::debug!("computing t0 polynomial...");
+        let eval_a = driver.fft(polys.a.to_owned(), &domains.extended_domain);
+        let eval_b = driver.fft(polys.b.to_owned(), &domains.extended_domain);
+        let eval_c = driver.fft(polys.c.to_owned(), &domains.extended_domain);
+        let eval_ab = driver.mul_vec(&eval_a, &eval_b)?;
+
+        let mut t0 = Vec::with_capacity(zkey.domain_size * 4);
+        for i in 0..zkey.domain_size * 4 {
+            // PI(X) = -sum_j public_input_j * L_j(X)
+            let pi = public_inputs
+                .iter()
+                .zip(zkey.lagrange.iter())
+                .fold(P::ScalarField::default(), |acc, (val, l)| {
+                    acc - *val * l.evaluations[i]
+                });
+            let a = driver.mul_with_public(&zkey.ql_poly.evaluations[i], &eval_a.index(i));
+            let b = driver.mul_with_public(&zkey.qr_poly.evaluations[i], &eval_b.index(i));
+            let ab = driver.mul_with_public(&zkey.qm_poly.evaluations[i], &eval_ab.index(i));
+            let c = driver.mul_with_public(&zkey.qo_poly.evaluations[i], &eval_c.index(i));
+            let res = driver.add(&a, &b);
+            let res = driver.add(&res, &ab);
+            let res = driver.add(&res, &c);
+            let res = driver.add_with_public(&(zkey.qc_poly.evaluations[i] + pi), &res);
+            t0.push(res);
+        }
+        let t0 = driver.ifft(&t0.into(), &domains.extended_domain);
+        let mut t0 = t0.into_iter().collect::<Vec<_>>();
+        fflonk_utils::div_by_zerofier::<T, P>(
+            driver,
+            &mut t0,
+            zkey.domain_size,
+            P::ScalarField::from(1u64),
+        );
+        t0.truncate(zkey.domain_size * 2 + 2);
+        tracing::debug!("computing t0 polynomial done!");
+        Ok(t0)
+    }
+
+    // Calculate the witnesses for the additions, since they are not part of the SharedWitness
+    fn calculate_additions(
+        driver: &mut T,
+        witness: SharedWitness<T, P>,
+        zkey: &ZKey<P>,
+    ) -> FflonkProofResult<FflonkWitness<T, P>> {
+        tracing::debug!("calculating addition {} constraints...", zkey.n_additions);
+        let mut witness = FflonkWitness::new(witness, zkey.n_additions);
+
+        for addition in zkey.additions.iter() {
+            let witness1 = fflonk_utils::get_witness(
+                driver,
+                &witness,
+                zkey,
+                addition.signal_id1.try_into().expect("u32 fits into usize"),
+            )?;
+            let witness2 = fflonk_utils::get_witness(
+                driver,
+                &witness,
+                zkey,
+                addition.signal_id2.try_into().expect("u32 fits into usize"),
+            )?;
+
+            let f1 = driver.mul_with_public(&addition.factor1, &witness1);
+            let f2 = driver.mul_with_public(&addition.factor2, &witness2);
+            let result = driver.add(&f1, &f2);
+            witness.addition_witness.push(result);
+        }
+        tracing::debug!("additions done!");
+        Ok(witness)
+    }
+
+    pub(super) fn init_round(
+        mut driver: T,
+        zkey: &'a ZKey<P>,
+        private_witness: SharedWitness<T, P>,
+    ) -> FflonkProofResult<Self> {
+        driver.set_phase("fflonk: init");
+        let fflonk_witness = Self::calculate_additions(&mut driver, private_witness, zkey)?;
+
+        Ok(Self {
+            challenges: Round1Challenges::random(&mut driver)?,
+            driver,
+            domains: Domains::new(zkey.domain_size)?,
+            data: FflonkDataRound1 {
+                witness: fflonk_witness,
+                zkey,
+            },
+        })
+    }
+
+    // Round 1 of https://eprint.iacr.org/2021/1167.pdf (page 16)
+    pub(super) fn round1(self) -> FflonkProofResult<Round2<'a, T, P>> {
+        let Self {
+            mut driver,
+            domains,
+            challenges,
+            data,
+        } = self;
+        driver.set_phase("fflonk: round 1");
+        let witness = &data.witness;
+        let zkey = &data.zkey;
+
+        // STEP 1.1 - Compute wire polynomials a(X), b(X) and c(X)
+        let mut polys =
+            Self::compute_wire_polynomials(&mut driver, &domains, &challenges, zkey, witness)?;
+
+        // STEP 1.2 - Compute the quotient polynomial T0(X)
+        let t0 = Self::compute_t0(
+            &mut driver,
+            &domains,
+            zkey,
+            &polys,
+            &witness.public_inputs[1..],
+        )?;
+
+        // STEP 1.3 - C1(X) = a(X^4) + X b(X^4) + X^2 c(X^4) + X^3 T0(X^4)
+        tracing::debug!("computing C1 polynomial...");
+        let mut c1 = vec![FieldShare::<T, P>::default(); zkey.domain_size * 8 + 8];
+        for (k, poly) in [&polys.a, &polys.b, &polys.c].into_iter().enumerate() {
+            for (i, coeff) in poly.clone().into_iter().enumerate() {
+                c1[i * 4 + k] = coeff;
+            }
+        }
+        for (i, coeff) in t0.into_iter().enumerate() {
+            c1[i * 4 + 3] = coeff;
+        }
+        polys.c1 = c1.into();
+
+        tracing::debug!("committing to C1 (MSM)");
+        // STEP 1.4 - Compute [C1]_1
+        let commit_c1 = MSMProvider::<P::G1>::msm_public_points(
+            &driver,
+            &zkey.p_tau[..polys.c1.get_len()],
+            &polys.c1,
+        );
+        let proof = Round1Proof::<P> {
+            commit_c1: driver.open_point(&commit_c1)?,
+        };
+        tracing::debug!("round1 result: {proof}");
+        Ok(Round2 {
+            driver,
+            domains,
+            challenges,
+            proof,
+            polys,
+            data: data.into(),
+        })
+    }
+}
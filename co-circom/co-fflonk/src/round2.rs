@@ -0,0 +1,364 @@
+use crate::{
+    fflonk_utils,
+    round1::{Round1Challenges, Round1Polys, Round1Proof},
+    round3::Round3,
+    types::{Domains, FflonkData, Keccak256Transcript},
+    FflonkProofError, FflonkProofResult, FieldShare, FieldShareVec,
+};
+use ark_ec::pairing::Pairing;
+use ark_ec::CurveGroup;
+use ark_poly::EvaluationDomain;
+use circom_types::fflonk::ZKey;
+use mpc_core::traits::{
+    FFTProvider, FieldShareVecTrait, MSMProvider, PairingEcMpcProtocol, PrimeFieldMpcProtocol,
+};
+use num_traits::One;
+
+// To reduce the number of communication rounds, we implement the array_prod_mul macro according to https://www.usenix.org/system/files/sec22-ozdemir.pdf, p11 first paragraph.
+macro_rules! array_prod_mul {
+    ($driver: expr, $inp: expr) => {{
+        // Do the multiplications of inp[i] * inp[i-1] in constant rounds
+        let len = $inp.len();
+        let r = (0..=len)
+            .map(|_| $driver.rand())
+            .collect::<Result<Vec<_>, _>>()?;
+        let r_inv = $driver.inv_many(&r)?;
+        let r_inv0 = vec![r_inv[0].clone(); len];
+        let mut unblind = $driver.mul_many(&r_inv0, &r[1..])?;
+
+        let mul = $driver.mul_many(&r[..len], &$inp)?;
+        let mut open = $driver.mul_open_many(&mul, &r_inv[1..])?;
+
+        for i in 1..open.len() {
+            open[i] = open[i] * open[i - 1];
+        }
+
+        for (unblind, open) in unblind.iter_mut().zip(open.iter()) {
+            *unblind = $driver.mul_with_public(open, unblind);
+        }
+        unblind
+    }};
+}
+
+// Round 2 of https://eprint.iacr.org/2021/1167.pdf (page 16)
+pub(super) struct Round2<'a, T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+{
+    pub(super) driver: T,
+    pub(super) domains: Domains<P::ScalarField>,
+    pub(super) challenges: Round1Challenges<T, P>,
+    pub(super) proof: Round1Proof<P>,
+    pub(super) polys: Round1Polys<T, P>,
+    pub(super) data: FflonkData<'a, T, P>,
+}
+
+pub(super) struct Round2Challenges<P: Pairing> {
+    pub(super) beta: P::ScalarField,
+    pub(super) gamma: P::ScalarField,
+}
+
+pub(super) struct Round2Proof<P: Pairing> {
+    pub(super) commit_c1: P::G1,
+    pub(super) commit_c2: P::G1,
+}
+
+pub(super) struct Round2Polys<T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    pub(super) a: FieldShareVec<T, P>,
+    pub(super) b: FieldShareVec<T, P>,
+    pub(super) c: FieldShareVec<T, P>,
+    pub(super) z: FieldShareVec<T, P>,
+    pub(super) t1: FieldShareVec<T, P>,
+    pub(super) t2: FieldShareVec<T, P>,
+    pub(super) c1: FieldShareVec<T, P>,
+    pub(super) c2: FieldShareVec<T, P>,
+}
+
+impl<P: Pairing> std::fmt::Display for Round2Proof<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "Round2Proof(C2: {})", self.commit_c2.into_affine())
+    }
+}
+
+// Round 2 of https://eprint.iacr.org/2021/1167.pdf (page 16)
+impl<'a, T, P: Pairing> Round2<'a, T, P>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+{
+    // Computes the permutation polynomial z(X) (see https://eprint.iacr.org/2019/953.pdf)
+    // To reduce the number of communication rounds, we implement the array_prod_mul macro according to https://www.usenix.org/system/files/sec22-ozdemir.pdf, p11 first paragraph.
+    fn compute_z(
+        driver: &mut T,
+        zkey: &ZKey<P>,
+        domains: &Domains<P::ScalarField>,
+        challenges: &Round2Challenges<P>,
+        blinding: &Round1Challenges<T, P>,
+        polys: &Round1Polys<T, P>,
+    ) -> FflonkProofResult<FieldShareVec<T, P>> {
+        tracing::debug!("computing z polynomial...");
+        let pow_root_of_unity = domains.root_of_unity_pow;
+        let mut w = P::ScalarField::one();
+        let mut n1 = Vec::with_capacity(zkey.domain_size);
+        let mut n2 = Vec::with_capacity(zkey.domain_size);
+        let mut n3 = Vec::with_capacity(zkey.domain_size);
+        let mut d1 = Vec::with_capacity(zkey.domain_size);
+        let mut d2 = Vec::with_capacity(zkey.domain_size);
+        let mut d3 = Vec::with_capacity(zkey.domain_size);
+        for i in 0..zkey.domain_size {
+            let a = polys.buffer_a.index(i);
+            let b = polys.buffer_b.index(i);
+            let c = polys.buffer_c.index(i);
+
+            // Z(X) := numArr / denArr
+            // numArr := (a + beta·ω + gamma)(b + beta·ω·k1 + gamma)(c + beta·ω·k2 + gamma)
+            let betaw = challenges.beta * w;
+
+            let n1_ = driver.add_with_public(&betaw, &a);
+            let n1_ = driver.add_with_public(&challenges.gamma, &n1_);
+
+            let tmp = zkey.verifying_key.k1 * betaw;
+            let n2_ = driver.add_with_public(&tmp, &b);
+            let n2_ = driver.add_with_public(&challenges.gamma, &n2_);
+
+            let tmp = zkey.verifying_key.k2 * betaw;
+            let n3_ = driver.add_with_public(&tmp, &c);
+            let n3_ = driver.add_with_public(&challenges.gamma, &n3_);
+
+            n1.push(n1_);
+            n2.push(n2_);
+            n3.push(n3_);
+
+            // denArr := (a + beta·sigma1 + gamma)(b + beta·sigma2 + gamma)(c + beta·sigma3 + gamma)
+            let d1_ =
+                driver.add_with_public(&(challenges.beta * zkey.s1_poly.evaluations[i * 4]), &a);
+            let d1_ = driver.add_with_public(&challenges.gamma, &d1_);
+
+            let d2_ =
+                driver.add_with_public(&(challenges.beta * zkey.s2_poly.evaluations[i * 4]), &b);
+            let d2_ = driver.add_with_public(&challenges.gamma, &d2_);
+
+            let d3_ =
+                driver.add_with_public(&(challenges.beta * zkey.s3_poly.evaluations[i * 4]), &c);
+            let d3_ = driver.add_with_public(&challenges.gamma, &d3_);
+
+            d1.push(d1_);
+            d2.push(d2_);
+            d3.push(d3_);
+
+            w *= &pow_root_of_unity;
+        }
+
+        let num = driver.mul_many(&n1, &n2)?;
+        let num = driver.mul_many(&num, &n3)?;
+        let den = driver.mul_many(&d1, &d2)?;
+        let den = driver.mul_many(&den, &d3)?;
+
+        // Do the multiplications of num[i] * num[i-1] and den[i] * den[i-1] in constant rounds
+        let num = array_prod_mul!(driver, num);
+        let den = array_prod_mul!(driver, den);
+
+        // Compute the inverse of denArr to compute in the next command the
+        // division numArr/denArr by multiplying num · 1/denArr
+        let den = driver.inv_many(&den)?;
+        let mut buffer_z = driver.mul_many(&num, &den)?;
+        buffer_z.rotate_right(1); // Required by SNARKJs/Plonk
+        let buffer_z = buffer_z.into();
+
+        // Compute polynomial coefficients z(X) from buffer_z
+        let poly_z = driver.ifft(&buffer_z, &domains.domain);
+        let poly_z = fflonk_utils::blind_coefficients::<T, P>(driver, &poly_z, &blinding.b[6..9]);
+
+        if poly_z.len() > zkey.domain_size + 3 {
+            Err(FflonkProofError::PolynomialDegreeTooLarge)
+        } else {
+            tracing::debug!("computing z polynomial done!");
+            Ok(poly_z.into())
+        }
+    }
+
+    // Computes the quotient polynomial T1(X) = (z(X) - 1) L_1(X) / Z_H(X), which enforces z(1) = 1
+    fn compute_t1(
+        driver: &mut T,
+        zkey: &ZKey<P>,
+        domains: &Domains<P::ScalarField>,
+        z: &FieldShareVec<T, P>,
+    ) -> Vec<FieldShare<T, P>> {
+        tracing::debug!("computing t1 polynomial...");
+        let eval_z = driver.fft(z.to_owned(), &domains.extended_domain);
+        let mut t1 = Vec::with_capacity(zkey.domain_size * 4);
+        for i in 0..zkey.domain_size * 4 {
+            let l1 = zkey.lagrange[0].evaluations[i];
+            let z = driver.add_with_public(&-P::ScalarField::one(), &eval_z.index(i));
+            t1.push(driver.mul_with_public(&l1, &z));
+        }
+        let t1 = driver.ifft(&t1.into(), &domains.extended_domain);
+        let mut t1 = t1.into_iter().collect::<Vec<_>>();
+        fflonk_utils::div_by_zerofier::<T, P>(
+            driver,
+            &mut t1,
+            zkey.domain_size,
+            P::ScalarField::one(),
+        );
+        t1.truncate(zkey.domain_size + 2);
+        tracing::debug!("computing t1 polynomial done!");
+        t1
+    }
+
+    // Computes the quotient polynomial T2(X) of the permutation argument. The numerator has degree
+    // 4n + 5, so we evaluate it on a domain of size 8n.
+    fn compute_t2(
+        driver: &mut T,
+        zkey: &ZKey<P>,
+        domains: &Domains<P::ScalarField>,
+        challenges: &Round2Challenges<P>,
+        polys: &Round1Polys<T, P>,
+        z: &FieldShareVec<T, P>,
+    ) -> FflonkProofResult<Vec<FieldShare<T, P>>> {
+        tracing::debug!("computing t2 polynomial...");
+        let size = zkey.domain_size * 8;
+        let domain8 = &domains.domain8;
+        let eval_a = driver.fft(polys.a.to_owned(), domain8);
+        let eval_b = driver.fft(polys.b.to_owned(), domain8);
+        let eval_c = driver.fft(polys.c.to_owned(), domain8);
+        let eval_z = driver.fft(z.to_owned(), domain8);
+        let eval_s1 = domain8.fft(&zkey.s1_poly.coeffs.coeffs);
+        let eval_s2 = domain8.fft(&zkey.s2_poly.coeffs.coeffs);
+        let eval_s3 = domain8.fft(&zkey.s3_poly.coeffs.coeffs);
+
+        let Round2Challenges { beta, gamma } = *challenges;
+        let k1 = zkey.verifying_key.k1;
+        let k2 = zkey.verifying_key.k2;
+        let mut n1 = Vec::with_capacity(size);
+        let mut n2 = Vec::with_capacity(size);
+        let mut n3 = Vec::with_capacity(size);
+        let mut d1 = Vec::with_capacity(size);
+        let mut d2 = Vec::with_capacity(size);
+        let mut d3 = Vec::with_capacity(size);
+        let mut x = P::ScalarField::one();
+        for i in 0..size {
+            let a = eval_a.index(i);
+            let b = eval_b.index(i);
+            let c = eval_c.index(i);
+            let betax = beta * x;
+            n1.push(driver.add_with_public(&(betax + gamma), &a));
+            n2.push(driver.add_with_public(&(betax * k1 + gamma), &b));
+            n3.push(driver.add_with_public(&(betax * k2 + gamma), &c));
+            d1.push(driver.add_with_public(&(beta * eval_s1[i] + gamma), &a));
+            d2.push(driver.add_with_public(&(beta * eval_s2[i] + gamma), &b));
+            d3.push(driver.add_with_public(&(beta * eval_s3[i] + gamma), &c));
+            x *= domain8.group_gen;
+        }
+        // z(Xω) on the domain of size 8n is a shift by 8
+        let eval_zw = (0..size)
+            .map(|i| eval_z.index((i + 8) % size))
+            .collect::<Vec<_>>();
+
+        let num = driver.mul_vec(&n1.into(), &n2.into())?;
+        let den = driver.mul_vec(&d1.into(), &d2.into())?;
+        let num = driver.mul_vec(&num, &n3.into())?;
+        let den = driver.mul_vec(&den, &d3.into())?;
+        let num = driver.mul_vec(&num, &eval_z)?;
+        let den = driver.mul_vec(&den, &eval_zw.into())?;
+        let mut t2 = num;
+        driver.sub_assign_vec(&mut t2, &den);
+
+        let t2 = driver.ifft(&t2, domain8);
+        let mut t2 = t2.into_iter().collect::<Vec<_>>();
+        fflonk_utils::div_by_zerofier::<T, P>(
+            driver,
+            &mut t2,
+            zkey.domain_size,
+            P::ScalarField::one(),
+        );
+        t2.truncate(zkey.domain_size * 3 + 6);
+        tracing::debug!("computing t2 polynomial done!");
+        Ok(t2)
+    }
+
+    // Round 2 of https://eprint.iacr.org/2021/1167.pdf (page 16)
+    pub(super) fn round2(self) -> FflonkProofResult<Round3<'a, T, P>> {
+        let Self {
+            mut driver,
+            data,
+            proof,
+            challenges: blinding,
+            domains,
+            polys,
+        } = self;
+        driver.set_phase("fflonk: round 2");
+        let zkey = &data.zkey;
+        let public_input = &data.witness.public_inputs;
+        tracing::debug!("building challenges for round2 with Keccak256..");
+        let mut transcript = Keccak256Transcript::<P>::default();
+        transcript.add_point(zkey.verifying_key.c0);
+        for val in public_input.iter().cloned() {
+            transcript.add_scalar(val);
+        }
+        transcript.add_point(proof.commit_c1.into());
+        let beta = transcript.get_challenge();
+
+        let mut transcript = Keccak256Transcript::<P>::default();
+        transcript.add_scalar(beta);
+        let gamma = transcript.get_challenge();
+        tracing::debug!("beta: {beta}, gamma: {gamma}");
+        let challenges = Round2Challenges { beta, gamma };
+
+        // STEP 2.1 - Compute the permutation polynomial z(X)
+        let z = Self::compute_z(&mut driver, zkey, &domains, &challenges, &blinding, &polys)?;
+        // STEP 2.2 - Compute the quotient polynomials T1(X) and T2(X)
+        let t1 = Self::compute_t1(&mut driver, zkey, &domains, &z);
+        let t2 = Self::compute_t2(&mut driver, zkey, &domains, &challenges, &polys, &z)?;
+
+        // STEP 2.3 - C2(X) = z(X^3) + X T1(X^3) + X^2 T2(X^3)
+        tracing::debug!("computing C2 polynomial...");
+        let mut c2 = vec![FieldShare::<T, P>::default(); zkey.domain_size * 9 + 18];
+        for (i, coeff) in z.clone().into_iter().enumerate() {
+            c2[i * 3] = coeff;
+        }
+        for (k, poly) in [&t1, &t2].into_iter().enumerate() {
+            for (i, coeff) in poly.iter().enumerate() {
+                c2[i * 3 + k + 1] = coeff.to_owned();
+            }
+        }
+        let c2 = FieldShareVec::<T, P>::from(c2);
+
+        // STEP 2.4 - Compute [C2]_1
+        tracing::debug!("committing to C2 (MSM)");
+        let commit_c2 =
+            MSMProvider::<P::G1>::msm_public_points(&driver, &zkey.p_tau[..c2.get_len()], &c2);
+        let proof = Round2Proof {
+            commit_c1: proof.commit_c1,
+            commit_c2: driver.open_point(&commit_c2)?,
+        };
+        tracing::debug!("round2 result: {proof}");
+
+        Ok(Round3 {
+            driver,
+            domains,
+            challenges,
+            proof,
+            polys: Round2Polys {
+                a: polys.a,
+                b: polys.b,
+                c: polys.c,
+                z,
+                t1: t1.into(),
+                t2: t2.into(),
+                c1: polys.c1,
+                c2,
+            },
+            data,
+        })
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{
+    fflonk_utils,
+    round2::{Round2Challenges, Round2Polys, Round2Proof},
+    round4::Round4,
+    types::{Domains, FflonkData, Keccak256Transcript, Roots},
+    FflonkProofResult, FieldShareVec,
+};
+use ark_ec::pairing::Pairing;
+use circom_types::{
+    fflonk::FflonkEvaluations,
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+use mpc_core::traits::{FFTProvider, MSMProvider, PairingEcMpcProtocol, PrimeFieldMpcProtocol};
+use num_traits::Zero;
+
+// Round 3 of https://eprint.iacr.org/2021/1167.pdf (page 16)
+pub(super) struct Round3<'a, T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+{
+    pub(super) driver: T,
+    pub(super) domains: Domains<P::ScalarField>,
+    pub(super) challenges: Round2Challenges<P>,
+    pub(super) proof: Round2Proof<P>,
+    pub(super) polys: Round2Polys<T, P>,
+    pub(super) data: FflonkData<'a, T, P>,
+}
+
+pub(super) struct Round3Challenges<P: Pairing> {
+    pub(super) beta: P::ScalarField,
+    pub(super) gamma: P::ScalarField,
+    pub(super) xi_seed: P::ScalarField,
+    pub(super) roots: Roots<P::ScalarField>,
+}
+
+pub(super) struct Round3Proof<P: Pairing + CircomArkworksPairingBridge>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    pub(super) commit_c1: P::G1,
+    pub(super) commit_c2: P::G1,
+    pub(super) evaluations: FflonkEvaluations<P>,
+}
+
+pub(super) struct Round3Polys<T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    pub(super) c1: FieldShareVec<T, P>,
+    pub(super) c2: FieldShareVec<T, P>,
+}
+
+// Round 3 of https://eprint.iacr.org/2021/1167.pdf (page 16)
+impl<'a, T, P: Pairing> Round3<'a, T, P>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+    P: CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    // Round 3 of https://eprint.iacr.org/2021/1167.pdf (page 16)
+    pub(super) fn round3(self) -> FflonkProofResult<Round4<'a, T, P>> {
+        let Self {
+            mut driver,
+            domains,
+            challenges,
+            proof,
+            polys,
+            data,
+        } = self;
+        driver.set_phase("fflonk: round 3");
+        let zkey = data.zkey;
+        let vk = &zkey.verifying_key;
+        tracing::debug!("building challenges for round3 with Keccak256..");
+        let mut transcript = Keccak256Transcript::<P>::default();
+        transcript.add_scalar(challenges.gamma);
+        transcript.add_point(proof.commit_c2.into());
+        let xi_seed = transcript.get_challenge();
+        let roots = fflonk_utils::calculate_roots(xi_seed, vk.w3, vk.w4, vk.w8, vk.wr);
+        let xi = roots.xi;
+        let xiw = xi * domains.root_of_unity_pow;
+        tracing::debug!("xi: {xi}");
+
+        // STEP 3.1 - Evaluate the polynomials at xi and xi * ω
+        tracing::debug!("evaluating polynomials...");
+        let eval_a = driver.evaluate_poly_public(polys.a, &xi);
+        let eval_b = driver.evaluate_poly_public(polys.b, &xi);
+        let eval_c = driver.evaluate_poly_public(polys.c, &xi);
+        let eval_z = driver.evaluate_poly_public(polys.z.to_owned(), &xi);
+        let eval_zw = driver.evaluate_poly_public(polys.z, &xiw);
+        let eval_t1w = driver.evaluate_poly_public(polys.t1, &xiw);
+        let eval_t2w = driver.evaluate_poly_public(polys.t2, &xiw);
+        let opened =
+            driver.open_many(&[eval_a, eval_b, eval_c, eval_z, eval_zw, eval_t1w, eval_t2w])?;
+
+        // the inverse is computed in round 5, as it depends on the challenge y
+        let evaluations = FflonkEvaluations {
+            ql: zkey.ql_poly.evaluate(&xi),
+            qr: zkey.qr_poly.evaluate(&xi),
+            qm: zkey.qm_poly.evaluate(&xi),
+            qo: zkey.qo_poly.evaluate(&xi),
+            qc: zkey.qc_poly.evaluate(&xi),
+            s1: zkey.s1_poly.evaluate(&xi),
+            s2: zkey.s2_poly.evaluate(&xi),
+            s3: zkey.s3_poly.evaluate(&xi),
+            a: opened[0],
+            b: opened[1],
+            c: opened[2],
+            z: opened[3],
+            zw: opened[4],
+            t1w: opened[5],
+            t2w: opened[6],
+            inv: P::ScalarField::zero(),
+        };
+        tracing::debug!("round3 done!");
+        Ok(Round4 {
+            driver,
+            domains,
+            challenges: Round3Challenges {
+                beta: challenges.beta,
+                gamma: challenges.gamma,
+                xi_seed,
+                roots,
+            },
+            proof: Round3Proof {
+                commit_c1: proof.commit_c1,
+                commit_c2: proof.commit_c2,
+                evaluations,
+            },
+            polys: Round3Polys {
+                c1: polys.c1,
+                c2: polys.c2,
+            },
+            data,
+        })
+    }
+}
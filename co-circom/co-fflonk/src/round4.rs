@@ -0,0 +1,231 @@
+use crate::{
+    fflonk_utils::{self, OpeningValues},
+    round3::{Round3Challenges, Round3Polys, Round3Proof},
+    round5::Round5,
+    types::{Domains, FflonkData, Keccak256Transcript, Remainders},
+    FflonkProofResult, FieldShare, FieldShareVec,
+};
+use ark_ec::pairing::Pairing;
+use ark_ec::CurveGroup;
+use circom_types::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
+use mpc_core::traits::{
+    FFTProvider, FieldShareVecTrait, MSMProvider, PairingEcMpcProtocol, PrimeFieldMpcProtocol,
+};
+
+// Round 4 of https://eprint.iacr.org/2021/1167.pdf (page 17)
+pub(super) struct Round4<'a, T, P: Pairing + CircomArkworksPairingBridge>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    pub(super) driver: T,
+    pub(super) domains: Domains<P::ScalarField>,
+    pub(super) challenges: Round3Challenges<P>,
+    pub(super) proof: Round3Proof<P>,
+    pub(super) polys: Round3Polys<T, P>,
+    pub(super) data: FflonkData<'a, T, P>,
+}
+
+pub(super) struct Round4Challenges<P: Pairing> {
+    pub(super) round3: Round3Challenges<P>,
+    pub(super) alpha: P::ScalarField,
+    pub(super) xiw: P::ScalarField,
+    pub(super) zh: P::ScalarField,
+}
+
+pub(super) struct Round4Proof<P: Pairing + CircomArkworksPairingBridge>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    pub(super) round3: Round3Proof<P>,
+    pub(super) commit_w1: P::G1,
+}
+
+pub(super) struct Round4Polys<T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    pub(super) c1: FieldShareVec<T, P>,
+    pub(super) c2: FieldShareVec<T, P>,
+    pub(super) w: Vec<FieldShare<T, P>>,
+    pub(super) remainders: Remainders<P::ScalarField>,
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> std::fmt::Display for Round4Proof<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "Round4Proof(W1: {})", self.commit_w1.into_affine())
+    }
+}
+
+// Round 4 of https://eprint.iacr.org/2021/1167.pdf (page 17)
+impl<'a, T, P: Pairing> Round4<'a, T, P>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+    P: CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    // Subtracts the public remainder from the shared polynomial and divides the result by X^n - beta
+    fn sub_and_divide(
+        driver: &mut T,
+        poly: &FieldShareVec<T, P>,
+        remainder: &[P::ScalarField],
+        zerofiers: &[P::ScalarField],
+        n: usize,
+    ) -> Vec<FieldShare<T, P>> {
+        let mut poly = poly.clone().into_iter().collect::<Vec<_>>();
+        for (coeff, r) in poly.iter_mut().zip(remainder.iter()) {
+            *coeff = driver.add_with_public(&-*r, coeff);
+        }
+        for beta in zerofiers {
+            fflonk_utils::div_by_zerofier::<T, P>(driver, &mut poly, n, *beta);
+        }
+        poly
+    }
+
+    // Computes W(X) = (C0(X) - r0(X)) / Z_T0(X) + alpha (C1(X) - r1(X)) / Z_T1(X)
+    //               + alpha^2 (C2(X) - r2(X)) / Z_T2(X)
+    fn compute_w(
+        driver: &mut T,
+        data: &FflonkData<T, P>,
+        polys: &Round3Polys<T, P>,
+        remainders: &Remainders<P::ScalarField>,
+        xi: P::ScalarField,
+        xiw: P::ScalarField,
+        alpha: P::ScalarField,
+    ) -> Vec<FieldShare<T, P>> {
+        tracing::debug!("computing w polynomial...");
+        let zkey = data.zkey;
+        let mut w0 = zkey.c0_poly.coeffs.to_owned();
+        for (coeff, r) in w0.iter_mut().zip(remainders.r0.iter()) {
+            *coeff -= r;
+        }
+        fflonk_utils::div_by_zerofier_public(&mut w0, 8, xi);
+
+        let w1 = Self::sub_and_divide(driver, &polys.c1, &remainders.r1, &[xi], 4);
+        let w2 = Self::sub_and_divide(driver, &polys.c2, &remainders.r2, &[xi, xiw], 3);
+
+        let alpha2 = alpha * alpha;
+        let mut w = w2
+            .iter()
+            .map(|coeff| driver.mul_with_public(&alpha2, coeff))
+            .collect::<Vec<_>>();
+        for (i, coeff) in w1.iter().enumerate() {
+            w[i] = driver.add_mul_public(&w[i], coeff, &alpha);
+        }
+        for (i, coeff) in w0.iter().enumerate() {
+            w[i] = driver.add_with_public(coeff, &w[i]);
+        }
+        tracing::debug!("computing w polynomial done!");
+        w
+    }
+
+    // Round 4 of https://eprint.iacr.org/2021/1167.pdf (page 17)
+    pub(super) fn round4(self) -> FflonkProofResult<Round5<'a, T, P>> {
+        let Self {
+            mut driver,
+            domains,
+            challenges,
+            proof,
+            polys,
+            data,
+        } = self;
+        driver.set_phase("fflonk: round 4");
+        let zkey = data.zkey;
+        let evaluations = &proof.evaluations;
+        tracing::debug!("building challenges for round4 with Keccak256..");
+        let mut transcript = Keccak256Transcript::<P>::default();
+        transcript.add_scalar(challenges.xi_seed);
+        transcript.add_scalar(evaluations.ql);
+        transcript.add_scalar(evaluations.qr);
+        transcript.add_scalar(evaluations.qm);
+        transcript.add_scalar(evaluations.qo);
+        transcript.add_scalar(evaluations.qc);
+        transcript.add_scalar(evaluations.s1);
+        transcript.add_scalar(evaluations.s2);
+        transcript.add_scalar(evaluations.s3);
+        transcript.add_scalar(evaluations.a);
+        transcript.add_scalar(evaluations.b);
+        transcript.add_scalar(evaluations.c);
+        transcript.add_scalar(evaluations.z);
+        transcript.add_scalar(evaluations.zw);
+        transcript.add_scalar(evaluations.t1w);
+        transcript.add_scalar(evaluations.t2w);
+        let alpha = transcript.get_challenge();
+        tracing::debug!("alpha: {alpha}");
+
+        let xi = challenges.roots.xi;
+        let xiw = xi * domains.root_of_unity_pow;
+        let (l, zh) = fflonk_utils::calculate_lagrange_evaluations(
+            zkey.power,
+            zkey.n_public,
+            xi,
+            domains.root_of_unity_pow,
+        );
+        let pi = fflonk_utils::calculate_pi(&data.witness.public_inputs, &l);
+
+        // STEP 4.1 - Compute the remainders r0(X), r1(X) and r2(X)
+        let remainders = fflonk_utils::calculate_remainders(
+            evaluations,
+            &OpeningValues {
+                xi,
+                xiw,
+                beta: challenges.beta,
+                gamma: challenges.gamma,
+                k1: zkey.verifying_key.k1,
+                k2: zkey.verifying_key.k2,
+                pi,
+                l1: l[0],
+                zh,
+            },
+        );
+
+        // STEP 4.2 - Compute W(X) and [W]_1
+        let w = Self::compute_w(&mut driver, &data, &polys, &remainders, xi, xiw, alpha);
+        let w_vec = FieldShareVec::<T, P>::from(w.clone());
+        tracing::debug!("committing to W (MSM)");
+        let commit_w1 = MSMProvider::<P::G1>::msm_public_points(
+            &driver,
+            &zkey.p_tau[..w_vec.get_len()],
+            &w_vec,
+        );
+        let proof = Round4Proof {
+            round3: proof,
+            commit_w1: driver.open_point(&commit_w1)?,
+        };
+        tracing::debug!("round4 result: {proof}");
+
+        Ok(Round5 {
+            driver,
+            domains,
+            challenges: Round4Challenges {
+                round3: challenges,
+                alpha,
+                xiw,
+                zh,
+            },
+            proof,
+            polys: Round4Polys {
+                c1: polys.c1,
+                c2: polys.c2,
+                w,
+                remainders,
+            },
+            data,
+        })
+    }
+}
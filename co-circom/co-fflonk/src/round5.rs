@@ -0,0 +1,161 @@
+use crate::{
+    fflonk_utils,
+    round4::{Round4Challenges, Round4Polys, Round4Proof},
+    types::{Domains, FflonkData, Keccak256Transcript},
+    FflonkProofResult, FieldShare, FieldShareVec,
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use circom_types::{
+    fflonk::{FflonkPolynomials, FflonkProof},
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+use mpc_core::traits::{
+    FFTProvider, FieldShareVecTrait, MSMProvider, PairingEcMpcProtocol, PrimeFieldMpcProtocol,
+};
+
+// Round 5 of https://eprint.iacr.org/2021/1167.pdf (page 17)
+pub(super) struct Round5<'a, T, P: Pairing + CircomArkworksPairingBridge>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    pub(super) driver: T,
+    pub(super) domains: Domains<P::ScalarField>,
+    pub(super) challenges: Round4Challenges<P>,
+    pub(super) proof: Round4Proof<P>,
+    pub(super) polys: Round4Polys<T, P>,
+    pub(super) data: FflonkData<'a, T, P>,
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> Round4Proof<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn into_final_proof(self, commit_w2: P::G1, inv: P::ScalarField) -> FflonkProof<P> {
+        let mut evaluations = self.round3.evaluations;
+        evaluations.inv = inv;
+        FflonkProof {
+            polynomials: FflonkPolynomials {
+                c1: P::G1Affine::from(self.round3.commit_c1),
+                c2: P::G1Affine::from(self.round3.commit_c2),
+                w1: P::G1Affine::from(self.commit_w1),
+                w2: P::G1Affine::from(commit_w2),
+            },
+            evaluations,
+            protocol: "fflonk".to_string(),
+            curve: P::get_circom_name(),
+        }
+    }
+}
+
+// Round 5 of https://eprint.iacr.org/2021/1167.pdf (page 17)
+impl<'a, T, P: Pairing> Round5<'a, T, P>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>
+        + PairingEcMpcProtocol<P>
+        + FFTProvider<P::ScalarField>
+        + MSMProvider<P::G1>
+        + MSMProvider<P::G2>,
+    P: CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    // Computes L(X) = C0(X) - r0(y) + q1 (C1(X) - r1(y)) + q2 (C2(X) - r2(y)) - Z_T0(y) W(X),
+    // which vanishes at y, and returns W'(X) = L(X) / (X - y)
+    fn compute_w2(
+        driver: &mut T,
+        data: &FflonkData<T, P>,
+        polys: &Round4Polys<T, P>,
+        y: P::ScalarField,
+        quotients: [P::ScalarField; 3],
+    ) -> Vec<FieldShare<T, P>> {
+        tracing::debug!("computing w2 polynomial...");
+        let [mul_h0, q1, q2] = quotients;
+        let remainders = &polys.remainders;
+        let r0 = fflonk_utils::evaluate(&remainders.r0, y);
+        let r1 = fflonk_utils::evaluate(&remainders.r1, y);
+        let r2 = fflonk_utils::evaluate(&remainders.r2, y);
+
+        let mut l = polys
+            .c2
+            .clone()
+            .into_iter()
+            .map(|coeff| driver.mul_with_public(&q2, &coeff))
+            .collect::<Vec<_>>();
+        l[0] = driver.add_with_public(&-(q2 * r2), &l[0]);
+        for (i, coeff) in polys.c1.clone().into_iter().enumerate() {
+            l[i] = driver.add_mul_public(&l[i], &coeff, &q1);
+        }
+        l[0] = driver.add_with_public(&-(q1 * r1), &l[0]);
+        for (i, coeff) in data.zkey.c0_poly.coeffs.iter().enumerate() {
+            l[i] = driver.add_with_public(coeff, &l[i]);
+        }
+        l[0] = driver.add_with_public(&-r0, &l[0]);
+        for (i, coeff) in polys.w.iter().enumerate() {
+            l[i] = driver.add_mul_public(&l[i], coeff, &-mul_h0);
+        }
+        fflonk_utils::div_by_zerofier::<T, P>(driver, &mut l, 1, y);
+        tracing::debug!("computing w2 polynomial done!");
+        l
+    }
+
+    // Round 5 of https://eprint.iacr.org/2021/1167.pdf (page 17)
+    pub(super) fn round5(self) -> FflonkProofResult<FflonkProof<P>> {
+        let Self {
+            mut driver,
+            domains,
+            challenges,
+            proof,
+            polys,
+            data,
+        } = self;
+        driver.set_phase("fflonk: round 5");
+        let zkey = data.zkey;
+        tracing::debug!("building challenges for round5 with Keccak256..");
+        let mut transcript = Keccak256Transcript::<P>::default();
+        transcript.add_scalar(challenges.alpha);
+        transcript.add_point(proof.commit_w1.into());
+        let y = transcript.get_challenge();
+        tracing::debug!("y: {y}");
+
+        let xi = challenges.round3.roots.xi;
+        let xiw = challenges.xiw;
+        let alpha = challenges.alpha;
+        let mul_h0 = y.pow([8]) - xi;
+        let mul_h1 = y.pow([4]) - xi;
+        let mul_h2 = (y.pow([3]) - xi) * (y.pow([3]) - xiw);
+        let q1 = alpha * mul_h0 * mul_h1.inverse().expect("Highly unlikely to be zero");
+        let q2 = alpha.square() * mul_h0 * mul_h2.inverse().expect("Highly unlikely to be zero");
+
+        // STEP 5.1 - Compute W'(X) and [W']_1
+        let w2 = Self::compute_w2(&mut driver, &data, &polys, y, [mul_h0, q1, q2]);
+        let w2 = FieldShareVec::<T, P>::from(w2);
+        tracing::debug!("committing to W' (MSM)");
+        let commit_w2 =
+            MSMProvider::<P::G1>::msm_public_points(&driver, &zkey.p_tau[..w2.get_len()], &w2);
+        let commit_w2 = driver.open_point(&commit_w2)?;
+
+        // STEP 5.2 - The inverse of all denominators the verifier needs
+        let inv = fflonk_utils::denominator_product(
+            &challenges.round3.roots,
+            y,
+            xiw,
+            challenges.zh,
+            zkey.power,
+            zkey.n_public,
+            domains.root_of_unity_pow,
+        )
+        .inverse()
+        .expect("Highly unlikely to be zero");
+
+        tracing::debug!("round5 done!");
+        Ok(proof.into_final_proof(commit_w2, inv))
+    }
+}
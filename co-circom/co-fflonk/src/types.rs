@@ -0,0 +1,172 @@
+use ark_ec::AffineRepr;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use circom_types::fflonk::ZKey;
+use co_circom_snarks::SharedWitness;
+use std::marker::PhantomData;
+
+use crate::{FflonkProofError, FflonkProofResult, FieldShare, FieldShareVec};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use mpc_core::traits::PrimeFieldMpcProtocol;
+use num_traits::Zero;
+use sha3::{Digest, Keccak256};
+
+pub(super) type Keccak256Transcript<P> = Transcript<Keccak256, P>;
+
+pub(super) struct Transcript<D, P>
+where
+    D: Digest,
+    P: Pairing,
+{
+    digest: D,
+    phantom_data: PhantomData<P>,
+}
+
+pub(super) struct Domains<F: PrimeField> {
+    pub(super) domain: Radix2EvaluationDomain<F>,
+    pub(super) extended_domain: Radix2EvaluationDomain<F>,
+    pub(super) domain8: Radix2EvaluationDomain<F>,
+    pub(super) root_of_unity_pow: F,
+}
+
+// The roots of the opening sets S0, S1 and S2 (which consists of h2w3 and h3w3) and the evaluation point xi
+pub(super) struct Roots<F> {
+    pub(super) h0w8: [F; 8],
+    pub(super) h1w4: [F; 4],
+    pub(super) h2w3: [F; 3],
+    pub(super) h3w3: [F; 3],
+    pub(super) xi: F,
+}
+
+// The coefficients of the remainders r0(X), r1(X) and r2(X) of the opening sets
+pub(super) struct Remainders<F> {
+    pub(super) r0: [F; 8],
+    pub(super) r1: [F; 4],
+    pub(super) r2: [F; 6],
+}
+
+pub(super) struct FflonkWitness<T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    pub(super) public_inputs: Vec<P::ScalarField>,
+    pub(super) witness: FieldShareVec<T, P>,
+    pub(super) addition_witness: Vec<FieldShare<T, P>>,
+}
+
+pub(super) struct FflonkData<'a, T, P: Pairing>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    pub(super) witness: FflonkWitness<T, P>,
+    pub(super) zkey: &'a ZKey<P>,
+}
+
+fn set_root<F: PrimeField>(domain: &mut Radix2EvaluationDomain<F>, root: F) {
+    domain.group_gen = root;
+    domain.group_gen_inv = root.inverse().expect("can compute inverse");
+}
+
+impl<F: PrimeField> Domains<F> {
+    pub(super) fn new(domain_size: usize) -> FflonkProofResult<Self> {
+        tracing::debug!("building domains/roots of unity for domain size: {domain_size}");
+        if domain_size & (domain_size - 1) != 0 || domain_size == 0 {
+            Err(FflonkProofError::InvalidDomainSize(domain_size))
+        } else {
+            let mut domain = Radix2EvaluationDomain::<F>::new(domain_size)
+                .ok_or(FflonkProofError::PolynomialDegreeTooLarge)?;
+            let mut extended_domain = Radix2EvaluationDomain::<F>::new(domain_size * 4)
+                .ok_or(FflonkProofError::PolynomialDegreeTooLarge)?;
+            let mut domain8 = Radix2EvaluationDomain::<F>::new(domain_size * 8)
+                .ok_or(FflonkProofError::PolynomialDegreeTooLarge)?;
+            let (_, roots_of_unity) = co_circom_snarks::utils::roots_of_unity();
+            let pow = usize::try_from(domain_size.ilog2()).expect("u32 fits into usize");
+
+            // snarkjs and arkworks use different roots of unity to compute (i)fft.
+            // therefore we compute the roots of unity by hand like snarkjs and
+            // set the root of unity accordingly by hand
+            set_root(&mut domain, roots_of_unity[pow]);
+            set_root(&mut extended_domain, roots_of_unity[pow + 2]);
+            set_root(&mut domain8, roots_of_unity[pow + 3]);
+
+            Ok(Self {
+                domain,
+                extended_domain,
+                domain8,
+                root_of_unity_pow: roots_of_unity[pow],
+            })
+        }
+    }
+}
+
+impl<T, P: Pairing> FflonkWitness<T, P>
+where
+    T: PrimeFieldMpcProtocol<P::ScalarField>,
+{
+    pub(super) fn new(mut shared_witness: SharedWitness<T, P>, n_additions: usize) -> Self {
+        // we have a Groth16 witness, therefore there is a leading one in the witness.
+        // we just write zero here instead of one to mirror snarkjs.
+        shared_witness.public_inputs[0] = P::ScalarField::zero();
+        Self {
+            public_inputs: shared_witness.public_inputs,
+            witness: shared_witness.witness,
+            addition_witness: Vec::with_capacity(n_additions),
+        }
+    }
+}
+impl<P: Pairing> Default for Keccak256Transcript<P> {
+    fn default() -> Self {
+        Self {
+            digest: Default::default(),
+            phantom_data: Default::default(),
+        }
+    }
+}
+
+impl<D, P> Transcript<D, P>
+where
+    D: Digest,
+    P: Pairing,
+{
+    pub(super) fn add_scalar(&mut self, scalar: P::ScalarField) {
+        let mut buf = vec![];
+        scalar
+            .serialize_uncompressed(&mut buf)
+            .expect("Can Fr write into Vec<u8>");
+        buf.reverse();
+        self.digest.update(&buf);
+    }
+
+    pub(super) fn add_point(&mut self, point: P::G1Affine) {
+        let byte_len: usize = P::BaseField::MODULUS_BIT_SIZE
+            .div_ceil(8)
+            .try_into()
+            .expect("u32 fits into usize");
+        let mut buf = Vec::with_capacity(byte_len);
+        if let Some((x, y)) = point.xy() {
+            x.serialize_uncompressed(&mut buf)
+                .expect("Can write Fq into Vec<u8>");
+            buf.reverse();
+            self.digest.update(&buf);
+            buf.clear();
+            y.serialize_uncompressed(&mut buf)
+                .expect("Can write Fq into Vec<u8>");
+            buf.reverse();
+            self.digest.update(&buf);
+        } else {
+            // we are at infinity - in this case, snarkjs writes (MODULUS_BIT_SIZE / 8) Zero-bytes
+            // to the input buffer. If we serialize with arkworks, we would
+            // get (MODULUS_BIT_SIZE / 8 - 1) Zero-bytes with a trailing byte indicating the length of
+            // the serialized group element, resulting in an incompatible hash. Therefore we simple resize
+            // the buffer with Zeros and write it to the hash instance.
+            buf.resize(byte_len * 2, 0);
+            self.digest.update(&buf);
+        }
+    }
+
+    pub(super) fn get_challenge(self) -> P::ScalarField {
+        let bytes = self.digest.finalize();
+        P::ScalarField::from_be_bytes_mod_order(&bytes)
+    }
+}
@@ -0,0 +1,176 @@
+//! The pairing based commitments to vectors of group elements, and the inner products and folding
+//! operations used by the prover and verifier of the aggregation.
+
+use ark_ec::{
+    pairing::{MillerLoopOutput, Pairing, PairingOutput},
+    AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{Field, One};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rayon::prelude::*;
+
+/// A commitment to vectors of group elements, consisting of one element of the target group for
+/// each of the two secrets `a` and `b` of the SRS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub(super) struct Commitment<P: Pairing> {
+    pub(super) t: PairingOutput<P>,
+    pub(super) u: PairingOutput<P>,
+}
+
+/// A commitment key, i.e., the powers of `a` and `b` in one of the source groups.
+#[derive(Clone, Copy)]
+pub(super) struct KeyRef<'a, G> {
+    pub(super) alpha: &'a [G],
+    pub(super) beta: &'a [G],
+}
+
+/// An owned commitment key, see [`KeyRef`].
+pub(super) struct Key<G> {
+    pub(super) alpha: Vec<G>,
+    pub(super) beta: Vec<G>,
+}
+
+impl<'a, G> KeyRef<'a, G> {
+    pub(super) fn split_at(self, mid: usize) -> (Self, Self) {
+        let (alpha_l, alpha_r) = self.alpha.split_at(mid);
+        let (beta_l, beta_r) = self.beta.split_at(mid);
+        (
+            Self {
+                alpha: alpha_l,
+                beta: beta_l,
+            },
+            Self {
+                alpha: alpha_r,
+                beta: beta_r,
+            },
+        )
+    }
+}
+
+impl<G: AffineRepr> Key<G> {
+    pub(super) fn as_ref(&self) -> KeyRef<'_, G> {
+        KeyRef {
+            alpha: &self.alpha,
+            beta: &self.beta,
+        }
+    }
+
+    /// Multiplies the i-th element of the key with `scalars[i]`.
+    pub(super) fn scale(key: KeyRef<G>, scalars: &[G::ScalarField]) -> Self {
+        Self {
+            alpha: scale_points(key.alpha, scalars),
+            beta: scale_points(key.beta, scalars),
+        }
+    }
+
+    /// Computes `left + x * right` for both halves of the key.
+    pub(super) fn fold(left: KeyRef<G>, right: KeyRef<G>, x: G::ScalarField) -> Self {
+        Self {
+            alpha: fold_points(left.alpha, right.alpha, x),
+            beta: fold_points(left.beta, right.beta, x),
+        }
+    }
+}
+
+impl<P: Pairing> Commitment<P> {
+    /// Commits to the vector `a` in G1 with the key `v` in G2.
+    pub(super) fn single(v: KeyRef<P::G2Affine>, a: &[P::G1Affine]) -> Self {
+        let (t, u) = rayon::join(
+            || inner_pairing_product::<P>(a, v.alpha),
+            || inner_pairing_product::<P>(a, v.beta),
+        );
+        Self { t, u }
+    }
+
+    /// Commits to the vectors `a` in G1 and `b` in G2 with the keys `v` in G2 and `w` in G1.
+    pub(super) fn pair(
+        v: KeyRef<P::G2Affine>,
+        w: KeyRef<P::G1Affine>,
+        a: &[P::G1Affine],
+        b: &[P::G2Affine],
+    ) -> Self {
+        let (a_com, b_com) = rayon::join(
+            || Self::single(v, a),
+            || {
+                let (t, u) = rayon::join(
+                    || inner_pairing_product::<P>(w.alpha, b),
+                    || inner_pairing_product::<P>(w.beta, b),
+                );
+                Self { t, u }
+            },
+        );
+        Self {
+            t: a_com.t + b_com.t,
+            u: a_com.u + b_com.u,
+        }
+    }
+
+    /// Computes `self + x * left + x_inv * right`, which is the commitment to the folded vectors
+    /// under the folded keys of a GIPA round.
+    pub(super) fn fold(
+        self,
+        left: Self,
+        right: Self,
+        x: P::ScalarField,
+        x_inv: P::ScalarField,
+    ) -> Self {
+        Self {
+            t: self.t + left.t * x + right.t * x_inv,
+            u: self.u + left.u * x + right.u * x_inv,
+        }
+    }
+}
+
+/// Computes prod_i e(a_i, b_i). The Miller loops of the chunks are computed in parallel, followed by
+/// a single final exponentiation.
+pub(super) fn inner_pairing_product<P: Pairing>(
+    a: &[P::G1Affine],
+    b: &[P::G2Affine],
+) -> PairingOutput<P> {
+    debug_assert_eq!(a.len(), b.len());
+    let chunk_size = a.len().div_ceil(rayon::current_num_threads().max(1)).max(1);
+    let miller_loop = a
+        .par_chunks(chunk_size)
+        .zip(b.par_chunks(chunk_size))
+        .map(|(a, b)| P::multi_miller_loop(a, b).0)
+        .reduce(P::TargetField::one, |x, y| x * y);
+    P::final_exponentiation(MillerLoopOutput(miller_loop))
+        .expect("final exponentiation of a Miller loop does not fail")
+}
+
+/// Computes sum_i s_i * a_i.
+pub(super) fn multiexponentiation<G: AffineRepr>(a: &[G], s: &[G::ScalarField]) -> G {
+    G::Group::msm_unchecked(a, s).into_affine()
+}
+
+/// Multiplies the i-th point with `scalars[i]`.
+pub(super) fn scale_points<G: AffineRepr>(points: &[G], scalars: &[G::ScalarField]) -> Vec<G> {
+    let scaled = points
+        .par_iter()
+        .zip(scalars)
+        .map(|(p, s)| *p * s)
+        .collect::<Vec<_>>();
+    G::Group::normalize_batch(&scaled)
+}
+
+/// Computes `left + x * right` element-wise.
+pub(super) fn fold_points<G: AffineRepr>(left: &[G], right: &[G], x: G::ScalarField) -> Vec<G> {
+    let folded = left
+        .par_iter()
+        .zip(right)
+        .map(|(l, r)| *r * x + l)
+        .collect::<Vec<_>>();
+    G::Group::normalize_batch(&folded)
+}
+
+/// Computes `left + x * right` element-wise.
+pub(super) fn fold_scalars<F: Field>(left: &[F], right: &[F], x: F) -> Vec<F> {
+    left.iter().zip(right).map(|(l, r)| *r * x + l).collect()
+}
+
+/// Returns x^i for i in 0..len.
+pub(super) fn powers<F: Field>(x: F, len: usize) -> Vec<F> {
+    std::iter::successors(Some(F::ONE), |p| Some(*p * x))
+        .take(len)
+        .collect()
+}
@@ -0,0 +1,196 @@
+//! This module implements the aggregation of Groth16 proofs for the same verification key following
+//! [SnarkPack](https://eprint.iacr.org/2021/529).
+//!
+//! An aggregate proof of n proofs (n a power of two) has size O(log n) and is verified in
+//! O(log n) time (plus the O(n) accumulation of the public inputs). The proofs are aggregated with
+//! [`Groth16::aggregate_proofs`](crate::Groth16::aggregate_proofs) and the aggregate proof is
+//! verified with [`Groth16::verify_aggregate_proof`](crate::Groth16::verify_aggregate_proof). Both
+//! require an [`AggregationSrs`], which is independent of the circuit.
+//!
+//! The aggregation does not use MPC, as the Groth16 proofs created by the parties are public.
+//!
+//! In a nutshell, the prover commits to the vectors A, B, and C of the proofs, derives a random r
+//! from the commitments and proves with a TIPP and a MIPP argument (sharing the same GIPA recursion)
+//! that `Z_AB = prod_i e(A_i, B_i)^(r^i)` and `Z_C = sum_i r^i C_i` for the committed vectors. The
+//! verifier then checks the random linear combination of the Groth16 equations using `Z_AB` and
+//! `Z_C`. The final commitment keys of the GIPA recursion are checked with KZG openings.
+
+mod commitment;
+mod prover;
+mod srs;
+mod transcript;
+mod verifier;
+
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use circom_types::{
+    groth16::JsonVerificationKey,
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+
+use commitment::Commitment;
+use transcript::Transcript;
+
+pub use srs::{AggregationSrs, ProverSrs, VerifierSrs};
+
+const TRANSCRIPT_LABEL: &[u8] = b"co-groth16 snarkpack";
+
+/// An aggregate proof of multiple Groth16 proofs for the same verification key. It can be
+/// (de)serialized with [`CanonicalSerialize`] and [`CanonicalDeserialize`].
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregateProof<P: Pairing> {
+    com_ab: Commitment<P>,
+    com_c: Commitment<P>,
+    ip_ab: PairingOutput<P>,
+    agg_c: P::G1Affine,
+    rounds: Vec<GipaRound<P>>,
+    last: GipaLast<P>,
+    openings: KzgOpenings<P>,
+}
+
+/// The cross terms of a single round of the GIPA recursion of the TIPP and MIPP arguments.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+struct GipaRound<P: Pairing> {
+    com_ab_l: Commitment<P>,
+    com_ab_r: Commitment<P>,
+    com_c_l: Commitment<P>,
+    com_c_r: Commitment<P>,
+    z_ab_l: PairingOutput<P>,
+    z_ab_r: PairingOutput<P>,
+    z_c_l: P::G1Affine,
+    z_c_r: P::G1Affine,
+}
+
+/// The folded vectors and commitment keys after the last round of the GIPA recursion.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+struct GipaLast<P: Pairing> {
+    a: P::G1Affine,
+    b: P::G2Affine,
+    c: P::G1Affine,
+    vkey_alpha: P::G2Affine,
+    vkey_beta: P::G2Affine,
+    wkey_alpha: P::G1Affine,
+    wkey_beta: P::G1Affine,
+}
+
+/// The KZG openings proving that the last commitment keys are the correctly folded keys of the SRS.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+struct KzgOpenings<P: Pairing> {
+    vkey_alpha: P::G2Affine,
+    vkey_beta: P::G2Affine,
+    wkey_alpha: P::G1Affine,
+    wkey_beta: P::G1Affine,
+}
+
+/// Creates the transcript and absorbs the statement, i.e., the verification key and the public
+/// inputs of all aggregated proofs.
+fn statement_transcript<P: Pairing + CircomArkworksPairingBridge>(
+    vk: &JsonVerificationKey<P>,
+    public_inputs: &[Vec<P::ScalarField>],
+) -> Transcript
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+    transcript.append(&vk.alpha_1);
+    transcript.append(&vk.beta_2);
+    transcript.append(&vk.gamma_2);
+    transcript.append(&vk.delta_2);
+    transcript.append(&vk.ic);
+    transcript.append(&public_inputs.to_vec());
+    transcript
+}
+
+fn check_public_inputs<P: Pairing + CircomArkworksPairingBridge>(
+    vk: &JsonVerificationKey<P>,
+    public_inputs: &[Vec<P::ScalarField>],
+) -> eyre::Result<()>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    srs::check_num_proofs(public_inputs.len())?;
+    let n_public = vk.ic.len() - 1;
+    eyre::ensure!(
+        public_inputs.iter().all(|inputs| inputs.len() == n_public),
+        "Invalid number of public inputs"
+    );
+    Ok(())
+}
+
+/// Returns the coefficients of prod_j (1 + c_j X^(n / 2^(j+1))) for n = 2^(c.len()). Folding a
+/// vector of length n with `left + c_j * right` in round j results in the inner product of the
+/// vector with these coefficients.
+fn folding_polynomial<F: Field>(c: &[F]) -> Vec<F> {
+    let mut coeffs = Vec::with_capacity(1 << c.len());
+    coeffs.push(F::ONE);
+    for c in c.iter().rev() {
+        let upper = coeffs.iter().map(|coeff| *coeff * c).collect::<Vec<_>>();
+        coeffs.extend(upper);
+    }
+    coeffs
+}
+
+/// Evaluates the polynomial of [`folding_polynomial`] at `z` in O(log n).
+fn evaluate_folding_polynomial<F: Field>(c: &[F], z: F) -> F {
+    let mut z_pow = z;
+    let mut result = F::ONE;
+    for c in c.iter().rev() {
+        result *= F::ONE + *c * z_pow;
+        z_pow.square_in_place();
+    }
+    result
+}
+
+/// Returns the coefficients of (f(X) - f(z)) / (X - z).
+fn kzg_quotient<F: Field>(coeffs: &[F], z: F) -> Vec<F> {
+    let mut quotient = vec![F::ZERO; coeffs.len().saturating_sub(1)];
+    let mut acc = F::ZERO;
+    for (q, coeff) in quotient.iter_mut().zip(coeffs.iter().skip(1)).rev() {
+        acc = acc * z + coeff;
+        *q = acc;
+    }
+    quotient
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+
+    use super::commitment::{fold_scalars, powers};
+
+    #[test]
+    fn folding_polynomial_matches_folding() {
+        let mut rng = rand::thread_rng();
+        let n = 16;
+        let challenges = (0..4)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let coeffs = super::folding_polynomial(&challenges);
+        let z = ark_bn254::Fr::rand(&mut rng);
+
+        // folding the powers of z results in the evaluation of the folding polynomial
+        let mut folded = powers(z, n);
+        for c in challenges.iter() {
+            let (left, right) = folded.split_at(folded.len() / 2);
+            folded = fold_scalars(left, right, *c);
+        }
+        let poly = DensePolynomial::from_coefficients_vec(coeffs.clone());
+        assert_eq!(folded, vec![poly.evaluate(&z)]);
+        assert_eq!(
+            super::evaluate_folding_polynomial(&challenges, z),
+            poly.evaluate(&z)
+        );
+
+        let quotient = DensePolynomial::from_coefficients_vec(super::kzg_quotient(&coeffs, z));
+        let x = ark_bn254::Fr::rand(&mut rng);
+        assert_eq!(
+            quotient.evaluate(&x) * (x - z),
+            poly.evaluate(&x) - poly.evaluate(&z)
+        );
+        assert!(super::kzg_quotient(&[ark_bn254::Fr::from(5u64)], z).is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+//! The prover of the aggregation, implemented on the [`Groth16`] type.
+
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use circom_types::{
+    groth16::{Groth16Proof, JsonVerificationKey},
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+use rayon::prelude::*;
+
+use crate::groth16::Groth16;
+
+use super::{
+    commitment::{
+        fold_points, fold_scalars, inner_pairing_product, multiexponentiation, powers,
+        scale_points, Commitment, Key, KeyRef,
+    },
+    AggregateProof, GipaLast, GipaRound, KzgOpenings, ProverSrs, Transcript,
+};
+
+impl<P: Pairing> Groth16<P>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P: Pairing + CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    /// Aggregates Groth16 proofs for the same verification key into a single [`AggregateProof`].
+    ///
+    /// The number of proofs must match the [`ProverSrs`] (a power of two), and the n-th public
+    /// input belongs to the n-th proof. The proofs are not verified, an aggregate proof of
+    /// invalid proofs is rejected by [`Groth16::verify_aggregate_proof`].
+    /// This method does not use MPC.
+    pub fn aggregate_proofs(
+        srs: &ProverSrs<P>,
+        vk: &JsonVerificationKey<P>,
+        proofs: &[Groth16Proof<P>],
+        public_inputs: &[Vec<P::ScalarField>],
+    ) -> eyre::Result<AggregateProof<P>> {
+        if proofs.len() != public_inputs.len() {
+            return Err(eyre::eyre!(
+                "Got {} proofs, but {} public inputs",
+                proofs.len(),
+                public_inputs.len()
+            ));
+        }
+        eyre::ensure!(
+            proofs.len() == srs.num_proofs(),
+            "the SRS is specialized for {} proofs, but got {} proofs",
+            srs.num_proofs(),
+            proofs.len()
+        );
+        super::check_public_inputs(vk, public_inputs)?;
+        let n = proofs.len();
+        let a = proofs.iter().map(|proof| proof.pi_a).collect::<Vec<_>>();
+        let b = proofs.iter().map(|proof| proof.pi_b).collect::<Vec<_>>();
+        let c = proofs.iter().map(|proof| proof.pi_c).collect::<Vec<_>>();
+
+        // v = (a^i, b^i) * G2 commits to vectors in G1, w = (a^(n+i), b^(n+i)) * G1 to vectors in G2
+        let vkey = KeyRef {
+            alpha: &srs.h_alpha_powers[..n],
+            beta: &srs.h_beta_powers[..n],
+        };
+        let wkey = KeyRef {
+            alpha: &srs.g_alpha_powers[n..2 * n],
+            beta: &srs.g_beta_powers[n..2 * n],
+        };
+
+        let mut transcript = super::statement_transcript(vk, public_inputs);
+        let (com_ab, com_c) = rayon::join(
+            || Commitment::pair(vkey, wkey, &a, &b),
+            || Commitment::single(vkey, &c),
+        );
+        transcript.append(&com_ab);
+        transcript.append(&com_c);
+        let r = transcript.challenge::<P::ScalarField>();
+        let r_inv = r.inverse().expect("challenges are not zero");
+
+        // B_i * r^i under the key w_i * r^(-i) has the same commitment as B under w
+        let r_powers = powers(r, n);
+        let b_r = scale_points(&b, &r_powers);
+        let wkey_r_inv = Key::scale(wkey, &powers(r_inv, n));
+        let (ip_ab, agg_c) = rayon::join(
+            || inner_pairing_product::<P>(&a, &b_r),
+            || multiexponentiation(&c, &r_powers),
+        );
+        transcript.append(&ip_ab);
+        transcript.append(&agg_c);
+
+        let (rounds, last, challenges) =
+            prove_gipa(&mut transcript, a, b_r, c, r_powers, vkey, wkey_r_inv);
+        transcript.append(&last);
+        let z = transcript.challenge::<P::ScalarField>();
+        let openings = open_keys(srs, &challenges, r_inv, z);
+
+        Ok(AggregateProof {
+            com_ab,
+            com_c,
+            ip_ab,
+            agg_c,
+            rounds,
+            last,
+            openings,
+        })
+    }
+}
+
+/// Runs the GIPA recursion of the TIPP argument for `prod_i e(A_i, B_i)` and the MIPP argument for
+/// `sum_i r_i C_i`. In every round, the vectors are split into halves and folded with a challenge
+/// x: A, C, and w with `left + x * right`, B, r, and v with `left + x^(-1) * right`. Returns the
+/// cross terms of all rounds, the values after the last round, and the challenges.
+fn prove_gipa<P: Pairing>(
+    transcript: &mut Transcript,
+    mut a: Vec<P::G1Affine>,
+    mut b: Vec<P::G2Affine>,
+    mut c: Vec<P::G1Affine>,
+    mut r: Vec<P::ScalarField>,
+    vkey: KeyRef<P::G2Affine>,
+    mut wkey: Key<P::G1Affine>,
+) -> (Vec<GipaRound<P>>, GipaLast<P>, Vec<P::ScalarField>) {
+    let mut vkey = Key {
+        alpha: vkey.alpha.to_vec(),
+        beta: vkey.beta.to_vec(),
+    };
+    let mut rounds = Vec::new();
+    let mut challenges = Vec::new();
+    while a.len() > 1 {
+        let mid = a.len() / 2;
+        let (a_l, a_r) = a.split_at(mid);
+        let (b_l, b_r) = b.split_at(mid);
+        let (c_l, c_r) = c.split_at(mid);
+        let (r_l, r_r) = r.split_at(mid);
+        let (v_l, v_r) = vkey.as_ref().split_at(mid);
+        let (w_l, w_r) = wkey.as_ref().split_at(mid);
+
+        let ((com_ab_l, com_ab_r), ((com_c_l, com_c_r), ((z_ab_l, z_ab_r), (z_c_l, z_c_r)))) =
+            rayon::join(
+                || {
+                    rayon::join(
+                        || Commitment::pair(v_l, w_r, a_r, b_l),
+                        || Commitment::pair(v_r, w_l, a_l, b_r),
+                    )
+                },
+                || {
+                    rayon::join(
+                        || {
+                            rayon::join(
+                                || Commitment::single(v_l, c_r),
+                                || Commitment::single(v_r, c_l),
+                            )
+                        },
+                        || {
+                            rayon::join(
+                                || {
+                                    (
+                                        inner_pairing_product::<P>(a_r, b_l),
+                                        inner_pairing_product::<P>(a_l, b_r),
+                                    )
+                                },
+                                || (multiexponentiation(c_r, r_l), multiexponentiation(c_l, r_r)),
+                            )
+                        },
+                    )
+                },
+            );
+        let round = GipaRound {
+            com_ab_l,
+            com_ab_r,
+            com_c_l,
+            com_c_r,
+            z_ab_l,
+            z_ab_r,
+            z_c_l,
+            z_c_r,
+        };
+        transcript.append(&round);
+        let x = transcript.challenge::<P::ScalarField>();
+        let x_inv = x.inverse().expect("challenges are not zero");
+
+        let next_a = fold_points(a_l, a_r, x);
+        let next_b = fold_points(b_l, b_r, x_inv);
+        let next_c = fold_points(c_l, c_r, x);
+        let next_r = fold_scalars(r_l, r_r, x_inv);
+        let next_vkey = Key::fold(v_l, v_r, x_inv);
+        let next_wkey = Key::fold(w_l, w_r, x);
+        (a, b, c, r, vkey, wkey) = (next_a, next_b, next_c, next_r, next_vkey, next_wkey);
+        rounds.push(round);
+        challenges.push(x);
+    }
+    let last = GipaLast {
+        a: a[0],
+        b: b[0],
+        c: c[0],
+        vkey_alpha: vkey.alpha[0],
+        vkey_beta: vkey.beta[0],
+        wkey_alpha: wkey.alpha[0],
+        wkey_beta: wkey.beta[0],
+    };
+    (rounds, last, challenges)
+}
+
+/// Computes the KZG openings at `z` of the polynomials whose evaluations at the secrets of the SRS
+/// are the discrete logarithms of the last commitment keys of the GIPA recursion.
+fn open_keys<P: Pairing>(
+    srs: &ProverSrs<P>,
+    challenges: &[P::ScalarField],
+    r_inv: P::ScalarField,
+    z: P::ScalarField,
+) -> KzgOpenings<P> {
+    let n = srs.num_proofs();
+    // the last v is f_v(a) * G2 with f_v(X) = prod_j (1 + x_j^(-1) X^(n / 2^(j+1)))
+    let vkey_challenges = challenges
+        .iter()
+        .map(|x| x.inverse().expect("challenges are not zero"))
+        .collect::<Vec<_>>();
+    let vkey_quotient = super::kzg_quotient(&super::folding_polynomial(&vkey_challenges), z);
+
+    // the last w is f_w(a) * G1 with f_w(X) = X^n prod_j (1 + x_j (X / r)^(n / 2^(j+1)))
+    let wkey_challenges = wkey_challenges(challenges, r_inv);
+    let mut wkey_poly = vec![P::ScalarField::ZERO; n];
+    wkey_poly.extend(super::folding_polynomial(&wkey_challenges));
+    let wkey_quotient = super::kzg_quotient(&wkey_poly, z);
+
+    let openings = [
+        (&srs.h_alpha_powers, &srs.g_alpha_powers),
+        (&srs.h_beta_powers, &srs.g_beta_powers),
+    ]
+    .into_par_iter()
+    .map(|(h_powers, g_powers)| {
+        (
+            multiexponentiation(&h_powers[..vkey_quotient.len()], &vkey_quotient),
+            multiexponentiation(&g_powers[..wkey_quotient.len()], &wkey_quotient),
+        )
+    })
+    .collect::<Vec<_>>();
+    KzgOpenings {
+        vkey_alpha: openings[0].0,
+        vkey_beta: openings[1].0,
+        wkey_alpha: openings[0].1,
+        wkey_beta: openings[1].1,
+    }
+}
+
+/// Returns the challenges `x_j * r^(-n / 2^(j+1))` of the folding polynomial of the w key, which is
+/// rescaled with the powers of r^(-1).
+pub(super) fn wkey_challenges<F: Field>(challenges: &[F], r_inv: F) -> Vec<F> {
+    // r^(-1), r^(-2), r^(-4), ... belong to the rounds in reverse order
+    let mut r_inv_powers = Vec::with_capacity(challenges.len());
+    let mut r_inv_pow = r_inv;
+    for _ in challenges {
+        r_inv_powers.push(r_inv_pow);
+        r_inv_pow.square_in_place();
+    }
+    challenges
+        .iter()
+        .zip(r_inv_powers.iter().rev())
+        .map(|(x, r_inv_pow)| *x * r_inv_pow)
+        .collect()
+}
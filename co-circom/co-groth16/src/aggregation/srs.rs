@@ -0,0 +1,166 @@
+//! The structured reference string (SRS) of the aggregation scheme.
+//!
+//! The SRS consists of the powers of two independent secrets `a` and `b` in G1 and G2. It is
+//! independent of the aggregated circuit, so it can be derived from two existing powers of tau
+//! ceremonies (see [`AggregationSrs::from_powers_of_tau`]).
+
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::UniformRand;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use circom_types::ptau::PowersOfTau;
+use rand::{CryptoRng, Rng};
+use rayon::prelude::*;
+
+use super::commitment::powers;
+
+/// The generic SRS for aggregating up to [`max_proofs`](Self::max_proofs) Groth16 proofs. Use
+/// [`specialize`](Self::specialize) to obtain the [`ProverSrs`] for a fixed number of proofs and
+/// [`verifier_srs`](Self::verifier_srs) to obtain the (constant size) [`VerifierSrs`].
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationSrs<P: Pairing> {
+    /// a^i * G1 for i in 0..2 * max_proofs
+    pub g_alpha_powers: Vec<P::G1Affine>,
+    /// b^i * G1 for i in 0..2 * max_proofs
+    pub g_beta_powers: Vec<P::G1Affine>,
+    /// a^i * G2 for i in 0..max_proofs
+    pub h_alpha_powers: Vec<P::G2Affine>,
+    /// b^i * G2 for i in 0..max_proofs
+    pub h_beta_powers: Vec<P::G2Affine>,
+}
+
+/// The part of the [`AggregationSrs`] that is required to aggregate exactly
+/// [`num_proofs`](Self::num_proofs) proofs.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProverSrs<P: Pairing> {
+    pub(super) g_alpha_powers: Vec<P::G1Affine>,
+    pub(super) g_beta_powers: Vec<P::G1Affine>,
+    pub(super) h_alpha_powers: Vec<P::G2Affine>,
+    pub(super) h_beta_powers: Vec<P::G2Affine>,
+}
+
+/// The part of the [`AggregationSrs`] that is required to verify an aggregate proof. Its size does
+/// not depend on the number of aggregated proofs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifierSrs<P: Pairing> {
+    /// The generator of G1
+    pub g: P::G1Affine,
+    /// The generator of G2
+    pub h: P::G2Affine,
+    /// a * G1
+    pub g_alpha: P::G1Affine,
+    /// b * G1
+    pub g_beta: P::G1Affine,
+    /// a * G2
+    pub h_alpha: P::G2Affine,
+    /// b * G2
+    pub h_beta: P::G2Affine,
+}
+
+impl<P: Pairing> AggregationSrs<P> {
+    /// Derives the SRS from two powers of tau files, where `a` is the tau of the first and `b` the
+    /// tau of the second one. The SRS is only secure if the two ptaus stem from independent
+    /// ceremonies, i.e., if nobody knows both taus or their relation. A ptau with power `p` allows
+    /// to aggregate up to 2^(p-1) proofs.
+    pub fn from_powers_of_tau(
+        ptau_alpha: &PowersOfTau<P>,
+        ptau_beta: &PowersOfTau<P>,
+    ) -> eyre::Result<Self> {
+        let power = ptau_alpha.power.min(ptau_beta.power);
+        eyre::ensure!(
+            power >= 2,
+            "the powers of tau must have at least power 2, but got power {power}"
+        );
+        eyre::ensure!(
+            ptau_alpha.tau_g1[0] == ptau_beta.tau_g1[0]
+                && ptau_alpha.tau_g2[0] == ptau_beta.tau_g2[0],
+            "the powers of tau use different generators"
+        );
+        eyre::ensure!(
+            ptau_alpha.tau_g1[1] != ptau_beta.tau_g1[1],
+            "the powers of tau must stem from two independent ceremonies"
+        );
+        let max_proofs = 1 << (power - 1);
+        Ok(Self {
+            g_alpha_powers: ptau_alpha.tau_g1[..2 * max_proofs].to_vec(),
+            g_beta_powers: ptau_beta.tau_g1[..2 * max_proofs].to_vec(),
+            h_alpha_powers: ptau_alpha.tau_g2[..max_proofs].to_vec(),
+            h_beta_powers: ptau_beta.tau_g2[..max_proofs].to_vec(),
+        })
+    }
+
+    /// Creates an SRS for up to `max_proofs` proofs (a power of two) from local randomness.
+    ///
+    /// **This SRS is insecure**, as whoever knows the secrets can forge aggregate proofs. Only use
+    /// it for testing, otherwise use [`from_powers_of_tau`](Self::from_powers_of_tau).
+    pub fn insecure_setup<R: Rng + CryptoRng>(
+        rng: &mut R,
+        max_proofs: usize,
+    ) -> eyre::Result<Self> {
+        check_num_proofs(max_proofs)?;
+        let alpha = P::ScalarField::rand(rng);
+        let beta = P::ScalarField::rand(rng);
+        Ok(Self {
+            g_alpha_powers: powers_of_generator::<P::G1Affine>(alpha, 2 * max_proofs),
+            g_beta_powers: powers_of_generator::<P::G1Affine>(beta, 2 * max_proofs),
+            h_alpha_powers: powers_of_generator::<P::G2Affine>(alpha, max_proofs),
+            h_beta_powers: powers_of_generator::<P::G2Affine>(beta, max_proofs),
+        })
+    }
+
+    /// Returns the maximum number of proofs that can be aggregated with this SRS.
+    pub fn max_proofs(&self) -> usize {
+        self.h_alpha_powers.len()
+    }
+
+    /// Returns the [`ProverSrs`] to aggregate exactly `num_proofs` proofs, which must be a power of
+    /// two between 2 and [`max_proofs`](Self::max_proofs).
+    pub fn specialize(&self, num_proofs: usize) -> eyre::Result<ProverSrs<P>> {
+        check_num_proofs(num_proofs)?;
+        eyre::ensure!(
+            num_proofs <= self.max_proofs(),
+            "the SRS only supports up to {} proofs, but got {num_proofs}",
+            self.max_proofs()
+        );
+        Ok(ProverSrs {
+            g_alpha_powers: self.g_alpha_powers[..2 * num_proofs].to_vec(),
+            g_beta_powers: self.g_beta_powers[..2 * num_proofs].to_vec(),
+            h_alpha_powers: self.h_alpha_powers[..num_proofs].to_vec(),
+            h_beta_powers: self.h_beta_powers[..num_proofs].to_vec(),
+        })
+    }
+
+    /// Returns the [`VerifierSrs`].
+    pub fn verifier_srs(&self) -> VerifierSrs<P> {
+        VerifierSrs {
+            g: self.g_alpha_powers[0],
+            h: self.h_alpha_powers[0],
+            g_alpha: self.g_alpha_powers[1],
+            g_beta: self.g_beta_powers[1],
+            h_alpha: self.h_alpha_powers[1],
+            h_beta: self.h_beta_powers[1],
+        }
+    }
+}
+
+impl<P: Pairing> ProverSrs<P> {
+    /// Returns the number of proofs that can be aggregated with this SRS.
+    pub fn num_proofs(&self) -> usize {
+        self.h_alpha_powers.len()
+    }
+}
+
+pub(super) fn check_num_proofs(num_proofs: usize) -> eyre::Result<()> {
+    eyre::ensure!(
+        num_proofs >= 2 && num_proofs.is_power_of_two(),
+        "the number of aggregated proofs must be a power of two and at least 2, but got {num_proofs}"
+    );
+    Ok(())
+}
+
+fn powers_of_generator<G: AffineRepr>(x: G::ScalarField, len: usize) -> Vec<G> {
+    let points = powers(x, len)
+        .par_iter()
+        .map(|p| G::generator() * p)
+        .collect::<Vec<_>>();
+    G::Group::normalize_batch(&points)
+}
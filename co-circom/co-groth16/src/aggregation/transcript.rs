@@ -0,0 +1,40 @@
+//! The Fiat-Shamir transcript of the aggregation.
+
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use sha3::{Digest, Keccak256};
+
+pub(super) struct Transcript {
+    digest: Keccak256,
+}
+
+impl Transcript {
+    pub(super) fn new(label: &[u8]) -> Self {
+        let mut digest = Keccak256::default();
+        digest.update(label);
+        Self { digest }
+    }
+
+    /// Absorbs an element. The length of its serialization is absorbed as well, so the encoding of
+    /// a sequence of elements is unambiguous.
+    pub(super) fn append<S: CanonicalSerialize>(&mut self, element: &S) {
+        let mut buf = Vec::with_capacity(element.uncompressed_size());
+        element
+            .serialize_uncompressed(&mut buf)
+            .expect("can serialize into a Vec");
+        self.digest.update((buf.len() as u64).to_le_bytes());
+        self.digest.update(&buf);
+    }
+
+    /// Squeezes a non-zero challenge, which is absorbed into the transcript afterwards.
+    pub(super) fn challenge<F: PrimeField>(&mut self) -> F {
+        loop {
+            let hash = self.digest.clone().finalize();
+            self.digest.update(hash);
+            let challenge = F::from_be_bytes_mod_order(&hash);
+            if !challenge.is_zero() {
+                return challenge;
+            }
+        }
+    }
+}
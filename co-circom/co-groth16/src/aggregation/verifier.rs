@@ -0,0 +1,155 @@
+//! The verifier of the aggregation, implemented on the [`Groth16`] type.
+
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::Field;
+use circom_types::{
+    groth16::JsonVerificationKey,
+    traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+use num_traits::Zero;
+
+use crate::groth16::Groth16;
+
+use super::{
+    commitment::{powers, Commitment, KeyRef},
+    prover::wkey_challenges,
+    AggregateProof, VerifierSrs,
+};
+
+impl<P: Pairing> Groth16<P>
+where
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+    P: Pairing + CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+{
+    /// Verifies an [`AggregateProof`] created by [`Groth16::aggregate_proofs`], where the n-th
+    /// public input belongs to the n-th aggregated proof.
+    ///
+    /// Returns `true` iff all aggregated proofs are valid (except with negligible probability).
+    /// This method does not use MPC.
+    pub fn verify_aggregate_proof(
+        srs: &VerifierSrs<P>,
+        vk: &JsonVerificationKey<P>,
+        proof: &AggregateProof<P>,
+        public_inputs: &[Vec<P::ScalarField>],
+    ) -> eyre::Result<bool> {
+        super::check_public_inputs(vk, public_inputs)?;
+        let n = public_inputs.len();
+        eyre::ensure!(
+            proof.rounds.len() == n.ilog2() as usize,
+            "the aggregate proof is for {} proofs, but got {n} public inputs",
+            1usize.checked_shl(proof.rounds.len() as u32).unwrap_or(0)
+        );
+
+        let mut transcript = super::statement_transcript(vk, public_inputs);
+        transcript.append(&proof.com_ab);
+        transcript.append(&proof.com_c);
+        let r = transcript.challenge::<P::ScalarField>();
+        let r_inv = r.inverse().expect("challenges are not zero");
+        transcript.append(&proof.ip_ab);
+        transcript.append(&proof.agg_c);
+
+        // replay the GIPA recursion on the commitments and inner products
+        let mut com_ab = proof.com_ab;
+        let mut com_c = proof.com_c;
+        let mut z_ab = proof.ip_ab;
+        let mut z_c = proof.agg_c.into_group();
+        let mut challenges = Vec::with_capacity(proof.rounds.len());
+        for round in proof.rounds.iter() {
+            transcript.append(round);
+            let x = transcript.challenge::<P::ScalarField>();
+            let x_inv = x.inverse().expect("challenges are not zero");
+            com_ab = com_ab.fold(round.com_ab_l, round.com_ab_r, x, x_inv);
+            com_c = com_c.fold(round.com_c_l, round.com_c_r, x, x_inv);
+            z_ab = z_ab + round.z_ab_l * x + round.z_ab_r * x_inv;
+            z_c += round.z_c_l * x + round.z_c_r * x_inv;
+            challenges.push(x);
+        }
+        let last = &proof.last;
+        transcript.append(last);
+        let z = transcript.challenge::<P::ScalarField>();
+
+        let x_inv_challenges = challenges
+            .iter()
+            .map(|x| x.inverse().expect("challenges are not zero"))
+            .collect::<Vec<_>>();
+
+        // the folded values must satisfy the relations for vectors of length one
+        let vkey = KeyRef {
+            alpha: &[last.vkey_alpha],
+            beta: &[last.vkey_beta],
+        };
+        let wkey = KeyRef {
+            alpha: &[last.wkey_alpha],
+            beta: &[last.wkey_beta],
+        };
+        let r_last = super::evaluate_folding_polynomial(&x_inv_challenges, r);
+        let gipa_valid = com_ab == Commitment::pair(vkey, wkey, &[last.a], &[last.b])
+            && com_c == Commitment::single(vkey, &[last.c])
+            && z_ab == P::pairing(last.a, last.b)
+            && z_c == last.c * r_last;
+
+        // the last keys must be the folded keys of the SRS
+        let f_v = super::evaluate_folding_polynomial(&x_inv_challenges, z);
+        let f_w = z.pow([n as u64])
+            * super::evaluate_folding_polynomial(&wkey_challenges(&challenges, r_inv), z);
+        let g_z = srs.g * z;
+        let h_z = srs.h * z;
+        let h_f_v = srs.h * f_v;
+        let g_f_w = srs.g * f_w;
+        let openings = &proof.openings;
+        // e(g, v - f_v(z) * h) = e((alpha - z) * g, opening) for the v key, and
+        // e(w - f_w(z) * g, h) = e(opening, (alpha - z) * h) for the w key
+        let kzg_valid = P::multi_pairing(
+            [srs.g.into_group(), g_z - srs.g_alpha],
+            [
+                last.vkey_alpha.into_group() - h_f_v,
+                openings.vkey_alpha.into_group(),
+            ],
+        )
+        .is_zero()
+            && P::multi_pairing(
+                [srs.g.into_group(), g_z - srs.g_beta],
+                [
+                    last.vkey_beta.into_group() - h_f_v,
+                    openings.vkey_beta.into_group(),
+                ],
+            )
+            .is_zero()
+            && P::multi_pairing(
+                [
+                    last.wkey_alpha.into_group() - g_f_w,
+                    openings.wkey_alpha.into_group(),
+                ],
+                [srs.h.into_group(), h_z - srs.h_alpha],
+            )
+            .is_zero()
+            && P::multi_pairing(
+                [
+                    last.wkey_beta.into_group() - g_f_w,
+                    openings.wkey_beta.into_group(),
+                ],
+                [srs.h.into_group(), h_z - srs.h_beta],
+            )
+            .is_zero();
+
+        // the Groth16 equations combined with the powers of r:
+        // prod_i e(A_i, B_i)^(r^i) = e(sum_i r^i alpha, beta) * e(sum_i r^i vk_x_i, gamma) * e(sum_i r^i C_i, delta)
+        let r_powers = powers(r, n);
+        let r_sum = r_powers.iter().sum::<P::ScalarField>();
+        let mut inputs_acc = vec![P::ScalarField::zero(); vk.ic.len() - 1];
+        for (r_pow, inputs) in r_powers.iter().zip(public_inputs) {
+            for (acc, input) in inputs_acc.iter_mut().zip(inputs) {
+                *acc += *r_pow * input;
+            }
+        }
+        let vk_x = vk.ic[0] * r_sum + P::G1::msm_unchecked(&vk.ic[1..], &inputs_acc);
+        let groth16_valid = proof.ip_ab
+            == P::multi_pairing(
+                [vk.alpha_1 * r_sum, vk_x, proof.agg_c.into_group()],
+                [vk.beta_2, vk.gamma_2, vk.delta_2],
+            );
+
+        Ok(gipa_valid && kzg_valid && groth16_valid)
+    }
+}
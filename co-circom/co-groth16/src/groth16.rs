@@ -4,13 +4,12 @@ use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::{FftField, PrimeField};
 use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
 use ark_relations::r1cs::{ConstraintMatrices, SynthesisError};
-use circom_types::groth16::{Groth16Proof, ZKey};
+use circom_types::groth16::{Groth16Proof, LazyPoints, LazyZKey, ZKey};
 use circom_types::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
 use co_circom_snarks::SharedWitness;
 use eyre::Result;
-use itertools::izip;
 use mpc_core::protocols::plain::PlainDriver;
-use mpc_core::traits::{EcMpcProtocol, MSMProvider};
+use mpc_core::traits::{EcMpcProtocol, FieldShareVecTrait, MSMProvider};
 use mpc_core::{
     protocols::rep3::{network::Rep3MpcNet, Rep3Protocol},
     traits::{FFTProvider, PairingEcMpcProtocol, PrimeFieldMpcProtocol},
@@ -18,7 +17,10 @@ use mpc_core::{
 use mpc_net::config::NetworkConfig;
 use num_traits::identities::One;
 use num_traits::ToPrimitive;
+use rayon::prelude::*;
+use std::borrow::Cow;
 use std::marker::PhantomData;
+use std::ops::Range;
 
 /// The plain [`Groth16`] type.
 ///
@@ -76,6 +78,114 @@ fn root_of_unity_for_groth16<F: PrimeField + FftField>(
     }
 }
 
+/// A query of the proving key, which is either held in memory or parsed from a memory-mapped zkey on demand.
+enum Query<'a, A> {
+    InMemory(&'a [A]),
+    Lazy(&'a LazyPoints<A>),
+}
+
+impl<'a, A: Clone> Query<'a, A> {
+    fn get(&self, range: Range<usize>) -> Result<Cow<'a, [A]>> {
+        match self {
+            Query::InMemory(query) => Ok(Cow::Borrowed(&query[range])),
+            Query::Lazy(query) => Ok(Cow::Owned(query.get(range)?)),
+        }
+    }
+}
+
+/// Describes how the quotient polynomial H is committed to by the h_query of a proving key.
+#[derive(Clone, Copy)]
+enum Reduction {
+    /// The h_query is used with the evaluations of H on a coset of the domain of size 2^pow, as in circom zkeys.
+    Circom { pow: usize },
+    /// The h_query is used with the coefficients of H, as in arkworks proving keys.
+    #[cfg(feature = "ark-groth16")]
+    Libsnark,
+}
+
+/// The parts of a [ZKey], [LazyZKey], or arkworks proving key which are required to create a proof.
+struct ProvingKey<'a, P: Pairing> {
+    reduction: Reduction,
+    matrices: &'a ConstraintMatrices<P::ScalarField>,
+    alpha_g1: P::G1Affine,
+    beta_g1: P::G1Affine,
+    beta_g2: P::G2Affine,
+    delta_g1: P::G1Affine,
+    delta_g2: P::G2Affine,
+    a_query: Query<'a, P::G1Affine>,
+    b_g1_query: Query<'a, P::G1Affine>,
+    b_g2_query: Query<'a, P::G2Affine>,
+    h_query: Query<'a, P::G1Affine>,
+    l_query: Query<'a, P::G1Affine>,
+    // The maximum number of points of a query used in a single MSM
+    chunk_size: usize,
+}
+
+impl<'a, P: Pairing> From<&'a ZKey<P>> for ProvingKey<'a, P> {
+    fn from(zkey: &'a ZKey<P>) -> Self {
+        Self {
+            reduction: Reduction::Circom { pow: zkey.pow },
+            matrices: &zkey.matrices,
+            alpha_g1: zkey.vk.alpha_g1,
+            beta_g1: zkey.beta_g1,
+            beta_g2: zkey.vk.beta_g2,
+            delta_g1: zkey.delta_g1,
+            delta_g2: zkey.vk.delta_g2,
+            a_query: Query::InMemory(&zkey.a_query),
+            b_g1_query: Query::InMemory(&zkey.b_g1_query),
+            b_g2_query: Query::InMemory(&zkey.b_g2_query),
+            h_query: Query::InMemory(&zkey.h_query),
+            l_query: Query::InMemory(&zkey.l_query),
+            // everything is in memory anyway
+            chunk_size: usize::MAX,
+        }
+    }
+}
+
+impl<'a, P: Pairing> ProvingKey<'a, P> {
+    fn from_lazy_zkey(zkey: &'a LazyZKey<P>, chunk_size: usize) -> Self {
+        Self {
+            reduction: Reduction::Circom { pow: zkey.pow },
+            matrices: &zkey.matrices,
+            alpha_g1: zkey.vk.alpha_g1,
+            beta_g1: zkey.beta_g1,
+            beta_g2: zkey.vk.beta_g2,
+            delta_g1: zkey.delta_g1,
+            delta_g2: zkey.vk.delta_g2,
+            a_query: Query::Lazy(&zkey.a_query),
+            b_g1_query: Query::Lazy(&zkey.b_g1_query),
+            b_g2_query: Query::Lazy(&zkey.b_g2_query),
+            h_query: Query::Lazy(&zkey.h_query),
+            l_query: Query::Lazy(&zkey.l_query),
+            chunk_size,
+        }
+    }
+}
+
+#[cfg(feature = "ark-groth16")]
+impl<'a, P: Pairing> ProvingKey<'a, P> {
+    fn from_ark_proving_key(
+        pk: &'a ark_groth16::ProvingKey<P>,
+        matrices: &'a ConstraintMatrices<P::ScalarField>,
+    ) -> Self {
+        Self {
+            reduction: Reduction::Libsnark,
+            matrices,
+            alpha_g1: pk.vk.alpha_g1,
+            beta_g1: pk.beta_g1,
+            beta_g2: pk.vk.beta_g2,
+            delta_g1: pk.delta_g1,
+            delta_g2: pk.vk.delta_g2,
+            a_query: Query::InMemory(&pk.a_query),
+            b_g1_query: Query::InMemory(&pk.b_g1_query),
+            b_g2_query: Query::InMemory(&pk.b_g2_query),
+            h_query: Query::InMemory(&pk.h_query),
+            l_query: Query::InMemory(&pk.l_query),
+            chunk_size: usize::MAX,
+        }
+    }
+}
+
 /// A Groth16 proof protocol that uses a collaborative MPC protocol to generate the proof.
 pub struct CoGroth16<T, P: Pairing>
 where
@@ -95,7 +205,8 @@ where
         + PairingEcMpcProtocol<P>
         + FFTProvider<P::ScalarField>
         + MSMProvider<P::G1>
-        + MSMProvider<P::G2>,
+        + MSMProvider<P::G2>
+        + Sync,
     P: CircomArkworksPairingBridge,
     P::BaseField: CircomArkworksPrimeFieldBridge,
     P::ScalarField: CircomArkworksPrimeFieldBridge,
@@ -115,27 +226,118 @@ where
         zkey: &ZKey<P>,
         private_witness: SharedWitness<T, P>,
     ) -> Result<Groth16Proof<P>> {
-        let matrices = &zkey.matrices;
+        self.prove_inner(ProvingKey::from(zkey), private_witness)
+    }
+
+    /// Execute the Groth16 prover using the internal MPC driver, where the queries of the proving key are parsed from the memory-mapped [LazyZKey] on demand.
+    /// The multi-scalar multiplications are computed in chunks of at most `chunk_size` points. Thus, the memory required for the queries is proportional to `chunk_size` instead of the size of the circuit.
+    pub fn prove_with_lazy_zkey(
+        &mut self,
+        zkey: &LazyZKey<P>,
+        chunk_size: usize,
+        private_witness: SharedWitness<T, P>,
+    ) -> Result<Groth16Proof<P>> {
+        eyre::ensure!(chunk_size > 0, "the chunk size must be positive");
+        self.prove_inner(
+            ProvingKey::from_lazy_zkey(zkey, chunk_size),
+            private_witness,
+        )
+    }
+
+    /// Execute the Groth16 prover using the internal MPC driver for a circuit which is not given as a circom zkey, but as an arkworks [ProvingKey](ark_groth16::ProvingKey) together with the [ConstraintMatrices] of its constraint system (see, e.g., [ConstraintSystem::to_matrices](ark_relations::r1cs::ConstraintSystem::to_matrices)).
+    /// The shared witness has to follow the variable order of the constraint system, i.e., the public inputs start with the constant 1 and the witness contains the private variables.
+    /// In contrast to [prove](Self::prove), the quotient polynomial is computed like in arkworks, so the proof can be verified with [ark_groth16::Groth16::verify_proof].
+    #[cfg(feature = "ark-groth16")]
+    pub fn prove_with_matrices(
+        &mut self,
+        pk: &ark_groth16::ProvingKey<P>,
+        matrices: &ConstraintMatrices<P::ScalarField>,
+        private_witness: SharedWitness<T, P>,
+    ) -> Result<ark_groth16::Proof<P>> {
+        eyre::ensure!(
+            private_witness.public_inputs.len() == matrices.num_instance_variables
+                && private_witness.witness.get_len() == matrices.num_witness_variables,
+            "the shared witness does not match the constraint matrices"
+        );
+        // the queries are indexed by the variables (and the domain) of the constraint system, so a
+        // proving key of another circuit must not lead to an out-of-bounds access
+        let num_variables = matrices.num_instance_variables + matrices.num_witness_variables;
+        for (name, len) in [
+            ("a_query", pk.a_query.len()),
+            ("b_g1_query", pk.b_g1_query.len()),
+            ("b_g2_query", pk.b_g2_query.len()),
+        ] {
+            eyre::ensure!(
+                len >= num_variables,
+                "the {name} of the proving key has {len} points, but the constraint system has {num_variables} variables"
+            );
+        }
+        eyre::ensure!(
+            pk.l_query.len() >= matrices.num_witness_variables,
+            "the l_query of the proving key has {} points, but the constraint system has {} witness variables",
+            pk.l_query.len(),
+            matrices.num_witness_variables
+        );
+        let domain_size = GeneralEvaluationDomain::<P::ScalarField>::new(
+            matrices.num_constraints + matrices.num_instance_variables,
+        )
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?
+        .size();
+        // H has degree domain_size - 2, so the h_query of arkworks has one point less than the domain
+        eyre::ensure!(
+            pk.h_query.len() >= domain_size - 1,
+            "the h_query of the proving key has {} points, but the domain of the constraint system has size {domain_size}",
+            pk.h_query.len()
+        );
+        let proof = self.prove_inner(
+            ProvingKey::from_ark_proving_key(pk, matrices),
+            private_witness,
+        )?;
+        Ok(ark_groth16::Proof {
+            a: proof.pi_a,
+            b: proof.pi_b,
+            c: proof.pi_c,
+        })
+    }
+
+    fn prove_inner(
+        &mut self,
+        pk: ProvingKey<P>,
+        private_witness: SharedWitness<T, P>,
+    ) -> Result<Groth16Proof<P>> {
+        let matrices = pk.matrices;
         let num_inputs = matrices.num_instance_variables;
         let num_constraints = matrices.num_constraints;
         let public_inputs = &private_witness.public_inputs;
         let private_witness = &private_witness.witness;
         tracing::debug!("calling witness map from matrices...");
-        let h = self.witness_map_from_matrices(
-            zkey.pow,
-            matrices,
-            num_constraints,
-            num_inputs,
-            public_inputs,
-            private_witness,
-        )?;
+        self.driver.set_phase("groth16: witness map");
+        let h = match pk.reduction {
+            Reduction::Circom { pow } => self.witness_map_from_matrices(
+                pow,
+                matrices,
+                num_constraints,
+                num_inputs,
+                public_inputs,
+                private_witness,
+            )?,
+            #[cfg(feature = "ark-groth16")]
+            Reduction::Libsnark => self.ark_witness_map_from_matrices(
+                matrices,
+                num_constraints,
+                num_inputs,
+                public_inputs,
+                private_witness,
+            )?,
+        };
         tracing::debug!("done!");
         tracing::debug!("getting r and s...");
+        self.driver.set_phase("groth16: randomness");
         let r = self.driver.rand()?;
         let s = self.driver.rand()?;
         tracing::debug!("done!");
         tracing::debug!("calling create_proof_with_assignment...");
-        self.create_proof_with_assignment(zkey, r, s, &h, &public_inputs[1..], private_witness)
+        self.create_proof_with_assignment(&pk, r, s, &h, &public_inputs[1..], private_witness)
     }
 
     fn witness_map_from_matrices(
@@ -151,19 +353,84 @@ where
             GeneralEvaluationDomain::<P::ScalarField>::new(num_constraints + num_inputs)
                 .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
         let root_of_unity = root_of_unity_for_groth16(power, &mut domain);
+        self.evaluate_on_coset(
+            &domain,
+            root_of_unity,
+            matrices,
+            num_constraints,
+            num_inputs,
+            public_inputs,
+            private_witness,
+        )
+    }
 
+    /// The witness map of arkworks (i.e., the `LibsnarkReduction`), which returns the coefficients of the quotient polynomial H instead of its evaluations.
+    #[cfg(feature = "ark-groth16")]
+    fn ark_witness_map_from_matrices(
+        &mut self,
+        matrices: &ConstraintMatrices<P::ScalarField>,
+        num_constraints: usize,
+        num_inputs: usize,
+        public_inputs: &[P::ScalarField],
+        private_witness: &FieldShareVec<T, P>,
+    ) -> Result<FieldShareVec<T, P>> {
+        let domain = GeneralEvaluationDomain::<P::ScalarField>::new(num_constraints + num_inputs)
+            .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let coset_shift = P::ScalarField::GENERATOR;
+        let mut h = self.evaluate_on_coset(
+            &domain,
+            coset_shift,
+            matrices,
+            num_constraints,
+            num_inputs,
+            public_inputs,
+            private_witness,
+        )?;
+        // divide by the vanishing polynomial, which is constant on the coset, and interpolate on the coset
+        let vanishing_polynomial_over_coset =
+            ark_ff::Field::inverse(&domain.evaluate_vanishing_polynomial(coset_shift))
+                .ok_or(SynthesisError::UnexpectedIdentity)?;
+        self.driver.ifft_in_place(&mut h, &domain);
+        self.driver.distribute_powers_and_mul_by_const(
+            &mut h,
+            ark_ff::Field::inverse(&coset_shift).expect("generator is not zero"),
+            vanishing_polynomial_over_coset,
+        );
+        // H has degree domain_size - 2, so the h_query has one point less than the domain
+        let h = (0..domain.size() - 1)
+            .map(|i| h.index(i))
+            .collect::<Vec<_>>();
+        Ok(FieldShareVec::<T, P>::from(h))
+    }
+
+    /// Computes the evaluations of A * B - C on the coset of the domain given by `coset_shift`, where A, B, and C are the polynomials interpolating the evaluations of the constraints on the domain.
+    /// Circom zkeys do not contain the matrix C, in which case the evaluations of C are computed as the product of the evaluations of A and B.
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_on_coset(
+        &mut self,
+        domain: &GeneralEvaluationDomain<P::ScalarField>,
+        coset_shift: P::ScalarField,
+        matrices: &ConstraintMatrices<P::ScalarField>,
+        num_constraints: usize,
+        num_inputs: usize,
+        public_inputs: &[P::ScalarField],
+        private_witness: &FieldShareVec<T, P>,
+    ) -> Result<FieldShareVec<T, P>> {
         let domain_size = domain.size();
-        let mut a = vec![FieldShare::<T, P>::default(); domain_size];
-        let mut b = vec![FieldShare::<T, P>::default(); domain_size];
         tracing::debug!("evaluating constraints..");
-        for (a, b, at_i, bt_i) in izip!(&mut a, &mut b, &matrices.a, &matrices.b) {
-            *a = self
-                .driver
-                .evaluate_constraint(at_i, public_inputs, private_witness);
-            *b = self
-                .driver
-                .evaluate_constraint(bt_i, public_inputs, private_witness);
-        }
+        let driver = &self.driver;
+        let evaluate_constraints = |matrix: &[Vec<(P::ScalarField, usize)>]| {
+            let mut evals = matrix
+                .par_iter()
+                .map(|lhs| driver.evaluate_constraint(lhs, public_inputs, private_witness))
+                .collect::<Vec<_>>();
+            evals.resize(domain_size, FieldShare::<T, P>::default());
+            evals
+        };
+        let (a, b) = rayon::join(
+            || evaluate_constraints(&matrices.a),
+            || evaluate_constraints(&matrices.b),
+        );
         tracing::debug!("done!");
         let mut a = FieldShareVec::<T, P>::from(a);
         let promoted_public = self.driver.promote_to_trivial_shares(public_inputs);
@@ -171,72 +438,99 @@ where
             .clone_from_slice(&mut a, &promoted_public, num_constraints, 0, num_inputs);
 
         let mut b = FieldShareVec::<T, P>::from(b);
-        let mut c = self.driver.mul_vec(&a, &b)?;
-        self.driver.ifft_in_place(&mut a, &domain);
-        self.driver.ifft_in_place(&mut b, &domain);
-        self.driver.distribute_powers_and_mul_by_const(
-            &mut a,
-            root_of_unity,
-            P::ScalarField::one(),
-        );
-        self.driver.distribute_powers_and_mul_by_const(
-            &mut b,
-            root_of_unity,
-            P::ScalarField::one(),
-        );
-        self.driver.fft_in_place(&mut a, &domain);
-        self.driver.fft_in_place(&mut b, &domain);
+        let mut c = if matrices.c.is_empty() {
+            self.driver.mul_vec(&a, &b)?
+        } else {
+            FieldShareVec::<T, P>::from(evaluate_constraints(&matrices.c))
+        };
+        self.driver.ifft_in_place(&mut a, domain);
+        self.driver.ifft_in_place(&mut b, domain);
+        self.driver
+            .distribute_powers_and_mul_by_const(&mut a, coset_shift, P::ScalarField::one());
+        self.driver
+            .distribute_powers_and_mul_by_const(&mut b, coset_shift, P::ScalarField::one());
+        self.driver.fft_in_place(&mut a, domain);
+        self.driver.fft_in_place(&mut b, domain);
         //this can be in-place so that we do not have to allocate memory
         let mut ab = self.driver.mul_vec(&a, &b)?;
         std::mem::drop(a);
         std::mem::drop(b);
 
-        self.driver.ifft_in_place(&mut c, &domain);
-        self.driver.distribute_powers_and_mul_by_const(
-            &mut c,
-            root_of_unity,
-            P::ScalarField::one(),
-        );
-        self.driver.fft_in_place(&mut c, &domain);
+        self.driver.ifft_in_place(&mut c, domain);
+        self.driver
+            .distribute_powers_and_mul_by_const(&mut c, coset_shift, P::ScalarField::one());
+        self.driver.fft_in_place(&mut c, domain);
 
         self.driver.sub_assign_vec(&mut ab, &c);
         Ok(ab)
     }
 
+    /// Computes the MSM of the points query\[offset..offset + scalars.len()\] and the shared scalars, where at most chunk_size points are parsed and used at once.
+    fn msm_chunked<C: CurveGroup>(
+        &self,
+        query: &Query<C::Affine>,
+        offset: usize,
+        scalars: &CurveFieldShareVec<T, C>,
+        chunk_size: usize,
+    ) -> Result<PointShare<T, C>>
+    where
+        T: EcMpcProtocol<C>,
+        T: MSMProvider<C>,
+    {
+        let len = scalars.get_len();
+        if len <= chunk_size {
+            let points = query.get(offset..offset + len)?;
+            return Ok(MSMProvider::<C>::msm_public_points(
+                &self.driver,
+                &points,
+                scalars,
+            ));
+        }
+        let mut acc = None;
+        for start in (0..len).step_by(chunk_size) {
+            let end = (start + chunk_size).min(len);
+            let points = query.get(offset + start..offset + end)?;
+            let scalars = CurveFieldShareVec::<T, C>::from(
+                (start..end).map(|i| scalars.index(i)).collect::<Vec<_>>(),
+            );
+            let res = MSMProvider::<C>::msm_public_points(&self.driver, &points, &scalars);
+            match acc.as_mut() {
+                Some(acc) => EcMpcProtocol::<C>::add_assign_points(&self.driver, acc, &res),
+                None => acc = Some(res),
+            }
+        }
+        Ok(acc.expect("there is at least one chunk"))
+    }
+
     fn calculate_coeff<C: CurveGroup>(
-        &mut self,
-        initial: PointShare<T, C>,
-        query: &[C::Affine],
+        &self,
+        query: &Query<C::Affine>,
         vk_param: C::Affine,
         input_assignment: &[C::ScalarField],
         aux_assignment: &CurveFieldShareVec<T, C>,
-    ) -> PointShare<T, C>
+        chunk_size: usize,
+    ) -> Result<PointShare<T, C>>
     where
         T: EcMpcProtocol<C>,
         T: MSMProvider<C>,
     {
         tracing::debug!("calculate coeffs..");
         let pub_len = input_assignment.len();
-        let pub_acc = C::msm_unchecked(&query[1..=pub_len], input_assignment);
-        let priv_acc = MSMProvider::<C>::msm_public_points(
-            &mut self.driver,
-            &query[1 + pub_len..],
-            aux_assignment,
-        );
+        let pub_query = query.get(0..pub_len + 1)?;
+        let pub_acc = C::msm_unchecked(&pub_query[1..], input_assignment);
+        let mut res = self.msm_chunked::<C>(query, 1 + pub_len, aux_assignment, chunk_size)?;
 
-        let mut res = initial;
-        EcMpcProtocol::<C>::add_assign_points_public_affine(&mut self.driver, &mut res, &query[0]);
-        EcMpcProtocol::<C>::add_assign_points_public_affine(&mut self.driver, &mut res, &vk_param);
-        EcMpcProtocol::<C>::add_assign_points_public(&mut self.driver, &mut res, &pub_acc);
-        EcMpcProtocol::<C>::add_assign_points(&mut self.driver, &mut res, &priv_acc);
+        EcMpcProtocol::<C>::add_assign_points_public_affine(&self.driver, &mut res, &pub_query[0]);
+        EcMpcProtocol::<C>::add_assign_points_public_affine(&self.driver, &mut res, &vk_param);
+        EcMpcProtocol::<C>::add_assign_points_public(&self.driver, &mut res, &pub_acc);
 
         tracing::debug!("done..");
-        res
+        Ok(res)
     }
 
     fn create_proof_with_assignment(
         &mut self,
-        zkey: &ZKey<P>,
+        pk: &ProvingKey<P>,
         r: FieldShare<T, P>,
         s: FieldShare<T, P>,
         h: &FieldShareVec<T, P>,
@@ -244,77 +538,84 @@ where
         aux_assignment: &FieldShareVec<T, P>,
     ) -> Result<Groth16Proof<P>> {
         tracing::debug!("create proof with assignment...");
-        //let c_acc_time = start_timer!(|| "Compute C");
-        let h_acc = MSMProvider::<P::G1>::msm_public_points(&mut self.driver, &zkey.h_query, h);
-
-        // Compute C
-        let l_aux_acc = MSMProvider::<P::G1>::msm_public_points(
-            &mut self.driver,
-            &zkey.l_query,
-            aux_assignment,
+        // The MSMs of the proof elements do not depend on each other, so we compute all of them in parallel before any communication happens. Afterwards, all proof elements are opened in a single round:
+        // A = alpha + sum_i a_i * A_i + r * delta
+        // B = beta + sum_i a_i * B_i + s * delta
+        // C = H + L + s * A + r * B_1 - r * s * delta = H + L + s * A + r * (B_1 - s * delta)
+        // Thus, we do not need to compute r * s, and C can be opened together with A and B by directly opening the products s * A and r * (B_1 - s * delta).
+        let chunk_size = pk.chunk_size;
+        self.driver.set_phase("groth16: msms");
+        let ((h_acc, l_aux_acc), (g_a, (g1_b, g2_b))) = rayon::join(
+            || {
+                rayon::join(
+                    || self.msm_chunked::<P::G1>(&pk.h_query, 0, h, chunk_size),
+                    || self.msm_chunked::<P::G1>(&pk.l_query, 0, aux_assignment, chunk_size),
+                )
+            },
+            || {
+                rayon::join(
+                    || {
+                        self.calculate_coeff::<P::G1>(
+                            &pk.a_query,
+                            pk.alpha_g1,
+                            input_assignment,
+                            aux_assignment,
+                            chunk_size,
+                        )
+                    },
+                    || {
+                        rayon::join(
+                            || {
+                                // In the original implementation this is skipped if r==0, however r is shared in our case. We also skip adding s * delta here, see above.
+                                self.calculate_coeff::<P::G1>(
+                                    &pk.b_g1_query,
+                                    pk.beta_g1,
+                                    input_assignment,
+                                    aux_assignment,
+                                    chunk_size,
+                                )
+                            },
+                            || {
+                                self.calculate_coeff::<P::G2>(
+                                    &pk.b_g2_query,
+                                    pk.beta_g2,
+                                    input_assignment,
+                                    aux_assignment,
+                                    chunk_size,
+                                )
+                            },
+                        )
+                    },
+                )
+            },
         );
-
-        let delta_g1 = zkey.delta_g1.into_group();
-        let rs = self.driver.mul(&r, &s)?;
-        let r_s_delta_g1 = self.driver.scalar_mul_public_point(&delta_g1, &rs);
-
-        //end_timer!(c_acc_time);
+        let (h_acc, l_aux_acc, mut g_a, g1_b, mut g2_b) = (h_acc?, l_aux_acc?, g_a?, g1_b?, g2_b?);
 
         // Compute A
-        // let a_acc_time = start_timer!(|| "Compute A");
+        let delta_g1 = pk.delta_g1.into_group();
         let r_g1 = self.driver.scalar_mul_public_point(&delta_g1, &r);
-
-        let g_a = self.calculate_coeff::<P::G1>(
-            r_g1,
-            &zkey.a_query,
-            zkey.vk.alpha_g1,
-            input_assignment,
-            aux_assignment,
-        );
-
-        // Open here since g_a is part of proof
-        let g_a_opened = EcMpcProtocol::<P::G1>::open_point(&mut self.driver, &g_a)?;
-        let s_g_a = self.driver.scalar_mul_public_point(&g_a_opened, &s);
-        // end_timer!(a_acc_time);
-
-        // Compute B in G1
-        // In original implementation this is skipped if r==0, however r is shared in our case
-        //  let b_g1_acc_time = start_timer!(|| "Compute B in G1");
-        let s_g1 = self.driver.scalar_mul_public_point(&delta_g1, &s);
-        let g1_b = self.calculate_coeff::<P::G1>(
-            s_g1,
-            &zkey.b_g1_query,
-            zkey.beta_g1,
-            input_assignment,
-            aux_assignment,
-        );
-        let r_g1_b = EcMpcProtocol::<P::G1>::scalar_mul(&mut self.driver, &g1_b, &r)?;
-        //  end_timer!(b_g1_acc_time);
+        EcMpcProtocol::<P::G1>::add_assign_points(&self.driver, &mut g_a, &r_g1);
 
         // Compute B in G2
-        let delta_g2 = zkey.vk.delta_g2.into_group();
-        //  let b_g2_acc_time = start_timer!(|| "Compute B in G2");
+        let delta_g2 = pk.delta_g2.into_group();
         let s_g2 = self.driver.scalar_mul_public_point(&delta_g2, &s);
-        let g2_b = self.calculate_coeff::<P::G2>(
-            s_g2,
-            &zkey.b_g2_query,
-            zkey.vk.beta_g2,
-            input_assignment,
-            aux_assignment,
-        );
-        // end_timer!(b_g2_acc_time);
+        EcMpcProtocol::<P::G2>::add_assign_points(&self.driver, &mut g2_b, &s_g2);
 
-        //  let c_time = start_timer!(|| "Finish C");
-        let mut g_c = s_g_a;
-        EcMpcProtocol::<P::G1>::add_assign_points(&mut self.driver, &mut g_c, &r_g1_b);
-        EcMpcProtocol::<P::G1>::sub_assign_points(&mut self.driver, &mut g_c, &r_s_delta_g1);
-        EcMpcProtocol::<P::G1>::add_assign_points(&mut self.driver, &mut g_c, &l_aux_acc);
-        EcMpcProtocol::<P::G1>::add_assign_points(&mut self.driver, &mut g_c, &h_acc);
-        //  end_timer!(c_time);
+        // Compute the local part of C
+        let mut g_c = h_acc;
+        EcMpcProtocol::<P::G1>::add_assign_points(&self.driver, &mut g_c, &l_aux_acc);
 
         tracing::debug!("almost done...");
-        let (g_c_opened, g2_b_opened) =
-            PairingEcMpcProtocol::<P>::open_two_points(&mut self.driver, &g_c, &g2_b)?;
+        self.driver.set_phase("groth16: open proof");
+        let (g_a_opened, g2_b_opened, g_c_opened) =
+            PairingEcMpcProtocol::<P>::open_two_points_and_mul_open(
+                &mut self.driver,
+                &g_a,
+                &g2_b,
+                &g_c,
+                &[g_a.to_owned(), g1_b],
+                &[s, r],
+            )?;
 
         Ok(Groth16Proof {
             pi_a: g_a_opened.into_affine(),
@@ -386,7 +687,8 @@ mod test {
             threshold,
             num_parties,
             &mut rng,
-        );
+        )
+        .unwrap();
         println!("{}", serde_json::to_string(&s1[0]).unwrap());
     }
 
@@ -1,5 +1,6 @@
 //! A library for creating and verifying Groth16 proofs in a collaborative fashion using MPC.
 #![warn(missing_docs)]
+pub mod aggregation;
 mod groth16;
 #[cfg(feature = "verifier")]
 mod verifier;
@@ -13,14 +14,16 @@ pub use groth16::Rep3CoGroth16;
 mod tests {
     use ark_bls12_381::Bls12_381;
     use ark_bn254::Bn254;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use circom_types::{
-        groth16::{Groth16Proof, JsonPublicInput, JsonVerificationKey, ZKey},
+        groth16::{Groth16Proof, JsonPublicInput, JsonVerificationKey, LazyZKey, ZKey},
         Witness,
     };
     use co_circom_snarks::SharedWitness;
     use mpc_core::protocols::plain::PlainDriver;
     use std::fs::{self, File};
 
+    use crate::aggregation::{AggregateProof, AggregationSrs};
     use crate::groth16::Groth16;
 
     #[test]
@@ -52,6 +55,132 @@ mod tests {
         assert!(verified);
     }
 
+    #[test]
+    fn create_proof_with_lazy_zkey_and_verify_bn254() {
+        for circuit in ["multiplier2", "poseidon"] {
+            let zkey_path = format!("../../test_vectors/Groth16/bn254/{circuit}/circuit.zkey");
+            let witness_file = File::open(format!(
+                "../../test_vectors/Groth16/bn254/{circuit}/witness.wtns"
+            ))
+            .unwrap();
+            let vk_file = File::open(format!(
+                "../../test_vectors/Groth16/bn254/{circuit}/verification_key.json"
+            ))
+            .unwrap();
+
+            let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+            let zkey = LazyZKey::<Bn254>::from_file(&File::open(zkey_path).unwrap()).unwrap();
+            let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(vk_file).unwrap();
+            let public_input = witness.values[..=zkey.n_public].to_vec();
+            for chunk_size in [1, 3, 100, usize::MAX] {
+                let witness = SharedWitness::<PlainDriver<ark_bn254::Fr>, Bn254> {
+                    public_inputs: public_input.clone(),
+                    witness: witness.values[zkey.n_public + 1..].to_vec(),
+                };
+                let mut groth16 = Groth16::<Bn254>::new(PlainDriver::default());
+                let proof = groth16
+                    .prove_with_lazy_zkey(&zkey, chunk_size, witness)
+                    .expect("proof generation works");
+                let verified =
+                    Groth16::verify(&vk, &proof, &public_input[1..]).expect("can verify");
+                assert!(verified);
+            }
+        }
+    }
+
+    #[test]
+    fn aggregate_proofs_and_verify_bn254() {
+        let zkey_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey").unwrap();
+        let witness_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+        let vk_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/verification_key.json")
+                .unwrap();
+        let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+        let zkey = ZKey::<Bn254>::from_reader(zkey_file).unwrap();
+        let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(vk_file).unwrap();
+        let public_input = witness.values[..=zkey.n_public].to_vec();
+
+        let mut rng = rand::thread_rng();
+        let srs = AggregationSrs::<Bn254>::insecure_setup(&mut rng, 8).unwrap();
+        let verifier_srs = srs.verifier_srs();
+        for num_proofs in [2, 4, 8] {
+            let proofs = (0..num_proofs)
+                .map(|_| {
+                    let witness = SharedWitness::<PlainDriver<ark_bn254::Fr>, Bn254> {
+                        public_inputs: public_input.clone(),
+                        witness: witness.values[zkey.n_public + 1..].to_vec(),
+                    };
+                    let mut groth16 = Groth16::<Bn254>::new(PlainDriver::default());
+                    groth16.prove(&zkey, witness).unwrap()
+                })
+                .collect::<Vec<_>>();
+            let public_inputs = vec![public_input[1..].to_vec(); num_proofs];
+            let prover_srs = srs.specialize(num_proofs).unwrap();
+            let proof =
+                Groth16::aggregate_proofs(&prover_srs, &vk, &proofs, &public_inputs).unwrap();
+            assert!(
+                Groth16::verify_aggregate_proof(&verifier_srs, &vk, &proof, &public_inputs)
+                    .unwrap()
+            );
+
+            let mut bytes = Vec::new();
+            proof.serialize_compressed(&mut bytes).unwrap();
+            let der_proof = AggregateProof::<Bn254>::deserialize_compressed(&*bytes).unwrap();
+            assert_eq!(proof, der_proof);
+
+            // wrong public input
+            let mut wrong_inputs = public_inputs.clone();
+            wrong_inputs[num_proofs - 1][0] += ark_bn254::Fr::from(1u64);
+            assert!(
+                !Groth16::verify_aggregate_proof(&verifier_srs, &vk, &proof, &wrong_inputs)
+                    .unwrap()
+            );
+            // the aggregate proof is bound to the number of proofs
+            assert!(Groth16::verify_aggregate_proof(
+                &verifier_srs,
+                &vk,
+                &proof,
+                &public_inputs[..num_proofs / 2]
+            )
+            .is_err());
+            // only a power of two (at least 2) proofs can be aggregated
+            let err = Groth16::verify_aggregate_proof(
+                &verifier_srs,
+                &vk,
+                &proof,
+                &public_inputs[..num_proofs - 1],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "the number of aggregated proofs must be a power of two and at least 2, but got {}",
+                    num_proofs - 1
+                )
+            );
+            // one invalid proof
+            let mut wrong_proofs = proofs;
+            wrong_proofs[num_proofs - 1].pi_c = wrong_proofs[0].pi_a;
+            let wrong_proof =
+                Groth16::aggregate_proofs(&prover_srs, &vk, &wrong_proofs, &public_inputs).unwrap();
+            assert!(!Groth16::verify_aggregate_proof(
+                &verifier_srs,
+                &vk,
+                &wrong_proof,
+                &public_inputs
+            )
+            .unwrap());
+        }
+        // the SRS is too small
+        assert!(srs.specialize(16).is_err());
+        // only a power of two (at least 2) proofs can be aggregated
+        for num_proofs in [0, 1, 3, 6] {
+            assert!(srs.specialize(num_proofs).is_err());
+        }
+    }
+
     #[test]
     fn verify_circom_proof_bn254() {
         let vk_string = fs::read_to_string(
@@ -72,6 +201,48 @@ mod tests {
         assert!(verified)
     }
 
+    #[test]
+    fn batch_verify_circom_proofs_bn254() {
+        let vk_string = fs::read_to_string(
+            "../../test_vectors/Groth16/bn254/multiplier2/verification_key.json",
+        )
+        .unwrap();
+        let proof_string =
+            fs::read_to_string("../../test_vectors/Groth16/bn254/multiplier2/circom.proof")
+                .unwrap();
+        let zkey_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey").unwrap();
+        let witness_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+
+        let vk = serde_json::from_str::<JsonVerificationKey<Bn254>>(&vk_string).unwrap();
+        let circom_proof = serde_json::from_str::<Groth16Proof<Bn254>>(&proof_string).unwrap();
+        let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+        let zkey = ZKey::<Bn254>::from_reader(zkey_file).unwrap();
+        let public_input = witness.values[..=zkey.n_public].to_vec();
+        let witness = SharedWitness::<PlainDriver<ark_bn254::Fr>, Bn254> {
+            public_inputs: public_input.clone(),
+            witness: witness.values[zkey.n_public + 1..].to_vec(),
+        };
+        let mut groth16 = Groth16::<Bn254>::new(PlainDriver::default());
+        let proof = groth16
+            .prove(&zkey, witness)
+            .expect("proof generation works");
+
+        let ser_proof = serde_json::to_string(&proof).unwrap();
+        let der_proof = serde_json::from_str::<Groth16Proof<Bn254>>(&ser_proof).unwrap();
+        let proofs = vec![circom_proof, proof, der_proof];
+        let mut public_inputs = vec![public_input[1..].to_vec(); 3];
+        let verified =
+            Groth16::<Bn254>::batch_verify(&vk, &proofs, &public_inputs).expect("can verify");
+        assert!(verified);
+
+        public_inputs[1][0] += ark_bn254::Fr::from(1u64);
+        let verified =
+            Groth16::<Bn254>::batch_verify(&vk, &proofs, &public_inputs).expect("can verify");
+        assert!(!verified);
+    }
+
     #[test]
     fn create_proof_and_verify_poseidon_hash_bn254() {
         let zkey_file =
@@ -204,4 +375,124 @@ mod tests {
             Groth16::<Bn254>::verify(&vk, &der_proof, &public_input[1..]).expect("can verify");
         assert!(verified)
     }
+
+    /// Proves knowledge of x such that x^3 + x + 5 = y for a public y.
+    #[cfg(feature = "ark-groth16")]
+    struct CubicCircuit {
+        x: ark_bn254::Fr,
+    }
+
+    #[cfg(feature = "ark-groth16")]
+    impl ark_relations::r1cs::ConstraintSynthesizer<ark_bn254::Fr> for CubicCircuit {
+        fn generate_constraints(
+            self,
+            cs: ark_relations::r1cs::ConstraintSystemRef<ark_bn254::Fr>,
+        ) -> ark_relations::r1cs::Result<()> {
+            use ark_relations::{lc, r1cs::Variable};
+            let x_val = self.x;
+            let x_squared_val = x_val * x_val;
+            let x_cubed_val = x_squared_val * x_val;
+            let y_val = x_cubed_val + x_val + ark_bn254::Fr::from(5u64);
+            let y = cs.new_input_variable(|| Ok(y_val))?;
+            let x = cs.new_witness_variable(|| Ok(x_val))?;
+            let x_squared = cs.new_witness_variable(|| Ok(x_squared_val))?;
+            let x_cubed = cs.new_witness_variable(|| Ok(x_cubed_val))?;
+            cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + x_squared)?;
+            cs.enforce_constraint(lc!() + x_squared, lc!() + x, lc!() + x_cubed)?;
+            cs.enforce_constraint(
+                lc!() + x_cubed + x + (ark_bn254::Fr::from(5u64), Variable::One),
+                lc!() + Variable::One,
+                lc!() + y,
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ark-groth16")]
+    fn create_proof_from_matrices_and_verify_bn254() {
+        use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, OptimizationGoal};
+
+        let mut rng = rand::thread_rng();
+        let circuit = CubicCircuit {
+            x: ark_bn254::Fr::from(3u64),
+        };
+        let pk = ark_groth16::Groth16::<Bn254>::generate_random_parameters_with_reduction(
+            CubicCircuit { x: circuit.x },
+            &mut rng,
+        )
+        .unwrap();
+
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.finalize();
+        let matrices = cs.to_matrices().unwrap();
+        let cs = cs.into_inner().unwrap();
+        let public_input = cs.instance_assignment;
+        let witness = SharedWitness::<PlainDriver<ark_bn254::Fr>, Bn254> {
+            public_inputs: public_input.clone(),
+            witness: cs.witness_assignment,
+        };
+
+        let mut groth16 = Groth16::<Bn254>::new(PlainDriver::default());
+        let proof = groth16
+            .prove_with_matrices(&pk, &matrices, witness)
+            .expect("proof generation works");
+        let pvk = ark_groth16::prepare_verifying_key(&pk.vk);
+        let verified =
+            ark_groth16::Groth16::<Bn254>::verify_proof(&pvk, &proof, &public_input[1..])
+                .expect("can verify");
+        assert!(verified);
+        assert_eq!(public_input[1], ark_bn254::Fr::from(35u64));
+    }
+
+    #[test]
+    #[cfg(feature = "ark-groth16")]
+    fn create_proof_from_matrices_with_mismatched_key_fails() {
+        use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, OptimizationGoal};
+
+        let mut rng = rand::thread_rng();
+        let circuit = CubicCircuit {
+            x: ark_bn254::Fr::from(3u64),
+        };
+        let pk = ark_groth16::Groth16::<Bn254>::generate_random_parameters_with_reduction(
+            CubicCircuit { x: circuit.x },
+            &mut rng,
+        )
+        .unwrap();
+
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.finalize();
+        let matrices = cs.to_matrices().unwrap();
+        let cs = cs.into_inner().unwrap();
+        let witness = SharedWitness::<PlainDriver<ark_bn254::Fr>, Bn254> {
+            public_inputs: cs.instance_assignment,
+            witness: cs.witness_assignment,
+        };
+
+        // a proving key for a smaller circuit, i.e., with one point less in one of the queries
+        for query in ["a_query", "b_g1_query", "b_g2_query", "l_query", "h_query"] {
+            let mut wrong_pk = pk.clone();
+            let truncated = match query {
+                "a_query" => wrong_pk.a_query.pop().is_some(),
+                "b_g1_query" => wrong_pk.b_g1_query.pop().is_some(),
+                "b_g2_query" => wrong_pk.b_g2_query.pop().is_some(),
+                "l_query" => wrong_pk.l_query.pop().is_some(),
+                _ => wrong_pk.h_query.pop().is_some(),
+            };
+            assert!(truncated);
+            let mut groth16 = Groth16::<Bn254>::new(PlainDriver::default());
+            let err = groth16
+                .prove_with_matrices(&wrong_pk, &matrices, witness.clone())
+                .expect_err("the proving key does not match the constraint system");
+            assert!(
+                err.to_string()
+                    .starts_with(&format!("the {query} of the proving key has")),
+                "{err}"
+            );
+        }
+    }
 }
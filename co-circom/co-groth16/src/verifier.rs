@@ -5,10 +5,13 @@
 
 use crate::groth16::Groth16;
 use ark_ec::pairing::Pairing;
+use ark_ec::VariableBaseMSM;
 use ark_groth16::Proof;
 use ark_groth16::VerifyingKey;
 use circom_types::groth16::{Groth16Proof, JsonVerificationKey};
 use circom_types::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
+use num_traits::Zero;
+use rand::Rng;
 
 use ark_groth16::Groth16 as ArkworksGroth16;
 
@@ -41,4 +44,58 @@ where
         let vk = ark_groth16::prepare_verifying_key(&vk);
         ArkworksGroth16::<P>::verify_proof(&vk, &proof, public_inputs)
     }
+
+    /// Verifies a batch of Groth16 proofs for the same verification key.
+    ///
+    /// The pairing equations of all proofs are combined with random coefficients, such that the
+    /// whole batch is checked with a single multi-pairing. The method returns `true` iff all proofs
+    /// are valid (except with negligible probability). If the batch is rejected, use
+    /// [`Groth16::verify`] to find the invalid proofs.
+    pub fn batch_verify(
+        vk: &JsonVerificationKey<P>,
+        proofs: &[Groth16Proof<P>],
+        public_inputs: &[Vec<P::ScalarField>],
+    ) -> Result<bool, eyre::Report> {
+        if proofs.len() != public_inputs.len() {
+            return Err(eyre::eyre!(
+                "Got {} proofs, but {} public inputs",
+                proofs.len(),
+                public_inputs.len()
+            ));
+        }
+        let n_public = vk.ic.len() - 1;
+        let mut rng = rand::thread_rng();
+
+        // e(A_i, B_i) = e(alpha, beta) * e(vk_x_i, gamma) * e(C_i, delta) for all i is batched to
+        // prod e(r_i A_i, B_i) = e(sum r_i alpha, beta) * e(sum r_i vk_x_i, gamma) * e(sum r_i C_i, delta)
+        let mut g1 = Vec::with_capacity(proofs.len() + 3);
+        let mut g2 = Vec::with_capacity(proofs.len() + 3);
+        let mut r_sum = P::ScalarField::zero();
+        let mut inputs_acc = vec![P::ScalarField::zero(); n_public];
+        let mut c_acc = P::G1::zero();
+        for (proof, inputs) in proofs.iter().zip(public_inputs) {
+            if inputs.len() != n_public {
+                return Err(eyre::eyre!("Invalid number of public inputs"));
+            }
+            // 128 bits of randomness are sufficient for the soundness of the batch
+            let r = P::ScalarField::from(rng.gen::<u128>());
+            r_sum += r;
+            for (acc, input) in inputs_acc.iter_mut().zip(inputs) {
+                *acc += r * input;
+            }
+            g1.push(proof.pi_a * r);
+            g2.push(proof.pi_b);
+            c_acc += proof.pi_c * r;
+        }
+        let vk_x = vk.ic[0] * r_sum + P::G1::msm_unchecked(&vk.ic[1..], &inputs_acc);
+
+        g1.push(-(vk.alpha_1 * r_sum));
+        g2.push(vk.beta_2);
+        g1.push(-vk_x);
+        g2.push(vk.gamma_2);
+        g1.push(-c_acc);
+        g2.push(vk.delta_2);
+
+        Ok(P::multi_pairing(g1, g2).is_zero())
+    }
 }
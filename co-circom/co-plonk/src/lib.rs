@@ -63,7 +63,8 @@ where
         + PairingEcMpcProtocol<P>
         + FFTProvider<P::ScalarField>
         + MSMProvider<P::G1>
-        + MSMProvider<P::G2>,
+        + MSMProvider<P::G2>
+        + Sync,
     P::ScalarField: CircomArkworksPrimeFieldBridge,
     P: Pairing + CircomArkworksPairingBridge,
     P::BaseField: CircomArkworksPrimeFieldBridge,
@@ -106,12 +107,12 @@ mod plonk_utils {
 
     use crate::types::{Domains, PlonkWitness};
     use crate::{FieldShare, FieldShareVec, PlonkProofError, PlonkProofResult};
-    use ark_ff::Field;
+    use ark_ff::{Field, PrimeField};
     use num_traits::One;
     use num_traits::Zero;
 
     pub(crate) fn get_witness<T, P: Pairing>(
-        driver: &mut T,
+        driver: &T,
         witness: &PlonkWitness<T, P>,
         zkey: &ZKey<P>,
         index: usize,
@@ -157,6 +158,17 @@ mod plonk_utils {
         res
     }
 
+    // Returns the first len powers of g, i.e., [1, g, g^2, ..., g^(len-1)]
+    pub(crate) fn powers<F: PrimeField>(g: F, len: usize) -> Vec<F> {
+        let mut res = Vec::with_capacity(len);
+        let mut pow = F::one();
+        for _ in 0..len {
+            res.push(pow);
+            pow *= g;
+        }
+        res
+    }
+
     pub(crate) fn calculate_lagrange_evaluations<P: Pairing>(
         power: usize,
         n_public: usize,
@@ -5,7 +5,7 @@
 //! see the [`Plonk::verify`] method.
 
 use crate::{plonk_utils, types::Domains, CoPlonk};
-use ark_ec::{pairing::Pairing, Group};
+use ark_ec::{pairing::Pairing, AffineRepr, Group};
 use ark_ff::Field;
 use circom_types::{
     plonk::{JsonVerificationKey, PlonkProof},
@@ -14,6 +14,7 @@ use circom_types::{
 use mpc_core::protocols::plain::PlainDriver;
 use num_traits::One;
 use num_traits::Zero;
+use rand::Rng;
 
 use crate::types::Keccak256Transcript;
 
@@ -170,6 +171,56 @@ where
         ))
     }
 
+    /// Verifies a batch of circom PLONK proofs for the same verification key.
+    ///
+    /// The KZG openings of all proofs are aggregated with random coefficients, such that the whole
+    /// batch is checked with a single (two-element) multi-pairing. The method returns `true` iff all
+    /// proofs are valid (except with negligible probability). If the batch is rejected, use
+    /// [`Plonk::verify`] to find the invalid proofs.
+    pub fn batch_verify(
+        vk: &JsonVerificationKey<P>,
+        proofs: &[PlonkProof<P>],
+        public_inputs: &[Vec<P::ScalarField>],
+    ) -> Result<bool, eyre::Report> {
+        if proofs.len() != public_inputs.len() {
+            return Err(eyre::eyre!(
+                "Got {} proofs, but {} public inputs",
+                proofs.len(),
+                public_inputs.len()
+            ));
+        }
+        let domains = Domains::<P::ScalarField>::new(1 << vk.power)?;
+        let mut rng = rand::thread_rng();
+
+        let mut a1_acc = P::G1::zero();
+        let mut b1_acc = P::G1::zero();
+        for (proof, public_inputs) in proofs.iter().zip(public_inputs) {
+            if vk.n_public != public_inputs.len() {
+                return Err(eyre::eyre!("Invalid number of public inputs"));
+            }
+            let challenges = VerifierChallenges::<P>::new(vk, proof, public_inputs);
+            let (l, xin) = plonk_utils::calculate_lagrange_evaluations::<P>(
+                vk.power,
+                vk.n_public,
+                &challenges.xi,
+                &domains,
+            );
+            let pi = plonk_utils::calculate_pi::<P>(public_inputs, &l);
+            let (r0, d) = Plonk::<P>::calculate_r0_d(vk, proof, &challenges, pi, &l[0], xin);
+
+            let e = Plonk::<P>::calculate_e(proof, &challenges, r0);
+            let f = Plonk::<P>::calculate_f(vk, proof, &challenges, d);
+            let (a1, b1) = Plonk::<P>::opening_points(proof, &challenges, e, f, &domains);
+
+            // 128 bits of randomness are sufficient for the soundness of the batch
+            let r = P::ScalarField::from(rng.gen::<u128>());
+            a1_acc += a1 * r;
+            b1_acc += b1 * r;
+        }
+
+        Ok(P::multi_pairing([a1_acc, -b1_acc], [vk.x2, P::G2Affine::generator()]).is_zero())
+    }
+
     pub(crate) fn calculate_r0_d(
         vk: &JsonVerificationKey<P>,
         proof: &PlonkProof<P>,
@@ -251,18 +302,30 @@ where
             + vk.s2 * challenges.v[4]
     }
 
-    fn valid_pairing(
-        vk: &JsonVerificationKey<P>,
+    // Computes A1 and B1 of the final check e(A1, [x]_2) = e(B1, [1]_2)
+    fn opening_points(
         proof: &PlonkProof<P>,
         challenges: &VerifierChallenges<P>,
         e: P::G1,
         f: P::G1,
         domains: &Domains<P::ScalarField>,
-    ) -> bool {
+    ) -> (P::G1, P::G1) {
         let s = challenges.u * challenges.xi * domains.root_of_unity_pow;
 
         let a1 = proof.wxi + proof.wxiw * challenges.u;
         let b1 = proof.wxi * challenges.xi + proof.wxiw * s - e + f;
+        (a1, b1)
+    }
+
+    fn valid_pairing(
+        vk: &JsonVerificationKey<P>,
+        proof: &PlonkProof<P>,
+        challenges: &VerifierChallenges<P>,
+        e: P::G1,
+        f: P::G1,
+        domains: &Domains<P::ScalarField>,
+    ) -> bool {
+        let (a1, b1) = Plonk::<P>::opening_points(proof, challenges, e, f, domains);
 
         let lhs = P::pairing(a1, vk.x2);
         let rhs = P::pairing(b1, P::G2::generator());
@@ -365,6 +428,31 @@ pub mod tests {
         assert!(Plonk::verify(&vk, &proof, &public_inputs.values).unwrap());
     }
 
+    #[test]
+    pub fn batch_verify_from_circom() {
+        let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bn254/multiplier2/verification_key.json").unwrap(),
+        )
+        .unwrap();
+        let proofs = (0..3)
+            .map(|_| {
+                serde_json::from_reader::<_, PlonkProof<Bn254>>(
+                    File::open("../../test_vectors/Plonk/bn254/multiplier2/circom.proof").unwrap(),
+                )
+                .unwrap()
+            })
+            .collect_vec();
+        let public_inputs: JsonPublicInput<ark_bn254::Fr> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bn254/multiplier2/public.json").unwrap(),
+        )
+        .unwrap();
+        let mut public_inputs = vec![public_inputs.values; 3];
+        assert!(Plonk::batch_verify(&vk, &proofs, &public_inputs).unwrap());
+
+        public_inputs[2][1] += ark_bn254::Fr::from(1u64);
+        assert!(!Plonk::batch_verify(&vk, &proofs, &public_inputs).unwrap());
+    }
+
     #[test]
     pub fn verify_poseidon_from_circom() {
         let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(
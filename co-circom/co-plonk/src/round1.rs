@@ -5,6 +5,7 @@ use co_circom_snarks::SharedWitness;
 use mpc_core::traits::{
     FFTProvider, FieldShareVecTrait, MSMProvider, PairingEcMpcProtocol, PrimeFieldMpcProtocol,
 };
+use rayon::prelude::*;
 
 use crate::{
     plonk_utils,
@@ -115,7 +116,8 @@ where
         + PairingEcMpcProtocol<P>
         + FFTProvider<P::ScalarField>
         + MSMProvider<P::G1>
-        + MSMProvider<P::G2>,
+        + MSMProvider<P::G2>
+        + Sync,
 {
     // Essentially the fft of the trace columns
     fn compute_wire_polynomials(
@@ -128,39 +130,17 @@ where
         tracing::debug!("computing wire polynomials...");
         let num_constraints = zkey.n_constraints;
 
-        let mut buffer_a = Vec::with_capacity(zkey.domain_size);
-        let mut buffer_b = Vec::with_capacity(zkey.domain_size);
-        let mut buffer_c = Vec::with_capacity(zkey.domain_size);
-
-        for i in 0..num_constraints {
-            buffer_a.push(plonk_utils::get_witness(
-                driver,
-                witness,
-                zkey,
-                zkey.map_a[i],
-            )?);
-            buffer_b.push(plonk_utils::get_witness(
-                driver,
-                witness,
-                zkey,
-                zkey.map_b[i],
-            )?);
-            buffer_c.push(plonk_utils::get_witness(
-                driver,
-                witness,
-                zkey,
-                zkey.map_c[i],
-            )?);
-        }
-        buffer_a.resize(zkey.domain_size, FieldShare::<T, P>::default());
-        buffer_b.resize(zkey.domain_size, FieldShare::<T, P>::default());
-        buffer_c.resize(zkey.domain_size, FieldShare::<T, P>::default());
-
-        // we could do that also during loop but this is more readable
-        // it may be even faster as this way it is better for the cache
-        let buffer_a = FieldShareVec::<T, P>::from(buffer_a);
-        let buffer_b = FieldShareVec::<T, P>::from(buffer_b);
-        let buffer_c = FieldShareVec::<T, P>::from(buffer_c);
+        let wire_buffer = |map: &[usize]| -> PlonkProofResult<FieldShareVec<T, P>> {
+            let mut buffer = map[..num_constraints]
+                .par_iter()
+                .map(|index| plonk_utils::get_witness(driver, witness, zkey, *index))
+                .collect::<PlonkProofResult<Vec<_>>>()?;
+            buffer.resize(zkey.domain_size, FieldShare::<T, P>::default());
+            Ok(FieldShareVec::<T, P>::from(buffer))
+        };
+        let buffer_a = wire_buffer(&zkey.map_a)?;
+        let buffer_b = wire_buffer(&zkey.map_b)?;
+        let buffer_c = wire_buffer(&zkey.map_c)?;
 
         tracing::debug!("iffts for buffers..");
         // Compute the coefficients of the wire polynomials a(X), b(X) and c(X) from A,B & C buffers
@@ -242,6 +222,7 @@ where
         zkey: &'a ZKey<P>,
         private_witness: SharedWitness<T, P>,
     ) -> PlonkProofResult<Self> {
+        driver.set_phase("plonk: init");
         let plonk_witness = Self::calculate_additions(&mut driver, private_witness, zkey)?;
 
         Ok(Self {
@@ -263,6 +244,7 @@ where
             challenges,
             data,
         } = self;
+        driver.set_phase("plonk: round 1");
         let witness = &data.witness;
         let zkey = &data.zkey;
         let p_tau = &zkey.p_tau;
@@ -274,17 +256,17 @@ where
         tracing::debug!("committing to polys (MSMs)");
         // STEP 1.3 - Compute [a]_1, [b]_1, [c]_1
         let commit_a = MSMProvider::<P::G1>::msm_public_points(
-            &mut driver,
+            &driver,
             &p_tau[..polys.a.poly.get_len()],
             &polys.a.poly,
         );
         let commit_b = MSMProvider::<P::G1>::msm_public_points(
-            &mut driver,
+            &driver,
             &p_tau[..polys.b.poly.get_len()],
             &polys.b.poly,
         );
         let commit_c = MSMProvider::<P::G1>::msm_public_points(
-            &mut driver,
+            &driver,
             &p_tau[..polys.c.poly.get_len()],
             &polys.c.poly,
         );
@@ -3,7 +3,7 @@ use crate::{
     round1::{Round1Challenges, Round1Polys, Round1Proof},
     round3::Round3,
     types::{Domains, Keccak256Transcript, PlonkData, PolyEval},
-    FieldShareVec, PlonkProofError, PlonkProofResult,
+    FieldShare, FieldShareVec, PlonkProofError, PlonkProofResult,
 };
 use ark_ec::pairing::Pairing;
 use ark_ec::CurveGroup;
@@ -11,7 +11,7 @@ use circom_types::plonk::ZKey;
 use mpc_core::traits::{
     FFTProvider, FieldShareVecTrait, MSMProvider, PairingEcMpcProtocol, PrimeFieldMpcProtocol,
 };
-use num_traits::One;
+use rayon::prelude::*;
 
 // To reduce the number of communication rounds, we implement the array_prod_mul macro according to https://www.usenix.org/system/files/sec22-ozdemir.pdf, p11 first paragraph.
 // TODO parallelize these? With a different network structure this might not be needed though
@@ -139,7 +139,8 @@ where
         + PairingEcMpcProtocol<P>
         + FFTProvider<P::ScalarField>
         + MSMProvider<P::G1>
-        + MSMProvider<P::G2>,
+        + MSMProvider<P::G2>
+        + Sync,
 {
     // Computes the permutation polynomial z(X) (see https://eprint.iacr.org/2019/953.pdf)
     // To reduce the number of communication rounds, we implement the array_prod_mul macro according to https://www.usenix.org/system/files/sec22-ozdemir.pdf, p11 first paragraph.
@@ -151,57 +152,37 @@ where
         polys: &Round1Polys<T, P>,
     ) -> PlonkProofResult<PolyEval<T, P>> {
         tracing::debug!("computing z polynomial...");
-        let pow_root_of_unity = domains.root_of_unity_pow;
-        let mut w = P::ScalarField::one();
-        let mut n1 = Vec::with_capacity(zkey.domain_size);
-        let mut n2 = Vec::with_capacity(zkey.domain_size);
-        let mut n3 = Vec::with_capacity(zkey.domain_size);
-        let mut d1 = Vec::with_capacity(zkey.domain_size);
-        let mut d2 = Vec::with_capacity(zkey.domain_size);
-        let mut d3 = Vec::with_capacity(zkey.domain_size);
-        for i in 0..zkey.domain_size {
-            let a = polys.buffer_a.index(i);
-            let b = polys.buffer_b.index(i);
-            let c = polys.buffer_c.index(i);
-
-            // Z(X) := numArr / denArr
-            // numArr := (a + beta·ω + gamma)(b + beta·ω·k1 + gamma)(c + beta·ω·k2 + gamma)
-            let betaw = challenges.beta * w;
-
-            let n1_ = driver.add_with_public(&betaw, &a);
-            let n1_ = driver.add_with_public(&challenges.gamma, &n1_);
-
-            let tmp = zkey.verifying_key.k1 * betaw;
-            let n2_ = driver.add_with_public(&tmp, &b);
-            let n2_ = driver.add_with_public(&challenges.gamma, &n2_);
-
-            let tmp = zkey.verifying_key.k2 * betaw;
-            let n3_ = driver.add_with_public(&tmp, &c);
-            let n3_ = driver.add_with_public(&challenges.gamma, &n3_);
-
-            n1.push(n1_);
-            n2.push(n2_);
-            n3.push(n3_);
-
-            // denArr := (a + beta·sigma1 + gamma)(b + beta·sigma2 + gamma)(c + beta·sigma3 + gamma)
-            let d1_ =
-                driver.add_with_public(&(challenges.beta * zkey.s1_poly.evaluations[i * 4]), &a);
-            let d1_ = driver.add_with_public(&challenges.gamma, &d1_);
-
-            let d2_ =
-                driver.add_with_public(&(challenges.beta * zkey.s2_poly.evaluations[i * 4]), &b);
-            let d2_ = driver.add_with_public(&challenges.gamma, &d2_);
-
-            let d3_ =
-                driver.add_with_public(&(challenges.beta * zkey.s3_poly.evaluations[i * 4]), &c);
-            let d3_ = driver.add_with_public(&challenges.gamma, &d3_);
-
-            d1.push(d1_);
-            d2.push(d2_);
-            d3.push(d3_);
-
-            w *= &pow_root_of_unity;
-        }
+        let powers = plonk_utils::powers(domains.root_of_unity_pow, zkey.domain_size);
+        // Computes (x_i + public_i + gamma) for the shared buffer x, without any network operation
+        let add_public = |buffer: &FieldShareVec<T, P>,
+                          public: &(dyn Fn(usize) -> P::ScalarField + Sync)|
+         -> Vec<FieldShare<T, P>> {
+            (0..zkey.domain_size)
+                .into_par_iter()
+                .map(|i| {
+                    let tmp = driver.add_with_public(&public(i), &buffer.index(i));
+                    driver.add_with_public(&challenges.gamma, &tmp)
+                })
+                .collect()
+        };
+
+        // Z(X) := numArr / denArr
+        // numArr := (a + beta·ω + gamma)(b + beta·ω·k1 + gamma)(c + beta·ω·k2 + gamma)
+        let betaw = |i: usize| challenges.beta * powers[i];
+        let n1 = add_public(&polys.buffer_a, &betaw);
+        let n2 = add_public(&polys.buffer_b, &|i| zkey.verifying_key.k1 * betaw(i));
+        let n3 = add_public(&polys.buffer_c, &|i| zkey.verifying_key.k2 * betaw(i));
+
+        // denArr := (a + beta·sigma1 + gamma)(b + beta·sigma2 + gamma)(c + beta·sigma3 + gamma)
+        let d1 = add_public(&polys.buffer_a, &|i| {
+            challenges.beta * zkey.s1_poly.evaluations[i * 4]
+        });
+        let d2 = add_public(&polys.buffer_b, &|i| {
+            challenges.beta * zkey.s2_poly.evaluations[i * 4]
+        });
+        let d3 = add_public(&polys.buffer_c, &|i| {
+            challenges.beta * zkey.s3_poly.evaluations[i * 4]
+        });
 
         // TODO parallelize these? With a different network structure this might not be needed though
         let num = driver.mul_many(&n1, &n2)?;
@@ -250,6 +231,7 @@ where
             domains,
             polys,
         } = self;
+        driver.set_phase("plonk: round 2");
         let zkey = &data.zkey;
         let public_input = &data.witness.public_inputs;
         tracing::debug!("building challenges for round2 with Keccak256..");
@@ -281,7 +263,7 @@ where
 
         tracing::debug!("committing to poly z (MSMs)");
         let commit_z = MSMProvider::<P::G1>::msm_public_points(
-            &mut driver,
+            &driver,
             &zkey.p_tau[..z.poly.get_len()],
             &z.poly,
         );
@@ -1,8 +1,9 @@
 use crate::{
+    plonk_utils,
     round2::{Round2Challenges, Round2Polys, Round2Proof},
     round4::Round4,
     types::{Domains, Keccak256Transcript, PlonkData, PolyEval},
-    FieldShareVec, PlonkProofResult,
+    FieldShare, FieldShareVec, PlonkProofResult,
 };
 use ark_ec::pairing::Pairing;
 use ark_ec::CurveGroup;
@@ -13,6 +14,7 @@ use mpc_core::traits::{
 };
 use num_traits::One;
 use num_traits::Zero;
+use rayon::prelude::*;
 
 // TODO parallelize these? With a different network structure this might not be needed though
 macro_rules! mul4vec {
@@ -198,7 +200,8 @@ where
         + PairingEcMpcProtocol<P>
         + FFTProvider<P::ScalarField>
         + MSMProvider<P::G1>
-        + MSMProvider<P::G2>,
+        + MSMProvider<P::G2>
+        + Sync,
 {
     fn get_z1(domains: &Domains<P::ScalarField>) -> [P::ScalarField; 4] {
         let zero = P::ScalarField::zero();
@@ -245,24 +248,22 @@ where
         let z1 = Self::get_z1(domains);
         let z2 = Self::get_z2(domains);
         let z3 = Self::get_z3(domains);
-        let mut w = P::ScalarField::one();
-        let mut ap = Vec::with_capacity(zkey.domain_size * 4);
-        let mut bp = Vec::with_capacity(zkey.domain_size * 4);
-        let mut cp = Vec::with_capacity(zkey.domain_size * 4);
+        let n4 = zkey.domain_size * 4;
 
         let pow_root_of_unity = domains.root_of_unity_pow;
         let pow_plus2_root_of_unity = domains.root_of_unity_pow_2;
-        // We do not want to have any network operation in here to reduce MPC rounds. To enforce this, we have a for_each loop here (Network operations require a result)
-        (0..zkey.domain_size * 4).for_each(|_| {
-            ap.push(driver.add_mul_public(&challenges.b[1], &challenges.b[0], &w));
-            bp.push(driver.add_mul_public(&challenges.b[3], &challenges.b[2], &w));
-            cp.push(driver.add_mul_public(&challenges.b[5], &challenges.b[4], &w));
-            w *= &pow_plus2_root_of_unity;
-        });
-
-        let ap_vec: FieldShareVec<T, P> = ap.into();
-        let bp_vec: FieldShareVec<T, P> = bp.into();
-        let cp_vec: FieldShareVec<T, P> = cp.into();
+        let powers = plonk_utils::powers(pow_plus2_root_of_unity, n4);
+        // We do not want to have any network operation in here to reduce MPC rounds. To enforce this, we only use the driver immutably in the parallel iterators (Network operations require a mutable driver)
+        let blind_evals = |b0: &FieldShare<T, P>, b1: &FieldShare<T, P>| -> FieldShareVec<T, P> {
+            powers
+                .par_iter()
+                .map(|w| driver.add_mul_public(b1, b0, w))
+                .collect::<Vec<_>>()
+                .into()
+        };
+        let ap_vec = blind_evals(&challenges.b[0], &challenges.b[1]);
+        let bp_vec = blind_evals(&challenges.b[2], &challenges.b[3]);
+        let cp_vec = blind_evals(&challenges.b[4], &challenges.b[5]);
 
         // TODO parallelize these? With a different network structure this might not be needed though
         let a_b = driver.mul_vec(&polys.poly_eval_a.eval, &polys.poly_eval_b.eval)?;
@@ -271,116 +272,111 @@ where
         let ap_bp = driver.mul_vec(&ap_vec, &bp_vec)?;
 
         // TODO keep RAM requirements in mind
-        let mut e1 = Vec::with_capacity(zkey.domain_size * 4);
-        let mut e1z = Vec::with_capacity(zkey.domain_size * 4);
-
-        let mut e2a = Vec::with_capacity(zkey.domain_size * 4);
-        let mut e2b = Vec::with_capacity(zkey.domain_size * 4);
-        let mut e2c = Vec::with_capacity(zkey.domain_size * 4);
-        let mut e2d = Vec::with_capacity(zkey.domain_size * 4);
-        let mut zp = Vec::with_capacity(zkey.domain_size * 4);
-
-        let mut e3a = Vec::with_capacity(zkey.domain_size * 4);
-        let mut e3b = Vec::with_capacity(zkey.domain_size * 4);
-        let mut e3c = Vec::with_capacity(zkey.domain_size * 4);
-        let mut e3d = Vec::with_capacity(zkey.domain_size * 4);
-        let mut zwp = Vec::with_capacity(zkey.domain_size * 4);
-        let mut w = P::ScalarField::one();
-        // We do not want to have any network operation in here to reduce MPC rounds. To enforce this, we have a for_each loop here (Network operations require a result)
-        (0..zkey.domain_size * 4).for_each(|i| {
-            let a = polys.poly_eval_a.eval.index(i);
-            let b = polys.poly_eval_b.eval.index(i);
-            let c = polys.poly_eval_c.eval.index(i);
-            let z = polys.z.eval.index(i);
-            let qm = zkey.qm_poly.evaluations[i];
-            let ql = zkey.ql_poly.evaluations[i];
-            let qr = zkey.qr_poly.evaluations[i];
-            let qo = zkey.qo_poly.evaluations[i];
-            let qc = zkey.qc_poly.evaluations[i];
-            let s1 = zkey.s1_poly.evaluations[i];
-            let s2 = zkey.s2_poly.evaluations[i];
-            let s3 = zkey.s3_poly.evaluations[i];
-            let a_bp = a_bp.index(i);
-            let a_b = a_b.index(i);
-            let ap_b = ap_b.index(i);
-            let ap = ap_vec.index(i);
-            let bp = bp_vec.index(i);
-
-            let w2 = w.square();
-            let zp_lhs = driver.mul_with_public(&w2, &challenges.b[6]);
-            let zp_rhs = driver.mul_with_public(&w, &challenges.b[7]);
-            let zp_ = driver.add(&zp_lhs, &zp_rhs);
-            let zp_ = driver.add(&challenges.b[8], &zp_);
-            zp.push(zp_);
-
-            let w_w = w * pow_root_of_unity;
-            let w_w2 = w_w.square();
-            let zw = polys
-                .z
-                .eval
-                .index((zkey.domain_size * 4 + 4 + i) % (zkey.domain_size * 4));
-            let zwp_lhs = driver.mul_with_public(&w_w2, &challenges.b[6]);
-            let zwp_rhs = driver.mul_with_public(&w_w, &challenges.b[7]);
-            let zwp_ = driver.add(&zwp_lhs, &zwp_rhs);
-            let zwp_ = driver.add(&challenges.b[8], &zwp_);
-            zwp.push(zwp_);
-
-            let mut a0 = driver.add(&a_bp, &ap_b);
-            let mod_i = i % 4;
-            if mod_i != 0 {
-                let z1 = z1[mod_i];
-                let ap_bp = ap_bp.index(i);
-                let tmp = driver.mul_with_public(&z1, &ap_bp);
-                a0 = driver.add(&a0, &tmp);
-            }
-
-            let (mut e1_, mut e1z_) = (a_b, a0);
-            e1_ = driver.mul_with_public(&qm, &e1_);
-            e1z_ = driver.mul_with_public(&qm, &e1z_);
-
-            e1_ = driver.add_mul_public(&e1_, &a, &ql);
-            e1z_ = driver.add_mul_public(&e1z_, &ap, &ql);
-
-            e1_ = driver.add_mul_public(&e1_, &b, &qr);
-            e1z_ = driver.add_mul_public(&e1z_, &bp, &qr);
-
-            e1_ = driver.add_mul_public(&e1_, &c, &qo);
-            e1z_ = driver.add_mul_public(&e1z_, &cp_vec.index(i), &qo);
-
-            let mut pi = T::zero_share();
-            for (j, lagrange) in zkey.lagrange.iter().enumerate() {
-                let l_eval = lagrange.evaluations[i];
-                let a_val = polys.buffer_a.index(j);
-                let tmp = driver.mul_with_public(&l_eval, &a_val);
-                pi = driver.sub(&pi, &tmp);
-            }
-
-            e1_ = driver.add(&e1_, &pi);
-            e1_ = driver.add_with_public(&qc, &e1_);
-            e1.push(e1_);
-            e1z.push(e1z_);
-
-            let betaw = challenges.beta * w;
-            e2a.push(driver.add_with_public(&(betaw + challenges.gamma), &a));
-            e2b.push(
-                driver.add_with_public(&(betaw * zkey.verifying_key.k1 + challenges.gamma), &b),
-            );
-            e2c.push(
-                driver.add_with_public(&(betaw * zkey.verifying_key.k2 + challenges.gamma), &c),
-            );
-
-            e2d.push(z.clone());
-            e3a.push(driver.add_with_public(&(s1 * challenges.beta + challenges.gamma), &a));
-            e3b.push(driver.add_with_public(&(s2 * challenges.beta + challenges.gamma), &b));
-            e3c.push(driver.add_with_public(&(s3 * challenges.beta + challenges.gamma), &c));
-            e3d.push(zw);
-            w *= pow_plus2_root_of_unity;
+        let (e1, e1z): (Vec<_>, Vec<_>) = (0..n4)
+            .into_par_iter()
+            .map(|i| {
+                let a = polys.poly_eval_a.eval.index(i);
+                let b = polys.poly_eval_b.eval.index(i);
+                let c = polys.poly_eval_c.eval.index(i);
+                let qm = zkey.qm_poly.evaluations[i];
+                let ql = zkey.ql_poly.evaluations[i];
+                let qr = zkey.qr_poly.evaluations[i];
+                let qo = zkey.qo_poly.evaluations[i];
+                let qc = zkey.qc_poly.evaluations[i];
+                let a_bp = a_bp.index(i);
+                let a_b = a_b.index(i);
+                let ap_b = ap_b.index(i);
+                let ap = ap_vec.index(i);
+                let bp = bp_vec.index(i);
+
+                let mut a0 = driver.add(&a_bp, &ap_b);
+                let mod_i = i % 4;
+                if mod_i != 0 {
+                    let z1 = z1[mod_i];
+                    let ap_bp = ap_bp.index(i);
+                    let tmp = driver.mul_with_public(&z1, &ap_bp);
+                    a0 = driver.add(&a0, &tmp);
+                }
+
+                let (mut e1_, mut e1z_) = (a_b, a0);
+                e1_ = driver.mul_with_public(&qm, &e1_);
+                e1z_ = driver.mul_with_public(&qm, &e1z_);
+
+                e1_ = driver.add_mul_public(&e1_, &a, &ql);
+                e1z_ = driver.add_mul_public(&e1z_, &ap, &ql);
+
+                e1_ = driver.add_mul_public(&e1_, &b, &qr);
+                e1z_ = driver.add_mul_public(&e1z_, &bp, &qr);
+
+                e1_ = driver.add_mul_public(&e1_, &c, &qo);
+                e1z_ = driver.add_mul_public(&e1z_, &cp_vec.index(i), &qo);
+
+                let mut pi = T::zero_share();
+                for (j, lagrange) in zkey.lagrange.iter().enumerate() {
+                    let l_eval = lagrange.evaluations[i];
+                    let a_val = polys.buffer_a.index(j);
+                    let tmp = driver.mul_with_public(&l_eval, &a_val);
+                    pi = driver.sub(&pi, &tmp);
+                }
+
+                e1_ = driver.add(&e1_, &pi);
+                e1_ = driver.add_with_public(&qc, &e1_);
+                (e1_, e1z_)
+            })
+            .unzip();
+
+        let (zp, zwp): (Vec<_>, Vec<_>) = powers
+            .par_iter()
+            .map(|w| {
+                let w2 = w.square();
+                let zp_lhs = driver.mul_with_public(&w2, &challenges.b[6]);
+                let zp_rhs = driver.mul_with_public(w, &challenges.b[7]);
+                let zp_ = driver.add(&zp_lhs, &zp_rhs);
+                let zp_ = driver.add(&challenges.b[8], &zp_);
+
+                let w_w = *w * pow_root_of_unity;
+                let w_w2 = w_w.square();
+                let zwp_lhs = driver.mul_with_public(&w_w2, &challenges.b[6]);
+                let zwp_rhs = driver.mul_with_public(&w_w, &challenges.b[7]);
+                let zwp_ = driver.add(&zwp_lhs, &zwp_rhs);
+                let zwp_ = driver.add(&challenges.b[8], &zwp_);
+                (zp_, zwp_)
+            })
+            .unzip();
+
+        let add_public = |evals: &FieldShareVec<T, P>,
+                          public: &(dyn Fn(usize) -> P::ScalarField + Sync)|
+         -> FieldShareVec<T, P> {
+            (0..n4)
+                .into_par_iter()
+                .map(|i| driver.add_with_public(&public(i), &evals.index(i)))
+                .collect::<Vec<_>>()
+                .into()
+        };
+        let betaw = |i: usize| challenges.beta * powers[i];
+        let e2a_vec = add_public(&polys.poly_eval_a.eval, &|i| betaw(i) + challenges.gamma);
+        let e2b_vec = add_public(&polys.poly_eval_b.eval, &|i| {
+            betaw(i) * zkey.verifying_key.k1 + challenges.gamma
+        });
+        let e2c_vec = add_public(&polys.poly_eval_c.eval, &|i| {
+            betaw(i) * zkey.verifying_key.k2 + challenges.gamma
+        });
+        let e2d_vec = polys.z.eval.clone();
+        let e3a_vec = add_public(&polys.poly_eval_a.eval, &|i| {
+            zkey.s1_poly.evaluations[i] * challenges.beta + challenges.gamma
         });
+        let e3b_vec = add_public(&polys.poly_eval_b.eval, &|i| {
+            zkey.s2_poly.evaluations[i] * challenges.beta + challenges.gamma
+        });
+        let e3c_vec = add_public(&polys.poly_eval_c.eval, &|i| {
+            zkey.s3_poly.evaluations[i] * challenges.beta + challenges.gamma
+        });
+        let e3d_vec = (0..n4)
+            .into_par_iter()
+            .map(|i| polys.z.eval.index((n4 + 4 + i) % n4))
+            .collect::<Vec<_>>()
+            .into();
 
-        let e2a_vec = e2a.into();
-        let e2b_vec = e2b.into();
-        let e2c_vec = e2c.into();
-        let e2d_vec = e2d.into();
         let zp_vec = zp.into();
 
         let [e2, e2z_0, e2z_1, e2z_2, e2z_3] = mul4vec!(
@@ -388,10 +384,6 @@ where
             &domain1
         );
 
-        let e3a_vec = e3a.into();
-        let e3b_vec = e3b.into();
-        let e3c_vec = e3c.into();
-        let e3d_vec = e3d.into();
         let zwp_vec = zwp.into();
 
         let [e3, e3z_0, e3z_1, e3z_2, e3z_3] = mul4vec!(
@@ -399,46 +391,44 @@ where
             &domain1
         );
 
-        let mut t_vec = Vec::with_capacity(zkey.domain_size * 4);
-        let mut tz_vec = Vec::with_capacity(zkey.domain_size * 4);
-        // We do not want to have any network operation in here to reduce MPC rounds. To enforce this, we have a for_each loop here (Network operations require a result)
-        (0..zkey.domain_size * 4).for_each(|i| {
-            let mut e2 = e2.index(i);
-            let mut e2z = mul4vec_post!(driver, e2z_0, e2z_1, e2z_2, e2z_3, i, z1, z2, z3);
-            let mut e3 = e3.index(i);
-            let mut e3z = mul4vec_post!(driver, e3z_0, e3z_1, e3z_2, e3z_3, i, z1, z2, z3);
+        let (t_vec, tz_vec): (Vec<_>, Vec<_>) = (0..n4)
+            .into_par_iter()
+            .map(|i| {
+                let mut e2 = e2.index(i);
+                let mut e2z = mul4vec_post!(driver, e2z_0, e2z_1, e2z_2, e2z_3, i, z1, z2, z3);
+                let mut e3 = e3.index(i);
+                let mut e3z = mul4vec_post!(driver, e3z_0, e3z_1, e3z_2, e3z_3, i, z1, z2, z3);
 
-            let z = polys.z.eval.index(i);
-            let zp = zp_vec.index(i);
+                let z = polys.z.eval.index(i);
+                let zp = zp_vec.index(i);
 
-            e2 = driver.mul_with_public(&challenges.alpha, &e2);
-            e2z = driver.mul_with_public(&challenges.alpha, &e2z);
+                e2 = driver.mul_with_public(&challenges.alpha, &e2);
+                e2z = driver.mul_with_public(&challenges.alpha, &e2z);
 
-            e3 = driver.mul_with_public(&challenges.alpha, &e3);
-            e3z = driver.mul_with_public(&challenges.alpha, &e3z);
+                e3 = driver.mul_with_public(&challenges.alpha, &e3);
+                e3z = driver.mul_with_public(&challenges.alpha, &e3z);
 
-            let mut e4 = driver.add_with_public(&-P::ScalarField::one(), &z);
-            e4 = driver.mul_with_public(&zkey.lagrange[0].evaluations[i], &e4);
-            e4 = driver.mul_with_public(&challenges.alpha2, &e4);
+                let mut e4 = driver.add_with_public(&-P::ScalarField::one(), &z);
+                e4 = driver.mul_with_public(&zkey.lagrange[0].evaluations[i], &e4);
+                e4 = driver.mul_with_public(&challenges.alpha2, &e4);
 
-            let mut e4z = driver.mul_with_public(&zkey.lagrange[0].evaluations[i], &zp);
-            e4z = driver.mul_with_public(&challenges.alpha2, &e4z);
+                let mut e4z = driver.mul_with_public(&zkey.lagrange[0].evaluations[i], &zp);
+                e4z = driver.mul_with_public(&challenges.alpha2, &e4z);
 
-            let mut t = driver.add(&e1[i], &e2);
-            t = driver.sub(&t, &e3);
-            t = driver.add(&t, &e4);
+                let mut t = driver.add(&e1[i], &e2);
+                t = driver.sub(&t, &e3);
+                t = driver.add(&t, &e4);
 
-            let mut tz = driver.add(&e1z[i], &e2z);
-            tz = driver.sub(&tz, &e3z);
-            tz = driver.add(&tz, &e4z);
-
-            t_vec.push(t);
-            tz_vec.push(tz);
-        });
+                let mut tz = driver.add(&e1z[i], &e2z);
+                tz = driver.sub(&tz, &e3z);
+                tz = driver.add(&tz, &e4z);
+                (t, tz)
+            })
+            .unzip();
         let mut coefficients_t = driver.ifft(&t_vec.into(), &domains.extended_domain);
         driver.neg_vec_in_place_limit(&mut coefficients_t, zkey.domain_size);
 
-        // We do not want to have any network operation in here to reduce MPC rounds. To enforce this, we have a for_each loop here (Network operations require a result)
+        // Every chunk of domain_size elements depends on the previous one, so this has to stay sequential
         (zkey.domain_size..zkey.domain_size * 4).for_each(|i| {
             let a_lhs = coefficients_t.index(i - zkey.domain_size);
             let a_rhs = coefficients_t.index(i);
@@ -479,6 +469,7 @@ where
             polys,
             data,
         } = self;
+        driver.set_phase("plonk: round 3");
         tracing::debug!("building challenges for round3 with Keccak256..");
         let mut transcript = Keccak256Transcript::<P>::default();
         // STEP 3.1 - Compute evaluation challenge alpha ∈ F
@@ -495,21 +486,12 @@ where
 
         tracing::debug!("committing to poly t (MSMs)");
         // Compute [T1]_1, [T2]_1, [T3]_1
-        let commit_t1 = MSMProvider::<P::G1>::msm_public_points(
-            &mut driver,
-            &data.zkey.p_tau[..t1.get_len()],
-            &t1,
-        );
-        let commit_t2 = MSMProvider::<P::G1>::msm_public_points(
-            &mut driver,
-            &data.zkey.p_tau[..t2.get_len()],
-            &t2,
-        );
-        let commit_t3 = MSMProvider::<P::G1>::msm_public_points(
-            &mut driver,
-            &data.zkey.p_tau[..t3.get_len()],
-            &t3,
-        );
+        let commit_t1 =
+            MSMProvider::<P::G1>::msm_public_points(&driver, &data.zkey.p_tau[..t1.get_len()], &t1);
+        let commit_t2 =
+            MSMProvider::<P::G1>::msm_public_points(&driver, &data.zkey.p_tau[..t2.get_len()], &t2);
+        let commit_t3 =
+            MSMProvider::<P::G1>::msm_public_points(&driver, &data.zkey.p_tau[..t3.get_len()], &t3);
 
         let opened = driver.open_point_many(&[commit_t1, commit_t2, commit_t3])?;
 
@@ -109,7 +109,8 @@ where
         + PairingEcMpcProtocol<P>
         + FFTProvider<P::ScalarField>
         + MSMProvider<P::G1>
-        + MSMProvider<P::G2>,
+        + MSMProvider<P::G2>
+        + Sync,
 {
     // Round 4 of https://eprint.iacr.org/2019/953.pdf (page 29)
     pub(super) fn round4(self) -> PlonkProofResult<Round5<'a, T, P>> {
@@ -121,6 +122,7 @@ where
             polys,
             data,
         } = self;
+        driver.set_phase("plonk: round 4");
         tracing::debug!("building challenges for round4 with Keccak256..");
         // STEP 4.1 - Compute evaluation challenge xi \in F_p
         let mut transcript = Keccak256Transcript::<P>::default();
@@ -17,6 +17,7 @@ use mpc_core::traits::{
 };
 use num_traits::One;
 use num_traits::Zero;
+use rayon::prelude::*;
 
 // Round 5 of https://eprint.iacr.org/2019/953.pdf (page 30)
 pub(super) struct Round5<'a, T, P: Pairing>
@@ -89,7 +90,8 @@ where
         + PairingEcMpcProtocol<P>
         + FFTProvider<P::ScalarField>
         + MSMProvider<P::G1>
-        + MSMProvider<P::G2>,
+        + MSMProvider<P::G2>
+        + Sync,
     P: CircomArkworksPairingBridge,
     P::BaseField: CircomArkworksPrimeFieldBridge,
     P::ScalarField: CircomArkworksPrimeFieldBridge,
@@ -119,9 +121,10 @@ where
             inout.resize(add_poly.len(), P::ScalarField::zero());
         }
 
-        for (inout, add) in inout.iter_mut().zip(add_poly.iter()) {
-            *inout += *add;
-        }
+        inout
+            .par_iter_mut()
+            .zip(add_poly.par_iter())
+            .for_each(|(inout, add)| *inout += *add);
     }
 
     fn add_factor_poly(
@@ -133,9 +136,10 @@ where
             inout.resize(add_poly.len(), P::ScalarField::zero());
         }
 
-        for (inout, add) in inout.iter_mut().zip(add_poly.iter()) {
-            *inout += *add * factor;
-        }
+        inout
+            .par_iter_mut()
+            .zip(add_poly.par_iter())
+            .for_each(|(inout, add)| *inout += *add * factor);
     }
 
     // The linearisation polynomial R(X) (see https://eprint.iacr.org/2019/953.pdf)
@@ -176,9 +180,7 @@ where
         let e24 = e2 + e4;
 
         let mut poly_r = zkey.qm_poly.coeffs.clone();
-        for coeff in poly_r.iter_mut() {
-            *coeff *= coef_ab;
-        }
+        poly_r.par_iter_mut().for_each(|coeff| *coeff *= coef_ab);
         Self::add_factor_poly(&mut poly_r.coeffs, &zkey.ql_poly.coeffs, proof.eval_a);
         Self::add_factor_poly(&mut poly_r.coeffs, &zkey.qr_poly.coeffs, proof.eval_b);
         Self::add_factor_poly(&mut poly_r.coeffs, &zkey.qo_poly.coeffs, proof.eval_c);
@@ -314,6 +316,7 @@ where
             polys,
             data,
         } = self;
+        driver.set_phase("plonk: round 5");
         tracing::debug!("building challenges for round5 with Keccak256..");
         let mut transcript = Keccak256Transcript::<P>::default();
         // STEP 5.1 - Compute evaluation challenge v \in F_p
@@ -348,9 +351,9 @@ where
 
         let p_tau = &data.zkey.p_tau;
         let commit_wxi =
-            MSMProvider::<P::G1>::msm_public_points(&mut driver, &p_tau[..wxi.get_len()], &wxi);
+            MSMProvider::<P::G1>::msm_public_points(&driver, &p_tau[..wxi.get_len()], &wxi);
         let commit_wxiw =
-            MSMProvider::<P::G1>::msm_public_points(&mut driver, &p_tau[..wxiw.get_len()], &wxiw);
+            MSMProvider::<P::G1>::msm_public_points(&driver, &p_tau[..wxiw.get_len()], &wxiw);
 
         let opened = driver.open_point_many(&[commit_wxi, commit_wxiw])?;
 
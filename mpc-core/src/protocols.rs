@@ -3,6 +3,9 @@
 //! Contains the implementations of the different MPC protocols. Currently, semi-honest 3-party replicated sharing (REP3) and semi-honest n-party Shamir secret sharing are implemented.
 
 pub mod bridges;
+pub mod communication;
+pub(crate) mod network;
 pub mod plain;
 pub mod rep3;
 pub mod shamir;
+pub(crate) mod utils;
@@ -23,6 +23,8 @@ impl RepToShamirNetwork<ShamirMpcNet> for Rep3MpcNet {
             net_handler,
             chan_next,
             chan_prev,
+            signal,
+            stats,
         } = self;
 
         let mut channels = HashMap::with_capacity(2);
@@ -35,6 +37,51 @@ impl RepToShamirNetwork<ShamirMpcNet> for Rep3MpcNet {
             runtime,
             net_handler,
             channels,
+            signal,
+            stats,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bridged_network_keeps_counting() {
+        let nets = Rep3MpcNet::in_memory().unwrap();
+        let handles = nets.map(|mut net| {
+            std::thread::spawn(move || {
+                let stats = net.communication_stats();
+                net.set_phase("rep3");
+                net.send_next(ark_bn254::Fr::from(1u64)).unwrap();
+                net.recv_prev::<ark_bn254::Fr>().unwrap();
+
+                let mut net: ShamirMpcNet = net.to_shamir_net();
+                net.set_phase("shamir");
+                let id = net.get_id();
+                net.send((id + 1) % 3, ark_bn254::Fr::from(2u64)).unwrap();
+                net.recv::<ark_bn254::Fr>((id + 2) % 3).unwrap();
+
+                let phases = stats.phases();
+                assert_eq!(phases.len(), 2);
+                assert_eq!(phases[0].0, "rep3");
+                assert_eq!(phases[1].0, "shamir");
+                for (_, phase) in phases {
+                    assert_eq!(phase.messages_sent, 1);
+                    assert_eq!(phase.messages_received, 1);
+                    assert_eq!(phase.rounds, 1);
+                }
+                assert_eq!(
+                    net.communication_stats().total(),
+                    stats.total(),
+                    "the Shamir network shares the counters of the REP3 network"
+                );
+                net.shutdown().unwrap();
+            })
+        });
+        for handle in handles {
+            handle.join().unwrap();
         }
     }
 }
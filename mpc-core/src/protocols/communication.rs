@@ -0,0 +1,176 @@
+//! # Communication Statistics
+//!
+//! Counts the messages, bytes and communication rounds of the network implementations of the MPC protocols, separately for each named phase of a protocol (e.g., "witness extension" or "plonk: round 3").
+
+use std::sync::{Arc, Mutex};
+
+/// The phase the communication is accounted to before a phase is set.
+pub const DEFAULT_PHASE: &str = "unnamed";
+
+/// The communication of a party in one phase of a protocol.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseStats {
+    /// The number of messages sent to other parties.
+    pub messages_sent: u64,
+    /// The number of messages received from other parties.
+    pub messages_received: u64,
+    /// The number of message bytes sent to other parties.
+    pub bytes_sent: u64,
+    /// The number of message bytes received from other parties.
+    pub bytes_received: u64,
+    /// The number of communication rounds, i.e., how often the party started sending after it received a message. A round that starts in one phase and ends in the next one is counted in the first phase.
+    pub rounds: u64,
+}
+
+impl std::ops::AddAssign for PhaseStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.messages_sent += rhs.messages_sent;
+        self.messages_received += rhs.messages_received;
+        self.bytes_sent += rhs.bytes_sent;
+        self.bytes_received += rhs.bytes_received;
+        self.rounds += rhs.rounds;
+    }
+}
+
+/// The communication statistics of a network, split into the phases of the protocol. A phase is started with [CommunicationStats::set_phase], all communication afterwards is accounted to it until the next phase starts. Phases with the same name are accounted together.
+///
+/// Clones share the same statistics, so a clone can be kept to read the statistics after the network was moved into a protocol or prover.
+#[derive(Debug, Clone)]
+pub struct CommunicationStats {
+    inner: Arc<Mutex<Phases>>,
+}
+
+#[derive(Debug)]
+struct Phases {
+    // the phases in the order they were started
+    phases: Vec<(String, PhaseStats)>,
+    current: usize,
+    // whether we received a message since we last sent one
+    received: bool,
+}
+
+impl Default for CommunicationStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommunicationStats {
+    /// Creates empty statistics in the [DEFAULT_PHASE].
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Phases {
+                phases: vec![(DEFAULT_PHASE.to_owned(), PhaseStats::default())],
+                current: 0,
+                received: true,
+            })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Phases> {
+        self.inner.lock().expect("stats are not poisoned")
+    }
+
+    /// Starts the phase with the given name.
+    pub fn set_phase(&self, phase: &str) {
+        let mut inner = self.lock();
+        inner.current = match inner.phases.iter().position(|(name, _)| name == phase) {
+            Some(index) => index,
+            None => {
+                inner.phases.push((phase.to_owned(), PhaseStats::default()));
+                inner.phases.len() - 1
+            }
+        };
+    }
+
+    /// Returns the name of the current phase.
+    pub fn phase(&self) -> String {
+        let inner = self.lock();
+        inner.phases[inner.current].0.clone()
+    }
+
+    /// Counts a message with `bytes` bytes sent in the current phase.
+    pub(crate) fn record_send(&self, bytes: usize) {
+        let mut inner = self.lock();
+        let new_round = std::mem::replace(&mut inner.received, false);
+        let current = inner.current;
+        let stats = &mut inner.phases[current].1;
+        stats.messages_sent += 1;
+        stats.bytes_sent += bytes as u64;
+        if new_round {
+            stats.rounds += 1;
+        }
+    }
+
+    /// Counts a message with `bytes` bytes received in the current phase.
+    pub(crate) fn record_recv(&self, bytes: usize) {
+        let mut inner = self.lock();
+        inner.received = true;
+        let current = inner.current;
+        let stats = &mut inner.phases[current].1;
+        stats.messages_received += 1;
+        stats.bytes_received += bytes as u64;
+    }
+
+    /// Returns the statistics of all phases in the order they were started. The [DEFAULT_PHASE] is only included if there was communication in it.
+    pub fn phases(&self) -> Vec<(String, PhaseStats)> {
+        self.lock()
+            .phases
+            .iter()
+            .enumerate()
+            .filter(|(i, (_, stats))| *i != 0 || *stats != PhaseStats::default())
+            .map(|(_, phase)| phase.clone())
+            .collect()
+    }
+
+    /// Returns the statistics summed over all phases.
+    pub fn total(&self) -> PhaseStats {
+        self.lock()
+            .phases
+            .iter()
+            .fold(PhaseStats::default(), |mut total, (_, stats)| {
+                total += *stats;
+                total
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_and_rounds() {
+        let stats = CommunicationStats::new();
+        let handle = stats.clone();
+        stats.set_phase("a");
+        stats.record_send(10);
+        stats.record_send(10);
+        stats.record_recv(5);
+        stats.record_recv(5);
+        stats.set_phase("b");
+        stats.record_send(3);
+        stats.record_recv(4);
+        stats.set_phase("a");
+        stats.record_send(1);
+        assert_eq!(handle.phase(), "a");
+
+        let phases = handle.phases();
+        assert_eq!(phases.len(), 2);
+        assert_eq!(phases[0].0, "a");
+        assert_eq!(
+            phases[0].1,
+            PhaseStats {
+                messages_sent: 3,
+                messages_received: 2,
+                bytes_sent: 21,
+                bytes_received: 10,
+                rounds: 2,
+            }
+        );
+        assert_eq!(phases[1].0, "b");
+        assert_eq!(phases[1].1.rounds, 1);
+        assert_eq!(handle.total().rounds, 3);
+        assert_eq!(handle.total().bytes_received, 14);
+    }
+}
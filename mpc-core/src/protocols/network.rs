@@ -0,0 +1,161 @@
+//! # Network Utils
+//!
+//! Helpers shared by the network implementations of the MPC protocols.
+
+use std::{collections::VecDeque, io, time::Duration};
+
+use bytes::BytesMut;
+use mpc_net::{
+    channel::{Channel, ChannelHandle},
+    codecs::{AbortSignal, ProtocolCodec, ProtocolMessage},
+    transport::{RecvStream, SendStream},
+};
+use tokio::sync::oneshot::{self, error::TryRecvError};
+
+/// How long we try to deliver an abort message to the other parties.
+const ABORT_SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A managed channel to another party. It keeps the results of the sends that did not finish yet,
+/// so that a failed send is reported by the next operation on the channel, and it stops waiting
+/// for messages if a party aborted the protocol or the receive timeout elapsed.
+#[derive(Debug)]
+pub(crate) struct PartyChannel {
+    party: usize,
+    handle: ChannelHandle<ProtocolMessage, BytesMut>,
+    pending_sends: VecDeque<oneshot::Receiver<io::Result<()>>>,
+    signal: AbortSignal,
+    recv_timeout: Option<Duration>,
+}
+
+impl PartyChannel {
+    /// Manages the channel to `party`, needs to be called within a tokio runtime.
+    pub(crate) fn manage(
+        party: usize,
+        chan: Channel<RecvStream, SendStream, ProtocolCodec>,
+        signal: AbortSignal,
+        recv_timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            party,
+            handle: ChannelHandle::manage(chan),
+            pending_sends: VecDeque::new(),
+            signal,
+            recv_timeout,
+        }
+    }
+
+    /// Returns the error of a party that aborted the protocol or of a send that failed since the
+    /// last call.
+    fn check(&mut self) -> io::Result<()> {
+        if let Some(err) = self.signal.error() {
+            return Err(err);
+        }
+        while let Some(pending) = self.pending_sends.front_mut() {
+            match pending.try_recv() {
+                Ok(result) => {
+                    self.pending_sends.pop_front();
+                    result?;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Closed) => {
+                    self.pending_sends.pop_front();
+                    return Err(send_channel_died());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Queues the message to be sent, reporting the errors of the previous sends.
+    pub(crate) fn send(&mut self, message: ProtocolMessage) -> io::Result<()> {
+        self.check()?;
+        let pending = self.handle.blocking_send(message);
+        self.pending_sends.push_back(pending);
+        Ok(())
+    }
+
+    /// Waits for all queued messages to be sent.
+    pub(crate) async fn flush(&mut self) -> io::Result<()> {
+        while let Some(pending) = self.pending_sends.pop_front() {
+            pending.await.map_err(|_| send_channel_died())??;
+        }
+        Ok(())
+    }
+
+    /// Receives the next message, blocking the current thread on the given runtime.
+    pub(crate) fn recv(&mut self, runtime: &tokio::runtime::Runtime) -> io::Result<BytesMut> {
+        self.check()?;
+        let recv = self.handle.blocking_recv();
+        let recv = async {
+            recv.await.map_err(|_| {
+                io::Error::new(io::ErrorKind::BrokenPipe, "receive channel end died")
+            })?
+        };
+        runtime.block_on(async {
+            let recv = async {
+                match self.recv_timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, recv).await.map_err(|_| {
+                        io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            format!(
+                                "did not receive a message of party {} within {timeout:?}",
+                                self.party
+                            ),
+                        )
+                    })?,
+                    None => recv.await,
+                }
+            };
+            tokio::select! {
+                // a party that aborted is the reason for any other error
+                biased;
+                err = self.signal.aborted() => Err(err),
+                result = recv => result,
+            }
+        })
+    }
+}
+
+fn send_channel_died() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "send channel end died")
+}
+
+/// Sends an abort message to all `channels` and waits (at most [ABORT_SEND_TIMEOUT]) until it is
+/// sent. Afterwards, the local `signal` is set, so the channels refuse any further operation.
+pub(crate) fn abort<'a>(
+    runtime: &tokio::runtime::Runtime,
+    my_id: usize,
+    signal: &AbortSignal,
+    channels: impl IntoIterator<Item = &'a mut PartyChannel>,
+    reason: &str,
+) -> io::Result<()> {
+    let mut channels = channels.into_iter().collect::<Vec<_>>();
+    let message = ProtocolMessage::Abort {
+        party: my_id,
+        reason: reason.to_owned(),
+    };
+    for chan in channels.iter_mut() {
+        // we abort anyway, so previous errors do not matter
+        let pending = chan.handle.blocking_send(message.clone());
+        chan.pending_sends.push_back(pending);
+    }
+    signal.abort(my_id, reason.to_owned());
+    runtime.block_on(async {
+        tokio::time::timeout(ABORT_SEND_TIMEOUT, async {
+            for chan in channels {
+                // only the abort message itself needs to get through
+                let abort_sent = chan.pending_sends.pop_back().expect("abort was queued");
+                chan.pending_sends.clear();
+                abort_sent.await.map_err(|_| send_channel_died())??;
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("could not send the abort message within {ABORT_SEND_TIMEOUT:?}"),
+            )
+        })?
+    })
+}
@@ -112,15 +112,15 @@ impl<F: PrimeField> PrimeFieldMpcProtocol<F> for PlainDriver<F> {
     type FieldShare = F;
     type FieldShareVec = Vec<F>;
 
-    fn add(&mut self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
+    fn add(&self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
         *a + b
     }
 
-    fn sub(&mut self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
+    fn sub(&self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
         *a - b
     }
 
-    fn add_with_public(&mut self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
+    fn add_with_public(&self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
         *a + b
     }
 
@@ -144,7 +144,7 @@ impl<F: PrimeField> PrimeFieldMpcProtocol<F> for PlainDriver<F> {
         Ok(a.iter().zip(b.iter()).map(|(a, b)| *a * b).collect())
     }
 
-    fn mul_with_public(&mut self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
+    fn mul_with_public(&self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
         *a * b
     }
 
@@ -174,7 +174,7 @@ impl<F: PrimeField> PrimeFieldMpcProtocol<F> for PlainDriver<F> {
         Ok(res)
     }
 
-    fn neg(&mut self, a: &Self::FieldShare) -> Self::FieldShare {
+    fn neg(&self, a: &Self::FieldShare) -> Self::FieldShare {
         -*a
     }
 
@@ -232,7 +232,7 @@ impl<F: PrimeField> PrimeFieldMpcProtocol<F> for PlainDriver<F> {
     }
 
     fn evaluate_constraint(
-        &mut self,
+        &self,
         lhs: &[(F, usize)],
         public_inputs: &[F],
         private_witness: &Self::FieldShareVec,
@@ -278,32 +278,32 @@ impl<F: PrimeField> PrimeFieldMpcProtocol<F> for PlainDriver<F> {
 impl<C: CurveGroup> EcMpcProtocol<C> for PlainDriver<C::ScalarField> {
     type PointShare = C;
 
-    fn add_points(&mut self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
+    fn add_points(&self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
         *a + b
     }
 
-    fn sub_points(&mut self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
+    fn sub_points(&self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
         *a - b
     }
 
-    fn add_assign_points(&mut self, a: &mut Self::PointShare, b: &Self::PointShare) {
+    fn add_assign_points(&self, a: &mut Self::PointShare, b: &Self::PointShare) {
         *a += b;
     }
 
-    fn sub_assign_points(&mut self, a: &mut Self::PointShare, b: &Self::PointShare) {
+    fn sub_assign_points(&self, a: &mut Self::PointShare, b: &Self::PointShare) {
         *a -= b;
     }
 
-    fn add_assign_points_public(&mut self, a: &mut Self::PointShare, b: &C) {
+    fn add_assign_points_public(&self, a: &mut Self::PointShare, b: &C) {
         *a += b;
     }
 
-    fn sub_assign_points_public(&mut self, a: &mut Self::PointShare, b: &C) {
+    fn sub_assign_points_public(&self, a: &mut Self::PointShare, b: &C) {
         *a -= b;
     }
 
     fn add_assign_points_public_affine(
-        &mut self,
+        &self,
         a: &mut Self::PointShare,
         b: &<C as CurveGroup>::Affine,
     ) {
@@ -311,19 +311,19 @@ impl<C: CurveGroup> EcMpcProtocol<C> for PlainDriver<C::ScalarField> {
     }
 
     fn sub_assign_points_public_affine(
-        &mut self,
+        &self,
         a: &mut Self::PointShare,
         b: &<C as CurveGroup>::Affine,
     ) {
         *a -= b;
     }
 
-    fn scalar_mul_public_point(&mut self, a: &C, b: &Self::FieldShare) -> Self::PointShare {
+    fn scalar_mul_public_point(&self, a: &C, b: &Self::FieldShare) -> Self::PointShare {
         *a * b
     }
 
     fn scalar_mul_public_scalar(
-        &mut self,
+        &self,
         a: &Self::PointShare,
         b: &<C>::ScalarField,
     ) -> Self::PointShare {
@@ -355,6 +355,21 @@ impl<P: Pairing> PairingEcMpcProtocol<P> for PlainDriver<P::ScalarField> {
     ) -> std::io::Result<(P::G1, P::G2)> {
         Ok((*a, *b))
     }
+
+    fn open_two_points_and_mul_open(
+        &mut self,
+        a: &<Self as EcMpcProtocol<P::G1>>::PointShare,
+        b: &<Self as EcMpcProtocol<P::G2>>::PointShare,
+        c: &<Self as EcMpcProtocol<P::G1>>::PointShare,
+        x: &[<Self as EcMpcProtocol<P::G1>>::PointShare],
+        y: &[<Self as PrimeFieldMpcProtocol<P::ScalarField>>::FieldShare],
+    ) -> std::io::Result<(P::G1, P::G2, P::G1)> {
+        let mut c = *c;
+        for (x, y) in x.iter().zip(y) {
+            c += *x * y;
+        }
+        Ok((*a, *b, c))
+    }
 }
 
 impl<F: PrimeField> FFTProvider<F> for PlainDriver<F> {
@@ -398,7 +413,7 @@ impl<F: PrimeField> FFTProvider<F> for PlainDriver<F> {
 
 impl<C: CurveGroup> MSMProvider<C> for PlainDriver<C::ScalarField> {
     fn msm_public_points(
-        &mut self,
+        &self,
         points: &[C::Affine],
         scalars: &Self::FieldShareVec,
     ) -> Self::PointShare {
@@ -8,6 +8,7 @@ use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Polynomial};
 use eyre::Report;
 use itertools::{izip, Itertools};
 use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
 use rngs::{Rep3CorrelatedRng, Rep3Rand, Rep3RandBitComp};
 use std::marker::PhantomData;
 use witness_extension_impl::Rep3VmType;
@@ -372,6 +373,7 @@ impl<F, N: Rep3Network> Rep3Protocol<F, N> {
 
     /// Constructs the Rep3 protocol from an established network.
     pub fn new(mut network: N) -> Result<Self, Report> {
+        network.set_phase("rep3: setup");
         let mut rand = Self::setup_prf(&mut network)?;
         let bitcomps = Self::setup_bitcomp(&mut network, &mut rand)?;
         let rngs = Rep3CorrelatedRng::new(rand, bitcomps.0, bitcomps.1);
@@ -481,11 +483,15 @@ impl<F: PrimeField, N: Rep3Network> PrimeFieldMpcProtocol<F> for Rep3Protocol<F,
     type FieldShare = Rep3PrimeFieldShare<F>;
     type FieldShareVec = Rep3PrimeFieldShareVec<F>;
 
-    fn add(&mut self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
+    fn set_phase(&mut self, phase: &str) {
+        self.network.set_phase(phase);
+    }
+
+    fn add(&self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
         a + b
     }
 
-    fn sub(&mut self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
+    fn sub(&self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
         a - b
     }
 
@@ -546,22 +552,28 @@ impl<F: PrimeField, N: Rep3Network> PrimeFieldMpcProtocol<F> for Rep3Protocol<F,
         Ok(res)
     }
 
-    fn neg(&mut self, a: &Self::FieldShare) -> Self::FieldShare {
+    fn neg(&self, a: &Self::FieldShare) -> Self::FieldShare {
         -a
     }
 
     fn neg_vec_in_place(&mut self, vec: &mut Self::FieldShareVec) {
-        for (a, b) in vec.a.iter_mut().zip(vec.b.iter_mut()) {
-            a.neg_in_place();
-            b.neg_in_place();
-        }
+        vec.a
+            .par_iter_mut()
+            .chain(vec.b.par_iter_mut())
+            .for_each(|x| {
+                x.neg_in_place();
+            });
     }
 
     fn neg_vec_in_place_limit(&mut self, vec: &mut Self::FieldShareVec, limit: usize) {
-        for (a, b) in vec.a.iter_mut().zip(vec.b.iter_mut()).take(limit) {
-            a.neg_in_place();
-            b.neg_in_place();
-        }
+        let limit_a = limit.min(vec.a.len());
+        let limit_b = limit.min(vec.b.len());
+        vec.a[..limit_a]
+            .par_iter_mut()
+            .chain(vec.b[..limit_b].par_iter_mut())
+            .for_each(|x| {
+                x.neg_in_place();
+            });
     }
 
     fn rand(&mut self) -> std::io::Result<Self::FieldShare> {
@@ -569,7 +581,7 @@ impl<F: PrimeField, N: Rep3Network> PrimeFieldMpcProtocol<F> for Rep3Protocol<F,
         Ok(Self::FieldShare { a, b })
     }
 
-    fn add_with_public(&mut self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
+    fn add_with_public(&self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
         let mut res = b.to_owned();
         match self.network.get_id() {
             id::PartyID::ID0 => res.a += a,
@@ -579,7 +591,7 @@ impl<F: PrimeField, N: Rep3Network> PrimeFieldMpcProtocol<F> for Rep3Protocol<F,
         res
     }
 
-    fn mul_with_public(&mut self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
+    fn mul_with_public(&self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
         b * a
     }
 
@@ -609,12 +621,20 @@ impl<F: PrimeField, N: Rep3Network> PrimeFieldMpcProtocol<F> for Rep3Protocol<F,
 
     fn add_vec(&mut self, a: &Self::FieldShareVec, b: &Self::FieldShareVec) -> Self::FieldShareVec {
         debug_assert_eq!(a.len(), b.len());
-        let a_vec = izip!(a.a.iter(), b.a.iter())
-            .map(|(a, b)| *a + b)
-            .collect::<Vec<_>>();
-        let b_vec = izip!(a.b.iter(), b.b.iter())
-            .map(|(a, b)| *a + b)
-            .collect::<Vec<_>>();
+        let (a_vec, b_vec) = rayon::join(
+            || {
+                (a.a.par_iter(), b.a.par_iter())
+                    .into_par_iter()
+                    .map(|(a, b)| *a + b)
+                    .collect::<Vec<_>>()
+            },
+            || {
+                (a.b.par_iter(), b.b.par_iter())
+                    .into_par_iter()
+                    .map(|(a, b)| *a + b)
+                    .collect::<Vec<_>>()
+            },
+        );
 
         Self::FieldShareVec::new(a_vec, b_vec)
     }
@@ -625,11 +645,30 @@ impl<F: PrimeField, N: Rep3Network> PrimeFieldMpcProtocol<F> for Rep3Protocol<F,
         b: &Self::FieldShareVec,
     ) -> std::io::Result<Self::FieldShareVec> {
         debug_assert_eq!(a.len(), b.len());
-        let local_a = izip!(a.a.iter(), a.b.iter(), b.a.iter(), b.b.iter())
-            .map(|(aa, ab, ba, bb)| {
-                *aa * ba + *aa * bb + *ab * ba + self.rngs.rand.masking_field_element::<F>()
-            })
-            .collect_vec();
+        // the masks have to be drawn sequentially from the correlated randomness, so we draw them while computing the products in parallel
+        let rand = &mut self.rngs.rand;
+        let (masks, mut local_a) = rayon::join(
+            || {
+                (0..a.len())
+                    .map(|_| rand.masking_field_element::<F>())
+                    .collect_vec()
+            },
+            || {
+                (
+                    a.a.par_iter(),
+                    a.b.par_iter(),
+                    b.a.par_iter(),
+                    b.b.par_iter(),
+                )
+                    .into_par_iter()
+                    .map(|(aa, ab, ba, bb)| *aa * ba + *aa * bb + *ab * ba)
+                    .collect::<Vec<_>>()
+            },
+        );
+        local_a
+            .par_iter_mut()
+            .zip(masks.par_iter())
+            .for_each(|(x, mask)| *x += mask);
         self.network.send_next_many(&local_a)?;
         let local_b = self.network.recv_prev_many()?;
         if local_b.len() != local_a.len() {
@@ -642,25 +681,21 @@ impl<F: PrimeField, N: Rep3Network> PrimeFieldMpcProtocol<F> for Rep3Protocol<F,
     }
 
     fn sub_assign_vec(&mut self, a: &mut Self::FieldShareVec, b: &Self::FieldShareVec) {
-        for (a, b) in izip!(a.a.iter_mut(), &b.a) {
-            *a -= b;
-        }
-        for (a, b) in izip!(a.b.iter_mut(), &b.b) {
-            *a -= b;
-        }
+        a.a.par_iter_mut()
+            .zip(b.a.par_iter())
+            .chain(a.b.par_iter_mut().zip(b.b.par_iter()))
+            .for_each(|(a, b)| *a -= b);
     }
 
     fn distribute_powers_and_mul_by_const(&mut self, coeffs: &mut Self::FieldShareVec, g: F, c: F) {
-        let mut pow = c;
-        for (a, b) in coeffs.a.iter_mut().zip(coeffs.b.iter_mut()) {
-            *a *= pow;
-            *b *= pow;
-            pow *= g;
-        }
+        rayon::join(
+            || crate::protocols::utils::distribute_powers_and_mul_by_const(&mut coeffs.a, g, c),
+            || crate::protocols::utils::distribute_powers_and_mul_by_const(&mut coeffs.b, g, c),
+        );
     }
 
     fn evaluate_constraint(
-        &mut self,
+        &self,
         lhs: &[(F, usize)],
         public_inputs: &[F],
         private_witness: &Self::FieldShareVec,
@@ -741,23 +776,23 @@ impl<F: PrimeField> Default for Rep3PrimeFieldShare<F> {
 impl<C: CurveGroup, N: Rep3Network> EcMpcProtocol<C> for Rep3Protocol<C::ScalarField, N> {
     type PointShare = Rep3PointShare<C>;
 
-    fn add_points(&mut self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
+    fn add_points(&self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
         a + b
     }
 
-    fn sub_points(&mut self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
+    fn sub_points(&self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
         a - b
     }
 
-    fn add_assign_points(&mut self, a: &mut Self::PointShare, b: &Self::PointShare) {
+    fn add_assign_points(&self, a: &mut Self::PointShare, b: &Self::PointShare) {
         *a += b;
     }
 
-    fn sub_assign_points(&mut self, a: &mut Self::PointShare, b: &Self::PointShare) {
+    fn sub_assign_points(&self, a: &mut Self::PointShare, b: &Self::PointShare) {
         *a -= b;
     }
 
-    fn add_assign_points_public(&mut self, a: &mut Self::PointShare, b: &C) {
+    fn add_assign_points_public(&self, a: &mut Self::PointShare, b: &C) {
         match self.network.get_id() {
             id::PartyID::ID0 => a.a += b,
             id::PartyID::ID1 => a.b += b,
@@ -765,7 +800,7 @@ impl<C: CurveGroup, N: Rep3Network> EcMpcProtocol<C> for Rep3Protocol<C::ScalarF
         }
     }
 
-    fn sub_assign_points_public(&mut self, a: &mut Self::PointShare, b: &C) {
+    fn sub_assign_points_public(&self, a: &mut Self::PointShare, b: &C) {
         match self.network.get_id() {
             id::PartyID::ID0 => a.a -= b,
             id::PartyID::ID1 => a.b -= b,
@@ -773,7 +808,7 @@ impl<C: CurveGroup, N: Rep3Network> EcMpcProtocol<C> for Rep3Protocol<C::ScalarF
         }
     }
 
-    fn add_assign_points_public_affine(&mut self, a: &mut Self::PointShare, b: &C::Affine) {
+    fn add_assign_points_public_affine(&self, a: &mut Self::PointShare, b: &C::Affine) {
         match self.network.get_id() {
             id::PartyID::ID0 => a.a += b,
             id::PartyID::ID1 => a.b += b,
@@ -781,7 +816,7 @@ impl<C: CurveGroup, N: Rep3Network> EcMpcProtocol<C> for Rep3Protocol<C::ScalarF
         }
     }
 
-    fn sub_assign_points_public_affine(&mut self, a: &mut Self::PointShare, b: &C::Affine) {
+    fn sub_assign_points_public_affine(&self, a: &mut Self::PointShare, b: &C::Affine) {
         match self.network.get_id() {
             id::PartyID::ID0 => a.a -= b,
             id::PartyID::ID1 => a.b -= b,
@@ -789,7 +824,7 @@ impl<C: CurveGroup, N: Rep3Network> EcMpcProtocol<C> for Rep3Protocol<C::ScalarF
         }
     }
 
-    fn scalar_mul_public_point(&mut self, a: &C, b: &Self::FieldShare) -> Self::PointShare {
+    fn scalar_mul_public_point(&self, a: &C, b: &Self::FieldShare) -> Self::PointShare {
         Self::PointShare {
             a: a.mul(b.a),
             b: a.mul(b.b),
@@ -797,7 +832,7 @@ impl<C: CurveGroup, N: Rep3Network> EcMpcProtocol<C> for Rep3Protocol<C::ScalarF
     }
 
     fn scalar_mul_public_scalar(
-        &mut self,
+        &self,
         a: &Self::PointShare,
         b: &<C>::ScalarField,
     ) -> Self::PointShare {
@@ -847,6 +882,31 @@ impl<P: Pairing, N: Rep3Network> PairingEcMpcProtocol<P> for Rep3Protocol<P::Sca
         r2 += b.a + b.b;
         Ok((r1, r2))
     }
+
+    fn open_two_points_and_mul_open(
+        &mut self,
+        a: &<Self as EcMpcProtocol<P::G1>>::PointShare,
+        b: &<Self as EcMpcProtocol<P::G2>>::PointShare,
+        c: &<Self as EcMpcProtocol<P::G1>>::PointShare,
+        x: &[<Self as EcMpcProtocol<P::G1>>::PointShare],
+        y: &[<Self as PrimeFieldMpcProtocol<P::ScalarField>>::FieldShare],
+    ) -> std::io::Result<(P::G1, P::G2, P::G1)> {
+        // The products result in an additive share, so we convert c to an additive share as well and mask the sum. Thus, the next party additionally gets the missing replicated shares of a and b.
+        let mut c = c.a + self.rngs.rand.masking_ec_element::<P::G1>();
+        for (x, y) in izip!(x, y) {
+            c += y * x;
+        }
+        self.network.send_next((a.b, b.b, c))?;
+        self.network.send(self.network.get_id().prev_id(), c)?;
+
+        let (mut r1, mut r2, c_prev) = self.network.recv_prev::<(P::G1, P::G2, P::G1)>()?;
+        let c_next = self
+            .network
+            .recv::<P::G1>(self.network.get_id().next_id())?;
+        r1 += a.a + a.b;
+        r2 += b.a + b.b;
+        Ok((r1, r2, c + c_prev + c_next))
+    }
 }
 
 impl<F: PrimeField, N: Rep3Network> FFTProvider<F> for Rep3Protocol<F, N> {
@@ -856,16 +916,17 @@ impl<F: PrimeField, N: Rep3Network> FFTProvider<F> for Rep3Protocol<F, N> {
         domain: &D,
     ) -> Self::FieldShareVec {
         tracing::trace!("> FFT for {} elements", data.len());
-        let a = domain.fft(&data.a);
-        let b = domain.fft(&data.b);
+        let (a, b) = rayon::join(|| domain.fft(&data.a), || domain.fft(&data.b));
         tracing::trace!("< FFT for {} elements", data.len());
         Self::FieldShareVec::new(a, b)
     }
 
     fn fft_in_place<D: EvaluationDomain<F>>(&mut self, data: &mut Self::FieldShareVec, domain: &D) {
         tracing::trace!("> FFT (in place) for {} elements", data.len());
-        domain.fft_in_place(&mut data.a);
-        domain.fft_in_place(&mut data.b);
+        rayon::join(
+            || domain.fft_in_place(&mut data.a),
+            || domain.fft_in_place(&mut data.b),
+        );
         tracing::trace!("< FFT (in place) for {} elements", data.len());
     }
 
@@ -875,8 +936,7 @@ impl<F: PrimeField, N: Rep3Network> FFTProvider<F> for Rep3Protocol<F, N> {
         domain: &D,
     ) -> Self::FieldShareVec {
         tracing::trace!("> IFFT (in place) for {} elements", data.len());
-        let a = domain.ifft(&data.a);
-        let b = domain.ifft(&data.b);
+        let (a, b) = rayon::join(|| domain.ifft(&data.a), || domain.ifft(&data.b));
         tracing::trace!("< IFFT (in place) for {} elements", data.len());
         Self::FieldShareVec::new(a, b)
     }
@@ -887,8 +947,10 @@ impl<F: PrimeField, N: Rep3Network> FFTProvider<F> for Rep3Protocol<F, N> {
         domain: &D,
     ) {
         tracing::trace!("> IFFT (in place) for {} elements", data.len());
-        domain.ifft_in_place(&mut data.a);
-        domain.ifft_in_place(&mut data.b);
+        rayon::join(
+            || domain.ifft_in_place(&mut data.a),
+            || domain.ifft_in_place(&mut data.b),
+        );
         tracing::trace!("< IFFT (in place) for {} elements", data.len());
     }
 
@@ -896,8 +958,7 @@ impl<F: PrimeField, N: Rep3Network> FFTProvider<F> for Rep3Protocol<F, N> {
         tracing::trace!("> evaluate poly public");
         let poly_a = DensePolynomial { coeffs: poly.a };
         let poly_b = DensePolynomial { coeffs: poly.b };
-        let a = poly_a.evaluate(point);
-        let b = poly_b.evaluate(point);
+        let (a, b) = rayon::join(|| poly_a.evaluate(point), || poly_b.evaluate(point));
         tracing::trace!("< evaluate poly public");
         Self::FieldShare::new(a, b)
     }
@@ -905,14 +966,16 @@ impl<F: PrimeField, N: Rep3Network> FFTProvider<F> for Rep3Protocol<F, N> {
 
 impl<C: CurveGroup, N: Rep3Network> MSMProvider<C> for Rep3Protocol<C::ScalarField, N> {
     fn msm_public_points(
-        &mut self,
+        &self,
         points: &[C::Affine],
         scalars: &Self::FieldShareVec,
     ) -> Self::PointShare {
         tracing::trace!("> MSM public points for {} elements", points.len());
         debug_assert_eq!(points.len(), scalars.len());
-        let res_a = C::msm_unchecked(points, &scalars.a);
-        let res_b = C::msm_unchecked(points, &scalars.b);
+        let (res_a, res_b) = rayon::join(
+            || C::msm_unchecked(points, &scalars.a),
+            || C::msm_unchecked(points, &scalars.b),
+        );
         tracing::trace!("< MSM public points for {} elements", points.len());
         Self::PointShare { a: res_a, b: res_b }
     }
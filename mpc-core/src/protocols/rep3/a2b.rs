@@ -157,8 +157,8 @@ impl<F: PrimeField, N: Rep3Network> Rep3Protocol<F, N> {
         let (mut mask, mask_b) = self.rngs.rand.random_biguint(bitlen);
         mask ^= mask_b;
         let local_a = (a & b) ^ mask;
-        self.network.send_next(local_a.to_owned())?;
-        let local_b = self.network.recv_prev()?;
+        self.network.send_next_bits(&local_a, bitlen)?;
+        let local_b = self.network.recv_prev_bits(bitlen)?;
         Ok(Rep3BigUintShare {
             a: local_a,
             b: local_b,
@@ -183,10 +183,10 @@ impl<F: PrimeField, N: Rep3Network> Rep3Protocol<F, N> {
 
         let local_a1 = (b1 & &a) ^ mask1;
         let local_a2 = (a & b2) ^ mask2;
-        self.network.send_next(local_a1.to_owned())?;
-        self.network.send_next(local_a2.to_owned())?;
-        let local_b1 = self.network.recv_prev()?;
-        let local_b2 = self.network.recv_prev()?;
+        self.network.send_next_bits(&local_a1, bitlen)?;
+        self.network.send_next_bits(&local_a2, bitlen)?;
+        let local_b1 = self.network.recv_prev_bits(bitlen)?;
+        let local_b2 = self.network.recv_prev_bits(bitlen)?;
 
         let r1 = Rep3BigUintShare {
             a: local_a1,
@@ -387,8 +387,8 @@ impl<F: PrimeField, N: Rep3Network> Rep3Protocol<F, N> {
         }
 
         // Reshare x01
-        self.network.send_next(x01.a.to_owned())?;
-        let local_b = self.network.recv_prev()?;
+        self.network.send_next_bits(&x01.a, Self::BITLEN)?;
+        let local_b = self.network.recv_prev_bits(Self::BITLEN)?;
         x01.b = local_b;
 
         self.low_depth_binary_add_mod_p(x01, x2)
@@ -471,24 +471,24 @@ impl<F: PrimeField, N: Rep3Network> Rep3Protocol<F, N> {
         }
 
         // Reshare y
-        self.network.send_next(y.a.to_owned())?;
-        let local_b = self.network.recv_prev()?;
+        self.network.send_next_bits(&y.a, Self::BITLEN)?;
+        let local_b = self.network.recv_prev_bits(Self::BITLEN)?;
         y.b = local_b;
 
         let z = self.low_depth_binary_add_mod_p(x, y)?;
 
         match self.network.get_id() {
             PartyID::ID0 => {
-                self.network.send_next(z.b.to_owned())?;
-                let rcv: BigUint = self.network.recv_prev()?;
+                self.network.send_next_bits(&z.b, Self::BITLEN)?;
+                let rcv = self.network.recv_prev_bits(Self::BITLEN)?;
                 res.a = (z.a ^ z.b ^ rcv).into();
             }
             PartyID::ID1 => {
-                let rcv: BigUint = self.network.recv_prev()?;
+                let rcv = self.network.recv_prev_bits(Self::BITLEN)?;
                 res.b = (z.a ^ z.b ^ rcv).into();
             }
             PartyID::ID2 => {
-                self.network.send_next(z.b)?;
+                self.network.send_next_bits(&z.b, Self::BITLEN)?;
             }
         }
         Ok(res)
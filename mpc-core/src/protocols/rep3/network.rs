@@ -5,9 +5,18 @@
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use bytes::{Bytes, BytesMut};
 use eyre::{bail, eyre, Report};
-use mpc_net::{channel::ChannelHandle, config::NetworkConfig, MpcNetworkHandler};
+use mpc_net::{
+    codecs::{AbortSignal, ProtocolMessage},
+    config::NetworkConfig,
+    MpcNetworkHandler,
+};
+use num_bigint::BigUint;
 
 use super::id::PartyID;
+use crate::protocols::{
+    communication::CommunicationStats,
+    network::{self, PartyChannel},
+};
 
 /// This trait defines the network interface for the REP3 protocol.
 pub trait Rep3Network {
@@ -61,6 +70,48 @@ pub trait Rep3Network {
     fn recv_prev_many<F: CanonicalDeserialize>(&mut self) -> std::io::Result<Vec<F>> {
         self.recv_many(self.get_id().prev_id())
     }
+
+    /// Sends a share of a bit vector with at most `bitlen` bits (e.g., of a [Rep3BigUintShare](super::Rep3BigUintShare)) to the party with id = next_id. This function has a default implementation for calling [Rep3Network::send_next], implementations can pack the bits more compactly.
+    fn send_next_bits(&mut self, data: &BigUint, bitlen: usize) -> std::io::Result<()> {
+        let _ = bitlen;
+        self.send_next(data.to_owned())
+    }
+
+    /// Receives a share of a bit vector with at most `bitlen` bits sent with [Rep3Network::send_next_bits] from the party with id = prev_id. This function has a default implementation for calling [Rep3Network::recv_prev].
+    fn recv_prev_bits(&mut self, bitlen: usize) -> std::io::Result<BigUint> {
+        let _ = bitlen;
+        self.recv_prev()
+    }
+
+    /// Marks the start of a named phase of the protocol (e.g., "groth16: witness map"), the communication afterwards is accounted to this phase until the next one starts (see [CommunicationStats]). This function has a default implementation that does nothing.
+    fn set_phase(&mut self, phase: &str) {
+        let _ = phase;
+    }
+}
+
+/// Packs a bit vector with at most `bitlen` bits into exactly `bitlen.div_ceil(8)` bytes.
+pub(crate) fn pack_bits(data: &BigUint, bitlen: usize) -> std::io::Result<Vec<u8>> {
+    if data.bits() > bitlen as u64 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("bit vector has more than {bitlen} bits"),
+        ));
+    }
+    let mut bytes = data.to_bytes_le();
+    bytes.resize(bitlen.div_ceil(8), 0);
+    Ok(bytes)
+}
+
+/// Unpacks a bit vector with at most `bitlen` bits packed by [pack_bits].
+pub(crate) fn unpack_bits(bytes: &[u8], bitlen: usize) -> std::io::Result<BigUint> {
+    let data = BigUint::from_bytes_le(bytes);
+    if bytes.len() != bitlen.div_ceil(8) || data.bits() > bitlen as u64 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("expected a bit vector with at most {bitlen} bits"),
+        ));
+    }
+    Ok(data)
 }
 
 /// This struct can be used to facilitate network communication for the REP3 MPC protocol.
@@ -69,8 +120,10 @@ pub struct Rep3MpcNet {
     pub(crate) id: PartyID,
     pub(crate) runtime: tokio::runtime::Runtime,
     pub(crate) net_handler: MpcNetworkHandler,
-    pub(crate) chan_next: ChannelHandle<Bytes, BytesMut>,
-    pub(crate) chan_prev: ChannelHandle<Bytes, BytesMut>,
+    pub(crate) chan_next: PartyChannel,
+    pub(crate) chan_prev: PartyChannel,
+    pub(crate) signal: AbortSignal,
+    pub(crate) stats: CommunicationStats,
 }
 
 impl Rep3MpcNet {
@@ -80,13 +133,55 @@ impl Rep3MpcNet {
             bail!("REP3 protocol requires exactly 3 parties")
         }
         let id = PartyID::try_from(config.my_id)?;
-        let runtime = tokio::runtime::Builder::new_multi_thread()
+        let runtime = Self::runtime()?;
+        let net_handler = runtime.block_on(MpcNetworkHandler::establish(config))?;
+        Self::with_handler(id, runtime, net_handler)
+    }
+
+    /// Constructs the network interface for a session of an established [MpcNetworkHandler]
+    /// (see [MpcNetworkHandler::fork_session]), so that several protocols can run concurrently
+    /// over the same connections. The network needs to contain exactly 3 parties with ids 0, 1,
+    /// and 2. The interface has its own runtime, but for QUIC, the runtime the connections were
+    /// established in needs to be running as long as the session is used.
+    pub fn from_session(session: MpcNetworkHandler) -> Result<Self, Report> {
+        if session.num_parties() != 3 {
+            bail!("REP3 protocol requires exactly 3 parties")
+        }
+        let id = PartyID::try_from(session.id())?;
+        Self::with_handler(id, Self::runtime()?, session)
+    }
+
+    /// Constructs the network interfaces of all three parties, which are connected in memory (see
+    /// [MpcNetworkHandler::in_memory]). The i-th interface belongs to the party with id i and can be
+    /// moved to its own thread to run the protocol in a single process.
+    pub fn in_memory() -> Result<[Self; 3], Report> {
+        let [handler0, handler1, handler2]: [MpcNetworkHandler; 3] =
+            MpcNetworkHandler::in_memory(3)
+                .try_into()
+                .expect("there are 3 handlers");
+        Ok([
+            Self::from_session(handler0)?,
+            Self::from_session(handler1)?,
+            Self::from_session(handler2)?,
+        ])
+    }
+
+    fn runtime() -> std::io::Result<tokio::runtime::Runtime> {
+        tokio::runtime::Builder::new_multi_thread()
             .worker_threads(2)
             .enable_all()
-            .build()?;
-        let (net_handler, chan_next, chan_prev) = runtime.block_on(async {
-            let mut net_handler = MpcNetworkHandler::establish(config).await?;
-            let mut channels = net_handler.get_byte_channels().await?;
+            .build()
+    }
+
+    fn with_handler(
+        id: PartyID,
+        runtime: tokio::runtime::Runtime,
+        mut net_handler: MpcNetworkHandler,
+    ) -> Result<Self, Report> {
+        let signal = AbortSignal::new();
+        let recv_timeout = net_handler.recv_timeout();
+        let (chan_next, chan_prev) = runtime.block_on(async {
+            let mut channels = net_handler.get_protocol_channels(&signal).await?;
             let chan_next = channels
                 .remove(&id.next_id().into())
                 .ok_or(eyre!("no next channel found"))?;
@@ -97,9 +192,11 @@ impl Rep3MpcNet {
                 bail!("unexpected channels found")
             }
 
-            let chan_next = ChannelHandle::manage(chan_next);
-            let chan_prev = ChannelHandle::manage(chan_prev);
-            Ok((net_handler, chan_next, chan_prev))
+            let chan_next =
+                PartyChannel::manage(id.next_id().into(), chan_next, signal.clone(), recv_timeout);
+            let chan_prev =
+                PartyChannel::manage(id.prev_id().into(), chan_prev, signal.clone(), recv_timeout);
+            Ok((chan_next, chan_prev))
         })?;
         Ok(Self {
             id,
@@ -107,58 +204,85 @@ impl Rep3MpcNet {
             net_handler,
             chan_next,
             chan_prev,
+            signal,
+            stats: CommunicationStats::new(),
         })
     }
 
-    /// Shuts down the network interface.
-    pub fn shutdown(self) {
+    /// Shuts down the network interface. Waits until all messages are sent and returns the error
+    /// of a failed send.
+    pub fn shutdown(self) -> std::io::Result<()> {
         let Self {
             id: _,
             runtime,
             net_handler,
-            chan_next,
-            chan_prev,
+            mut chan_next,
+            mut chan_prev,
+            signal: _,
+            stats: _,
         } = self;
-        drop(chan_next);
-        drop(chan_prev);
         runtime.block_on(async {
+            let flushed = chan_next.flush().await.and(chan_prev.flush().await);
+            drop(chan_next);
+            drop(chan_prev);
             net_handler.shutdown().await;
-        });
+            flushed
+        })
     }
 
-    /// Sends bytes over the network to the target party.
+    /// Aborts the protocol, e.g., because of a fatal error, by sending the reason to the other
+    /// parties. Their pending and future operations on the network fail with an error of kind
+    /// [std::io::ErrorKind::ConnectionAborted] that contains the reason, as do ours.
+    pub fn abort(&mut self, reason: &str) -> std::io::Result<()> {
+        network::abort(
+            &self.runtime,
+            self.id.into(),
+            &self.signal,
+            [&mut self.chan_next, &mut self.chan_prev],
+            reason,
+        )
+    }
+
+    /// Sends bytes over the network to the target party. Returns the error of a previous send
+    /// that failed in the meantime.
     pub fn send_bytes(&mut self, target: PartyID, data: Bytes) -> std::io::Result<()> {
+        let len = data.len();
         if target == self.id.next_id() {
-            std::mem::drop(self.chan_next.blocking_send(data));
-            Ok(())
+            self.chan_next.send(ProtocolMessage::Data(data))?;
         } else if target == self.id.prev_id() {
-            std::mem::drop(self.chan_prev.blocking_send(data));
-            Ok(())
+            self.chan_prev.send(ProtocolMessage::Data(data))?;
         } else {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 "Cannot send to self",
             ));
         }
+        self.stats.record_send(len);
+        Ok(())
     }
 
-    /// Receives bytes over the network from the party with the given id.
+    /// Receives bytes over the network from the party with the given id. Fails if a party aborted
+    /// the protocol or no message arrived within the receive timeout of the [NetworkConfig].
     pub fn recv_bytes(&mut self, from: PartyID) -> std::io::Result<BytesMut> {
         let data = if from == self.id.prev_id() {
-            self.chan_prev.blocking_recv().blocking_recv()
+            self.chan_prev.recv(&self.runtime)?
         } else if from == self.id.next_id() {
-            self.chan_next.blocking_recv().blocking_recv()
+            self.chan_next.recv(&self.runtime)?
         } else {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 "Cannot recv from self",
             ));
         };
-        let data = data.map_err(|_| {
-            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "receive channel end died")
-        })??;
+        self.stats.record_recv(data.len());
         Ok(data)
     }
+
+    /// Returns the communication statistics of this network per phase of the protocol. The statistics are shared with the network, so they can be read after the network was moved into a protocol or prover.
+    pub fn communication_stats(&self) -> CommunicationStats {
+        self.stats.clone()
+    }
+
     pub(crate) fn _id(&self) -> PartyID {
         self.id
     }
@@ -189,4 +313,146 @@ impl Rep3Network for Rep3MpcNet {
 
         Ok(res)
     }
+
+    fn send_next_bits(&mut self, data: &BigUint, bitlen: usize) -> std::io::Result<()> {
+        let bytes = pack_bits(data, bitlen)?;
+        self.send_bytes(self.id.next_id(), Bytes::from(bytes))
+    }
+
+    fn recv_prev_bits(&mut self, bitlen: usize) -> std::io::Result<BigUint> {
+        let bytes = self.recv_bytes(self.id.prev_id())?;
+        unpack_bits(&bytes, bitlen)
+    }
+
+    fn set_phase(&mut self, phase: &str) {
+        self.stats.set_phase(phase);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_memory_send_next_recv_prev() {
+        let nets = Rep3MpcNet::in_memory().unwrap();
+        let handles = nets.map(|mut net| {
+            std::thread::spawn(move || {
+                let id = usize::from(net.get_id()) as u64;
+                for round in 0..3u64 {
+                    net.send_next(ark_bn254::Fr::from(10 * id + round)).unwrap();
+                    let prev = net.recv_prev::<ark_bn254::Fr>().unwrap();
+                    let prev_id = usize::from(net.get_id().prev_id()) as u64;
+                    assert_eq!(prev, ark_bn254::Fr::from(10 * prev_id + round));
+                }
+                let (sent, received) = net
+                    .net_handler
+                    .get_send_receive(net.get_id().next_id().into())
+                    .unwrap();
+                assert!(sent > 0);
+                assert_eq!(received, 0);
+                net.shutdown().unwrap();
+            })
+        });
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn concurrent_sessions() {
+        let handlers = MpcNetworkHandler::in_memory(3);
+        let handles = handlers
+            .into_iter()
+            .flat_map(|handler| {
+                // the parties fork their sessions in different orders
+                let mut sessions = vec![handler.fork_session(1), handler.fork_session(2)];
+                if handler.id() == 1 {
+                    sessions.reverse();
+                }
+                sessions
+            })
+            .map(|session| {
+                std::thread::spawn(move || {
+                    let session_id = session.session_id();
+                    let mut net = Rep3MpcNet::from_session(session).unwrap();
+                    let id = usize::from(net.get_id()) as u64;
+                    for round in 0..3u64 {
+                        net.send_next(ark_bn254::Fr::from(100 * session_id + 10 * id + round))
+                            .unwrap();
+                        let prev = net.recv_prev::<ark_bn254::Fr>().unwrap();
+                        let prev_id = usize::from(net.get_id().prev_id()) as u64;
+                        assert_eq!(
+                            prev,
+                            ark_bn254::Fr::from(100 * session_id + 10 * prev_id + round)
+                        );
+                    }
+                    net.shutdown().unwrap();
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn pack_bits_roundtrip() {
+        for bitlen in [1, 8, 9, 254] {
+            let data = (BigUint::from(1u8) << (bitlen - 1)) | BigUint::from(1u8);
+            let bytes = pack_bits(&data, bitlen).unwrap();
+            assert_eq!(bytes.len(), bitlen.div_ceil(8));
+            assert_eq!(unpack_bits(&bytes, bitlen).unwrap(), data);
+        }
+        assert_eq!(pack_bits(&BigUint::from(0u8), 254).unwrap(), vec![0; 32]);
+        assert!(pack_bits(&BigUint::from(2u8), 1).is_err());
+        assert!(unpack_bits(&[2], 1).is_err());
+        assert!(unpack_bits(&[0, 0], 8).is_err());
+    }
+
+    #[test]
+    fn abort_reaches_parties_waiting_for_others() {
+        let [mut net0, net1, net2] = Rep3MpcNet::in_memory().unwrap();
+        // party 1 waits for party 0 and party 2 waits for party 1, party 0 aborts
+        let wait = |mut net: Rep3MpcNet| {
+            std::thread::spawn(move || {
+                let err = net.recv_prev::<ark_bn254::Fr>().unwrap_err();
+                // keep the network alive, so the others do not see a closed connection instead
+                (net, err)
+            })
+        };
+        let (waiting1, waiting2) = (wait(net1), wait(net2));
+        net0.abort("failed assertion").unwrap();
+        let (_net1, err1) = waiting1.join().unwrap();
+        let (_net2, err2) = waiting2.join().unwrap();
+        for err in [err1, err2] {
+            assert_eq!(err.kind(), std::io::ErrorKind::ConnectionAborted);
+            assert_eq!(
+                err.to_string(),
+                "party 0 aborted the protocol: failed assertion"
+            );
+        }
+        let err = net0.send_next(ark_bn254::Fr::from(1u64)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::ConnectionAborted);
+    }
+
+    #[test]
+    fn recv_timeout() {
+        let [mut handler0, handler1, handler2]: [MpcNetworkHandler; 3] =
+            MpcNetworkHandler::in_memory(3).try_into().unwrap();
+        handler0.set_recv_timeout(Some(std::time::Duration::from_millis(50)));
+        let mut net0 = Rep3MpcNet::from_session(handler0).unwrap();
+        let _net1 = Rep3MpcNet::from_session(handler1).unwrap();
+        let _net2 = Rep3MpcNet::from_session(handler2).unwrap();
+        let err = net0.recv_prev::<ark_bn254::Fr>().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn send_to_gone_party_fails() {
+        let [mut net0, net1, _net2] = Rep3MpcNet::in_memory().unwrap();
+        drop(net1);
+        net0.send_next(ark_bn254::Fr::from(1u64)).unwrap();
+        assert!(net0.shutdown().is_err());
+    }
 }
@@ -21,6 +21,7 @@ use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Polynomial};
 use eyre::{bail, Report};
 use itertools::{izip, Itertools};
 use rand::{Rng as _, SeedableRng};
+use rayon::prelude::*;
 use std::marker::PhantomData;
 
 pub mod fieldshare;
@@ -460,22 +461,26 @@ impl<F: PrimeField, N: ShamirNetwork> PrimeFieldMpcProtocol<F> for ShamirProtoco
     type FieldShare = ShamirPrimeFieldShare<F>;
     type FieldShareVec = ShamirPrimeFieldShareVec<F>;
 
-    fn add(&mut self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
+    fn set_phase(&mut self, phase: &str) {
+        self.network.set_phase(phase);
+    }
+
+    fn add(&self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
         a + b
     }
 
-    fn sub(&mut self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
+    fn sub(&self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare {
         a - b
     }
 
-    fn add_with_public(&mut self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
+    fn add_with_public(&self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
         b + a
     }
 
     fn sub_assign_vec(&mut self, a: &mut Self::FieldShareVec, b: &Self::FieldShareVec) {
-        for (a, b) in izip!(a.a.iter_mut(), &b.a) {
-            *a -= b;
-        }
+        a.a.par_iter_mut()
+            .zip(b.a.par_iter())
+            .for_each(|(a, b)| *a -= b);
     }
 
     fn mul(
@@ -501,7 +506,7 @@ impl<F: PrimeField, N: ShamirNetwork> PrimeFieldMpcProtocol<F> for ShamirProtoco
         Ok(ShamirPrimeFieldShare::convert_vec_rev(res.a))
     }
 
-    fn mul_with_public(&mut self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
+    fn mul_with_public(&self, a: &F, b: &Self::FieldShare) -> Self::FieldShare {
         b * a
     }
 
@@ -534,20 +539,21 @@ impl<F: PrimeField, N: ShamirNetwork> PrimeFieldMpcProtocol<F> for ShamirProtoco
         Ok(res)
     }
 
-    fn neg(&mut self, a: &Self::FieldShare) -> Self::FieldShare {
+    fn neg(&self, a: &Self::FieldShare) -> Self::FieldShare {
         -a
     }
 
     fn neg_vec_in_place(&mut self, vec: &mut Self::FieldShareVec) {
-        for a in vec.a.iter_mut() {
+        vec.a.par_iter_mut().for_each(|a| {
             a.neg_in_place();
-        }
+        });
     }
 
     fn neg_vec_in_place_limit(&mut self, vec: &mut Self::FieldShareVec, limit: usize) {
-        for a in vec.a.iter_mut().take(limit) {
+        let limit = limit.min(vec.a.len());
+        vec.a[..limit].par_iter_mut().for_each(|a| {
             a.neg_in_place();
-        }
+        });
     }
 
     fn rand(&mut self) -> std::io::Result<Self::FieldShare> {
@@ -585,7 +591,11 @@ impl<F: PrimeField, N: ShamirNetwork> PrimeFieldMpcProtocol<F> for ShamirProtoco
 
     fn add_vec(&mut self, a: &Self::FieldShareVec, b: &Self::FieldShareVec) -> Self::FieldShareVec {
         Self::FieldShareVec {
-            a: a.a.iter().zip(b.a.iter()).map(|(a, b)| *a + b).collect(),
+            a: a.a
+                .par_iter()
+                .zip(b.a.par_iter())
+                .map(|(a, b)| *a + b)
+                .collect(),
         }
     }
 
@@ -596,12 +606,11 @@ impl<F: PrimeField, N: ShamirNetwork> PrimeFieldMpcProtocol<F> for ShamirProtoco
     ) -> std::io::Result<Self::FieldShareVec> {
         let len = a.len();
         debug_assert_eq!(len, b.len());
-        let mut muls = Vec::with_capacity(len);
-
-        for (a, b) in izip!(a.a.iter(), b.a.iter()) {
-            let mul = *a * b;
-            muls.push(mul);
-        }
+        let muls =
+            a.a.par_iter()
+                .zip(b.a.par_iter())
+                .map(|(a, b)| *a * b)
+                .collect();
         self.degree_reduce_vec(muls)
     }
 
@@ -615,15 +624,11 @@ impl<F: PrimeField, N: ShamirNetwork> PrimeFieldMpcProtocol<F> for ShamirProtoco
     }
 
     fn distribute_powers_and_mul_by_const(&mut self, coeffs: &mut Self::FieldShareVec, g: F, c: F) {
-        let mut pow = c;
-        for a in coeffs.a.iter_mut() {
-            *a *= pow;
-            pow *= g;
-        }
+        crate::protocols::utils::distribute_powers_and_mul_by_const(&mut coeffs.a, g, c);
     }
 
     fn evaluate_constraint(
-        &mut self,
+        &self,
         lhs: &[(F, usize)],
         public_inputs: &[F],
         private_witness: &Self::FieldShareVec,
@@ -697,32 +702,32 @@ impl<F: PrimeField, N: ShamirNetwork> PrimeFieldMpcProtocol<F> for ShamirProtoco
 impl<C: CurveGroup, N: ShamirNetwork> EcMpcProtocol<C> for ShamirProtocol<C::ScalarField, N> {
     type PointShare = ShamirPointShare<C>;
 
-    fn add_points(&mut self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
+    fn add_points(&self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
         a + b
     }
 
-    fn sub_points(&mut self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
+    fn sub_points(&self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare {
         a - b
     }
 
-    fn add_assign_points(&mut self, a: &mut Self::PointShare, b: &Self::PointShare) {
+    fn add_assign_points(&self, a: &mut Self::PointShare, b: &Self::PointShare) {
         *a += b;
     }
 
-    fn sub_assign_points(&mut self, a: &mut Self::PointShare, b: &Self::PointShare) {
+    fn sub_assign_points(&self, a: &mut Self::PointShare, b: &Self::PointShare) {
         *a -= b;
     }
 
-    fn add_assign_points_public(&mut self, a: &mut Self::PointShare, b: &C) {
+    fn add_assign_points_public(&self, a: &mut Self::PointShare, b: &C) {
         a.a += b
     }
 
-    fn sub_assign_points_public(&mut self, a: &mut Self::PointShare, b: &C) {
+    fn sub_assign_points_public(&self, a: &mut Self::PointShare, b: &C) {
         a.a -= b
     }
 
     fn add_assign_points_public_affine(
-        &mut self,
+        &self,
         a: &mut Self::PointShare,
         b: &<C as CurveGroup>::Affine,
     ) {
@@ -730,19 +735,19 @@ impl<C: CurveGroup, N: ShamirNetwork> EcMpcProtocol<C> for ShamirProtocol<C::Sca
     }
 
     fn sub_assign_points_public_affine(
-        &mut self,
+        &self,
         a: &mut Self::PointShare,
         b: &<C as CurveGroup>::Affine,
     ) {
         a.a -= b
     }
 
-    fn scalar_mul_public_point(&mut self, a: &C, b: &Self::FieldShare) -> Self::PointShare {
+    fn scalar_mul_public_point(&self, a: &C, b: &Self::FieldShare) -> Self::PointShare {
         Self::PointShare { a: a.mul(b.a) }
     }
 
     fn scalar_mul_public_scalar(
-        &mut self,
+        &self,
         a: &Self::PointShare,
         b: &<C>::ScalarField,
     ) -> Self::PointShare {
@@ -804,6 +809,39 @@ impl<P: Pairing, N: ShamirNetwork> PairingEcMpcProtocol<P> for ShamirProtocol<P:
 
         Ok((r1, r2))
     }
+
+    fn open_two_points_and_mul_open(
+        &mut self,
+        a: &<Self as EcMpcProtocol<P::G1>>::PointShare,
+        b: &<Self as EcMpcProtocol<P::G2>>::PointShare,
+        c: &<Self as EcMpcProtocol<P::G1>>::PointShare,
+        x: &[<Self as EcMpcProtocol<P::G1>>::PointShare],
+        y: &[<Self as PrimeFieldMpcProtocol<P::ScalarField>>::FieldShare],
+    ) -> std::io::Result<(P::G1, P::G2, P::G1)> {
+        // The products are shares of degree 2t, so we need to receive from 2t+1 parties. The first t+1 of them are enough to reconstruct a and b.
+        let mut c = c.a;
+        for (x, y) in izip!(x, y) {
+            c += (y * x).a;
+        }
+
+        let rcv: Vec<(P::G1, P::G2, P::G1)> = self
+            .network
+            .broadcast_next((a.a, b.a, c), 2 * self.threshold + 1)?;
+        let mut r1 = Vec::with_capacity(rcv.len());
+        let mut r2 = Vec::with_capacity(rcv.len());
+        let mut r3 = Vec::with_capacity(rcv.len());
+        for (x, y, z) in rcv {
+            r1.push(x);
+            r2.push(y);
+            r3.push(z);
+        }
+
+        let r1 = ShamirCore::reconstruct_point(&r1[..=self.threshold], &self.open_lagrange_t);
+        let r2 = ShamirCore::reconstruct_point(&r2[..=self.threshold], &self.open_lagrange_t);
+        let r3 = ShamirCore::reconstruct_point(&r3, &self.open_lagrange_2t);
+
+        Ok((r1, r2, r3))
+    }
 }
 
 impl<F: PrimeField, N: ShamirNetwork> FFTProvider<F> for ShamirProtocol<F, N> {
@@ -1009,7 +1047,7 @@ impl<F: PrimeField> ShamirRng<F> {
 
 impl<C: CurveGroup, N: ShamirNetwork> MSMProvider<C> for ShamirProtocol<C::ScalarField, N> {
     fn msm_public_points(
-        &mut self,
+        &self,
         points: &[C::Affine],
         scalars: &Self::FieldShareVec,
     ) -> Self::PointShare {
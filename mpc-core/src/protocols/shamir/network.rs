@@ -5,9 +5,18 @@
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use bytes::{Bytes, BytesMut};
 use eyre::{bail, eyre, Report};
-use mpc_net::{channel::ChannelHandle, config::NetworkConfig, MpcNetworkHandler};
+use mpc_net::{
+    codecs::{AbortSignal, ProtocolMessage},
+    config::NetworkConfig,
+    MpcNetworkHandler,
+};
 use std::collections::HashMap;
 
+use crate::protocols::{
+    communication::CommunicationStats,
+    network::{self, PartyChannel},
+};
+
 /// This trait defines the network interface for the Shamir protocol.
 pub trait ShamirNetwork {
     /// Returns the id of the party. The id is in the range 0 <= id < num_parties
@@ -56,6 +65,11 @@ pub trait ShamirNetwork {
         data: F,
         num: usize,
     ) -> std::io::Result<Vec<F>>;
+
+    /// Marks the start of a named phase of the protocol (e.g., "plonk: round 3"), the communication afterwards is accounted to this phase until the next one starts (see [CommunicationStats]). This function has a default implementation that does nothing.
+    fn set_phase(&mut self, phase: &str) {
+        let _ = phase;
+    }
 }
 
 /// This struct can be used to facilitate network communication for the Shamir MPC protocol.
@@ -64,7 +78,9 @@ pub struct ShamirMpcNet {
     pub(crate) num_parties: usize,
     pub(crate) runtime: tokio::runtime::Runtime,
     pub(crate) net_handler: MpcNetworkHandler,
-    pub(crate) channels: HashMap<usize, ChannelHandle<Bytes, BytesMut>>,
+    pub(crate) channels: HashMap<usize, PartyChannel>,
+    pub(crate) signal: AbortSignal,
+    pub(crate) stats: CommunicationStats,
 }
 
 impl ShamirMpcNet {
@@ -79,13 +95,55 @@ impl ShamirMpcNet {
         if id >= num_parties {
             bail!("Invalid party id={} for {} parties", id, num_parties)
         }
-        let runtime = tokio::runtime::Builder::new_multi_thread()
+        let runtime = Self::runtime(num_parties)?;
+        let net_handler = runtime.block_on(MpcNetworkHandler::establish(config))?;
+        Self::with_handler(id, num_parties, runtime, net_handler)
+    }
+
+    /// Constructs the network interface for a session of an established [MpcNetworkHandler]
+    /// (see [MpcNetworkHandler::fork_session]), so that several protocols can run concurrently
+    /// over the same connections. The network needs to contain at least 3 parties. The interface
+    /// has its own runtime, but for QUIC, the runtime the connections were established in needs to
+    /// be running as long as the session is used.
+    pub fn from_session(session: MpcNetworkHandler) -> Result<Self, Report> {
+        let num_parties = session.num_parties();
+        if num_parties <= 2 {
+            bail!("Shamir protocol requires at least 3 parties")
+        }
+        let id = session.id();
+        Self::with_handler(id, num_parties, Self::runtime(num_parties)?, session)
+    }
+
+    /// Constructs the network interfaces of `num_parties` parties (at least 3), which are connected
+    /// in memory (see [MpcNetworkHandler::in_memory]). The i-th interface belongs to the party with
+    /// id i and can be moved to its own thread to run the protocol in a single process.
+    pub fn in_memory(num_parties: usize) -> Result<Vec<Self>, Report> {
+        if num_parties <= 2 {
+            bail!("Shamir protocol requires at least 3 parties")
+        }
+        MpcNetworkHandler::in_memory(num_parties)
+            .into_iter()
+            .map(Self::from_session)
+            .collect()
+    }
+
+    fn runtime(num_parties: usize) -> std::io::Result<tokio::runtime::Runtime> {
+        tokio::runtime::Builder::new_multi_thread()
             .worker_threads(num_parties - 1)
             .enable_all()
-            .build()?;
-        let (net_handler, channels) = runtime.block_on(async {
-            let mut net_handler = MpcNetworkHandler::establish(config).await?;
-            let mut channels = net_handler.get_byte_channels().await?;
+            .build()
+    }
+
+    fn with_handler(
+        id: usize,
+        num_parties: usize,
+        runtime: tokio::runtime::Runtime,
+        mut net_handler: MpcNetworkHandler,
+    ) -> Result<Self, Report> {
+        let signal = AbortSignal::new();
+        let recv_timeout = net_handler.recv_timeout();
+        let channels = runtime.block_on(async {
+            let mut channels = net_handler.get_protocol_channels(&signal).await?;
 
             let mut channels_ = HashMap::with_capacity(num_parties - 1);
 
@@ -94,7 +152,10 @@ impl ShamirMpcNet {
                     let chan = channels
                         .remove(&other_id)
                         .ok_or_else(|| eyre!("no channel found for party id={}", other_id))?;
-                    channels_.insert(other_id, ChannelHandle::manage(chan));
+                    channels_.insert(
+                        other_id,
+                        PartyChannel::manage(other_id, chan, signal.clone(), recv_timeout),
+                    );
                 }
             }
 
@@ -102,7 +163,7 @@ impl ShamirMpcNet {
                 bail!("unexpected channels found")
             }
 
-            Ok((net_handler, channels_))
+            Ok(channels_)
         })?;
         Ok(Self {
             id,
@@ -110,30 +171,54 @@ impl ShamirMpcNet {
             runtime,
             net_handler,
             channels,
+            signal,
+            stats: CommunicationStats::new(),
         })
     }
 
-    /// Shuts down the network interface.
-    pub fn shutdown(self) {
+    /// Shuts down the network interface. Waits until all messages are sent and returns the error
+    /// of a failed send.
+    pub fn shutdown(self) -> std::io::Result<()> {
         let Self {
             id: _,
             num_parties: _,
             runtime,
             net_handler,
             channels,
+            signal: _,
+            stats: _,
         } = self;
-        for chan in channels.into_iter() {
-            drop(chan);
-        }
         runtime.block_on(async {
+            let mut flushed = Ok(());
+            for mut chan in channels.into_values() {
+                flushed = flushed.and(chan.flush().await);
+                drop(chan);
+            }
             net_handler.shutdown().await;
-        });
+            flushed
+        })
+    }
+
+    /// Aborts the protocol, e.g., because of a fatal error, by sending the reason to the other
+    /// parties. Their pending and future operations on the network fail with an error of kind
+    /// [std::io::ErrorKind::ConnectionAborted] that contains the reason, as do ours.
+    pub fn abort(&mut self, reason: &str) -> std::io::Result<()> {
+        network::abort(
+            &self.runtime,
+            self.id,
+            &self.signal,
+            self.channels.values_mut(),
+            reason,
+        )
     }
 
-    /// Sends bytes over the network to the target party.
+    /// Sends bytes over the network to the target party. Returns the error of a previous send
+    /// that failed in the meantime.
     pub fn send_bytes(&mut self, target: usize, data: Bytes) -> std::io::Result<()> {
         if let Some(chan) = self.channels.get_mut(&target) {
-            std::mem::drop(chan.blocking_send(data));
+            let len = data.len();
+            chan.send(ProtocolMessage::Data(data))?;
+            self.stats.record_send(len);
             Ok(())
         } else {
             Err(std::io::Error::new(
@@ -143,21 +228,24 @@ impl ShamirMpcNet {
         }
     }
 
-    /// Receives bytes over the network from the party with the given id.
+    /// Receives bytes over the network from the party with the given id. Fails if a party aborted
+    /// the protocol or no message arrived within the receive timeout of the [NetworkConfig].
     pub fn recv_bytes(&mut self, from: usize) -> std::io::Result<BytesMut> {
-        let data = if let Some(chan) = self.channels.get_mut(&from) {
-            chan.blocking_recv().blocking_recv()
+        if let Some(chan) = self.channels.get_mut(&from) {
+            let data = chan.recv(&self.runtime)?;
+            self.stats.record_recv(data.len());
+            Ok(data)
         } else {
-            return Err(std::io::Error::new(
+            Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 format!("No channel found for party id={}", from),
-            ));
-        };
+            ))
+        }
+    }
 
-        let data = data.map_err(|_| {
-            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "receive channel end died")
-        })??;
-        Ok(data)
+    /// Returns the communication statistics of this network per phase of the protocol. The statistics are shared with the network, so they can be read after the network was moved into a protocol or prover.
+    pub fn communication_stats(&self) -> CommunicationStats {
+        self.stats.clone()
     }
 
     pub(crate) fn _id(&self) -> usize {
@@ -264,4 +352,64 @@ impl ShamirNetwork for ShamirMpcNet {
 
         Ok(res)
     }
+
+    fn set_phase(&mut self, phase: &str) {
+        self.stats.set_phase(phase);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_memory_broadcast() {
+        let num_parties = 4;
+        let nets = ShamirMpcNet::in_memory(num_parties).unwrap();
+        let handles = nets
+            .into_iter()
+            .map(|mut net| {
+                std::thread::spawn(move || {
+                    let id = net.get_id() as u64;
+                    let all = net.broadcast(ark_bn254::Fr::from(id)).unwrap();
+                    let expected = (0..num_parties as u64)
+                        .map(ark_bn254::Fr::from)
+                        .collect::<Vec<_>>();
+                    assert_eq!(all, expected);
+                    net.shutdown().unwrap();
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(ShamirMpcNet::in_memory(2).is_err());
+    }
+
+    #[test]
+    fn concurrent_sessions() {
+        let num_parties = 3;
+        let handles = MpcNetworkHandler::in_memory(num_parties)
+            .into_iter()
+            .flat_map(|handler| [handler.fork_session(7), handler.fork_session(8)])
+            .map(|session| {
+                std::thread::spawn(move || {
+                    let session_id = session.session_id();
+                    let mut net = ShamirMpcNet::from_session(session).unwrap();
+                    let id = net.get_id() as u64;
+                    let all = net
+                        .broadcast(ark_bn254::Fr::from(10 * session_id + id))
+                        .unwrap();
+                    let expected = (0..num_parties as u64)
+                        .map(|id| ark_bn254::Fr::from(10 * session_id + id))
+                        .collect::<Vec<_>>();
+                    assert_eq!(all, expected);
+                    net.shutdown().unwrap();
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
 }
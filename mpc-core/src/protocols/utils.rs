@@ -0,0 +1,55 @@
+//! # Protocol Utils
+//!
+//! Helpers shared by the local (i.e., non-interactive) computations of the MPC protocols.
+
+use ark_ff::PrimeField;
+use rayon::prelude::*;
+
+// Below this size we do not split the work into chunks, as the overhead of rayon dominates.
+const MIN_PARALLEL_CHUNK_SIZE: usize = 1 << 10;
+
+/// Multiplies the i-th element of `coeffs` with c*g^i. The vector is split into chunks which are processed in parallel, where each chunk starts with the corresponding power of g.
+pub(crate) fn distribute_powers_and_mul_by_const<F: PrimeField>(coeffs: &mut [F], g: F, c: F) {
+    let num_chunks = rayon::current_num_threads().max(1);
+    let chunk_size = coeffs
+        .len()
+        .div_ceil(num_chunks)
+        .max(MIN_PARALLEL_CHUNK_SIZE);
+    coeffs
+        .par_chunks_mut(chunk_size)
+        .enumerate()
+        .for_each(|(i, chunk)| {
+            let mut pow = c * g.pow([(i * chunk_size) as u64]);
+            for coeff in chunk.iter_mut() {
+                *coeff *= pow;
+                pow *= g;
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+
+    #[test]
+    fn distribute_powers_matches_sequential() {
+        let mut rng = thread_rng();
+        let g = ark_bn254::Fr::rand(&mut rng);
+        let c = ark_bn254::Fr::rand(&mut rng);
+        let coeffs = (0..5000)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        let mut expected = coeffs.clone();
+        let mut pow = c;
+        for coeff in expected.iter_mut() {
+            *coeff *= pow;
+            pow *= g;
+        }
+
+        let mut parallel = coeffs;
+        super::distribute_powers_and_mul_by_const(&mut parallel, g, c);
+        assert_eq!(expected, parallel);
+    }
+}
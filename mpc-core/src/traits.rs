@@ -18,6 +18,7 @@ pub trait FieldShareVecTrait:
     + CanonicalDeserialize
     + Default
     + std::fmt::Debug
+    + Send
     + Sync
 {
     /// The type of a share of a field element.
@@ -26,6 +27,7 @@ pub trait FieldShareVecTrait:
         + Clone
         + CanonicalSerialize
         + CanonicalDeserialize
+        + Send
         + Sync
         + Default;
 
@@ -47,6 +49,7 @@ pub trait PrimeFieldMpcProtocol<F: PrimeField> {
         + Clone
         + CanonicalSerialize
         + CanonicalDeserialize
+        + Send
         + Sync
         + Default;
 
@@ -54,13 +57,13 @@ pub trait PrimeFieldMpcProtocol<F: PrimeField> {
     type FieldShareVec: FieldShareVecTrait<FieldShare = Self::FieldShare>;
 
     /// Add two shares: \[c\] = \[a\] + \[b\]
-    fn add(&mut self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare;
+    fn add(&self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare;
 
     /// Subtract the share b from the share a: \[c\] = \[a\] - \[b\]
-    fn sub(&mut self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare;
+    fn sub(&self, a: &Self::FieldShare, b: &Self::FieldShare) -> Self::FieldShare;
 
     /// Add a public value a to the share b: \[c\] = a + \[b\]
-    fn add_with_public(&mut self, a: &F, b: &Self::FieldShare) -> Self::FieldShare;
+    fn add_with_public(&self, a: &F, b: &Self::FieldShare) -> Self::FieldShare;
 
     /// Elementwise subtraction of two vectors of shares in place: \[a_i\] -= \[b_i\]
     fn sub_assign_vec(&mut self, a: &mut Self::FieldShareVec, b: &Self::FieldShareVec);
@@ -93,11 +96,11 @@ pub trait PrimeFieldMpcProtocol<F: PrimeField> {
     }
 
     /// Multiply a share b by a public value a: c = a * \[b\].
-    fn mul_with_public(&mut self, a: &F, b: &Self::FieldShare) -> Self::FieldShare;
+    fn mul_with_public(&self, a: &F, b: &Self::FieldShare) -> Self::FieldShare;
 
     /// Convenience method for \[a\] + \[b\] * c
     fn add_mul_public(
-        &mut self,
+        &self,
         a: &Self::FieldShare,
         b: &Self::FieldShare,
         c: &F,
@@ -135,7 +138,7 @@ pub trait PrimeFieldMpcProtocol<F: PrimeField> {
     fn inv_many(&mut self, a: &[Self::FieldShare]) -> std::io::Result<Vec<Self::FieldShare>>;
 
     /// Negates a shared value: \[b\] = -\[a\].
-    fn neg(&mut self, a: &Self::FieldShare) -> Self::FieldShare;
+    fn neg(&self, a: &Self::FieldShare) -> Self::FieldShare;
 
     /// Negates a vector of shared values: \[b\] = -\[a\] for every element in place.
     fn neg_vec_in_place(&mut self, a: &mut Self::FieldShareVec);
@@ -173,7 +176,7 @@ pub trait PrimeFieldMpcProtocol<F: PrimeField> {
 
     /// Each value of lhs consists of a coefficient c and an index i. This function computes the sum of the coefficients times the corresponding public input or private witness. In other words, an accumulator a is initialized to 0, and for each (c, i) in lhs, a += c * public_inputs\[i\] is computed if i corresponds to a public input, or c * private_witness[i - public_inputs.len()] if i corresponds to a private witness.
     fn evaluate_constraint(
-        &mut self,
+        &self,
         lhs: &[(F, usize)],
         public_inputs: &[F],
         private_witness: &Self::FieldShareVec,
@@ -215,6 +218,11 @@ pub trait PrimeFieldMpcProtocol<F: PrimeField> {
         a: &[Self::FieldShare],
         b: &[Self::FieldShare],
     ) -> std::io::Result<Vec<F>>;
+
+    /// Marks the start of a named phase of the protocol (e.g., "groth16: witness map"), the communication afterwards is accounted to this phase until the next one starts (see [CommunicationStats](crate::protocols::communication::CommunicationStats)). This function has a default implementation that does nothing.
+    fn set_phase(&mut self, phase: &str) {
+        let _ = phase;
+    }
 }
 
 /// This is some place holder definition. This will change most likely
@@ -460,38 +468,38 @@ pub trait CircomWitnessExtensionProtocol<F: PrimeField>: PrimeFieldMpcProtocol<F
 /// A trait encompassing basic operations for MPC protocols over elliptic curves.
 pub trait EcMpcProtocol<C: CurveGroup>: PrimeFieldMpcProtocol<C::ScalarField> {
     /// The type of a share of a elliptic curve point.
-    type PointShare: CanonicalDeserialize + CanonicalDeserialize + Clone + Sync;
+    type PointShare: CanonicalDeserialize + CanonicalDeserialize + Clone + Send + Sync;
 
     /// Add two shared points: \[C\] = \[A\] + \[B\]
-    fn add_points(&mut self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare;
+    fn add_points(&self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare;
 
     /// Subtract the shared point B from the shared point A: \[C\] = \[A\] - \[B\]
-    fn sub_points(&mut self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare;
+    fn sub_points(&self, a: &Self::PointShare, b: &Self::PointShare) -> Self::PointShare;
 
     /// Add a shared point B in place to the shared point A: \[A\] += \[B\]
-    fn add_assign_points(&mut self, a: &mut Self::PointShare, b: &Self::PointShare);
+    fn add_assign_points(&self, a: &mut Self::PointShare, b: &Self::PointShare);
 
     /// Subtract a shared point B in place from the shared point A: \[A\] -= \[B\]
-    fn sub_assign_points(&mut self, a: &mut Self::PointShare, b: &Self::PointShare);
+    fn sub_assign_points(&self, a: &mut Self::PointShare, b: &Self::PointShare);
 
     /// Add a public point B to the shared point A in place: \[A\] += B
-    fn add_assign_points_public(&mut self, a: &mut Self::PointShare, b: &C);
+    fn add_assign_points_public(&self, a: &mut Self::PointShare, b: &C);
 
     /// Subtract a public point B from the shared point A in place: \[A\] -= B
-    fn sub_assign_points_public(&mut self, a: &mut Self::PointShare, b: &C);
+    fn sub_assign_points_public(&self, a: &mut Self::PointShare, b: &C);
 
     /// Add a public affine point B to the shared point A in place: \[A\] += B
-    fn add_assign_points_public_affine(&mut self, a: &mut Self::PointShare, b: &C::Affine);
+    fn add_assign_points_public_affine(&self, a: &mut Self::PointShare, b: &C::Affine);
 
     /// Subtract a public affine point B from the shared point A in place: \[A\] -= B
-    fn sub_assign_points_public_affine(&mut self, a: &mut Self::PointShare, b: &C::Affine);
+    fn sub_assign_points_public_affine(&self, a: &mut Self::PointShare, b: &C::Affine);
 
     /// Multiplies a public point B to the shared point A in place: \[A\] *= B
-    fn scalar_mul_public_point(&mut self, a: &C, b: &Self::FieldShare) -> Self::PointShare;
+    fn scalar_mul_public_point(&self, a: &C, b: &Self::FieldShare) -> Self::PointShare;
 
     /// Multiplies a public share b to the shared point A: \[A\] *= b
     fn scalar_mul_public_scalar(
-        &mut self,
+        &self,
         a: &Self::PointShare,
         b: &C::ScalarField,
     ) -> Self::PointShare;
@@ -518,6 +526,16 @@ pub trait PairingEcMpcProtocol<P: Pairing>: EcMpcProtocol<P::G1> + EcMpcProtocol
         a: &<Self as EcMpcProtocol<P::G1>>::PointShare,
         b: &<Self as EcMpcProtocol<P::G2>>::PointShare,
     ) -> std::io::Result<(P::G1, P::G2)>;
+
+    /// Opens the points a (from G1) and b (from G2) and the point c + sum_i \[x_i\] * \[y_i\] (from G1) in a single communication round. Similar to [PrimeFieldMpcProtocol::mul_open], the products are opened directly instead of being multiplied and opened in separate rounds.
+    fn open_two_points_and_mul_open(
+        &mut self,
+        a: &<Self as EcMpcProtocol<P::G1>>::PointShare,
+        b: &<Self as EcMpcProtocol<P::G2>>::PointShare,
+        c: &<Self as EcMpcProtocol<P::G1>>::PointShare,
+        x: &[<Self as EcMpcProtocol<P::G1>>::PointShare],
+        y: &[<Self as PrimeFieldMpcProtocol<P::ScalarField>>::FieldShare],
+    ) -> std::io::Result<(P::G1, P::G2, P::G1)>;
 }
 
 /// A trait representing the application of the Fast Fourier Transform (FFT) in MPC.
@@ -550,7 +568,7 @@ pub trait FFTProvider<F: PrimeField>: PrimeFieldMpcProtocol<F> {
 pub trait MSMProvider<C: CurveGroup>: EcMpcProtocol<C> {
     /// Computes tha mutli-scalar product of a vector of shared values and a vector of public points. In other words, it computes sum_i=0^n-1 [scalars\[i\]] * points\[i\].
     fn msm_public_points(
-        &mut self,
+        &self,
         points: &[C::Affine],
         scalars: &Self::FieldShareVec,
     ) -> Self::PointShare;
@@ -165,7 +165,7 @@ mod field_share {
             .zip(x_shares.into_iter().zip(y_shares))
         {
             thread::spawn(move || {
-                let mut rep3 = Rep3Protocol::new(net).unwrap();
+                let rep3 = Rep3Protocol::new(net).unwrap();
                 tx.send(rep3.add(&x, &y))
             });
         }
@@ -195,7 +195,7 @@ mod field_share {
             .zip(x_shares.into_iter().zip(y_shares))
         {
             thread::spawn(move || {
-                let mut rep3 = Rep3Protocol::new(net).unwrap();
+                let rep3 = Rep3Protocol::new(net).unwrap();
                 tx.send(rep3.sub(&x, &y))
             });
         }
@@ -430,7 +430,7 @@ mod field_share {
             .zip(x_shares.into_iter())
         {
             thread::spawn(move || {
-                let mut rep3 = Rep3Protocol::new(net).unwrap();
+                let rep3 = Rep3Protocol::new(net).unwrap();
                 tx.send(rep3.neg(&x))
             });
         }
@@ -704,7 +704,7 @@ mod curve_share {
     use tokio::sync::oneshot;
 
     use crate::protocols::rep3::Rep3TestNetwork;
-    use mpc_core::traits::EcMpcProtocol;
+    use mpc_core::traits::{EcMpcProtocol, PairingEcMpcProtocol};
 
     #[tokio::test]
     async fn rep3_add() {
@@ -725,7 +725,7 @@ mod curve_share {
             .zip(x_shares.into_iter().zip(y_shares))
         {
             thread::spawn(move || {
-                let mut rep3 = Rep3Protocol::new(net).unwrap();
+                let rep3 = Rep3Protocol::new(net).unwrap();
                 tx.send(rep3.add_points(&x, &y))
             });
         }
@@ -755,7 +755,7 @@ mod curve_share {
             .zip(x_shares.into_iter().zip(y_shares))
         {
             thread::spawn(move || {
-                let mut rep3 = Rep3Protocol::new(net).unwrap();
+                let rep3 = Rep3Protocol::new(net).unwrap();
                 tx.send(rep3.sub_points(&x, &y))
             });
         }
@@ -784,7 +784,7 @@ mod curve_share {
             .zip(scalar_shares.into_iter())
         {
             thread::spawn(move || {
-                let mut rep3 = Rep3Protocol::new(net).unwrap();
+                let rep3 = Rep3Protocol::new(net).unwrap();
                 tx.send(rep3.scalar_mul_public_point(&public_point, &scalar))
             });
         }
@@ -813,7 +813,7 @@ mod curve_share {
             .zip(point_shares.into_iter())
         {
             thread::spawn(move || {
-                let mut rep3 = Rep3Protocol::new(net).unwrap();
+                let rep3 = Rep3Protocol::new(net).unwrap();
                 tx.send(rep3.scalar_mul_public_scalar(&point, &public_scalar))
             });
         }
@@ -823,4 +823,57 @@ mod curve_share {
         let is_result = rep3::utils::combine_curve_point(result1, result2, result3);
         assert_eq!(is_result, should_result);
     }
+
+    #[tokio::test]
+    async fn rep3_open_two_points_and_mul_open() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let a = ark_bn254::G1Projective::rand(&mut rng);
+        let b = ark_bn254::G2Projective::rand(&mut rng);
+        let c = ark_bn254::G1Projective::rand(&mut rng);
+        let x = ark_bn254::G1Projective::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let a_shares = rep3::utils::share_curve_point(a, &mut rng);
+        let b_shares = rep3::utils::share_curve_point(b, &mut rng);
+        let c_shares = rep3::utils::share_curve_point(c, &mut rng);
+        let x_shares = rep3::utils::share_curve_point(x, &mut rng);
+        let y_shares = rep3::utils::share_field_element(y, &mut rng);
+        let should_result = (a, b, c + x * y);
+        let (tx1, rx1) = oneshot::channel();
+        let (tx2, rx2) = oneshot::channel();
+        let (tx3, rx3) = oneshot::channel();
+        for ((net, tx), (((a, b), c), (x, y))) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(
+                a_shares
+                    .into_iter()
+                    .zip(b_shares)
+                    .zip(c_shares)
+                    .zip(x_shares.into_iter().zip(y_shares)),
+            )
+        {
+            thread::spawn(move || {
+                let mut rep3 = Rep3Protocol::new(net).unwrap();
+                tx.send(
+                    PairingEcMpcProtocol::<ark_bn254::Bn254>::open_two_points_and_mul_open(
+                        &mut rep3,
+                        &a,
+                        &b,
+                        &c,
+                        &[x],
+                        &[y],
+                    )
+                    .unwrap(),
+                )
+            });
+        }
+        let result1 = rx1.await.unwrap();
+        let result2 = rx2.await.unwrap();
+        let result3 = rx3.await.unwrap();
+        assert_eq!(result1, should_result);
+        assert_eq!(result2, should_result);
+        assert_eq!(result3, should_result);
+    }
 }
@@ -242,7 +242,7 @@ mod field_share {
 
         for (net, tx, x, y) in izip!(test_network.get_party_networks(), tx, x_shares, y_shares) {
             thread::spawn(move || {
-                let mut shamir = ShamirProtocol::new(threshold, net).unwrap();
+                let shamir = ShamirProtocol::new(threshold, net).unwrap();
                 tx.send(shamir.add(&x, &y))
             });
         }
@@ -287,7 +287,7 @@ mod field_share {
 
         for (net, tx, x, y) in izip!(test_network.get_party_networks(), tx, x_shares, y_shares) {
             thread::spawn(move || {
-                let mut shamir = ShamirProtocol::new(threshold, net).unwrap();
+                let shamir = ShamirProtocol::new(threshold, net).unwrap();
                 tx.send(shamir.sub(&x, &y))
             });
         }
@@ -531,7 +531,7 @@ mod field_share {
 
         for (net, tx, x) in izip!(test_network.get_party_networks(), tx, x_shares) {
             thread::spawn(move || {
-                let mut shamir = ShamirProtocol::new(threshold, net).unwrap();
+                let shamir = ShamirProtocol::new(threshold, net).unwrap();
                 tx.send(shamir.neg(&x))
             });
         }
@@ -612,7 +612,7 @@ mod curve_share {
     use itertools::{izip, Itertools};
     use mpc_core::{
         protocols::shamir::{self, ShamirProtocol},
-        traits::EcMpcProtocol,
+        traits::{EcMpcProtocol, PairingEcMpcProtocol},
     };
     use rand::thread_rng;
     use tokio::sync::oneshot;
@@ -636,7 +636,7 @@ mod curve_share {
 
         for (net, tx, x, y) in izip!(test_network.get_party_networks(), tx, x_shares, y_shares) {
             thread::spawn(move || {
-                let mut shamir = ShamirProtocol::new(threshold, net).unwrap();
+                let shamir = ShamirProtocol::new(threshold, net).unwrap();
                 tx.send(shamir.add_points(&x, &y))
             });
         }
@@ -681,7 +681,7 @@ mod curve_share {
 
         for (net, tx, x, y) in izip!(test_network.get_party_networks(), tx, x_shares, y_shares) {
             thread::spawn(move || {
-                let mut shamir = ShamirProtocol::new(threshold, net).unwrap();
+                let shamir = ShamirProtocol::new(threshold, net).unwrap();
                 tx.send(shamir.sub_points(&x, &y))
             });
         }
@@ -726,7 +726,7 @@ mod curve_share {
 
         for (net, tx, scalar) in izip!(test_network.get_party_networks(), tx, scalar_shares) {
             thread::spawn(move || {
-                let mut shamir = ShamirProtocol::new(threshold, net).unwrap();
+                let shamir = ShamirProtocol::new(threshold, net).unwrap();
                 tx.send(shamir.scalar_mul_public_point(&public_point, &scalar))
             });
         }
@@ -771,7 +771,7 @@ mod curve_share {
 
         for (net, tx, point) in izip!(test_network.get_party_networks(), tx, point_shares) {
             thread::spawn(move || {
-                let mut shamir = ShamirProtocol::new(threshold, net).unwrap();
+                let shamir = ShamirProtocol::new(threshold, net).unwrap();
                 tx.send(shamir.scalar_mul_public_scalar(&point, &public_scalar))
             });
         }
@@ -796,4 +796,63 @@ mod curve_share {
         shamir_scalar_mul_public_scalar_inner(3, 1).await;
         shamir_scalar_mul_public_scalar_inner(10, 4).await;
     }
+
+    async fn shamir_open_two_points_and_mul_open_inner(num_parties: usize, threshold: usize) {
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut rng = thread_rng();
+        let a = ark_bn254::G1Projective::rand(&mut rng);
+        let b = ark_bn254::G2Projective::rand(&mut rng);
+        let c = ark_bn254::G1Projective::rand(&mut rng);
+        let x = ark_bn254::G1Projective::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let a_shares = shamir::utils::share_curve_point(a, threshold, num_parties, &mut rng);
+        let b_shares = shamir::utils::share_curve_point(b, threshold, num_parties, &mut rng);
+        let c_shares = shamir::utils::share_curve_point(c, threshold, num_parties, &mut rng);
+        let x_shares = shamir::utils::share_curve_point(x, threshold, num_parties, &mut rng);
+        let y_shares = shamir::utils::share_field_element(y, threshold, num_parties, &mut rng);
+        let should_result = (a, b, c + x * y);
+
+        let mut tx = Vec::with_capacity(num_parties);
+        let mut rx = Vec::with_capacity(num_parties);
+        for _ in 0..num_parties {
+            let (t, r) = oneshot::channel();
+            tx.push(t);
+            rx.push(r);
+        }
+
+        for (net, tx, a, b, c, x, y) in izip!(
+            test_network.get_party_networks(),
+            tx,
+            a_shares,
+            b_shares,
+            c_shares,
+            x_shares,
+            y_shares
+        ) {
+            thread::spawn(move || {
+                let mut shamir = ShamirProtocol::new(threshold, net).unwrap();
+                tx.send(
+                    PairingEcMpcProtocol::<ark_bn254::Bn254>::open_two_points_and_mul_open(
+                        &mut shamir,
+                        &a,
+                        &b,
+                        &c,
+                        &[x],
+                        &[y],
+                    )
+                    .unwrap(),
+                )
+            });
+        }
+
+        for r in rx {
+            assert_eq!(r.await.unwrap(), should_result);
+        }
+    }
+
+    #[tokio::test]
+    async fn shamir_open_two_points_and_mul_open() {
+        shamir_open_two_points_and_mul_open_inner(3, 1).await;
+        shamir_open_two_points_and_mul_open_inner(10, 4).await;
+    }
 }
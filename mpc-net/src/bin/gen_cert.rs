@@ -1,5 +1,5 @@
 use color_eyre::{eyre::Context, Result};
-use rcgen::CertifiedKey;
+use mpc_net::keys::{self, KeyFormat};
 use std::path::PathBuf;
 
 use clap::Parser;
@@ -7,25 +7,25 @@ use clap::Parser;
 /// Certificate Generator for MPC-NET
 #[derive(Debug, PartialEq, Parser)]
 struct CliArgs {
-    /// The path to the .der certificate file
+    /// The path to the certificate file
     #[clap(short, long)]
     cert_path: PathBuf,
-    /// The path to the .der key file
+    /// The path to the key file
     #[clap(short, long)]
     key_path: PathBuf,
     /// The subject alternative names for the certificate
     #[clap(short, long)]
     sans: Vec<String>,
+    /// The encoding of the certificate and key files
+    #[clap(short, long, value_enum, default_value_t = KeyFormat::Der)]
+    format: KeyFormat,
 }
 
 fn main() -> Result<()> {
     let args = CliArgs::parse();
 
-    let CertifiedKey { cert, key_pair } =
-        rcgen::generate_simple_self_signed(args.sans).context("generating self-signed cert")?;
-    let key = key_pair.serialize_der();
-    std::fs::write(args.key_path, key).context("writing key file")?;
-    let cert = cert.der();
+    let (cert, key) = keys::generate_self_signed(args.sans, args.format)?;
+    keys::write_private_key(&args.key_path, &key)?;
     std::fs::write(args.cert_path, cert).context("writing certificate file")?;
     Ok(())
 }
@@ -1,9 +1,20 @@
 //! Codecs for serializing and deserializing messages over the network.
-use std::io;
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, OnceLock,
+    },
+};
 
-use bytes::{Buf, BufMut, BytesMut};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use serde::{de::DeserializeOwned, Serialize};
-use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
+use tokio_util::{
+    codec::{Decoder, Encoder, LengthDelimitedCodec},
+    sync::CancellationToken,
+};
+
+use crate::config::Compression;
 
 /// A codec that serializes and deserializes messages using [bincode].
 #[derive(Default, Debug)]
@@ -76,3 +87,396 @@ where
         Ok(Some(result))
     }
 }
+
+const DATA_TAG: u8 = 0;
+const ABORT_TAG: u8 = 1;
+#[cfg(feature = "lz4")]
+const LZ4_TAG: u8 = 2;
+#[cfg(feature = "zstd")]
+const ZSTD_TAG: u8 = 3;
+
+const MAX_FRAME_LENGTH: usize = 1_000_000_000;
+/// Smaller messages are not compressed, as they hardly shrink.
+const MIN_COMPRESSION_LEN: usize = 64;
+
+/// A message of an MPC protocol, sent with the [ProtocolCodec].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolMessage {
+    /// The data of the protocol.
+    Data(Bytes),
+    /// The sending party aborted the protocol, e.g., because of a fatal error.
+    Abort {
+        /// The id of the aborting party.
+        party: usize,
+        /// Why the party aborted.
+        reason: String,
+    },
+}
+
+/// Signals to all channels of a party that another party aborted the protocol. Clones share the
+/// signal, only the first abort is recorded.
+#[derive(Debug, Clone, Default)]
+pub struct AbortSignal {
+    token: CancellationToken,
+    abort: Arc<OnceLock<(usize, String)>>,
+}
+
+impl AbortSignal {
+    /// Creates a new signal that is not aborted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that `party` aborted the protocol and wakes everyone waiting in [AbortSignal::aborted].
+    pub fn abort(&self, party: usize, reason: String) {
+        // only the first abort is relevant
+        let _ = self.abort.set((party, reason));
+        self.token.cancel();
+    }
+
+    /// Returns an error describing the abort, or `None` if no party aborted.
+    pub fn error(&self) -> Option<io::Error> {
+        self.abort.get().map(|(party, reason)| {
+            io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                format!("party {party} aborted the protocol: {reason}"),
+            )
+        })
+    }
+
+    /// Waits until a party aborted the protocol and returns an error describing the abort.
+    pub async fn aborted(&self) -> io::Error {
+        self.token.cancelled().await;
+        self.error().expect("the abort is set before cancelling")
+    }
+}
+
+/// The number of message bytes sent to and received from a party before and after compression.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressionStats {
+    /// The bytes of the sent messages before compression.
+    pub sent_uncompressed: u64,
+    /// The bytes of the sent messages after compression.
+    pub sent_compressed: u64,
+    /// The bytes of the received messages after decompression.
+    pub received_uncompressed: u64,
+    /// The bytes of the received messages before decompression.
+    pub received_compressed: u64,
+}
+
+impl CompressionStats {
+    /// The number of bytes saved by compressing the sent messages.
+    pub fn saved_sent(&self) -> u64 {
+        self.sent_uncompressed.saturating_sub(self.sent_compressed)
+    }
+
+    /// The number of bytes saved by the compression of the received messages.
+    pub fn saved_received(&self) -> u64 {
+        self.received_uncompressed
+            .saturating_sub(self.received_compressed)
+    }
+}
+
+/// The shared counters behind [CompressionStats].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CompressionCounters {
+    sent_uncompressed: Arc<AtomicU64>,
+    sent_compressed: Arc<AtomicU64>,
+    received_uncompressed: Arc<AtomicU64>,
+    received_compressed: Arc<AtomicU64>,
+}
+
+impl CompressionCounters {
+    pub(crate) fn get(&self) -> CompressionStats {
+        CompressionStats {
+            sent_uncompressed: self.sent_uncompressed.load(Ordering::Relaxed),
+            sent_compressed: self.sent_compressed.load(Ordering::Relaxed),
+            received_uncompressed: self.received_uncompressed.load(Ordering::Relaxed),
+            received_compressed: self.received_compressed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A codec for [ProtocolMessage]s, which prefixes every length-delimited frame with a tag. The
+/// decoder yields the data of the messages. Receiving an abort message triggers the
+/// [AbortSignal] of the codec and yields an error of kind [io::ErrorKind::ConnectionAborted].
+///
+/// The data can be compressed (see [ProtocolCodec::with_compression]). Small messages and messages
+/// that do not shrink are sent uncompressed, and the decoder decompresses every message whose
+/// compression is available.
+#[derive(Debug, Clone)]
+pub struct ProtocolCodec {
+    inner: LengthDelimitedCodec,
+    signal: AbortSignal,
+    compression: Compression,
+    counters: CompressionCounters,
+}
+
+impl ProtocolCodec {
+    /// Creates a new [ProtocolCodec] that triggers the given signal on an abort message.
+    pub fn new(signal: AbortSignal) -> Self {
+        let mut inner = LengthDelimitedCodec::new();
+        inner.set_max_frame_length(MAX_FRAME_LENGTH);
+        Self {
+            inner,
+            signal,
+            compression: Compression::None,
+            counters: CompressionCounters::default(),
+        }
+    }
+
+    /// Compresses the data of the sent messages. Encoding fails if the compression is not
+    /// available (see [Compression::is_available]).
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Counts the message bytes before and after compression in the given counters.
+    pub(crate) fn with_counters(mut self, counters: CompressionCounters) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    /// Compresses the data, returns `None` if it should be sent uncompressed.
+    fn compress(&self, data: &[u8]) -> io::Result<Option<(u8, Vec<u8>)>> {
+        if data.len() < MIN_COMPRESSION_LEN {
+            return Ok(None);
+        }
+        if self.compression == Compression::None {
+            return Ok(None);
+        }
+        let (tag, compressed) = compress_with(self.compression, data)?;
+        // the header of a compressed message contains the uncompressed length
+        Ok((compressed.len() + 4 < data.len()).then_some((tag, compressed)))
+    }
+}
+
+/// Decompresses the data of a message with the tag of a compression.
+fn decompress(tag: u8, mut data: BytesMut) -> io::Result<BytesMut> {
+    if data.len() < 4 {
+        return Err(invalid_message());
+    }
+    let len = usize::try_from(data.get_u32()).expect("u32 fits into usize");
+    if len > MAX_FRAME_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("decompressed message of {len} bytes exceeds the maximum frame length"),
+        ));
+    }
+    let mut decompressed = BytesMut::zeroed(len);
+    let decompressed_len = decompress_into(tag, &data, &mut decompressed)?;
+    if decompressed_len != len {
+        return Err(invalid_message());
+    }
+    Ok(decompressed)
+}
+
+#[cfg_attr(not(any(feature = "lz4", feature = "zstd")), allow(unused_variables))]
+fn compress_with(compression: Compression, data: &[u8]) -> io::Result<(u8, Vec<u8>)> {
+    match compression {
+        #[cfg(feature = "lz4")]
+        Compression::Lz4 => Ok((LZ4_TAG, lz4_flex::block::compress(data))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Ok((ZSTD_TAG, zstd::bulk::compress(data, 0)?)),
+        #[allow(unreachable_patterns)]
+        compression => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("compression {compression:?} is not available"),
+        )),
+    }
+}
+
+#[cfg_attr(not(any(feature = "lz4", feature = "zstd")), allow(unused_variables))]
+fn decompress_into(tag: u8, data: &[u8], decompressed: &mut [u8]) -> io::Result<usize> {
+    match tag {
+        #[cfg(feature = "lz4")]
+        LZ4_TAG => lz4_flex::block::decompress_into(data, decompressed)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        #[cfg(feature = "zstd")]
+        ZSTD_TAG => zstd::bulk::decompress_to_buffer(data, decompressed),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "received a message with a compression that is not available",
+        )),
+    }
+}
+
+fn invalid_message() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "received an invalid message")
+}
+
+impl Encoder<ProtocolMessage> for ProtocolCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: ProtocolMessage, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let buf = match item {
+            ProtocolMessage::Data(data) => {
+                self.counters
+                    .sent_uncompressed
+                    .fetch_add(data.len() as u64, Ordering::Relaxed);
+                let buf = match self.compress(&data)? {
+                    Some((tag, compressed)) => {
+                        let mut buf = BytesMut::with_capacity(compressed.len() + 5);
+                        buf.put_u8(tag);
+                        buf.put_u32(u32::try_from(data.len()).map_err(|_| {
+                            io::Error::new(io::ErrorKind::InvalidInput, "message is too large")
+                        })?);
+                        buf.put(compressed.as_slice());
+                        buf
+                    }
+                    None => {
+                        let mut buf = BytesMut::with_capacity(data.len() + 1);
+                        buf.put_u8(DATA_TAG);
+                        buf.put(data);
+                        buf
+                    }
+                };
+                self.counters
+                    .sent_compressed
+                    .fetch_add(buf.len() as u64 - 1, Ordering::Relaxed);
+                buf
+            }
+            ProtocolMessage::Abort { party, reason } => {
+                let mut buf = BytesMut::with_capacity(reason.len() + 5);
+                buf.put_u8(ABORT_TAG);
+                buf.put_u32(u32::try_from(party).expect("party id fits into u32"));
+                buf.put(reason.as_bytes());
+                buf
+            }
+        };
+        self.inner.encode(buf.freeze(), dst)
+    }
+}
+
+impl Decoder for ProtocolCodec {
+    type Item = BytesMut;
+
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut buf = match self.inner.decode(src)? {
+            Some(buf) => buf,
+            None => return Ok(None),
+        };
+        if buf.is_empty() {
+            return Err(invalid_message());
+        }
+        match buf.get_u8() {
+            ABORT_TAG => {
+                if buf.len() < 4 {
+                    return Err(invalid_message());
+                }
+                let party = usize::try_from(buf.get_u32()).expect("u32 fits into usize");
+                let reason = String::from_utf8_lossy(&buf).into_owned();
+                self.signal.abort(party, reason);
+                Err(self.signal.error().expect("we just aborted"))
+            }
+            tag => {
+                self.counters
+                    .received_compressed
+                    .fetch_add(buf.len() as u64, Ordering::Relaxed);
+                let data = if tag == DATA_TAG {
+                    buf
+                } else {
+                    decompress(tag, buf)?
+                };
+                self.counters
+                    .received_uncompressed
+                    .fetch_add(data.len() as u64, Ordering::Relaxed);
+                Ok(Some(data))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protocol_codec_roundtrip() {
+        let signal = AbortSignal::new();
+        let mut codec = ProtocolCodec::new(signal.clone());
+        let mut buf = BytesMut::new();
+        codec
+            .encode(ProtocolMessage::Data(Bytes::from_static(b"data")), &mut buf)
+            .unwrap();
+        codec
+            .encode(
+                ProtocolMessage::Abort {
+                    party: 2,
+                    reason: "out of memory".to_owned(),
+                },
+                &mut buf,
+            )
+            .unwrap();
+
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), &b"data"[..]);
+        assert!(signal.error().is_none());
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionAborted);
+        assert_eq!(
+            err.to_string(),
+            "party 2 aborted the protocol: out of memory"
+        );
+        assert_eq!(signal.error().unwrap().to_string(), err.to_string());
+    }
+
+    fn roundtrip(compression: Compression) -> CompressionStats {
+        let counters = CompressionCounters::default();
+        let mut codec = ProtocolCodec::new(AbortSignal::new())
+            .with_compression(compression)
+            .with_counters(counters.clone());
+        let large = Bytes::from(vec![0u8; 1024]);
+        let small = Bytes::from_static(b"small");
+        let mut buf = BytesMut::new();
+        codec
+            .encode(ProtocolMessage::Data(large.clone()), &mut buf)
+            .unwrap();
+        codec
+            .encode(ProtocolMessage::Data(small.clone()), &mut buf)
+            .unwrap();
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), large);
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), small);
+        counters.get()
+    }
+
+    #[test]
+    fn uncompressed_stats() {
+        let stats = roundtrip(Compression::None);
+        assert_eq!(stats.sent_uncompressed, 1029);
+        assert_eq!(stats.saved_sent(), 0);
+        assert_eq!(stats.received_uncompressed, 1029);
+        assert_eq!(stats.saved_received(), 0);
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn lz4_roundtrip() {
+        let stats = roundtrip(Compression::Lz4);
+        assert!(stats.saved_sent() > 0);
+        assert_eq!(stats.saved_sent(), stats.saved_received());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd_roundtrip() {
+        let stats = roundtrip(Compression::Zstd);
+        assert!(stats.saved_sent() > 0);
+        assert_eq!(stats.saved_sent(), stats.saved_received());
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn unavailable_compression_fails() {
+        let mut codec = ProtocolCodec::new(AbortSignal::new()).with_compression(Compression::Zstd);
+        let err = codec
+            .encode(
+                ProtocolMessage::Data(Bytes::from(vec![0u8; 1024])),
+                &mut BytesMut::new(),
+            )
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    }
+}
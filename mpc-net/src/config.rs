@@ -3,14 +3,18 @@ use color_eyre::eyre;
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::Formatter,
-    net::{SocketAddr, ToSocketAddrs},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
     path::PathBuf,
+    time::Duration,
 };
 
 /// A network address wrapper.
+///
+/// In the config, an address is written as `hostname:port`. IPv6 addresses are enclosed in
+/// brackets, e.g., `[::1]:10000`.
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub struct Address {
-    /// The hostname of the address, will be DNS resolved. This hostname is also checked to be contained in the certificate for the party.
+    /// The hostname of the address, will be DNS resolved. This hostname is also checked to be contained in the certificate for the party. IPv6 addresses are stored without brackets.
     pub hostname: String,
     /// The port of the address.
     pub port: u16,
@@ -18,33 +22,62 @@ pub struct Address {
 
 impl std::fmt::Display for Address {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}:{}", self.hostname, self.port)
+        if self.hostname.contains(':') {
+            write!(f, "[{}]:{}", self.hostname, self.port)
+        } else {
+            write!(f, "{}:{}", self.hostname, self.port)
+        }
+    }
+}
+
+impl std::str::FromStr for Address {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hostname, port) = s
+            .rsplit_once(':')
+            .ok_or_else(|| eyre::eyre!("invalid address {s}, expected hostname:port"))?;
+        let hostname = match hostname.strip_prefix('[') {
+            Some(ipv6) => ipv6
+                .strip_suffix(']')
+                .ok_or_else(|| eyre::eyre!("invalid address {s}, missing closing bracket"))?,
+            None if hostname.contains(':') => {
+                return Err(eyre::eyre!(
+                    "invalid address {s}, IPv6 addresses must be enclosed in brackets"
+                ))
+            }
+            None => hostname,
+        };
+        if hostname.is_empty() {
+            return Err(eyre::eyre!("invalid address {s}, missing hostname"));
+        }
+        let port = port
+            .parse()
+            .map_err(|err| eyre::eyre!("invalid port in address {s}: {err}"))?;
+        Ok(Address {
+            hostname: hostname.to_string(),
+            port,
+        })
     }
 }
 
 impl ToSocketAddrs for Address {
     type Iter = std::vec::IntoIter<SocketAddr>;
     fn to_socket_addrs(&self) -> std::io::Result<Self::Iter> {
-        format!("{}:{}", self.hostname, self.port).to_socket_addrs()
+        (self.hostname.as_str(), self.port).to_socket_addrs()
     }
 }
 
 impl Serialize for Address {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        serializer.serialize_str(&format!("{}:{}", self.hostname, self.port))
+        serializer.serialize_str(&self.to_string())
     }
 }
 
 impl<'de> Deserialize<'de> for Address {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         let s = String::deserialize(deserializer)?;
-        let parts: Vec<&str> = s.split(':').collect();
-        if parts.len() != 2 {
-            return Err(serde::de::Error::custom("invalid address format"));
-        }
-        let hostname = parts[0].to_string();
-        let port = parts[1].parse().map_err(serde::de::Error::custom)?;
-        Ok(Address { hostname, port })
+        s.parse().map_err(serde::de::Error::custom)
     }
 }
 
@@ -55,10 +88,221 @@ pub struct NetworkParty {
     pub id: usize,
     /// The DNS name of the party.
     pub dns_name: Address,
-    /// The path to the public certificate of the party.
+    /// The path to the public certificate of the party, DER or PEM encoded.
     pub cert_path: PathBuf,
 }
 
+/// The transport protocol used for the connections between the parties.
+#[derive(
+    Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    /// QUIC over UDP, with a single connection to each party that multiplexes all channels.
+    #[default]
+    Quic,
+    /// TCP, secured with TLS, with a separate connection to each party for every set of channels.
+    /// Every session (see [MpcNetworkHandler::fork_session](crate::MpcNetworkHandler::fork_session))
+    /// thus pays a TCP and TLS handshake with each party.
+    Tcp,
+}
+
+/// The compression of the messages of MPC protocols (see
+/// [ProtocolCodec](crate::codecs::ProtocolCodec)). The compressions other than `None` need the
+/// cargo feature of the same name. A party can decompress the messages of all compressions that
+/// are enabled at compile time, so the parties do not need to use the same compression.
+#[derive(
+    Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// The messages are sent as they are.
+    #[default]
+    None,
+    /// Fast compression with LZ4.
+    Lz4,
+    /// Stronger, but slower compression with Zstandard.
+    Zstd,
+}
+
+impl Compression {
+    /// Whether the compression is available, i.e., its cargo feature is enabled.
+    pub fn is_available(&self) -> bool {
+        match self {
+            Compression::None => true,
+            Compression::Lz4 => cfg!(feature = "lz4"),
+            Compression::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+}
+
+/// The settings for establishing the connections between the parties.
+///
+/// Connection attempts that fail (e.g., because the other party did not start yet) are retried
+/// with exponential backoff until the connections to all parties are established or the timeout
+/// is reached.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[serde(default)]
+pub struct ConnectionSetup {
+    /// The timeout for establishing the connections to all parties in milliseconds.
+    pub timeout_ms: u64,
+    /// The initial delay between two connection attempts in milliseconds, doubled after every
+    /// failed attempt.
+    pub initial_backoff_ms: u64,
+    /// The maximum delay between two connection attempts in milliseconds.
+    pub max_backoff_ms: u64,
+    /// The time in milliseconds a connecting peer has for the TLS handshake and for telling us its
+    /// id and session. Peers that take longer are dropped, so they cannot stall the setup.
+    pub handshake_timeout_ms: u64,
+}
+
+impl Default for ConnectionSetup {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 60_000,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 5_000,
+            handshake_timeout_ms: 5_000,
+        }
+    }
+}
+
+impl ConnectionSetup {
+    /// The timeout for establishing the connections to all parties.
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    /// The initial delay between two connection attempts.
+    pub fn initial_backoff(&self) -> Duration {
+        Duration::from_millis(self.initial_backoff_ms)
+    }
+
+    /// The maximum delay between two connection attempts.
+    pub fn max_backoff(&self) -> Duration {
+        Duration::from_millis(self.max_backoff_ms)
+    }
+
+    /// The time a connecting peer has for the handshake and for telling us its id and session.
+    pub fn handshake_timeout(&self) -> Duration {
+        Duration::from_millis(self.handshake_timeout_ms)
+    }
+}
+
+/// The emulated properties of a link from one party to another, see [NetworkProfile].
+#[derive(
+    Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash,
+)]
+#[serde(default)]
+pub struct LinkProfile {
+    /// The one-way latency of the link in milliseconds.
+    pub latency_ms: u64,
+    /// The maximum deviation from the latency in milliseconds. The latency of every write is
+    /// drawn uniformly from `latency_ms ± jitter_ms`, but the data of a stream stays in order.
+    pub jitter_ms: u64,
+    /// The bandwidth of the link in Mbit/s, unlimited if not set. The bandwidth is shared by all
+    /// streams over the link.
+    pub bandwidth_mbps: Option<u64>,
+}
+
+impl LinkProfile {
+    /// The one-way latency of the link.
+    pub fn latency(&self) -> Duration {
+        Duration::from_millis(self.latency_ms)
+    }
+
+    /// The maximum deviation from the latency.
+    pub fn jitter(&self) -> Duration {
+        Duration::from_millis(self.jitter_ms)
+    }
+
+    /// The time it takes to put the given number of bytes onto the link.
+    pub fn transmission_time(&self, bytes: usize) -> Duration {
+        match self.bandwidth_mbps {
+            Some(mbps) => Duration::from_secs_f64(bytes as f64 * 8.0 / (mbps as f64 * 1e6)),
+            None => Duration::ZERO,
+        }
+    }
+}
+
+/// A link with its own [LinkProfile] in a [NetworkProfile].
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct ProfileLink {
+    /// The id of the sending party.
+    pub from: usize,
+    /// The id of the receiving party.
+    pub to: usize,
+    /// The emulated properties of the link.
+    #[serde(flatten)]
+    pub profile: LinkProfile,
+}
+
+/// The profile of an emulated network between parties running in the same process (see
+/// [MpcNetworkHandler::emulated](crate::MpcNetworkHandler::emulated)), e.g., to predict how a
+/// protocol behaves when the parties are on different continents.
+///
+/// The links between the parties are directed and use the default [LinkProfile] given by the
+/// top-level keys, unless they are listed in `links`:
+///
+/// ```toml
+/// latency_ms = 40
+/// jitter_ms = 5
+/// bandwidth_mbps = 1000
+///
+/// [[links]]
+/// from = 0
+/// to = 2
+/// latency_ms = 120
+/// bandwidth_mbps = 100
+/// ```
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[serde(default)]
+pub struct NetworkProfile {
+    /// The profile of the links that are not listed in `links`.
+    #[serde(flatten)]
+    pub default: LinkProfile,
+    /// The links with their own profile.
+    pub links: Vec<ProfileLink>,
+}
+
+impl NetworkProfile {
+    /// The profile of the link from party `from` to party `to`.
+    pub fn link(&self, from: usize, to: usize) -> LinkProfile {
+        self.links
+            .iter()
+            .find(|link| link.from == from && link.to == to)
+            .map_or(self.default, |link| link.profile)
+    }
+
+    /// Basic sanity checks for the profile of a network with `num_parties` parties.
+    pub fn check_profile(&self, num_parties: usize) -> eyre::Result<()> {
+        let profiles = std::iter::once(&self.default).chain(self.links.iter().map(|l| &l.profile));
+        for profile in profiles {
+            if profile.bandwidth_mbps == Some(0) {
+                return Err(eyre::eyre!("the bandwidth must be positive"));
+            }
+        }
+        let mut links = Vec::with_capacity(self.links.len());
+        for link in &self.links {
+            if link.from >= num_parties || link.to >= num_parties || link.from == link.to {
+                return Err(eyre::eyre!(
+                    "invalid link from party {} to party {} in a network of {} parties",
+                    link.from,
+                    link.to,
+                    num_parties
+                ));
+            }
+            links.push((link.from, link.to));
+        }
+        links.sort_unstable();
+        links.dedup();
+        if links.len() != self.links.len() {
+            return Err(eyre::eyre!("duplicate links found"));
+        }
+        Ok(())
+    }
+}
+
 /// The network configuration.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub struct NetworkConfig {
@@ -68,11 +312,57 @@ pub struct NetworkConfig {
     pub my_id: usize,
     /// The [SocketAddr] we bind to.
     pub bind_addr: SocketAddr,
-    /// The path to our private key file.
+    /// The path to our private key file, DER or PEM encoded (see [keys](crate::keys)).
     pub key_path: PathBuf,
+    /// The transport protocol, defaults to [Transport::Quic]. Only QUIC multiplexes the channels
+    /// of all sessions over one connection per party, TCP opens a new TLS connection for every set
+    /// of channels.
+    #[serde(default)]
+    pub transport: Transport,
+    /// The settings for establishing the connections, see [ConnectionSetup].
+    #[serde(default)]
+    pub setup: ConnectionSetup,
+    /// The time in milliseconds we wait for a message of another party before giving up. If not
+    /// set, we wait until the message arrives or the connection fails.
+    #[serde(default)]
+    pub recv_timeout_ms: Option<u64>,
+    /// The compression of the messages we send, defaults to [Compression::None].
+    #[serde(default)]
+    pub compression: Compression,
 }
 
 impl NetworkConfig {
+    /// Creates the configuration of party `my_id` in a network of the given parties. The party
+    /// binds to the port of its own address on all interfaces (IPv6 if its address is an IPv6
+    /// address, IPv4 otherwise). All other settings are the defaults.
+    pub fn new(parties: Vec<NetworkParty>, my_id: usize, key_path: PathBuf) -> eyre::Result<Self> {
+        let me = parties
+            .iter()
+            .find(|p| p.id == my_id)
+            .ok_or_else(|| eyre::eyre!("my_id {my_id} not found in list of parties"))?;
+        let ip = match me.dns_name.hostname.parse() {
+            Ok(IpAddr::V6(_)) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+            _ => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        };
+        let config = Self {
+            bind_addr: SocketAddr::new(ip, me.dns_name.port),
+            parties,
+            my_id,
+            key_path,
+            transport: Transport::default(),
+            setup: ConnectionSetup::default(),
+            recv_timeout_ms: None,
+            compression: Compression::default(),
+        };
+        config.check_config()?;
+        Ok(config)
+    }
+
+    /// The time we wait for a message of another party, see [NetworkConfig::recv_timeout_ms].
+    pub fn recv_timeout(&self) -> Option<Duration> {
+        self.recv_timeout_ms.map(Duration::from_millis)
+    }
+
     /// Basic sanity checks for the configuration.
     pub fn check_config(&self) -> eyre::Result<()> {
         // sanity check config
@@ -94,6 +384,78 @@ impl NetworkConfig {
         if ids.len() != self.parties.len() {
             return Err(eyre::eyre!("duplicate party ids found"));
         }
+        // 3. check that we can back off and accept connections
+        if self.setup.initial_backoff_ms == 0
+            || self.setup.initial_backoff_ms > self.setup.max_backoff_ms
+        {
+            return Err(eyre::eyre!(
+                "the initial backoff must be positive and at most the maximum backoff"
+            ));
+        }
+        if self.setup.handshake_timeout_ms == 0 {
+            return Err(eyre::eyre!("the handshake timeout must be positive"));
+        }
+        // 4. check that we can receive messages at all
+        if self.recv_timeout_ms == Some(0) {
+            return Err(eyre::eyre!("the receive timeout must be positive"));
+        }
+        // 5. check that we can compress
+        if !self.compression.is_available() {
+            return Err(eyre::eyre!(
+                "compression {:?} is not available, mpc-net was built without its feature",
+                self.compression
+            ));
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_addresses() {
+        let addr: Address = "localhost:10000".parse().unwrap();
+        assert_eq!(addr.hostname, "localhost");
+        assert_eq!(addr.port, 10000);
+        assert_eq!(addr.to_string(), "localhost:10000");
+
+        let addr: Address = "[::1]:10001".parse().unwrap();
+        assert_eq!(addr.hostname, "::1");
+        assert_eq!(addr.port, 10001);
+        assert_eq!(addr.to_string(), "[::1]:10001");
+        assert_eq!(
+            addr.to_socket_addrs().unwrap().next().unwrap(),
+            "[::1]:10001".parse().unwrap()
+        );
+
+        for invalid in [
+            "localhost",
+            "::1:10000",
+            "[::1:10000",
+            ":10000",
+            "host:port",
+        ] {
+            assert!(invalid.parse::<Address>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn new_config_binds_to_own_port() {
+        let parties = ["127.0.0.1:10000", "[::1]:10001"]
+            .into_iter()
+            .enumerate()
+            .map(|(id, addr)| NetworkParty {
+                id,
+                dns_name: addr.parse().unwrap(),
+                cert_path: format!("cert{id}.pem").into(),
+            })
+            .collect::<Vec<_>>();
+        let config = NetworkConfig::new(parties.clone(), 0, "key0.pem".into()).unwrap();
+        assert_eq!(config.bind_addr, "0.0.0.0:10000".parse().unwrap());
+        let config = NetworkConfig::new(parties.clone(), 1, "key1.pem".into()).unwrap();
+        assert_eq!(config.bind_addr, "[::]:10001".parse().unwrap());
+        assert!(NetworkConfig::new(parties, 2, "key2.pem".into()).is_err());
+    }
+}
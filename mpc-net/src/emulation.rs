@@ -0,0 +1,318 @@
+//! Network emulation for the in-memory transport, which delays the data between the parties
+//! according to a [NetworkProfile] to predict how protocols behave in real networks.
+use std::{
+    collections::HashMap,
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{ready, Context, Poll},
+};
+
+use bytes::{Buf, Bytes};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::mpsc,
+    time::{Instant, Sleep},
+};
+
+use crate::config::{LinkProfile, NetworkProfile};
+
+/// The data written to an emulated stream, together with the time it arrives at the receiver.
+type Delivery = (Instant, Bytes);
+
+/// The emulated network between all parties.
+#[derive(Debug)]
+pub(crate) struct Emulation {
+    links: HashMap<(usize, usize), Arc<Link>>,
+    rounds: Vec<Arc<RoundCounter>>,
+}
+
+/// A directed link between two parties. The bandwidth of the link is shared by all streams.
+#[derive(Debug)]
+struct Link {
+    profile: LinkProfile,
+    state: Mutex<LinkState>,
+}
+
+#[derive(Debug)]
+struct LinkState {
+    // the time when the data written so far is completely put onto the link
+    busy_until: Instant,
+    rng: StdRng,
+}
+
+/// Counts the communication rounds of a party, i.e., how often it started sending after it
+/// received data.
+#[derive(Debug)]
+pub(crate) struct RoundCounter {
+    rounds: AtomicU64,
+    received: AtomicBool,
+}
+
+impl Default for RoundCounter {
+    fn default() -> Self {
+        Self {
+            rounds: AtomicU64::new(0),
+            received: AtomicBool::new(true),
+        }
+    }
+}
+
+impl RoundCounter {
+    fn sent(&self) {
+        if self.received.swap(false, Ordering::Relaxed) {
+            self.rounds.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn received(&self) {
+        self.received.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn get(&self) -> u64 {
+        self.rounds.load(Ordering::Relaxed)
+    }
+}
+
+impl Link {
+    /// Puts `len` bytes onto the link and returns the time they arrive at the receiver.
+    fn transmit(&self, len: usize) -> Instant {
+        let mut state = self.state.lock().expect("link is not poisoned");
+        let now = Instant::now();
+        let start = state.busy_until.max(now);
+        state.busy_until = start + self.profile.transmission_time(len);
+        let jitter = self.profile.jitter();
+        let latency = if jitter.is_zero() {
+            self.profile.latency()
+        } else {
+            let min = self.profile.latency().saturating_sub(jitter);
+            let max = self.profile.latency() + jitter;
+            state.rng.gen_range(min..=max)
+        };
+        state.busy_until + latency
+    }
+}
+
+impl Emulation {
+    /// Creates the emulated network between `num_parties` parties with the given profile.
+    pub(crate) fn new(num_parties: usize, profile: &NetworkProfile) -> Self {
+        let now = Instant::now();
+        let mut links = HashMap::with_capacity(num_parties * num_parties);
+        for from in 0..num_parties {
+            for to in (0..num_parties).filter(|&to| to != from) {
+                let link = Link {
+                    profile: profile.link(from, to),
+                    state: Mutex::new(LinkState {
+                        busy_until: now,
+                        rng: StdRng::from_entropy(),
+                    }),
+                };
+                links.insert((from, to), Arc::new(link));
+            }
+        }
+        Self {
+            links,
+            rounds: (0..num_parties).map(|_| Arc::default()).collect(),
+        }
+    }
+
+    /// Creates a pipe between party `a` and party `b` and returns the ends of both parties.
+    pub(crate) fn pipe(
+        &self,
+        a: usize,
+        b: usize,
+    ) -> (
+        (EmulatedRecvStream, EmulatedSendStream),
+        (EmulatedRecvStream, EmulatedSendStream),
+    ) {
+        let (send_ab, recv_ab) = self.stream(a, b);
+        let (send_ba, recv_ba) = self.stream(b, a);
+        ((recv_ba, send_ab), (recv_ab, send_ba))
+    }
+
+    fn stream(&self, from: usize, to: usize) -> (EmulatedSendStream, EmulatedRecvStream) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let send = EmulatedSendStream {
+            tx: Some(tx),
+            link: Arc::clone(&self.links[&(from, to)]),
+            last_arrival: Instant::now(),
+            rounds: Arc::clone(&self.rounds[from]),
+        };
+        let recv = EmulatedRecvStream {
+            rx,
+            pending: None,
+            buffer: Bytes::new(),
+            rounds: Arc::clone(&self.rounds[to]),
+        };
+        (send, recv)
+    }
+
+    /// Returns the number of communication rounds of party `i`.
+    pub(crate) fn rounds(&self, i: usize) -> Option<u64> {
+        self.rounds.get(i).map(|rounds| rounds.get())
+    }
+}
+
+/// The write half of an emulated stream. Writing never blocks, the data is delivered to the
+/// receiver after it was transmitted over the link.
+#[derive(Debug)]
+pub(crate) struct EmulatedSendStream {
+    tx: Option<mpsc::UnboundedSender<Delivery>>,
+    link: Arc<Link>,
+    // the data of a stream arrives in order, even with jitter
+    last_arrival: Instant,
+    rounds: Arc<RoundCounter>,
+}
+
+/// The read half of an emulated stream.
+#[derive(Debug)]
+pub(crate) struct EmulatedRecvStream {
+    rx: mpsc::UnboundedReceiver<Delivery>,
+    pending: Option<(Pin<Box<Sleep>>, Bytes)>,
+    buffer: Bytes,
+    rounds: Arc<RoundCounter>,
+}
+
+impl AsyncWrite for EmulatedSendStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let arrival = self.link.transmit(buf.len()).max(self.last_arrival);
+        self.last_arrival = arrival;
+        let tx = self
+            .tx
+            .as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "the stream was shut down"))?;
+        tx.send((arrival, Bytes::copy_from_slice(buf)))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "the receiver was dropped"))?;
+        self.rounds.sent();
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.tx = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncRead for EmulatedRecvStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            if !self.buffer.is_empty() {
+                let len = self.buffer.len().min(buf.remaining());
+                buf.put_slice(&self.buffer[..len]);
+                self.buffer.advance(len);
+                return Poll::Ready(Ok(()));
+            }
+            if let Some((sleep, _)) = self.pending.as_mut() {
+                ready!(sleep.as_mut().poll(cx));
+                let (_, data) = self.pending.take().expect("data is pending");
+                self.buffer = data;
+                self.rounds.received();
+                continue;
+            }
+            match ready!(self.rx.poll_recv(cx)) {
+                Some((arrival, data)) => {
+                    self.pending = Some((Box::pin(tokio::time::sleep_until(arrival)), data));
+                }
+                // the sender shut down the stream
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::config::ProfileLink;
+
+    fn assert_delay(elapsed: Duration, expected: Duration) {
+        assert!(elapsed >= expected, "{elapsed:?} < {expected:?}");
+        assert!(elapsed < expected + Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn delays_by_latency_and_bandwidth() {
+        let profile: NetworkProfile = toml::from_str(
+            r#"
+            latency_ms = 10
+            bandwidth_mbps = 8
+
+            [[links]]
+            from = 1
+            to = 0
+            latency_ms = 100
+            "#,
+        )
+        .unwrap();
+        profile.check_profile(2).unwrap();
+        let emulation = Emulation::new(2, &profile);
+        let ((mut recv0, mut send0), (mut recv1, mut send1)) = emulation.pipe(0, 1);
+
+        // 1000 bytes take 1 ms at 8 Mbit/s
+        let start = Instant::now();
+        send0.write_all(&[1; 1000]).await.unwrap();
+        let mut buf = [0; 1000];
+        recv1.read_exact(&mut buf).await.unwrap();
+        assert_delay(start.elapsed(), Duration::from_millis(11));
+
+        let start = Instant::now();
+        send1.write_all(b"pong").await.unwrap();
+        let mut buf = [0; 4];
+        recv0.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"pong");
+        assert_delay(start.elapsed(), Duration::from_millis(100));
+
+        assert_eq!(emulation.rounds(0), Some(1));
+        assert_eq!(emulation.rounds(1), Some(1));
+        send0.write_all(b"ping").await.unwrap();
+        assert_eq!(emulation.rounds(0), Some(2));
+    }
+
+    #[test]
+    fn invalid_profiles() {
+        let link = |from, to| ProfileLink {
+            from,
+            to,
+            profile: LinkProfile::default(),
+        };
+        let profile = NetworkProfile {
+            links: vec![link(0, 1), link(0, 1)],
+            ..Default::default()
+        };
+        assert!(profile.check_profile(3).is_err());
+        let profile = NetworkProfile {
+            links: vec![link(0, 3)],
+            ..Default::default()
+        };
+        assert!(profile.check_profile(3).is_err());
+        let profile = NetworkProfile {
+            default: LinkProfile {
+                bandwidth_mbps: Some(0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(profile.check_profile(3).is_err());
+    }
+}
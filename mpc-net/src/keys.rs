@@ -0,0 +1,146 @@
+//! Reading and generating the certificates and private keys of the parties.
+//!
+//! Certificates and keys are accepted DER or PEM encoded. Private keys can be RSA (PKCS#1),
+//! ECDSA (SEC1) or any key in PKCS#8 (e.g., ECDSA or Ed25519), as long as rustls supports it.
+use std::{io::Write, path::Path};
+
+use color_eyre::eyre::{self, Context};
+use quinn::rustls::pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer};
+use rcgen::CertifiedKey;
+use serde::{Deserialize, Serialize};
+
+/// The encoding of generated certificates and keys.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Hash,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyFormat {
+    /// Binary DER, the private key in PKCS#8.
+    Der,
+    /// Base64 encoded PEM, the private key in PKCS#8.
+    #[default]
+    Pem,
+}
+
+impl KeyFormat {
+    /// The file extension for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            KeyFormat::Der => "der",
+            KeyFormat::Pem => "pem",
+        }
+    }
+}
+
+/// Generates a self-signed certificate for the given subject alternative names, together with its
+/// ECDSA P-256 private key. Names that are IP addresses become IP address SANs, all other names
+/// DNS name SANs. Returns the encoded certificate and the encoded private key.
+pub fn generate_self_signed(
+    sans: Vec<String>,
+    format: KeyFormat,
+) -> eyre::Result<(Vec<u8>, Vec<u8>)> {
+    let CertifiedKey { cert, key_pair } =
+        rcgen::generate_simple_self_signed(sans).context("generating self-signed cert")?;
+    Ok(match format {
+        KeyFormat::Der => (cert.der().to_vec(), key_pair.serialize_der()),
+        KeyFormat::Pem => (
+            cert.pem().into_bytes(),
+            key_pair.serialize_pem().into_bytes(),
+        ),
+    })
+}
+
+/// Writes an encoded private key to `path`, truncating an existing file. On unix, a new file is
+/// created with mode 0o600, i.e., only its owner can read the key.
+pub fn write_private_key(path: &Path, key: &[u8]) -> eyre::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("creating private key {}", path.display()))?;
+    file.write_all(key)
+        .with_context(|| format!("writing private key {}", path.display()))?;
+    Ok(())
+}
+
+fn is_pem(data: &[u8]) -> bool {
+    data.iter()
+        .skip_while(|b| b.is_ascii_whitespace())
+        .take(10)
+        .eq(b"-----BEGIN")
+}
+
+/// Reads a DER or PEM encoded certificate. Of a PEM file, the first certificate is used.
+pub fn read_certificate(path: &Path) -> eyre::Result<CertificateDer<'static>> {
+    let data =
+        std::fs::read(path).with_context(|| format!("reading certificate {}", path.display()))?;
+    if is_pem(&data) {
+        CertificateDer::from_pem_slice(&data)
+            .map_err(|err| eyre::eyre!("invalid PEM certificate {}: {err}", path.display()))
+    } else {
+        Ok(CertificateDer::from(data))
+    }
+}
+
+/// Reads a DER or PEM encoded private key in PKCS#1, SEC1 or PKCS#8 format. Of a PEM file, the
+/// first private key is used.
+pub fn read_private_key(path: &Path) -> eyre::Result<PrivateKeyDer<'static>> {
+    let data =
+        std::fs::read(path).with_context(|| format!("reading private key {}", path.display()))?;
+    if is_pem(&data) {
+        PrivateKeyDer::from_pem_slice(&data)
+            .map_err(|err| eyre::eyre!("invalid PEM private key {}: {err}", path.display()))
+    } else {
+        PrivateKeyDer::try_from(data)
+            .map_err(|err| eyre::eyre!("invalid DER private key {}: {err}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_generated_keys() {
+        let dir = std::env::temp_dir().join(format!("mpc-net-keys-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for format in [KeyFormat::Der, KeyFormat::Pem] {
+            let (cert, key) =
+                generate_self_signed(vec!["localhost".to_owned(), "::1".to_owned()], format)
+                    .unwrap();
+            let cert_path = dir.join(format!("cert.{}", format.extension()));
+            let key_path = dir.join(format!("key.{}", format.extension()));
+            std::fs::write(&cert_path, &cert).unwrap();
+            write_private_key(&key_path, &key).unwrap();
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = std::fs::metadata(&key_path).unwrap().permissions().mode();
+                assert_eq!(mode & 0o777, 0o600);
+            }
+            assert_eq!(is_pem(&cert), format == KeyFormat::Pem);
+            read_certificate(&cert_path).unwrap();
+            assert!(matches!(
+                read_private_key(&key_path).unwrap(),
+                PrivateKeyDer::Pkcs8(_)
+            ));
+        }
+        let garbage = dir.join("garbage.pem");
+        std::fs::write(&garbage, "-----BEGIN GARBAGE-----").unwrap();
+        assert!(read_private_key(&garbage).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
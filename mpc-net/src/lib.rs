@@ -3,192 +3,267 @@
 use std::{
     collections::{BTreeMap, HashMap},
     io,
-    net::{SocketAddr, ToSocketAddrs},
     sync::Arc,
     time::Duration,
 };
 
 use channel::{BytesChannel, Channel};
-use codecs::BincodeCodec;
-use color_eyre::eyre::{self, Context, Report};
-use config::NetworkConfig;
-use quinn::{
-    crypto::rustls::QuicClientConfig,
-    rustls::{
-        pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer},
-        RootCertStore,
-    },
-};
-use quinn::{
-    ClientConfig, Connection, Endpoint, IdleTimeout, RecvStream, SendStream, TransportConfig,
-    VarInt,
-};
+use codecs::{AbortSignal, BincodeCodec, CompressionCounters, CompressionStats, ProtocolCodec};
+use color_eyre::eyre::{self, Report};
+use config::{Compression, NetworkConfig, NetworkProfile, Transport};
+use memory::MemoryTransport;
+use quic::QuicTransport;
 use serde::{de::DeserializeOwned, Serialize};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tcp::TcpTransport;
+use tls::TlsConfig;
+use tokio::time::Instant;
 use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
+use transport::{RecvStream, SendStream};
 
 pub mod channel;
 pub mod codecs;
 pub mod config;
+mod emulation;
+pub mod keys;
+mod memory;
+mod quic;
+mod retry;
+mod tcp;
+mod tls;
+pub mod transport;
 
 /// A network handler for MPC protocols.
+///
+/// A handler belongs to a session, and the channels it sets up are only connected to the channels
+/// set up by the handlers of the same session of the other parties. The handlers created by
+/// [MpcNetworkHandler::establish], [MpcNetworkHandler::in_memory] and
+/// [MpcNetworkHandler::emulated] belong to session 0, further sessions over the same connections
+/// are created with [MpcNetworkHandler::fork_session].
 #[derive(Debug)]
 pub struct MpcNetworkHandler {
-    // this is a btreemap because we rely on iteration order
-    connections: BTreeMap<usize, Connection>,
-    endpoints: Vec<Endpoint>,
+    transport: Arc<TransportHandler>,
+    session_id: u64,
     my_id: usize,
+    num_parties: usize,
+    recv_timeout: Option<Duration>,
+    compression: Compression,
+    // the compression statistics of the protocol channels to each party, shared by all sessions
+    compression_stats: Arc<BTreeMap<usize, CompressionCounters>>,
+}
+
+#[derive(Debug)]
+enum TransportHandler {
+    Quic(QuicTransport),
+    Tcp(Box<TcpTransport>),
+    Memory(MemoryTransport),
 }
 
 impl MpcNetworkHandler {
     /// Tries to establish a connection to other parties in the network based on the provided [NetworkConfig].
+    ///
+    /// The connections are mutually authenticated: every party presents its certificate from the
+    /// configured `cert_path`, and a connection is rejected if the certificate of the peer does not
+    /// belong to the party id it claims. Certificates and keys can be DER or PEM encoded (see
+    /// [keys]). The [Transport] is selected in the [NetworkConfig].
+    ///
+    /// The parties can be started in any order, failed connection attempts are retried until the
+    /// timeout of the [ConnectionSetup](config::ConnectionSetup) is reached.
     pub async fn establish(config: NetworkConfig) -> Result<Self, Report> {
         config.check_config()?;
-        let certs: HashMap<usize, CertificateDer> = config
-            .parties
-            .iter()
-            .map(|p| {
-                let cert = std::fs::read(&p.cert_path)
-                    .with_context(|| format!("reading certificate of party {}", p.id))?;
-                Ok((p.id, CertificateDer::from(cert)))
+        let tls = TlsConfig::new(&config)?;
+        let timeout = config.setup.timeout();
+        let recv_timeout = config.recv_timeout();
+        let compression = config.compression;
+        let deadline = Instant::now() + timeout;
+        let transport = async {
+            Ok::<_, Report>(match config.transport {
+                Transport::Quic => {
+                    TransportHandler::Quic(QuicTransport::establish(config, tls, deadline).await?)
+                }
+                Transport::Tcp => TransportHandler::Tcp(Box::new(
+                    TcpTransport::establish(config, tls, deadline).await?,
+                )),
             })
-            .collect::<Result<_, Report>>()?;
-
-        let mut root_store = RootCertStore::empty();
-        for (id, cert) in &certs {
-            root_store
-                .add(cert.clone())
-                .with_context(|| format!("adding certificate for party {} to root store", id))?;
-        }
-        let crypto = quinn::rustls::ClientConfig::builder()
-            .with_root_certificates(root_store)
-            .with_no_client_auth();
-
-        let client_config = {
-            let mut transport_config = TransportConfig::default();
-            transport_config.max_idle_timeout(Some(
-                IdleTimeout::try_from(Duration::from_secs(60)).unwrap(),
-            ));
-            // atm clients send keepalive packets
-            transport_config.keep_alive_interval(Some(Duration::from_secs(1)));
-            let mut client_config =
-                ClientConfig::new(Arc::new(QuicClientConfig::try_from(crypto)?));
-            client_config.transport_config(Arc::new(transport_config));
-            client_config
         };
+        let transport = tokio::time::timeout_at(deadline, transport)
+            .await
+            .map_err(|_| eyre::eyre!("could not connect to all parties within {timeout:?}"))??;
+        Ok(MpcNetworkHandler::new(transport, recv_timeout, compression))
+    }
 
-        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(
-            std::fs::read(config.key_path).context("reading own key file")?,
-        ));
-        let server_config =
-            quinn::ServerConfig::with_single_cert(vec![certs[&config.my_id].clone()], key)
-                .context("creating our server config")?;
-        let our_socket_addr = config.bind_addr;
+    /// Creates the network handlers of `num_parties` parties that run in the same process and are
+    /// connected in memory, where the i-th handler belongs to the party with id i. The handlers
+    /// provide the same channels as the handlers created by [MpcNetworkHandler::establish], so
+    /// they can be moved to different threads to run a protocol without certificates or sockets.
+    pub fn in_memory(num_parties: usize) -> Vec<Self> {
+        MemoryTransport::new_parties(num_parties)
+            .into_iter()
+            .map(|memory| {
+                MpcNetworkHandler::new(TransportHandler::Memory(memory), None, Compression::None)
+            })
+            .collect()
+    }
 
-        let mut endpoints = Vec::new();
-        let server_endpoint = quinn::Endpoint::server(server_config.clone(), our_socket_addr)?;
+    /// Creates the network handlers of `num_parties` parties that run in the same process and are
+    /// connected by an emulated network, where the i-th handler belongs to the party with id i.
+    /// Like [MpcNetworkHandler::in_memory], but the data between the parties is delayed according
+    /// to the latency, jitter and bandwidth of the links in the [NetworkProfile], e.g., to predict
+    /// how a protocol behaves when the parties are on different continents. The handlers also count
+    /// the communication rounds, see [MpcNetworkHandler::get_rounds].
+    pub fn emulated(num_parties: usize, profile: &NetworkProfile) -> Result<Vec<Self>, Report> {
+        profile.check_profile(num_parties)?;
+        Ok(MemoryTransport::new_emulated_parties(num_parties, profile)
+            .into_iter()
+            .map(|memory| {
+                MpcNetworkHandler::new(TransportHandler::Memory(memory), None, Compression::None)
+            })
+            .collect())
+    }
 
-        let mut connections = BTreeMap::new();
+    fn new(
+        transport: TransportHandler,
+        recv_timeout: Option<Duration>,
+        compression: Compression,
+    ) -> Self {
+        let (my_id, party_ids) = match &transport {
+            TransportHandler::Quic(quic) => (quic.my_id(), quic.party_ids().collect::<Vec<_>>()),
+            TransportHandler::Tcp(tcp) => (tcp.my_id(), tcp.party_ids().collect()),
+            TransportHandler::Memory(memory) => (memory.my_id(), memory.party_ids().collect()),
+        };
+        MpcNetworkHandler {
+            transport: Arc::new(transport),
+            session_id: 0,
+            my_id,
+            num_parties: party_ids.len() + 1,
+            recv_timeout,
+            compression,
+            compression_stats: Arc::new(
+                party_ids
+                    .into_iter()
+                    .map(|id| (id, CompressionCounters::default()))
+                    .collect(),
+            ),
+        }
+    }
 
-        for party in config.parties {
-            if party.id == config.my_id {
-                // skip self
-                continue;
-            }
-            if party.id < config.my_id {
-                // connect to party, we are client
-
-                let party_addresses: Vec<SocketAddr> = party
-                    .dns_name
-                    .to_socket_addrs()
-                    .with_context(|| format!("while resolving DNS name for {}", party.dns_name))?
-                    .collect();
-                if party_addresses.is_empty() {
-                    return Err(eyre::eyre!("could not resolve DNS name {}", party.dns_name));
-                }
-                let party_addr = party_addresses[0];
-                let local_client_socket: SocketAddr = match party_addr {
-                    SocketAddr::V4(_) => {
-                        "0.0.0.0:0".parse().expect("hardcoded IP address is valid")
-                    }
-                    SocketAddr::V6(_) => "[::]:0".parse().expect("hardcoded IP address is valid"),
-                };
-                let endpoint = quinn::Endpoint::client(local_client_socket)
-                    .with_context(|| format!("creating client endpoint to party {}", party.id))?;
-                let conn = endpoint
-                    .connect_with(client_config.clone(), party_addr, &party.dns_name.hostname)
-                    .with_context(|| {
-                        format!("setting up client connection with party {}", party.id)
-                    })?
-                    .await
-                    .with_context(|| format!("connecting as a client to party {}", party.id))?;
-                let mut uni = conn.open_uni().await?;
-                uni.write_u32(u32::try_from(config.my_id).expect("party id fits into u32"))
-                    .await?;
-                uni.flush().await?;
-                uni.finish()?;
-                tracing::trace!(
-                    "Conn with id {} from {} to {}",
-                    conn.stable_id(),
-                    endpoint.local_addr().unwrap(),
-                    conn.remote_address(),
-                );
-                assert!(connections.insert(party.id, conn).is_none());
-                endpoints.push(endpoint);
-            } else {
-                // we are the server, accept a connection
-                if let Some(maybe_conn) = server_endpoint.accept().await {
-                    let conn = maybe_conn.await?;
-                    tracing::trace!(
-                        "Conn with id {} from {} to {}",
-                        conn.stable_id(),
-                        server_endpoint.local_addr().unwrap(),
-                        conn.remote_address(),
-                    );
-                    let mut uni = conn.accept_uni().await?;
-                    let other_party_id = uni.read_u32().await?;
-                    assert!(connections
-                        .insert(
-                            usize::try_from(other_party_id).expect("u32 fits into usize"),
-                            conn
-                        )
-                        .is_none());
-                } else {
-                    return Err(eyre::eyre!(
-                        "server endpoint did not accept a connection from party {}",
-                        party.id
-                    ));
-                }
-            }
+    /// Creates a handler for the session with the given id, which shares the transport of this
+    /// handler. This allows running several protocols between the same parties concurrently
+    /// (e.g., one per proof request) without establishing the network again. With
+    /// [Transport::Quic], the channels of all sessions are streams over the same connections. TCP
+    /// does not multiplex, so with [Transport::Tcp] every set of channels opens a new TLS
+    /// connection to each party and pays a TCP and TLS handshake.
+    ///
+    /// All parties have to use the same session id for the same protocol run, and a session id must
+    /// not be used by two handlers at the same time. Session 0 is the session of the handler
+    /// returned by [MpcNetworkHandler::establish]. The connection statistics are shared between all
+    /// sessions.
+    pub fn fork_session(&self, session_id: u64) -> Self {
+        MpcNetworkHandler {
+            transport: Arc::clone(&self.transport),
+            session_id,
+            my_id: self.my_id,
+            num_parties: self.num_parties,
+            recv_timeout: self.recv_timeout,
+            compression: self.compression,
+            compression_stats: Arc::clone(&self.compression_stats),
         }
-        endpoints.push(server_endpoint);
+    }
 
-        Ok(MpcNetworkHandler {
-            connections,
-            endpoints,
-            my_id: config.my_id,
-        })
+    /// Returns the id of the session of this handler.
+    pub fn session_id(&self) -> u64 {
+        self.session_id
+    }
+
+    /// Returns our own party id.
+    pub fn id(&self) -> usize {
+        self.my_id
+    }
+
+    /// Returns the number of parties in the network, including ourselves.
+    pub fn num_parties(&self) -> usize {
+        self.num_parties
+    }
+
+    /// Returns the time the protocols should wait for a message of another party, see
+    /// [NetworkConfig::recv_timeout_ms]. Sessions inherit the timeout of the handler they are
+    /// forked from.
+    pub fn recv_timeout(&self) -> Option<Duration> {
+        self.recv_timeout
+    }
+
+    /// Sets the time the protocols should wait for a message of another party.
+    pub fn set_recv_timeout(&mut self, recv_timeout: Option<Duration>) {
+        self.recv_timeout = recv_timeout;
+    }
+
+    /// Returns the compression of the protocol channels, see [NetworkConfig::compression].
+    pub fn compression(&self) -> Compression {
+        self.compression
     }
 
-    /// Returns the number of sent and received bytes.
+    /// Sets the compression of the protocol channels that are set up afterwards.
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.compression = compression;
+    }
+
+    /// Returns the number of sent and received bytes. For QUIC, these are the UDP bytes, for TCP
+    /// and in-memory connections the bytes of the streams (without the overhead of TLS and TCP).
     pub fn get_send_receive(&self, i: usize) -> std::io::Result<(u64, u64)> {
-        let conn = self
-            .connections
+        match self.transport.as_ref() {
+            TransportHandler::Quic(quic) => quic.get_send_receive(i),
+            TransportHandler::Tcp(tcp) => tcp.get_send_receive(i),
+            TransportHandler::Memory(memory) => memory.get_send_receive(i),
+        }
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such connection"))
+    }
+
+    /// Returns the number of communication rounds of an emulated network (see
+    /// [MpcNetworkHandler::emulated]), i.e., how often we started sending after we received data
+    /// from another party. Returns `None` for the other transports.
+    pub fn get_rounds(&self) -> Option<u64> {
+        match self.transport.as_ref() {
+            TransportHandler::Memory(memory) => memory.get_rounds(),
+            TransportHandler::Quic(_) | TransportHandler::Tcp(_) => None,
+        }
+    }
+
+    /// Returns the number of message bytes sent to and received from party `i` over protocol
+    /// channels (see [MpcNetworkHandler::get_protocol_channels]) before and after compression.
+    pub fn get_compression_stats(&self, i: usize) -> std::io::Result<CompressionStats> {
+        self.compression_stats
             .get(&i)
-            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such connection"))?;
-        let stats = conn.stats();
-        Ok((stats.udp_tx.bytes, stats.udp_rx.bytes))
+            .map(CompressionCounters::get)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such connection"))
     }
 
     /// Prints the connection statistics.
     pub fn print_connection_stats(&self, out: &mut impl std::io::Write) -> std::io::Result<()> {
-        for (i, conn) in &self.connections {
-            let stats = conn.stats();
+        let ids = match self.transport.as_ref() {
+            TransportHandler::Quic(quic) => quic.party_ids().collect::<Vec<_>>(),
+            TransportHandler::Tcp(tcp) => tcp.party_ids().collect(),
+            TransportHandler::Memory(memory) => memory.party_ids().collect(),
+        };
+        for i in ids {
+            let (sent, received) = self.get_send_receive(i)?;
             writeln!(
                 out,
                 "Connection {} stats:\n\tSENT: {} bytes\n\tRECV: {} bytes",
-                i, stats.udp_tx.bytes, stats.udp_rx.bytes
+                i, sent, received
             )?;
+            let compression = self.get_compression_stats(i)?;
+            if compression.saved_sent() > 0 || compression.saved_received() > 0 {
+                writeln!(
+                    out,
+                    "\tCOMPRESSION: saved {} of {} sent and {} of {} received message bytes",
+                    compression.saved_sent(),
+                    compression.sent_uncompressed,
+                    compression.saved_received(),
+                    compression.received_uncompressed
+                )?;
+            }
+        }
+        if let Some(rounds) = self.get_rounds() {
+            writeln!(out, "Communication rounds: {}", rounds)?;
         }
         Ok(())
     }
@@ -222,37 +297,589 @@ impl MpcNetworkHandler {
         &mut self,
         codec: C,
     ) -> std::io::Result<HashMap<usize, Channel<RecvStream, SendStream, C>>> {
-        let mut channels = HashMap::with_capacity(self.connections.len() - 1);
-        for (&id, conn) in &mut self.connections {
-            if id < self.my_id {
-                // we are the client, so we are the receiver
-                let (mut send_stream, mut recv_stream) = conn.open_bi().await?;
-                send_stream.write_u32(self.my_id as u32).await?;
-                let their_id = recv_stream.read_u32().await?;
-                assert!(their_id == id as u32);
-                let conn = Channel::new(recv_stream, send_stream, codec.clone());
-                assert!(channels.insert(id, conn).is_none());
-            } else {
-                // we are the server, so we are the sender
-                let (mut send_stream, mut recv_stream) = conn.accept_bi().await?;
-                let their_id = recv_stream.read_u32().await?;
-                assert!(their_id == id as u32);
-                send_stream.write_u32(self.my_id as u32).await?;
-                let conn = Channel::new(recv_stream, send_stream, codec.clone());
-                assert!(channels.insert(id, conn).is_none());
+        self.open_channels(|_| codec.clone()).await
+    }
+
+    /// Set up a new [Channel] using the [ProtocolCodec] between each party, which compresses the
+    /// messages as configured and triggers the given [AbortSignal] if a party aborts. The
+    /// resulting map maps the id of the party to its respective [Channel].
+    pub async fn get_protocol_channels(
+        &mut self,
+        signal: &AbortSignal,
+    ) -> std::io::Result<HashMap<usize, Channel<RecvStream, SendStream, ProtocolCodec>>> {
+        let compression_stats = Arc::clone(&self.compression_stats);
+        let compression = self.compression;
+        self.open_channels(|id| {
+            ProtocolCodec::new(signal.clone())
+                .with_compression(compression)
+                .with_counters(compression_stats[&id].clone())
+        })
+        .await
+    }
+
+    async fn open_channels<MSend, C: Encoder<MSend> + Decoder + Clone>(
+        &self,
+        codec: impl Fn(usize) -> C,
+    ) -> std::io::Result<HashMap<usize, Channel<RecvStream, SendStream, C>>> {
+        let streams = match self.transport.as_ref() {
+            TransportHandler::Quic(quic) => quic.open_streams(self.session_id).await?,
+            TransportHandler::Tcp(tcp) => tcp.open_streams(self.session_id).await?,
+            TransportHandler::Memory(memory) => memory.open_streams(self.session_id),
+        };
+        Ok(streams
+            .into_iter()
+            .map(|(id, (recv_stream, send_stream))| {
+                (id, Channel::new(recv_stream, send_stream, codec(id)))
+            })
+            .collect())
+    }
+
+    /// Shutdown all connections. For QUIC, this calls [`quinn::Endpoint::wait_idle`] on all endpoints.
+    ///
+    /// The connections are shared by all sessions, so they are only shut down by the handler of
+    /// the last session.
+    pub async fn shutdown(self) {
+        let Ok(transport) = Arc::try_unwrap(self.transport) else {
+            return;
+        };
+        match transport {
+            TransportHandler::Quic(quic) => quic.shutdown().await,
+            // the TCP connections and in-memory pipes are closed with their channels
+            TransportHandler::Tcp(_) | TransportHandler::Memory(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        net::{SocketAddr, TcpListener, ToSocketAddrs, UdpSocket},
+        path::{Path, PathBuf},
+    };
+
+    use bytes::Bytes;
+    use futures::{SinkExt, StreamExt};
+    use quinn::{crypto::rustls::QuicClientConfig, rustls};
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+    use crate::config::{ConnectionSetup, NetworkParty};
+
+    /// A temporary directory for the certificates and keys of a test, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("mpc-net-{name}-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Writes a new certificate and key for 127.0.0.1 to the directory.
+    fn write_keys(dir: &Path, name: &str) -> (PathBuf, PathBuf) {
+        let (cert, key) =
+            keys::generate_self_signed(vec!["127.0.0.1".to_owned()], keys::KeyFormat::Pem).unwrap();
+        let cert_path = dir.join(format!("{name}.cert.pem"));
+        let key_path = dir.join(format!("{name}.key.pem"));
+        std::fs::write(&cert_path, cert).unwrap();
+        std::fs::write(&key_path, key).unwrap();
+        (cert_path, key_path)
+    }
+
+    /// Returns a port on 127.0.0.1 that is free for both TCP and UDP.
+    fn free_port() -> u16 {
+        loop {
+            let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = tcp.local_addr().unwrap().port();
+            if UdpSocket::bind(("127.0.0.1", port)).is_ok() {
+                return port;
             }
         }
-        Ok(channels)
     }
 
-    /// Shutdown all connections, and call [`quinn::Endpoint::wait_idle`] on all of them
-    pub async fn shutdown(self) {
-        for conn in self.connections.into_values() {
-            conn.close(0u32.into(), b"");
+    /// The configs of `num_parties` parties on 127.0.0.1 with a short setup timeout, where the
+    /// i-th config belongs to party i.
+    fn configs(dir: &Path, num_parties: usize, transport: Transport) -> Vec<NetworkConfig> {
+        let (parties, key_paths): (Vec<_>, Vec<_>) = (0..num_parties)
+            .map(|id| {
+                let (cert_path, key_path) = write_keys(dir, &format!("party{id}"));
+                let party = NetworkParty {
+                    id,
+                    dns_name: format!("127.0.0.1:{}", free_port()).parse().unwrap(),
+                    cert_path,
+                };
+                (party, key_path)
+            })
+            .unzip();
+        key_paths
+            .into_iter()
+            .enumerate()
+            .map(|(my_id, key_path)| NetworkConfig {
+                bind_addr: SocketAddr::new([127, 0, 0, 1].into(), parties[my_id].dns_name.port),
+                parties: parties.clone(),
+                my_id,
+                key_path,
+                transport,
+                setup: ConnectionSetup {
+                    timeout_ms: 5_000,
+                    initial_backoff_ms: 10,
+                    max_backoff_ms: 100,
+                    handshake_timeout_ms: 1_000,
+                },
+                recv_timeout_ms: None,
+                compression: Compression::None,
+            })
+            .collect()
+    }
+
+    fn party_addr(config: &NetworkConfig, id: usize) -> SocketAddr {
+        config.parties[id]
+            .dns_name
+            .to_socket_addrs()
+            .unwrap()
+            .next()
+            .unwrap()
+    }
+
+    /// Establishes the network of `victim` while `attacker` runs, and returns the error of the
+    /// victim.
+    async fn victim_error(victim: NetworkConfig, attacker: NetworkConfig) -> String {
+        let attacker = tokio::spawn(MpcNetworkHandler::establish(attacker));
+        let err = MpcNetworkHandler::establish(victim)
+            .await
+            .expect_err("the victim must reject the attacker");
+        attacker.abort();
+        format!("{err:#}")
+    }
+
+    /// Starts the parties in the order 2, 0, 1, so party 2 has to retry connecting to the parties
+    /// that are not running yet.
+    async fn reversed_start_order(transport: Transport) {
+        let dir = TempDir::new(&format!("start-order-{transport:?}"));
+        let configs = configs(&dir.0, 3, transport);
+        let mut parties = Vec::new();
+        for id in [2, 0, 1] {
+            parties.push(tokio::spawn(MpcNetworkHandler::establish(
+                configs[id].clone(),
+            )));
+            // a few backoffs of the connection attempts of party 2
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        for party in parties {
+            let net = party
+                .await
+                .unwrap()
+                .expect("setup succeeds in any start order");
+            net.shutdown().await;
+        }
+    }
+
+    /// Party 2 never starts, so parties 0 and 1 wait for it until the setup timeout.
+    async fn party_never_starts(transport: Transport) {
+        let dir = TempDir::new(&format!("never-starts-{transport:?}"));
+        let mut configs = configs(&dir.0, 3, transport);
+        configs.truncate(2);
+        for config in configs.iter_mut() {
+            config.setup.timeout_ms = 1_000;
+        }
+        let start = Instant::now();
+        let errors =
+            futures::future::join_all(configs.into_iter().map(MpcNetworkHandler::establish)).await;
+        assert!(start.elapsed() >= Duration::from_secs(1));
+        assert!(start.elapsed() < Duration::from_secs(3));
+        for result in errors {
+            let err = format!("{:#}", result.expect_err("party 2 never starts"));
+            assert!(
+                err.contains("could not connect to all parties within 1s"),
+                "{err}"
+            );
         }
-        for endpoint in self.endpoints {
-            endpoint.wait_idle().await;
-            endpoint.close(VarInt::from_u32(0), &[]);
+    }
+
+    /// The config of an attacker that pretends to be the party of `config`, but binds to another
+    /// port, such that the legitimate party can still start.
+    fn attacker_config(config: &NetworkConfig) -> NetworkConfig {
+        let mut attacker = config.clone();
+        attacker.bind_addr.set_port(free_port());
+        attacker
+    }
+
+    /// Sends a message to every other party and receives theirs, first on a [Channel] and then on
+    /// a [BytesChannel].
+    async fn exchange(net: &mut MpcNetworkHandler) {
+        let my_id = net.my_id;
+        let session = net.session_id;
+
+        let mut channels = net.get_serde_bincode_channels::<String>().await.unwrap();
+        assert_eq!(channels.len(), net.num_parties - 1);
+        for (id, channel) in channels.iter_mut() {
+            channel
+                .send(format!("{my_id} -> {id} in session {session}"))
+                .await
+                .unwrap();
+        }
+        for (id, channel) in channels.iter_mut() {
+            let msg = channel.next().await.unwrap().unwrap();
+            assert_eq!(msg, format!("{id} -> {my_id} in session {session}"));
+        }
+        // closing waits for the other party, so all channels are closed concurrently
+        let close = channels.into_values().map(Channel::close::<String>);
+        futures::future::try_join_all(close).await.unwrap();
+
+        let mut channels = net.get_byte_channels().await.unwrap();
+        assert_eq!(channels.len(), net.num_parties - 1);
+        for (&id, channel) in channels.iter_mut() {
+            let msg = Bytes::from(vec![my_id as u8, id as u8, session as u8]);
+            channel.send(msg).await.unwrap();
+        }
+        for (&id, channel) in channels.iter_mut() {
+            let msg = channel.next().await.unwrap().unwrap();
+            assert_eq!(msg.as_ref(), [id as u8, my_id as u8, session as u8]);
+        }
+        let close = channels.into_values().map(Channel::close::<Bytes>);
+        futures::future::try_join_all(close).await.unwrap();
+    }
+
+    /// Establishes the network of the party and exchanges messages with all other parties. The
+    /// connections stay open until all parties passed the `closed` barrier.
+    async fn run_party(config: NetworkConfig, closed: Arc<tokio::sync::Barrier>) {
+        let mut net = MpcNetworkHandler::establish(config).await.unwrap();
+        exchange(&mut net).await;
+        closed.wait().await;
+        net.shutdown().await;
+    }
+
+    /// Starts party 0, lets the attacker connect to it and then starts the other parties. Party 0
+    /// must drop the connection of the attacker, but still establish the network with the other
+    /// parties.
+    async fn attacker_is_skipped<T: Send + 'static>(
+        configs: Vec<NetworkConfig>,
+        attacker: impl Future<Output = T> + Send + 'static,
+    ) {
+        let closed = Arc::new(tokio::sync::Barrier::new(configs.len()));
+        let mut configs = configs.into_iter();
+        let victim = configs.next().unwrap();
+        let mut parties = vec![tokio::spawn(run_party(victim, Arc::clone(&closed)))];
+        let attacker = tokio::spawn(attacker);
+        // party 0 handles the attacker before the legitimate parties connect
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        parties.extend(configs.map(|config| tokio::spawn(run_party(config, Arc::clone(&closed)))));
+        let parties =
+            tokio::time::timeout(Duration::from_secs(10), futures::future::join_all(parties))
+                .await
+                .expect("party 0 must connect to the legitimate parties");
+        attacker.abort();
+        for party in parties {
+            party.unwrap();
+        }
+    }
+
+    /// An attacker claims to be party 1, but presents the (valid) certificate of party 2 to party 0.
+    async fn client_with_certificate_of_another_party(transport: Transport) {
+        let dir = TempDir::new(&format!("client-cert-{transport:?}"));
+        let configs = configs(&dir.0, 3, transport);
+        let mut attacker = attacker_config(&configs[1]);
+        attacker.parties[1].cert_path = configs[2].parties[2].cert_path.clone();
+        attacker.key_path = configs[2].key_path.clone();
+        attacker_is_skipped(configs, MpcNetworkHandler::establish(attacker)).await;
+    }
+
+    /// An attacker claims to be party 2 to party 0, but presents the certificate of party 1.
+    async fn certificate_bound_to_claimed_id(transport: Transport) {
+        let dir = TempDir::new(&format!("claimed-id-{transport:?}"));
+        let configs = configs(&dir.0, 3, transport);
+        let mut attacker = attacker_config(&configs[1]);
+        attacker.my_id = 2;
+        attacker.parties[1].id = 2;
+        attacker.parties[2].id = 1;
+        attacker_is_skipped(configs, MpcNetworkHandler::establish(attacker)).await;
+    }
+
+    /// Party 0 presents the (valid) certificate of party 2 to party 1.
+    async fn server_with_certificate_of_another_party(transport: Transport) {
+        let dir = TempDir::new(&format!("server-cert-{transport:?}"));
+        let mut configs = configs(&dir.0, 3, transport);
+        let mut attacker = configs[0].clone();
+        attacker.parties[0].cert_path = configs[2].parties[2].cert_path.clone();
+        attacker.key_path = configs[2].key_path.clone();
+        let err = victim_error(configs.swap_remove(1), attacker).await;
+        assert!(
+            err.contains("party 0 at 127.0.0.1")
+                && err.contains("presented a certificate that does not belong to party 0"),
+            "{err}"
+        );
+    }
+
+    /// An attacker claims to be party 1, but presents a certificate that does not belong to any
+    /// party, so the TLS handshake fails.
+    async fn client_with_foreign_certificate(transport: Transport) {
+        let dir = TempDir::new(&format!("foreign-cert-{transport:?}"));
+        let configs = configs(&dir.0, 2, transport);
+        let (foreign_cert, foreign_key) = write_keys(&dir.0, "foreign");
+        let mut attacker = attacker_config(&configs[1]);
+        attacker.parties[1].cert_path = foreign_cert;
+        attacker.key_path = foreign_key;
+        attacker_is_skipped(configs, MpcNetworkHandler::establish(attacker)).await;
+    }
+
+    /// Connects to party 0 without a client certificate, retrying until party 0 is up, and
+    /// returns the connection after sending the given id and session, which party 0 reads before
+    /// it checks the certificate. `write_id` is false for peers that stall after the handshake.
+    async fn connect_without_certificate(
+        config: &NetworkConfig,
+        id: u32,
+        write_id: bool,
+    ) -> Result<Box<dyn std::any::Any + Send>, Report> {
+        let server_addr = party_addr(config, 0);
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(keys::read_certificate(&config.parties[0].cert_path)?)?;
+        let crypto = Arc::new(
+            rustls::ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth(),
+        );
+        let setup = &config.setup;
+        let deadline = Instant::now() + setup.timeout();
+        match config.transport {
+            Transport::Quic => {
+                let endpoint = quinn::Endpoint::client("127.0.0.1:0".parse().unwrap())?;
+                let client_config =
+                    quinn::ClientConfig::new(Arc::new(QuicClientConfig::try_from(crypto)?));
+                let conn = retry::with_backoff(setup, deadline, || async {
+                    Ok(endpoint
+                        .connect_with(client_config.clone(), server_addr, "127.0.0.1")?
+                        .await?)
+                })
+                .await?;
+                if write_id {
+                    let mut uni = conn.open_uni().await?;
+                    uni.write_u32(id).await?;
+                    uni.finish()?;
+                }
+                Ok(Box::new((endpoint, conn)))
+            }
+            Transport::Tcp => {
+                let server_name = rustls::pki_types::ServerName::try_from("127.0.0.1")?;
+                let mut stream = retry::with_backoff(setup, deadline, || async {
+                    let tcp = tokio::net::TcpStream::connect(server_addr).await?;
+                    Ok(tokio_rustls::TlsConnector::from(Arc::clone(&crypto))
+                        .connect(server_name.clone(), tcp)
+                        .await?)
+                })
+                .await?;
+                if write_id {
+                    stream.write_u32(id).await?;
+                    stream.write_u64(0).await?;
+                }
+                stream.flush().await?;
+                Ok(Box::new(stream))
+            }
+        }
+    }
+
+    /// An attacker claims to be party 1, but does not present a certificate.
+    async fn client_without_certificate(transport: Transport) {
+        let dir = TempDir::new(&format!("no-cert-{transport:?}"));
+        let configs = configs(&dir.0, 2, transport);
+        let victim = configs[0].clone();
+        let attacker = async move {
+            let _conn = connect_without_certificate(&victim, 1, true).await?;
+            // keep the connection open until we are aborted
+            std::future::pending::<()>().await;
+            Ok::<_, Report>(())
+        };
+        attacker_is_skipped(configs, attacker).await;
+    }
+
+    /// A peer completes the handshake with party 0, but never sends its id.
+    async fn peer_stalls_after_handshake(transport: Transport) {
+        let dir = TempDir::new(&format!("stall-{transport:?}"));
+        let configs = configs(&dir.0, 2, transport);
+        let victim = configs[0].clone();
+        let attacker = async move {
+            let _conn = connect_without_certificate(&victim, 1, false).await?;
+            std::future::pending::<()>().await;
+            Ok::<_, Report>(())
+        };
+        attacker_is_skipped(configs, attacker).await;
+    }
+
+    /// Establishes the network of each party and exchanges messages with all other parties.
+    async fn exchange_messages(transport: Transport) {
+        let dir = TempDir::new(&format!("exchange-{transport:?}"));
+        let configs = configs(&dir.0, 3, transport);
+        let closed = Arc::new(tokio::sync::Barrier::new(configs.len()));
+        let parties = configs
+            .into_iter()
+            .map(|config| tokio::spawn(run_party(config, Arc::clone(&closed))));
+        for party in futures::future::join_all(parties).await {
+            party.unwrap();
         }
     }
+
+    /// Runs two sessions of each party concurrently. Party 0 opens the channels of session 2
+    /// before those of session 1 and all other parties the other way around, so party 0 first
+    /// accepts the streams of session 1 and has to keep them until session 1 accepts them.
+    async fn concurrent_sessions(transport: Transport) {
+        let dir = TempDir::new(&format!("sessions-{transport:?}"));
+        let configs = configs(&dir.0, 3, transport);
+        let closed = Arc::new(tokio::sync::Barrier::new(configs.len()));
+        let parties = configs.into_iter().map(|config| {
+            let closed = Arc::clone(&closed);
+            tokio::spawn(async move {
+                let net = MpcNetworkHandler::establish(config).await.unwrap();
+                let (first, second) = if net.id() == 0 { (2, 1) } else { (1, 2) };
+                let mut first = net.fork_session(first);
+                let mut second = net.fork_session(second);
+                tokio::join!(exchange(&mut first), async {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    exchange(&mut second).await;
+                });
+                drop((first, second));
+                closed.wait().await;
+                net.shutdown().await;
+            })
+        });
+        for party in futures::future::join_all(parties).await {
+            party.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn tcp_exchanges_messages() {
+        exchange_messages(Transport::Tcp).await;
+    }
+
+    #[tokio::test]
+    async fn quic_exchanges_messages() {
+        exchange_messages(Transport::Quic).await;
+    }
+
+    #[tokio::test]
+    async fn quic_runs_concurrent_sessions() {
+        concurrent_sessions(Transport::Quic).await;
+    }
+
+    #[tokio::test]
+    async fn tcp_runs_concurrent_sessions() {
+        concurrent_sessions(Transport::Tcp).await;
+    }
+
+    #[tokio::test]
+    async fn quic_setup_in_reversed_start_order() {
+        reversed_start_order(Transport::Quic).await;
+    }
+
+    #[tokio::test]
+    async fn tcp_setup_in_reversed_start_order() {
+        reversed_start_order(Transport::Tcp).await;
+    }
+
+    #[tokio::test]
+    async fn quic_setup_times_out_if_party_never_starts() {
+        party_never_starts(Transport::Quic).await;
+    }
+
+    #[tokio::test]
+    async fn tcp_setup_times_out_if_party_never_starts() {
+        party_never_starts(Transport::Tcp).await;
+    }
+
+    #[tokio::test]
+    async fn quic_skips_client_with_certificate_of_another_party() {
+        client_with_certificate_of_another_party(Transport::Quic).await;
+    }
+
+    #[tokio::test]
+    async fn quic_rejects_server_with_certificate_of_another_party() {
+        server_with_certificate_of_another_party(Transport::Quic).await;
+    }
+
+    #[tokio::test]
+    async fn quic_skips_client_with_foreign_certificate() {
+        client_with_foreign_certificate(Transport::Quic).await;
+    }
+
+    #[tokio::test]
+    async fn quic_skips_client_without_certificate() {
+        client_without_certificate(Transport::Quic).await;
+    }
+
+    #[tokio::test]
+    async fn tcp_skips_client_with_certificate_of_another_party() {
+        client_with_certificate_of_another_party(Transport::Tcp).await;
+    }
+
+    #[tokio::test]
+    async fn tcp_rejects_server_with_certificate_of_another_party() {
+        server_with_certificate_of_another_party(Transport::Tcp).await;
+    }
+
+    #[tokio::test]
+    async fn tcp_skips_client_with_foreign_certificate() {
+        client_with_foreign_certificate(Transport::Tcp).await;
+    }
+
+    #[tokio::test]
+    async fn tcp_skips_client_without_certificate() {
+        client_without_certificate(Transport::Tcp).await;
+    }
+
+    #[tokio::test]
+    async fn quic_skips_peer_that_stalls_after_handshake() {
+        peer_stalls_after_handshake(Transport::Quic).await;
+    }
+
+    #[tokio::test]
+    async fn tcp_skips_peer_that_stalls_after_handshake() {
+        peer_stalls_after_handshake(Transport::Tcp).await;
+    }
+
+    /// A peer opens a TCP connection to party 0, but never starts the TLS handshake.
+    #[tokio::test]
+    async fn tcp_skips_peer_that_stalls_before_handshake() {
+        let dir = TempDir::new("stall-tls");
+        let configs = configs(&dir.0, 2, Transport::Tcp);
+        let victim_addr = party_addr(&configs[0], 0);
+        let setup = configs[0].setup.clone();
+        let attacker = async move {
+            let deadline = Instant::now() + setup.timeout();
+            let _tcp = retry::with_backoff(&setup, deadline, || async {
+                Ok(tokio::net::TcpStream::connect(victim_addr).await?)
+            })
+            .await?;
+            std::future::pending::<()>().await;
+            Ok::<_, Report>(())
+        };
+        attacker_is_skipped(configs, attacker).await;
+    }
+
+    #[tokio::test]
+    async fn quic_binds_certificate_to_claimed_id() {
+        certificate_bound_to_claimed_id(Transport::Quic).await;
+    }
+
+    #[tokio::test]
+    async fn tcp_binds_certificate_to_claimed_id() {
+        certificate_bound_to_claimed_id(Transport::Tcp).await;
+    }
+
+    #[tokio::test]
+    async fn rejects_duplicate_party_ids() {
+        let dir = TempDir::new("duplicate-id");
+        let mut config = configs(&dir.0, 3, Transport::Quic).swap_remove(0);
+        config.parties[2].id = 1;
+        let err = MpcNetworkHandler::establish(config)
+            .await
+            .expect_err("duplicate ids must be rejected");
+        assert!(format!("{err:#}").contains("duplicate party ids found"));
+    }
 }
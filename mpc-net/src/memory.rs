@@ -0,0 +1,142 @@
+//! The in-memory transport, connecting parties that run in the same process.
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use tokio::io::DuplexStream;
+
+use crate::{
+    config::NetworkProfile,
+    emulation::{EmulatedRecvStream, EmulatedSendStream, Emulation},
+    transport::{ByteCounters, RecvStream, SendStream},
+};
+
+/// The capacity of the in-memory pipes, writing blocks until the other party reads.
+const PIPE_CAPACITY: usize = 1 << 20;
+
+/// The halves of the pipes that were created by one party but not yet picked up by the other
+/// one, indexed by (creating party, other party, session id) in the order they were created.
+type Pipes = Arc<Mutex<HashMap<(usize, usize, u64), VecDeque<PipeEnd>>>>;
+
+/// The end of a pipe that belongs to one party.
+#[derive(Debug)]
+enum PipeEnd {
+    Duplex(DuplexStream),
+    Emulated(EmulatedRecvStream, EmulatedSendStream),
+}
+
+/// Connects the parties with in-memory pipes. Whichever party of a pair opens its `i`-th stream
+/// of a session first creates the `i`-th pipe of the session between the two, so opening streams
+/// never blocks. With an [Emulation], the pipes delay the data like the links of a real network.
+#[derive(Debug)]
+pub(crate) struct MemoryTransport {
+    my_id: usize,
+    pipes: Pipes,
+    emulation: Option<Arc<Emulation>>,
+    stats: BTreeMap<usize, ByteCounters>,
+}
+
+impl MemoryTransport {
+    /// Creates the transports of `num_parties` parties, where the i-th transport belongs to the
+    /// party with id i.
+    pub(crate) fn new_parties(num_parties: usize) -> Vec<Self> {
+        Self::with_emulation(num_parties, None)
+    }
+
+    /// Creates the transports of `num_parties` parties that are connected by an emulated network
+    /// with the given profile, where the i-th transport belongs to the party with id i.
+    pub(crate) fn new_emulated_parties(num_parties: usize, profile: &NetworkProfile) -> Vec<Self> {
+        let emulation = Emulation::new(num_parties, profile);
+        Self::with_emulation(num_parties, Some(Arc::new(emulation)))
+    }
+
+    fn with_emulation(num_parties: usize, emulation: Option<Arc<Emulation>>) -> Vec<Self> {
+        let pipes = Pipes::default();
+        (0..num_parties)
+            .map(|my_id| Self {
+                my_id,
+                pipes: Arc::clone(&pipes),
+                emulation: emulation.clone(),
+                stats: (0..num_parties)
+                    .filter(|&id| id != my_id)
+                    .map(|id| (id, ByteCounters::default()))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Opens a new stream of the session to each party.
+    pub(crate) fn open_streams(&self, session: u64) -> HashMap<usize, (RecvStream, SendStream)> {
+        let mut pipes = self.pipes.lock().expect("pipes are not poisoned");
+        self.stats
+            .iter()
+            .map(|(&id, counters)| {
+                let pipe = pipes
+                    .get_mut(&(id, self.my_id, session))
+                    .and_then(VecDeque::pop_front)
+                    .unwrap_or_else(|| {
+                        let (ours, theirs) = self.create_pipe(id);
+                        pipes
+                            .entry((self.my_id, id, session))
+                            .or_default()
+                            .push_back(theirs);
+                        ours
+                    });
+                let streams = match pipe {
+                    PipeEnd::Duplex(pipe) => {
+                        let (read, write) = tokio::io::split(pipe);
+                        (
+                            RecvStream::memory(read, counters),
+                            SendStream::memory(write, counters),
+                        )
+                    }
+                    PipeEnd::Emulated(read, write) => (
+                        RecvStream::emulated(read, counters),
+                        SendStream::emulated(write, counters),
+                    ),
+                };
+                (id, streams)
+            })
+            .collect()
+    }
+
+    /// Creates a pipe to party `id` and returns our end and the end of the other party.
+    fn create_pipe(&self, id: usize) -> (PipeEnd, PipeEnd) {
+        match &self.emulation {
+            Some(emulation) => {
+                let ((our_read, our_write), (their_read, their_write)) =
+                    emulation.pipe(self.my_id, id);
+                (
+                    PipeEnd::Emulated(our_read, our_write),
+                    PipeEnd::Emulated(their_read, their_write),
+                )
+            }
+            None => {
+                let (ours, theirs) = tokio::io::duplex(PIPE_CAPACITY);
+                (PipeEnd::Duplex(ours), PipeEnd::Duplex(theirs))
+            }
+        }
+    }
+
+    /// Returns the number of communication rounds of an emulated network, see
+    /// [MpcNetworkHandler::get_rounds](crate::MpcNetworkHandler::get_rounds).
+    pub(crate) fn get_rounds(&self) -> Option<u64> {
+        self.emulation
+            .as_ref()
+            .and_then(|emulation| emulation.rounds(self.my_id))
+    }
+
+    /// Returns the number of sent and received bytes on all streams to party `i`.
+    pub(crate) fn get_send_receive(&self, i: usize) -> Option<(u64, u64)> {
+        self.stats.get(&i).map(ByteCounters::get)
+    }
+
+    pub(crate) fn my_id(&self) -> usize {
+        self.my_id
+    }
+
+    pub(crate) fn party_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.stats.keys().copied()
+    }
+}
@@ -0,0 +1,322 @@
+//! The QUIC transport, based on [quinn].
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    io,
+    net::{SocketAddr, ToSocketAddrs},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use color_eyre::eyre::{self, Context, Report};
+use quinn::{
+    crypto::rustls::{QuicClientConfig, QuicServerConfig},
+    rustls::pki_types::CertificateDer,
+    ClientConfig, Connection, Endpoint, IdleTimeout, TransportConfig, VarInt,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    time::Instant,
+};
+
+use crate::{
+    config::{NetworkConfig, NetworkParty},
+    retry,
+    tls::TlsConfig,
+    transport::{RecvStream, SendStream},
+};
+
+/// Returns the end-entity certificate the peer of the connection authenticated with.
+fn peer_certificate(conn: &Connection) -> Option<CertificateDer<'static>> {
+    conn.peer_identity()
+        .and_then(|identity| identity.downcast::<Vec<CertificateDer<'static>>>().ok())
+        .and_then(|certs| certs.into_iter().next())
+}
+
+/// Connects to the party as a client, retrying until the deadline.
+async fn connect(
+    config: &NetworkConfig,
+    tls: &TlsConfig,
+    client_config: &ClientConfig,
+    party: &NetworkParty,
+    deadline: Instant,
+) -> Result<(usize, Endpoint, Connection), Report> {
+    let party_addresses: Vec<SocketAddr> = party
+        .dns_name
+        .to_socket_addrs()
+        .with_context(|| format!("while resolving DNS name for {}", party.dns_name))?
+        .collect();
+    if party_addresses.is_empty() {
+        return Err(eyre::eyre!("could not resolve DNS name {}", party.dns_name));
+    }
+    let party_addr = party_addresses[0];
+    let local_client_socket: SocketAddr = match party_addr {
+        SocketAddr::V4(_) => "0.0.0.0:0".parse().expect("hardcoded IP address is valid"),
+        SocketAddr::V6(_) => "[::]:0".parse().expect("hardcoded IP address is valid"),
+    };
+    let endpoint = quinn::Endpoint::client(local_client_socket)
+        .with_context(|| format!("creating client endpoint to party {}", party.id))?;
+    let conn = retry::with_backoff(&config.setup, deadline, || async {
+        endpoint
+            .connect_with(client_config.clone(), party_addr, &party.dns_name.hostname)
+            .with_context(|| format!("setting up client connection with party {}", party.id))?
+            .await
+            .with_context(|| format!("connecting as a client to party {}", party.id))
+    })
+    .await?;
+    tls.check_server(
+        party.id,
+        conn.remote_address(),
+        peer_certificate(&conn).as_ref(),
+    )?;
+    let mut uni = conn.open_uni().await?;
+    uni.write_u32(u32::try_from(config.my_id).expect("party id fits into u32"))
+        .await?;
+    uni.flush().await?;
+    uni.finish()?;
+    tracing::trace!(
+        "Conn with id {} from {} to {}",
+        conn.stable_id(),
+        endpoint.local_addr().unwrap(),
+        conn.remote_address(),
+    );
+    Ok((party.id, endpoint, conn))
+}
+
+/// Accepts connections until `num_accept` parties are connected, in any order. Connections that
+/// fail or stall before the client sent its id (e.g., aborted attempts) or that do not belong to
+/// the party they claim are skipped.
+async fn accept(
+    config: &NetworkConfig,
+    tls: &TlsConfig,
+    server_endpoint: &Endpoint,
+    num_accept: usize,
+) -> Result<BTreeMap<usize, Connection>, Report> {
+    let mut connections = BTreeMap::new();
+    while connections.len() < num_accept {
+        let incoming = server_endpoint
+            .accept()
+            .await
+            .ok_or_else(|| eyre::eyre!("server endpoint was closed"))?;
+        // the connections are accepted one after another, so a peer that stalls must not block
+        // the other connections
+        let handshake = async {
+            let conn = incoming.await.context("failed to accept a connection")?;
+            tracing::trace!(
+                "Conn with id {} from {} to {}",
+                conn.stable_id(),
+                server_endpoint.local_addr().unwrap(),
+                conn.remote_address(),
+            );
+            let id = async { conn.accept_uni().await?.read_u32().await }
+                .await
+                .with_context(|| format!("failed to read the id of {}", conn.remote_address()))?;
+            Ok::<_, Report>((conn, usize::try_from(id).expect("u32 fits into usize")))
+        };
+        let timeout = config.setup.handshake_timeout();
+        let (conn, other_party_id) = match tokio::time::timeout(timeout, handshake).await {
+            Ok(Ok(accepted)) => accepted,
+            Ok(Err(err)) => {
+                tracing::warn!("skipping connection: {err:#}");
+                continue;
+            }
+            Err(_) => {
+                tracing::warn!("skipping a peer that stalled for {timeout:?}");
+                continue;
+            }
+        };
+        // the claimed id must be bound to the certificate the client authenticated with. Anyone
+        // can reach our port, so we drop the connection instead of giving up.
+        if let Err(err) = tls.check_client(
+            config.my_id,
+            other_party_id,
+            conn.remote_address(),
+            peer_certificate(&conn).as_ref(),
+        ) {
+            tracing::warn!("rejecting connection: {err:#}");
+            conn.close(0u32.into(), b"");
+            continue;
+        }
+        if let Some(old) = connections.insert(other_party_id, conn) {
+            tracing::warn!("party {other_party_id} reconnected, closing its previous connection");
+            old.close(0u32.into(), b"");
+        }
+    }
+    Ok(connections)
+}
+
+/// The bidirectional streams accepted for a session, indexed by party and session id.
+type PendingStreams = HashMap<(usize, u64), VecDeque<(quinn::SendStream, quinn::RecvStream)>>;
+
+/// One QUIC connection to each party, new channels are opened as bidirectional streams that are
+/// tagged with the id of the session that opened them.
+#[derive(Debug)]
+pub(crate) struct QuicTransport {
+    // this is a btreemap because we rely on iteration order
+    connections: BTreeMap<usize, Connection>,
+    endpoints: Vec<Endpoint>,
+    my_id: usize,
+    // streams of sessions that did not accept them yet
+    pending: Mutex<PendingStreams>,
+    // only one session accepts the streams of a party at a time
+    accepting: BTreeMap<usize, tokio::sync::Mutex<()>>,
+}
+
+impl QuicTransport {
+    /// Connects to the parties with smaller ids and accepts the connections of the parties with
+    /// larger ids concurrently, so the parties can be started in any order.
+    pub(crate) async fn establish(
+        config: NetworkConfig,
+        tls: TlsConfig,
+        deadline: Instant,
+    ) -> Result<Self, Report> {
+        let client_config = {
+            let mut transport_config = TransportConfig::default();
+            transport_config.max_idle_timeout(Some(
+                IdleTimeout::try_from(Duration::from_secs(60)).unwrap(),
+            ));
+            // atm clients send keepalive packets
+            transport_config.keep_alive_interval(Some(Duration::from_secs(1)));
+            let mut client_config =
+                ClientConfig::new(Arc::new(QuicClientConfig::try_from(tls.client.clone())?));
+            client_config.transport_config(Arc::new(transport_config));
+            client_config
+        };
+
+        let server_config = quinn::ServerConfig::with_crypto(Arc::new(QuicServerConfig::try_from(
+            tls.server.clone(),
+        )?));
+        let server_endpoint = quinn::Endpoint::server(server_config, config.bind_addr)?;
+
+        let connect = futures::future::try_join_all(
+            config
+                .parties
+                .iter()
+                .filter(|party| party.id < config.my_id)
+                .map(|party| connect(&config, &tls, &client_config, party, deadline)),
+        );
+        let num_accept = config
+            .parties
+            .iter()
+            .filter(|party| party.id > config.my_id)
+            .count();
+        let accept = accept(&config, &tls, &server_endpoint, num_accept);
+        let (connected, mut connections) = tokio::try_join!(connect, accept)?;
+
+        let mut endpoints = Vec::with_capacity(connected.len() + 1);
+        for (id, endpoint, conn) in connected {
+            assert!(connections.insert(id, conn).is_none());
+            endpoints.push(endpoint);
+        }
+        endpoints.push(server_endpoint);
+
+        let accepting = connections
+            .keys()
+            .map(|&id| (id, tokio::sync::Mutex::default()))
+            .collect();
+        Ok(Self {
+            connections,
+            endpoints,
+            my_id: config.my_id,
+            pending: Mutex::default(),
+            accepting,
+        })
+    }
+
+    /// Opens a new bidirectional stream of the session to each party.
+    pub(crate) async fn open_streams(
+        &self,
+        session: u64,
+    ) -> io::Result<HashMap<usize, (RecvStream, SendStream)>> {
+        let mut streams = HashMap::with_capacity(self.connections.len());
+        for (&id, conn) in &self.connections {
+            if id < self.my_id {
+                // we are the client, so we are the receiver
+                let (mut send_stream, mut recv_stream) = conn.open_bi().await?;
+                send_stream.write_u32(self.my_id as u32).await?;
+                send_stream.write_u64(session).await?;
+                let their_id = recv_stream.read_u32().await?;
+                assert!(their_id == id as u32);
+                assert!(streams
+                    .insert(id, (recv_stream.into(), send_stream.into()))
+                    .is_none());
+            } else {
+                // we are the server, so we are the sender
+                let (mut send_stream, recv_stream) = self.accept_bi(id, conn, session).await?;
+                send_stream.write_u32(self.my_id as u32).await?;
+                assert!(streams
+                    .insert(id, (recv_stream.into(), send_stream.into()))
+                    .is_none());
+            }
+        }
+        Ok(streams)
+    }
+
+    /// Accepts the next bidirectional stream of party `id` for the session. Streams for other
+    /// sessions are kept until these sessions accept them.
+    async fn accept_bi(
+        &self,
+        id: usize,
+        conn: &Connection,
+        session: u64,
+    ) -> io::Result<(quinn::SendStream, quinn::RecvStream)> {
+        loop {
+            if let Some(streams) = self.take_pending(id, session) {
+                return Ok(streams);
+            }
+            let _accepting = self.accepting[&id].lock().await;
+            // another session could have accepted our stream while we waited
+            if let Some(streams) = self.take_pending(id, session) {
+                return Ok(streams);
+            }
+            let (send_stream, mut recv_stream) = conn.accept_bi().await?;
+            let their_id = recv_stream.read_u32().await?;
+            assert!(their_id == id as u32);
+            let their_session = recv_stream.read_u64().await?;
+            if their_session == session {
+                return Ok((send_stream, recv_stream));
+            }
+            self.pending
+                .lock()
+                .expect("pending streams are not poisoned")
+                .entry((id, their_session))
+                .or_default()
+                .push_back((send_stream, recv_stream));
+        }
+    }
+
+    fn take_pending(
+        &self,
+        id: usize,
+        session: u64,
+    ) -> Option<(quinn::SendStream, quinn::RecvStream)> {
+        self.pending
+            .lock()
+            .expect("pending streams are not poisoned")
+            .get_mut(&(id, session))
+            .and_then(VecDeque::pop_front)
+    }
+
+    /// Returns the number of sent and received UDP bytes of the connection to party `i`.
+    pub(crate) fn get_send_receive(&self, i: usize) -> Option<(u64, u64)> {
+        let stats = self.connections.get(&i)?.stats();
+        Some((stats.udp_tx.bytes, stats.udp_rx.bytes))
+    }
+
+    pub(crate) fn my_id(&self) -> usize {
+        self.my_id
+    }
+
+    pub(crate) fn party_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.connections.keys().copied()
+    }
+
+    pub(crate) async fn shutdown(self) {
+        for conn in self.connections.into_values() {
+            conn.close(0u32.into(), b"");
+        }
+        for endpoint in self.endpoints {
+            endpoint.wait_idle().await;
+            endpoint.close(VarInt::from_u32(0), &[]);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Retrying connection attempts with exponential backoff.
+use std::{future::Future, time::Duration};
+
+use color_eyre::eyre::{self, Report};
+use tokio::time::Instant;
+
+use crate::config::ConnectionSetup;
+
+/// The waiting times between the connection attempts: starting at the initial backoff of the
+/// [ConnectionSetup], doubled after each attempt up to the maximum backoff.
+fn backoff_schedule(setup: &ConnectionSetup) -> impl Iterator<Item = Duration> {
+    let max_backoff = setup.max_backoff();
+    std::iter::successors(Some(setup.initial_backoff()), move |backoff| {
+        Some((*backoff * 2).min(max_backoff))
+    })
+}
+
+/// Runs `attempt` until it succeeds, waiting between the attempts according to the backoff of the
+/// [ConnectionSetup]. A running attempt is cancelled at the `deadline`. Returns the error of the
+/// last attempt if the next attempt would start after the `deadline`.
+pub(crate) async fn with_backoff<T, F, Fut>(
+    setup: &ConnectionSetup,
+    deadline: Instant,
+    mut attempt: F,
+) -> Result<T, Report>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Report>>,
+{
+    let mut schedule = backoff_schedule(setup);
+    loop {
+        let err = match tokio::time::timeout_at(deadline, attempt()).await {
+            Ok(Ok(result)) => return Ok(result),
+            Ok(Err(err)) => err,
+            Err(_) => eyre::eyre!("connection attempt did not finish before the setup timeout"),
+        };
+        let backoff = schedule.next().expect("the backoff schedule is infinite");
+        // we give up if the next attempt would start after the deadline
+        if Instant::now() + backoff >= deadline {
+            return Err(err.wrap_err(format!(
+                "giving up after retrying for {:?}",
+                setup.timeout()
+            )));
+        }
+        tracing::debug!("connection attempt failed, retrying in {backoff:?}: {err:#}");
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(initial_backoff_ms: u64, max_backoff_ms: u64) -> ConnectionSetup {
+        ConnectionSetup {
+            timeout_ms: 1_000,
+            initial_backoff_ms,
+            max_backoff_ms,
+            handshake_timeout_ms: 1_000,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let schedule: Vec<_> = backoff_schedule(&setup(100, 1_000))
+            .take(7)
+            .map(|backoff| backoff.as_millis())
+            .collect();
+        assert_eq!(schedule, [100, 200, 400, 800, 1_000, 1_000, 1_000]);
+    }
+
+    #[test]
+    fn backoff_starts_at_max() {
+        let schedule: Vec<_> = backoff_schedule(&setup(500, 500))
+            .take(3)
+            .map(|backoff| backoff.as_millis())
+            .collect();
+        assert_eq!(schedule, [500, 500, 500]);
+    }
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let setup = setup(10, 20);
+        let mut attempts = 0;
+        let result = with_backoff(&setup, Instant::now() + setup.timeout(), || {
+            attempts += 1;
+            let attempt = attempts;
+            async move {
+                if attempt < 3 {
+                    Err(eyre::eyre!("attempt {attempt} failed"))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(result, 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_at_deadline() {
+        let setup = ConnectionSetup {
+            timeout_ms: 200,
+            ..setup(10, 20)
+        };
+        let start = Instant::now();
+        let err = with_backoff(&setup, start + setup.timeout(), || async {
+            Err::<(), _>(eyre::eyre!("connection refused"))
+        })
+        .await
+        .unwrap_err();
+        // the last attempt would have started after the deadline
+        assert!(start.elapsed() <= setup.timeout());
+        let err = format!("{err:#}");
+        assert!(err.contains("giving up after retrying for 200ms"), "{err}");
+        assert!(err.contains("connection refused"), "{err}");
+    }
+}
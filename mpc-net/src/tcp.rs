@@ -0,0 +1,305 @@
+//! The TCP transport, secured with TLS via [tokio_rustls].
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    io,
+    net::ToSocketAddrs,
+    sync::{Arc, Mutex},
+};
+
+use color_eyre::eyre::{self, Context, Report};
+use quinn::rustls::pki_types::ServerName;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::Instant,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector, TlsStream};
+
+use crate::{
+    config::{ConnectionSetup, NetworkConfig, NetworkParty},
+    retry,
+    tls::TlsConfig,
+    transport::{ByteCounters, RecvStream, SendStream},
+};
+
+/// The authenticated connections of parties that are ahead of us, indexed by party and session id.
+type PendingStreams = HashMap<(usize, u64), VecDeque<TlsStream<TcpStream>>>;
+
+/// As TCP does not multiplex streams, every set of channels uses a new TLS connection to each
+/// party, which is tagged with the id of the session that opened it. The party with the larger id
+/// connects to the party with the smaller id.
+#[derive(Debug)]
+pub(crate) struct TcpTransport {
+    // the other parties, sorted by id
+    parties: Vec<NetworkParty>,
+    my_id: usize,
+    setup: ConnectionSetup,
+    tls: TlsConfig,
+    listener: TcpListener,
+    // connections of sessions that did not accept them yet
+    pending: Mutex<PendingStreams>,
+    // only one session accepts connections at a time
+    accepting: tokio::sync::Mutex<()>,
+    // the connections opened by `establish`, used for the first set of channels of session 0
+    established: Mutex<Option<HashMap<usize, TlsStream<TcpStream>>>>,
+    stats: BTreeMap<usize, ByteCounters>,
+}
+
+impl TcpTransport {
+    pub(crate) async fn establish(
+        config: NetworkConfig,
+        tls: TlsConfig,
+        deadline: Instant,
+    ) -> Result<Self, Report> {
+        let listener = TcpListener::bind(config.bind_addr)
+            .await
+            .with_context(|| format!("binding to {}", config.bind_addr))?;
+        let mut parties = config
+            .parties
+            .into_iter()
+            .filter(|party| party.id != config.my_id)
+            .collect::<Vec<_>>();
+        parties.sort_by_key(|party| party.id);
+        let stats = parties
+            .iter()
+            .map(|party| (party.id, ByteCounters::default()))
+            .collect();
+        let transport = Self {
+            parties,
+            my_id: config.my_id,
+            setup: config.setup,
+            tls,
+            listener,
+            pending: Mutex::default(),
+            accepting: tokio::sync::Mutex::default(),
+            established: Mutex::default(),
+            stats,
+        };
+        let established = transport.connect_all(0, deadline).await?;
+        *transport
+            .established
+            .lock()
+            .expect("established connections are not poisoned") = Some(established);
+        Ok(transport)
+    }
+
+    /// Opens a new authenticated connection of the session to each party. Connects to the parties
+    /// with smaller ids and accepts the connections of the parties with larger ids concurrently, so
+    /// the parties can be started in any order.
+    async fn connect_all(
+        &self,
+        session: u64,
+        deadline: Instant,
+    ) -> Result<HashMap<usize, TlsStream<TcpStream>>, Report> {
+        let connect = futures::future::try_join_all(
+            self.parties
+                .iter()
+                .filter(|party| party.id < self.my_id)
+                .map(|party| connect(self.my_id, session, &self.setup, &self.tls, party, deadline)),
+        );
+        let accept = async {
+            let mut streams = Vec::new();
+            for party in self.parties.iter().filter(|party| party.id > self.my_id) {
+                let stream = self.accept(party.id, session).await?;
+                streams.push((party.id, stream));
+            }
+            Ok::<_, Report>(streams)
+        };
+        let (connected, accepted) = tokio::try_join!(connect, accept)?;
+        Ok(connected.into_iter().chain(accepted).collect())
+    }
+
+    /// Opens a new stream of the session to each party, i.e., a new TLS connection.
+    pub(crate) async fn open_streams(
+        &self,
+        session: u64,
+    ) -> io::Result<HashMap<usize, (RecvStream, SendStream)>> {
+        let established = if session == 0 {
+            self.established
+                .lock()
+                .expect("established connections are not poisoned")
+                .take()
+        } else {
+            None
+        };
+        let streams = match established {
+            Some(streams) => streams,
+            None => {
+                let timeout = self.setup.timeout();
+                tokio::time::timeout(timeout, self.connect_all(session, Instant::now() + timeout))
+                    .await
+                    .map_err(|_| {
+                        io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            format!("could not connect to all parties within {timeout:?}"),
+                        )
+                    })?
+                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?
+            }
+        };
+        Ok(streams
+            .into_iter()
+            .map(|(id, stream)| {
+                let counters = &self.stats[&id];
+                let (read, write) = tokio::io::split(stream);
+                (
+                    id,
+                    (
+                        RecvStream::tcp(read, counters),
+                        SendStream::tcp(write, counters),
+                    ),
+                )
+            })
+            .collect())
+    }
+
+    /// Returns the next connection of party `id` for the session, accepting connections (possibly
+    /// of other parties or sessions) until there is one. Connections that fail or stall before the
+    /// client sent its id and session (e.g., aborted attempts) or that do not belong to the party
+    /// they claim are skipped.
+    async fn accept(&self, id: usize, session: u64) -> Result<TlsStream<TcpStream>, Report> {
+        loop {
+            if let Some(stream) = self.take_pending(id, session) {
+                return Ok(stream);
+            }
+            let _accepting = self.accepting.lock().await;
+            // another session could have accepted our connection while we waited
+            if let Some(stream) = self.take_pending(id, session) {
+                return Ok(stream);
+            }
+            let (tcp, addr) = self
+                .listener
+                .accept()
+                .await
+                .with_context(|| format!("accepting a connection from party {id}"))?;
+            tcp.set_nodelay(true)?;
+            // we hold `accepting`, so a peer that stalls must not block the other connections
+            let handshake = async {
+                let mut stream = TlsAcceptor::from(Arc::clone(&self.tls.server))
+                    .accept(tcp)
+                    .await
+                    .context("TLS handshake failed")?;
+                let their_id = stream.read_u32().await.context("failed to read the id")?;
+                let their_session = stream
+                    .read_u64()
+                    .await
+                    .context("failed to read the session")?;
+                Ok::<_, Report>((stream, their_id, their_session))
+            };
+            let timeout = self.setup.handshake_timeout();
+            let (stream, other_party_id, other_session) =
+                match tokio::time::timeout(timeout, handshake).await {
+                    Ok(Ok((stream, their_id, their_session))) => (
+                        stream,
+                        usize::try_from(their_id).expect("u32 fits into usize"),
+                        their_session,
+                    ),
+                    Ok(Err(err)) => {
+                        tracing::warn!("skipping the peer at {addr}: {err:#}");
+                        continue;
+                    }
+                    Err(_) => {
+                        tracing::warn!("skipping the peer at {addr}, it stalled for {timeout:?}");
+                        continue;
+                    }
+                };
+            // the claimed id must be bound to the certificate the client authenticated with. Anyone
+            // can reach our port, so we drop the connection instead of giving up.
+            if let Err(err) = self.tls.check_client(
+                self.my_id,
+                other_party_id,
+                addr,
+                stream
+                    .get_ref()
+                    .1
+                    .peer_certificates()
+                    .and_then(|certs| certs.first()),
+            ) {
+                tracing::warn!("rejecting connection: {err:#}");
+                continue;
+            }
+            tracing::trace!("TCP conn from {} to {}", addr, self.listener.local_addr()?);
+            if other_party_id == id && other_session == session {
+                return Ok(stream.into());
+            }
+            self.pending
+                .lock()
+                .expect("pending connections are not poisoned")
+                .entry((other_party_id, other_session))
+                .or_default()
+                .push_back(stream.into());
+        }
+    }
+
+    fn take_pending(&self, id: usize, session: u64) -> Option<TlsStream<TcpStream>> {
+        self.pending
+            .lock()
+            .expect("pending connections are not poisoned")
+            .get_mut(&(id, session))
+            .and_then(VecDeque::pop_front)
+    }
+
+    /// Returns the number of sent and received bytes on all streams to party `i`, without the
+    /// overhead of TLS and TCP.
+    pub(crate) fn get_send_receive(&self, i: usize) -> Option<(u64, u64)> {
+        self.stats.get(&i).map(ByteCounters::get)
+    }
+
+    pub(crate) fn my_id(&self) -> usize {
+        self.my_id
+    }
+
+    pub(crate) fn party_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.stats.keys().copied()
+    }
+}
+
+/// Connects to the party as a client for the session, retrying until the deadline.
+async fn connect(
+    my_id: usize,
+    session: u64,
+    setup: &ConnectionSetup,
+    tls: &TlsConfig,
+    party: &NetworkParty,
+    deadline: Instant,
+) -> Result<(usize, TlsStream<TcpStream>), Report> {
+    let party_addr = party
+        .dns_name
+        .to_socket_addrs()
+        .with_context(|| format!("while resolving DNS name for {}", party.dns_name))?
+        .next()
+        .ok_or_else(|| eyre::eyre!("could not resolve DNS name {}", party.dns_name))?;
+    let server_name = ServerName::try_from(party.dns_name.hostname.clone())
+        .with_context(|| format!("invalid DNS name {}", party.dns_name.hostname))?;
+    let mut stream = retry::with_backoff(setup, deadline, || async {
+        let tcp = TcpStream::connect(party_addr)
+            .await
+            .with_context(|| format!("connecting as a client to party {}", party.id))?;
+        tcp.set_nodelay(true)?;
+        TlsConnector::from(Arc::clone(&tls.client))
+            .connect(server_name.clone(), tcp)
+            .await
+            .with_context(|| format!("TLS handshake with party {}", party.id))
+    })
+    .await?;
+    tls.check_server(
+        party.id,
+        party_addr,
+        stream
+            .get_ref()
+            .1
+            .peer_certificates()
+            .and_then(|certs| certs.first()),
+    )?;
+    stream
+        .write_u32(u32::try_from(my_id).expect("party id fits into u32"))
+        .await?;
+    stream.write_u64(session).await?;
+    stream.flush().await?;
+    tracing::trace!(
+        "TCP conn from {} to {}",
+        stream.get_ref().0.local_addr()?,
+        party_addr
+    );
+    Ok((party.id, stream.into()))
+}
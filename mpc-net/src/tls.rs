@@ -0,0 +1,106 @@
+//! The mutually authenticated TLS configuration shared by all transports.
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+use color_eyre::eyre::{self, Context, Report};
+use quinn::rustls::{self, pki_types::CertificateDer, server::WebPkiClientVerifier, RootCertStore};
+
+use crate::{config::NetworkConfig, keys};
+
+/// The certificates of all parties and the TLS configurations derived from them.
+#[derive(Debug)]
+pub(crate) struct TlsConfig {
+    certs: HashMap<usize, CertificateDer<'static>>,
+    /// We authenticate ourselves with our own certificate when connecting as a client.
+    pub(crate) client: Arc<rustls::ClientConfig>,
+    /// As a server, we only accept the certificates of the parties. Clients without a certificate
+    /// complete the handshake, such that [TlsConfig::check_client] rejects them with a clear error.
+    pub(crate) server: Arc<rustls::ServerConfig>,
+}
+
+impl TlsConfig {
+    pub(crate) fn new(config: &NetworkConfig) -> Result<Self, Report> {
+        let certs: HashMap<usize, CertificateDer> = config
+            .parties
+            .iter()
+            .map(|p| {
+                let cert = keys::read_certificate(&p.cert_path)
+                    .with_context(|| format!("reading certificate of party {}", p.id))?;
+                Ok((p.id, cert))
+            })
+            .collect::<Result<_, Report>>()?;
+
+        let mut root_store = RootCertStore::empty();
+        for (id, cert) in &certs {
+            root_store
+                .add(cert.clone())
+                .with_context(|| format!("adding certificate for party {} to root store", id))?;
+        }
+        let root_store = Arc::new(root_store);
+        let my_cert = certs[&config.my_id].clone();
+        let key = keys::read_private_key(&config.key_path).context("reading own key file")?;
+
+        let client = rustls::ClientConfig::builder()
+            .with_root_certificates(Arc::clone(&root_store))
+            .with_client_auth_cert(vec![my_cert.clone()], key.clone_key())
+            .context("creating our client config")?;
+
+        let client_verifier = WebPkiClientVerifier::builder(root_store)
+            .allow_unauthenticated()
+            .build()
+            .context("creating client certificate verifier")?;
+        let server = rustls::ServerConfig::builder()
+            .with_client_cert_verifier(client_verifier)
+            .with_single_cert(vec![my_cert], key)
+            .context("creating our server config")?;
+
+        Ok(Self {
+            certs,
+            client: Arc::new(client),
+            server: Arc::new(server),
+        })
+    }
+
+    /// Checks that the party we connected to as a client presented its own certificate.
+    pub(crate) fn check_server(
+        &self,
+        id: usize,
+        addr: SocketAddr,
+        peer_cert: Option<&CertificateDer>,
+    ) -> eyre::Result<()> {
+        let peer_cert =
+            peer_cert.ok_or_else(|| eyre::eyre!("peer at {addr} did not present a certificate"))?;
+        if *peer_cert != self.certs[&id] {
+            return Err(eyre::eyre!(
+                "party {id} at {addr} presented a certificate that does not belong to party {id}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that a client, which claims to be the party `claimed_id`, is a party that connects
+    /// to us (i.e., has a larger id than `my_id`) and authenticated with the certificate of this
+    /// party.
+    pub(crate) fn check_client(
+        &self,
+        my_id: usize,
+        claimed_id: usize,
+        addr: SocketAddr,
+        peer_cert: Option<&CertificateDer>,
+    ) -> eyre::Result<()> {
+        let peer_cert =
+            peer_cert.ok_or_else(|| eyre::eyre!("peer at {addr} did not present a certificate"))?;
+        match self.certs.get(&claimed_id) {
+            Some(cert) if claimed_id > my_id => {
+                if peer_cert != cert {
+                    return Err(eyre::eyre!(
+                        "peer at {addr} claimed to be party {claimed_id}, but presented a certificate that does not belong to party {claimed_id}"
+                    ));
+                }
+                Ok(())
+            }
+            _ => Err(eyre::eyre!(
+                "peer at {addr} claimed to be party {claimed_id}, which is not a party that connects to us"
+            )),
+        }
+    }
+}
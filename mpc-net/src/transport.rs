@@ -0,0 +1,214 @@
+//! The read and write halves of the streams underlying a [`Channel`](crate::channel::Channel),
+//! independent of the transport used by the [`MpcNetworkHandler`](crate::MpcNetworkHandler).
+use std::{
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf, ReadHalf, WriteHalf},
+    net::TcpStream,
+};
+use tokio_rustls::TlsStream;
+
+use crate::emulation::{EmulatedRecvStream, EmulatedSendStream};
+
+/// The read half of a stream to another party.
+#[derive(Debug)]
+pub struct RecvStream(RecvStreamInner);
+
+/// The write half of a stream to another party.
+#[derive(Debug)]
+pub struct SendStream(SendStreamInner);
+
+#[derive(Debug)]
+enum RecvStreamInner {
+    Quic(quinn::RecvStream),
+    Tcp(Counting<ReadHalf<TlsStream<TcpStream>>>),
+    Memory(Counting<ReadHalf<DuplexStream>>),
+    Emulated(Counting<EmulatedRecvStream>),
+}
+
+#[derive(Debug)]
+enum SendStreamInner {
+    Quic(quinn::SendStream),
+    Tcp(Counting<WriteHalf<TlsStream<TcpStream>>>),
+    Memory(Counting<WriteHalf<DuplexStream>>),
+    Emulated(Counting<EmulatedSendStream>),
+}
+
+/// The number of (application level) bytes sent to and received from a party.
+#[derive(Debug, Default)]
+pub(crate) struct ByteCounters {
+    pub(crate) sent: Arc<AtomicU64>,
+    pub(crate) received: Arc<AtomicU64>,
+}
+
+impl ByteCounters {
+    pub(crate) fn get(&self) -> (u64, u64) {
+        (
+            self.sent.load(Ordering::Relaxed),
+            self.received.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// A stream that counts the bytes read from or written to it.
+#[derive(Debug)]
+struct Counting<S> {
+    stream: S,
+    bytes: Arc<AtomicU64>,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Counting<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        let poll = Pin::new(&mut self.stream).poll_read(cx, buf);
+        self.bytes
+            .fetch_add((buf.filled().len() - filled) as u64, Ordering::Relaxed);
+        poll
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Counting<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.stream).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = poll {
+            self.bytes.fetch_add(written as u64, Ordering::Relaxed);
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+impl From<quinn::RecvStream> for RecvStream {
+    fn from(stream: quinn::RecvStream) -> Self {
+        Self(RecvStreamInner::Quic(stream))
+    }
+}
+
+impl From<quinn::SendStream> for SendStream {
+    fn from(stream: quinn::SendStream) -> Self {
+        Self(SendStreamInner::Quic(stream))
+    }
+}
+
+impl RecvStream {
+    /// Creates the read half of a TCP stream, counting the received bytes.
+    pub(crate) fn tcp(stream: ReadHalf<TlsStream<TcpStream>>, counters: &ByteCounters) -> Self {
+        Self(RecvStreamInner::Tcp(Counting {
+            stream,
+            bytes: Arc::clone(&counters.received),
+        }))
+    }
+
+    /// Creates the read half of an in-memory stream, counting the received bytes.
+    pub(crate) fn memory(stream: ReadHalf<DuplexStream>, counters: &ByteCounters) -> Self {
+        Self(RecvStreamInner::Memory(Counting {
+            stream,
+            bytes: Arc::clone(&counters.received),
+        }))
+    }
+
+    /// Creates the read half of an emulated in-memory stream, counting the received bytes.
+    pub(crate) fn emulated(stream: EmulatedRecvStream, counters: &ByteCounters) -> Self {
+        Self(RecvStreamInner::Emulated(Counting {
+            stream,
+            bytes: Arc::clone(&counters.received),
+        }))
+    }
+}
+
+impl SendStream {
+    /// Creates the write half of a TCP stream, counting the sent bytes.
+    pub(crate) fn tcp(stream: WriteHalf<TlsStream<TcpStream>>, counters: &ByteCounters) -> Self {
+        Self(SendStreamInner::Tcp(Counting {
+            stream,
+            bytes: Arc::clone(&counters.sent),
+        }))
+    }
+
+    /// Creates the write half of an in-memory stream, counting the sent bytes.
+    pub(crate) fn memory(stream: WriteHalf<DuplexStream>, counters: &ByteCounters) -> Self {
+        Self(SendStreamInner::Memory(Counting {
+            stream,
+            bytes: Arc::clone(&counters.sent),
+        }))
+    }
+
+    /// Creates the write half of an emulated in-memory stream, counting the sent bytes.
+    pub(crate) fn emulated(stream: EmulatedSendStream, counters: &ByteCounters) -> Self {
+        Self(SendStreamInner::Emulated(Counting {
+            stream,
+            bytes: Arc::clone(&counters.sent),
+        }))
+    }
+}
+
+impl AsyncRead for RecvStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().0 {
+            RecvStreamInner::Quic(stream) => Pin::new(stream).poll_read(cx, buf),
+            RecvStreamInner::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            RecvStreamInner::Memory(stream) => Pin::new(stream).poll_read(cx, buf),
+            RecvStreamInner::Emulated(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for SendStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match &mut self.get_mut().0 {
+            SendStreamInner::Quic(stream) => AsyncWrite::poll_write(Pin::new(stream), cx, buf),
+            SendStreamInner::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            SendStreamInner::Memory(stream) => Pin::new(stream).poll_write(cx, buf),
+            SendStreamInner::Emulated(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().0 {
+            SendStreamInner::Quic(stream) => AsyncWrite::poll_flush(Pin::new(stream), cx),
+            SendStreamInner::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            SendStreamInner::Memory(stream) => Pin::new(stream).poll_flush(cx),
+            SendStreamInner::Emulated(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().0 {
+            SendStreamInner::Quic(stream) => AsyncWrite::poll_shutdown(Pin::new(stream), cx),
+            SendStreamInner::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+            SendStreamInner::Memory(stream) => Pin::new(stream).poll_shutdown(cx),
+            SendStreamInner::Emulated(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+# Creates the snarkjs FFLONK test vectors for the multiplier2 circuit of the Plonk test vectors, and
+# a proof of co-circom (three parties in MPC) for the zkey of snarkjs, which snarkjs must accept.
+# Needs snarkjs >= 0.7 on the PATH.
+set -euo pipefail
+cd "$(dirname "$0")"
+vectors=$(pwd)
+circuit=$vectors/../../../Plonk/bn254/multiplier2
+tmp=$(mktemp -d)
+trap 'rm -rf "$tmp"' EXIT
+
+snarkjs powersoftau new bn128 6 "$tmp/pot_0000.ptau"
+snarkjs powersoftau contribute "$tmp/pot_0000.ptau" "$tmp/pot_0001.ptau" --name="test vectors" -e="test vectors"
+snarkjs powersoftau prepare phase2 "$tmp/pot_0001.ptau" "$tmp/pot_final.ptau"
+snarkjs fflonk setup "$circuit/circuit.r1cs" "$tmp/pot_final.ptau" circuit.zkey
+snarkjs zkey export verificationkey circuit.zkey verification_key.json
+snarkjs fflonk prove circuit.zkey "$circuit/witness.wtns" circom.proof public.json
+snarkjs fflonk verify verification_key.json public.json circom.proof
+
+# the network configs of the examples are relative to a directory next to examples/configs
+cd ../../../../co-circom/co-circom/examples/plonk
+cargo run --release --bin co-circom -- split-witness --witness "$circuit/witness.wtns" --r1cs "$circuit/circuit.r1cs" --zkey "$vectors/circuit.zkey" --protocol REP3 --curve BN254 --out-dir "$tmp"
+for i in 0 1 2; do
+    cargo run --release --bin co-circom -- generate-proof fflonk --witness "$tmp/witness.wtns.$i.shared" --zkey "$vectors/circuit.zkey" --protocol REP3 --curve BN254 --config "../configs/party$((i + 1)).toml" --out "$tmp/proof.$i.json" &
+done
+for pid in $(jobs -p); do
+    wait "$pid"
+done
+cd "$vectors"
+cp "$tmp/proof.0.json" co-circom.proof
+snarkjs fflonk verify verification_key.json public.json co-circom.proof
@@ -0,0 +1,8 @@
+#!/usr/bin/env bash
+# Creates the golden files of the Solidity export (see co-circom/src/solidity.rs) from the test
+# vectors in this directory. Needs snarkjs >= 0.7 on the PATH.
+set -euo pipefail
+cd "$(dirname "$0")"
+
+snarkjs zkey export solidityverifier circuit.zkey verifier.sol
+snarkjs zkey export soliditycalldata public.json circom.proof > calldata.txt
@@ -443,7 +443,7 @@ fn shamir_witness_from_plain_witness<P: Pairing>(
     values.extend(witness.witness);
     let num_public_inputs = witness.public_inputs.len();
     let witness = Witness { values };
-    SharedWitness::share_shamir(witness, num_public_inputs, degree, num_parties, &mut rng).to_vec()
+    SharedWitness::share_shamir(witness, num_public_inputs, degree, num_parties, &mut rng).unwrap()
 }
 
 fn poseidon_groth16_rep3_bn254(c: &mut Criterion) {
@@ -1,6 +1,12 @@
 use ark_bls12_381::Bls12_381;
 use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
+use ark_ec::scalar_mul::fixed_base::FixedBase;
+use ark_ec::{AffineRepr, CurveGroup, Group};
+use ark_ff::{Field, PrimeField};
+use circom_types::fflonk::{FflonkProof, JsonVerificationKey as FflonkVK, ZKey as FflonkZK};
 use circom_types::plonk::PlonkProof;
+use circom_types::ptau::PowersOfTau;
 use circom_types::Witness;
 use circom_types::{
     groth16::{Groth16Proof, JsonPublicInput, JsonVerificationKey as Groth16VK, ZKey as Groth16ZK},
@@ -8,6 +14,7 @@ use circom_types::{
     R1CS,
 };
 use co_circom_snarks::SharedWitness;
+use co_fflonk::{CoFflonk, Fflonk};
 use co_groth16::{CoGroth16, Groth16};
 use co_plonk::CoPlonk;
 use co_plonk::Plonk;
@@ -104,3 +111,87 @@ macro_rules! add_test_impl {
 }
 e2e_test!("multiplier2");
 e2e_test!("poseidon");
+
+// There are no FFLONK zkeys from snarkjs in the test vectors, therefore we run the setup with a
+// ptau for a known tau. The ptau only contains the first two powers in G2, which is enough for FFLONK.
+fn ptau_from_tau<P: Pairing>(power: usize, tau: P::ScalarField) -> PowersOfTau<P> {
+    let powers = (0..(1 << (power + 1)) - 1)
+        .scan(P::ScalarField::ONE, |acc, _| {
+            let current = *acc;
+            *acc *= tau;
+            Some(current)
+        })
+        .collect::<Vec<_>>();
+    let scalar_size = P::ScalarField::MODULUS_BIT_SIZE as usize;
+    let window = FixedBase::get_mul_window_size(powers.len());
+    let table = FixedBase::get_window_table(scalar_size, window, P::G1::generator());
+    let tau_g1 = FixedBase::msm::<P::G1>(scalar_size, window, &table, &powers);
+    let g2 = P::G2Affine::generator();
+    PowersOfTau {
+        power,
+        ceremony_power: power,
+        tau_g1: P::G1::normalize_batch(&tau_g1),
+        tau_g2: vec![g2, (g2 * tau).into_affine()],
+        alpha_tau_g1: vec![],
+        beta_tau_g1: vec![],
+        beta_g2: g2,
+        contributions: vec![],
+        lagrange: None,
+    }
+}
+
+macro_rules! add_fflonk_test_impl {
+    ($curve: ident, $name: expr, $ptau_power: expr) => {
+        paste::item! {
+            #[test]
+            fn [< e2e_proof_ $name _ $curve:lower _fflonk>] () {
+                let r1cs_file =
+                    File::open(format!("../test_vectors/Plonk/{}/{}/circuit.r1cs", stringify!([< $curve:lower >]), $name)).unwrap();
+                let witness_file =
+                    File::open(format!("../test_vectors/Plonk/{}/{}/witness.wtns", stringify!([< $curve:lower >]), $name)).unwrap();
+                let witness = Witness::<[< ark_ $curve:lower >]::Fr>::from_reader(witness_file).unwrap();
+                let r1cs = R1CS::<$curve>::from_reader(r1cs_file).unwrap();
+                let ptau = ptau_from_tau::<$curve>($ptau_power, [< ark_ $curve:lower >]::Fr::from(42u64));
+                let zkey1 = FflonkZK::<$curve>::from_r1cs_and_ptau(&r1cs, &ptau).unwrap();
+                let vk = FflonkVK::from(&zkey1);
+                let zkey2 = zkey1.clone();
+                let zkey3 = zkey1.clone();
+                //ignore leading 1 for verification
+                let public_input = witness.values[1..r1cs.num_inputs].to_vec();
+                let mut rng = thread_rng();
+                let [witness_share1, witness_share2, witness_share3] =
+                    SharedWitness::share_rep3(witness, r1cs.num_inputs, &mut rng);
+                let test_network = Rep3TestNetwork::default();
+                let mut threads = vec![];
+                for (net, x, zkey) in izip!(
+                    test_network.get_party_networks(),
+                    [witness_share1, witness_share2, witness_share3].into_iter(),
+                    [zkey1, zkey2, zkey3].into_iter()
+                ) {
+                    threads.push(thread::spawn(move || {
+                        let rep3 = Rep3Protocol::<[< ark_ $curve:lower >]::Fr, PartyTestNetwork>::new(net).unwrap();
+                        let prover = CoFflonk::<
+                            Rep3Protocol<[< ark_ $curve:lower >]::Fr, PartyTestNetwork>,
+                            $curve,
+                        >::new(rep3);
+                        prover.prove(&zkey, x).unwrap()
+                    }));
+                }
+                let result3 = threads.pop().unwrap().join().unwrap();
+                let result2 = threads.pop().unwrap().join().unwrap();
+                let result1 = threads.pop().unwrap().join().unwrap();
+                assert_eq!(result1, result2);
+                assert_eq!(result2, result3);
+                let ser_proof = serde_json::to_string(&result1).unwrap();
+                let der_proof = serde_json::from_str::<FflonkProof<$curve>>(&ser_proof).unwrap();
+                assert_eq!(der_proof, result2);
+                let verified =
+                    Fflonk::<$curve>::verify(&vk, &der_proof, &public_input).expect("can verify");
+                assert!(verified);
+            }
+        }
+    };
+}
+add_fflonk_test_impl!(Bn254, "multiplier2", 6);
+add_fflonk_test_impl!(Bls12_381, "multiplier2", 6);
+add_fflonk_test_impl!(Bn254, "poseidon", 15);